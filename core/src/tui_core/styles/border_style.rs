@@ -0,0 +1,43 @@
+/*
+ *   Copyright (c) 2023 R3BL LLC
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+use get_size::GetSize;
+use serde::{Deserialize, Serialize};
+
+/// The set of box drawing characters that is used to paint a border.
+///
+/// ```text
+/// Single   Double   Rounded  Thick    Ascii
+/// ┌──┐     ╔══╗     ╭──╮     ┏━━┓     +--+
+/// │  │     ║  ║     │  │     ┃  ┃     |  |
+/// └──┘     ╚══╝     ╰──╯     ┗━━┛     +--+
+/// ```
+///
+/// When it is used in a [Style](crate::Style) that is applied to a `FlexBox`, the border takes up
+/// 1 cell on each side of the box (just like `padding`, it only affects the contents inside the
+/// box).
+#[derive(
+    Serialize, Deserialize, Clone, PartialEq, Eq, Copy, Hash, GetSize, Debug, Default,
+)]
+pub enum BorderStyle {
+    #[default]
+    Single,
+    Double,
+    Rounded,
+    Thick,
+    Ascii,
+}
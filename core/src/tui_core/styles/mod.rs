@@ -16,12 +16,14 @@
  */
 
 // Attach sources.
+pub mod border_style;
 pub mod hex_color_parser;
 pub mod style;
 pub mod stylesheet;
 pub mod tui_color;
 
 // Re-export.
+pub use border_style::*;
 pub use hex_color_parser::*;
 pub use style::*;
 pub use stylesheet::*;
@@ -77,6 +77,10 @@ pub struct Style {
     ///
     /// [`FlexBox` docs](https://docs.rs/r3bl_rs_utils/latest/r3bl_rs_utils/tui/layout/flex_box/struct.FlexBox.html).
     pub padding: Option<ChUnit>,
+    /// The border takes up 1 cell on each side inside a `FlexBox`, outside of its `padding`. This
+    /// only reserves the space for it, use `FlexBox::render_border()` to paint it. Unlike
+    /// `padding`, it isn't inherited by nested `FlexBox`es.
+    pub border: Option<BorderStyle>,
     pub lolcat: bool,
 }
 
@@ -122,6 +126,9 @@ mod addition {
             if other.padding.is_some() {
                 new_style.padding = other.padding;
             }
+            if other.border.is_some() {
+                new_style.border = other.border;
+            }
             if other.reverse {
                 new_style.reverse = other.reverse;
             }
@@ -222,6 +229,10 @@ mod style_helpers {
                 msg_vec.push(format!("pad:{padding:?}"))
             }
 
+            if let Some(border) = self.border {
+                msg_vec.push(format!("brd:{border:?}"))
+            }
+
             msg_vec.join("‐")
         }
     }
@@ -266,6 +277,10 @@ mod style_helpers {
                 msg_vec.push("strikethrough".to_string())
             }
 
+            if let Some(border) = self.border {
+                msg_vec.push(format!("border: {border:?}"))
+            }
+
             write!(
                 f,
                 "Style {{ {} | fg: {:?} | bg: {:?} | padding: {:?} }}",
@@ -330,6 +345,7 @@ mod test_style {
             color_fg: color!(@red).into(),
             color_bg: color!(0, 0, 0).into(),
             padding: Some(ch!(10)),
+            border: Some(BorderStyle::Rounded),
             ..Style::default()
         };
 
@@ -344,6 +360,7 @@ mod test_style {
         assert_eq2!(style.color_fg, color!(@red).into());
         assert_eq2!(style.color_bg, color!(0, 0, 0).into());
         assert_eq2!(style.padding, Some(ch!(10)));
+        assert_eq2!(style.border, Some(BorderStyle::Rounded));
     }
 
     #[test]
@@ -137,7 +137,7 @@ pub enum TuiColor {
     Ansi(AnsiValue),
 }
 
-#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Copy, Hash, GetSize)]
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Copy, Hash, GetSize, Debug)]
 pub enum ANSIBasicColor {
     /// Black color.
     Black,
@@ -129,6 +129,7 @@ pub fn derive_macro_builder(input: TokenStream) -> TokenStream {
 ///   id: "my_style",          /* Optional. */
 ///   attrib: [dim, bold]      /* Optional. */
 ///   padding: 10,             /* Optional. */
+///   border: BorderStyle::Rounded, /* Optional. */
 ///   color_fg: TuiColor::Blue, /* Optional. */
 ///   color_bg: TuiColor::Red,  /* Optional. */
 /// }
@@ -136,6 +137,7 @@ pub fn derive_macro_builder(input: TokenStream) -> TokenStream {
 ///
 /// - All the attributes are:
 ///   - `bold`, `dim`, `underline`, `reverse`, `hidden`, `strikethrough`
+/// - `border` takes a `BorderStyle` enum value (or a variable holding one).
 /// - `color_fg` and `color_bg` can take any of the following:
 ///   - Color enum value.
 ///   - Rgb value.
@@ -25,6 +25,7 @@ pub(crate) fn code_gen(
         id,
         attrib_vec,
         padding,
+        border,
         color_fg,
         color_bg,
         lolcat,
@@ -48,6 +49,15 @@ pub(crate) fn code_gen(
         None => quote! {},
     };
 
+    let maybe_border_expr = match border {
+        Some(border_expr) => {
+            quote! {
+              border: Some(#border_expr),
+            }
+        }
+        None => quote! {},
+    };
+
     let maybe_color_fg_expr = match color_fg {
         Some(color_expr) => {
             quote! {
@@ -86,6 +96,7 @@ pub(crate) fn code_gen(
         hidden: #has_attrib_hidden,
         strikethrough: #has_attrib_strikethrough,
         #maybe_padding_expr
+        #maybe_border_expr
         #maybe_color_fg_expr
         #maybe_color_bg_expr
         #maybe_lolcat_expr
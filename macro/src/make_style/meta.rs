@@ -35,6 +35,7 @@ pub(crate) struct StyleMetadata {
     pub id: Expr,                /* Only required field. */
     pub attrib_vec: Vec<Attrib>, /* Attributes are optional. */
     pub padding: Option<ChUnit>, /* Optional. */
+    pub border: Option<Expr>,    /* Optional. */
     pub color_fg: Option<Expr>,  /* Optional. */
     pub color_bg: Option<Expr>,  /* Optional. */
     pub lolcat: Option<LitBool>, /* Optional. */
@@ -29,6 +29,7 @@ use crate::utils::IdentExt;
 ///   id: "my_style",          /* Optional. */
 ///   attrib: [dim, bold]      /* Optional. */
 ///   padding: 10,             /* Optional. */
+///   border: BorderStyle::Rounded, /* Optional. */
 ///   color_fg: TuiColor::Blue, /* Optional. */
 ///   color_bg: TuiColor::Red,  /* Optional. */
 ///   lolcat: true,            /* Optional. */
@@ -45,6 +46,7 @@ impl Parse for StyleMetadata {
             id: Verbatim(quote! { u8::MAX }),
             attrib_vec: Vec::new(),
             padding: None,
+            border: None,
             color_fg: None,
             color_bg: None,
             lolcat: None,
@@ -54,6 +56,7 @@ impl Parse for StyleMetadata {
         parse_optional_id(&input, &mut metadata)?;
         parse_optional_attrib(&input, &mut metadata)?;
         parse_optional_padding(&input, &mut metadata)?;
+        parse_optional_border(&input, &mut metadata)?;
         parse_optional_color_fg(&input, &mut metadata)?;
         parse_optional_color_bg(&input, &mut metadata)?;
         parse_optional_lolcat(&input, &mut metadata)?;
@@ -74,6 +77,7 @@ pub(crate) mod custom_keywords {
     syn::custom_keyword!(hidden);
     syn::custom_keyword!(strikethrough);
     syn::custom_keyword!(padding);
+    syn::custom_keyword!(border);
     syn::custom_keyword!(color_fg);
     syn::custom_keyword!(color_bg);
     syn::custom_keyword!(lolcat);
@@ -186,6 +190,27 @@ fn parse_optional_padding(
     Ok(())
 }
 
+// Parse border (optional).
+fn parse_optional_border(
+    input: &ParseStream,
+    metadata: &mut StyleMetadata,
+) -> Result<()> {
+    let lookahead = input.lookahead1();
+
+    if lookahead.peek(custom_keywords::border) {
+        input.parse::<custom_keywords::border>()?;
+        input.parse::<Token![:]>()?;
+        let border_expr = input.parse::<Expr>()?;
+        metadata.border = Some(border_expr);
+        call_if_true!(
+            DEBUG_MAKE_STYLE_MOD,
+            println!("🚀 border: {:#?}", metadata.border)
+        );
+    }
+
+    Ok(())
+}
+
 // Parse color_fg (optional).
 fn parse_optional_color_fg(
     input: &ParseStream,
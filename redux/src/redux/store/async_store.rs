@@ -31,6 +31,7 @@ use crate::{redux::{AsyncMiddlewareSpawnsVec,
 
 pub type SharedStore<S, A> = Arc<RwLock<Store<S, A>>>;
 
+#[cfg(not(feature = "dispatch_tracker"))]
 #[macro_export]
 macro_rules! spawn_dispatch_action {
     ($store: expr, $action: expr) => {{
@@ -41,6 +42,19 @@ macro_rules! spawn_dispatch_action {
     }};
 }
 
+/// Dispatch the action in a spawned task. If this is called while a future runs in
+/// [DispatchTracker::scope](crate::DispatchTracker::scope), then the task is tracked by it.
+#[cfg(feature = "dispatch_tracker")]
+#[macro_export]
+macro_rules! spawn_dispatch_action {
+    ($store: expr, $action: expr) => {{
+        let store_copy = $store.clone();
+        $crate::DispatchTracker::spawn(async move {
+            store_copy.write().await.dispatch_action($action).await;
+        });
+    }};
+}
+
 /// Thread safe and async Redux store (using [`tokio`]).
 pub struct Store<S, A>
 where
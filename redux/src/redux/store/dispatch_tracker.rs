@@ -0,0 +1,89 @@
+/*
+ *   Copyright (c) 2023 R3BL LLC
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+use std::{future::Future,
+          sync::{Arc, Mutex}};
+
+use tokio::task::JoinHandle;
+
+tokio::task_local! {
+    static DISPATCH_TRACKER: DispatchTracker;
+}
+
+/// Keeps track of the tasks that are spawned by [spawn_dispatch_action!](crate::spawn_dispatch_action)
+/// while a future runs in [DispatchTracker::scope], so that they can all be awaited w/
+/// [DispatchTracker::wait_for_pending]. The actions that are dispatched by these tasks (eg: by a
+/// subscriber) are tracked too.
+///
+/// When there's no [DispatchTracker] in scope, actions are dispatched in a detached task (just
+/// like before). This is used by the headless main event loop in `r3bl_tui`, which has to wait
+/// for all the actions that an input event dispatched before it captures the frame.
+///
+/// This is only available w/ the `dispatch_tracker` feature. When it is disabled,
+/// [spawn_dispatch_action!](crate::spawn_dispatch_action) just calls [tokio::spawn].
+#[derive(Debug, Clone, Default)]
+pub struct DispatchTracker {
+    pending_join_handles: Arc<Mutex<Vec<JoinHandle<()>>>>,
+}
+
+impl DispatchTracker {
+    /// Run `future`, and track all the actions that are dispatched by it.
+    pub async fn scope<F: Future>(&self, future: F) -> F::Output {
+        DISPATCH_TRACKER.scope(self.clone(), future).await
+    }
+
+    /// Spawn a task that runs `future`. It is tracked by the [DispatchTracker] that is in scope
+    /// (if any).
+    pub fn spawn<F>(future: F)
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        match DISPATCH_TRACKER.try_with(|it| it.clone()) {
+            Ok(tracker) => {
+                let join_handle =
+                    tokio::spawn(DISPATCH_TRACKER.scope(tracker.clone(), future));
+                tracker.get_pending_join_handles().push(join_handle);
+            }
+            Err(_) => {
+                tokio::spawn(future);
+            }
+        }
+    }
+
+    /// Wait for all the tracked tasks to complete, including the ones that are spawned while
+    /// waiting.
+    pub async fn wait_for_pending(&self) {
+        loop {
+            let join_handles = std::mem::take(&mut *self.get_pending_join_handles());
+            if join_handles.is_empty() {
+                break;
+            }
+            for join_handle in join_handles {
+                let _ = join_handle.await;
+            }
+        }
+    }
+
+    pub fn get_pending_count(&self) -> usize { self.get_pending_join_handles().len() }
+
+    /// A poisoned lock is ignored, since the [Vec] is never left in an inconsistent state.
+    fn get_pending_join_handles(&self) -> std::sync::MutexGuard<'_, Vec<JoinHandle<()>>> {
+        self.pending_join_handles
+            .lock()
+            .unwrap_or_else(|it| it.into_inner())
+    }
+}
@@ -17,6 +17,10 @@
 
 // Attach sources.
 pub mod async_store;
+#[cfg(feature = "dispatch_tracker")]
+pub mod dispatch_tracker;
 
 // Re-export.
 pub use async_store::*;
+#[cfg(feature = "dispatch_tracker")]
+pub use dispatch_tracker::*;
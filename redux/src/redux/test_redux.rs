@@ -85,6 +85,39 @@ mod tests {
         run_mw_example_spawns(&shared_vec, &shared_store.clone()).await;
     }
 
+    #[cfg(feature = "dispatch_tracker")]
+    #[tokio::test]
+    async fn test_dispatch_tracker_waits_for_spawned_actions() {
+        use crate::DispatchTracker;
+
+        let mut _store = Store::<State, Action>::default();
+        _store.add_reducer(MyReducer::new()).await;
+        let shared_store: SharedStore<State, Action> = Arc::new(RwLock::new(_store));
+
+        // Hold the store, so that the spawned actions can't run until they are waited for.
+        let store_guard = shared_store.read().await;
+        let dispatch_tracker = DispatchTracker::default();
+        dispatch_tracker
+            .scope(async {
+                spawn_dispatch_action!(shared_store, Action::Add(1, 2));
+                // The action that is dispatched by this task is tracked too.
+                let shared_store = shared_store.clone();
+                DispatchTracker::spawn(async move {
+                    tokio::task::yield_now().await;
+                    spawn_dispatch_action!(shared_store, Action::Add(3, 4));
+                });
+            })
+            .await;
+        // Not tracked, since it isn't in the scope.
+        spawn_dispatch_action!(shared_store, Action::Noop);
+        assert_eq2!(dispatch_tracker.get_pending_count(), 2);
+        drop(store_guard);
+
+        dispatch_tracker.wait_for_pending().await;
+        assert_eq2!(dispatch_tracker.get_pending_count(), 0);
+        assert_eq2!(shared_store.read().await.get_state().stack, vec![7]);
+    }
+
     /// ```text
     /// ╭──────────────────────────────────────────────────────╮
     /// │ Test helpers: Reset shared object.                   │
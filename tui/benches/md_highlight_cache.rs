@@ -0,0 +1,107 @@
+/*
+ *   Copyright (c) 2023 R3BL LLC
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+//! Keystroke latency of the markdown syntax highlighter, w/ & w/o [MdHighlightCache]. Each
+//! iteration types (or deletes) a single character in the middle of the document, which is what
+//! happens in between two renders of the editor. Run w/ `cargo bench`.
+
+#![feature(test)]
+extern crate test;
+
+use r3bl_tui::*;
+use syntect::parsing::SyntaxSet;
+use test::Bencher;
+
+/// A document w/ `line_count` lines, made up of the kinds of blocks that show up in a note.
+fn make_document(line_count: usize) -> Vec<US> {
+    let section = [
+        "# Heading",
+        "",
+        "Some text w/ *bold*, _italic_ & `code` in it.",
+        "- [ ] todo item",
+        "- [x] done item",
+        "```rs",
+        "fn main() {",
+        "    println!(\"Hello, world!\");",
+        "}",
+        "```",
+    ];
+    section
+        .iter()
+        .cycle()
+        .take(line_count)
+        .map(|it| US::from(*it))
+        .collect()
+}
+
+/// Returns the document before & after typing a character in the middle of it.
+fn make_keystroke(line_count: usize) -> (Vec<US>, Vec<US>) {
+    let before = make_document(line_count);
+    let mut after = before.clone();
+    let row_index = line_count / 2 + 2;
+    after[row_index] = US::from(format!("{}!", after[row_index].string));
+    (before, after)
+}
+
+fn bench_cache(bencher: &mut Bencher, line_count: usize) {
+    let syntax_set = SyntaxSet::load_defaults_newlines();
+    let theme = load_default_theme();
+    let (before, after) = make_keystroke(line_count);
+    let mut cache = MdHighlightCache::default();
+    cache.update(&before, &None, Some((&syntax_set, &theme)));
+
+    let mut is_after = false;
+    bencher.iter(|| {
+        is_after = !is_after;
+        let lines = if is_after { &after } else { &before };
+        cache.update(lines, &None, Some((&syntax_set, &theme)));
+        cache.get_lines().len()
+    });
+}
+
+fn bench_full(bencher: &mut Bencher, line_count: usize) {
+    let syntax_set = SyntaxSet::load_defaults_newlines();
+    let theme = load_default_theme();
+    let (before, after) = make_keystroke(line_count);
+
+    let mut is_after = false;
+    bencher.iter(|| {
+        is_after = !is_after;
+        let lines = if is_after { &after } else { &before };
+        try_parse_and_highlight(lines, &None, Some((&syntax_set, &theme)))
+            .map(|it| it.len())
+            .ok()
+    });
+}
+
+#[bench]
+fn keystroke_cache_500_lines(bencher: &mut Bencher) { bench_cache(bencher, 500); }
+
+#[bench]
+fn keystroke_cache_5_000_lines(bencher: &mut Bencher) { bench_cache(bencher, 5_000); }
+
+#[bench]
+fn keystroke_cache_50_000_lines(bencher: &mut Bencher) { bench_cache(bencher, 50_000); }
+
+#[bench]
+fn keystroke_full_500_lines(bencher: &mut Bencher) { bench_full(bencher, 500); }
+
+#[bench]
+fn keystroke_full_5_000_lines(bencher: &mut Bencher) { bench_full(bencher, 5_000); }
+
+#[bench]
+fn keystroke_full_50_000_lines(bencher: &mut Bencher) { bench_full(bencher, 50_000); }
@@ -0,0 +1,257 @@
+/*
+ *   Copyright (c) 2023 R3BL LLC
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+//! The number of bytes that are painted to the terminal for the diff between 2 frames, w/
+//! [VerticalShift] & [render_diff_to_render_ops], and w/ the previous implementation (which moved
+//! the cursor & reset the colors for every changed cell). Run w/
+//! `cargo bench --bench render_diff -- --nocapture` to see the byte counts, eg:
+//!
+//! ```text
+//! keystroke: 620 bytes per cell -> 80 bytes optimized
+//! scroll: 5764 bytes per cell -> 51 bytes optimized
+//! clear: 2110 bytes per cell -> 25 bytes optimized
+//! ```
+
+#![feature(test)]
+extern crate test;
+
+use std::sync::Arc;
+
+use r3bl_rs_utils_core::*;
+use r3bl_rs_utils_macro::style;
+use r3bl_tui::*;
+use test::Bencher;
+use tokio::{runtime::Runtime, sync::RwLock};
+
+const WINDOW_SIZE: Size = Size {
+    col_count: ChUnit { value: 80 },
+    row_count: ChUnit { value: 24 },
+};
+
+/// Each line of the frame is made up of `(text, maybe_style)` spans.
+type Frame<'a> = Vec<Vec<(&'a str, Option<Style>)>>;
+
+fn make_offscreen_buffer(frame: &Frame) -> OffscreenBuffer {
+    let mut it = OffscreenBuffer::new_with_capacity_initialized(WINDOW_SIZE);
+    for (row_index, spans) in frame.iter().enumerate() {
+        let mut col_index = 0;
+        for (text, maybe_style) in spans {
+            for grapheme in UnicodeString::from(*text).vec_segment {
+                it.buffer[row_index][col_index] = PixelChar::PlainText {
+                    content: grapheme,
+                    maybe_style: *maybe_style,
+                };
+                col_index += 1;
+            }
+        }
+    }
+    it
+}
+
+/// An editor w/ a status bar at the bottom.
+fn make_editor_frame<'a>(lines: &[&'a str], status: &'a str) -> Frame<'a> {
+    let text_style =
+        Some(style! { color_fg: color!(@white) color_bg: color!(@dark_grey) });
+    let keyword_style = Some(style! { attrib: [bold] color_fg: color!(@cyan) });
+    let status_style = Some(style! { attrib: [reverse] color_fg: color!(@yellow) });
+    let mut it: Frame = lines
+        .iter()
+        .map(|line| match line.split_once(' ') {
+            Some((keyword, rest)) => {
+                vec![(keyword, keyword_style), (" ", None), (rest, text_style)]
+            }
+            None => vec![(*line, text_style)],
+        })
+        .collect();
+    it.resize(ch!(@to_usize WINDOW_SIZE.row_count) - 1, vec![]);
+    it.push(vec![(status, status_style)]);
+    it
+}
+
+const LINES: [&str; 12] = [
+    "fn main() {",
+    "let message = \"Hello, world!\";",
+    "println!(\"{message}\");",
+    "for index in 0..10 {",
+    "if index % 2 == 0 {",
+    "println!(\"{index} is even\");",
+    "}",
+    "}",
+    "let it = vec![1, 2, 3];",
+    "assert_eq!(it.len(), 3);",
+    "}",
+    "",
+];
+
+/// Returns the frames before & after.
+fn make_scenario(name: &str) -> (OffscreenBuffer, OffscreenBuffer) {
+    let (before, after) = match name {
+        // Type a character in the middle of a line.
+        "keystroke" => {
+            let mut lines = LINES.to_vec();
+            let before = make_editor_frame(&lines, "NORMAL | main.rs | 2:10");
+            lines[1] = "let messages = \"Hello, world!\";";
+            let after = make_editor_frame(&lines, "NORMAL | main.rs | 2:11");
+            (before, after)
+        }
+        // Scroll down by 1 line.
+        "scroll" => {
+            let before = make_editor_frame(&LINES, "NORMAL | main.rs | 1:1");
+            let after = make_editor_frame(&LINES[1..], "NORMAL | main.rs | 2:1");
+            (before, after)
+        }
+        // Delete all the lines.
+        "clear" => {
+            let before = make_editor_frame(&LINES, "NORMAL | main.rs | 1:1");
+            let after = make_editor_frame(&[], "NORMAL | main.rs | 1:1");
+            (before, after)
+        }
+        _ => unreachable!(),
+    };
+    (
+        make_offscreen_buffer(&before),
+        make_offscreen_buffer(&after),
+    )
+}
+
+fn get_diff_chunks(
+    before: &OffscreenBuffer,
+    after: &OffscreenBuffer,
+) -> PixelCharDiffChunks {
+    match before.diff(after) {
+        OffscreenBufferDiffResult::Comparable(it) => it,
+        OffscreenBufferDiffResult::NotComparable => unreachable!(),
+    }
+}
+
+/// Scroll the rows that moved, and then paint the diff (same as [paint]).
+fn render_diff_optimized(before: &OffscreenBuffer, after: &OffscreenBuffer) -> RenderOps {
+    let mut before = before.clone();
+    let mut it = match VerticalShift::detect(&before, after) {
+        Some(vertical_shift) => {
+            vertical_shift.apply_to(&mut before);
+            vertical_shift.to_render_ops()
+        }
+        None => render_ops!(),
+    };
+    it.extend(
+        render_diff_to_render_ops(&get_diff_chunks(&before, after), WINDOW_SIZE).list,
+    );
+    it
+}
+
+/// The previous implementation of [OffscreenBufferPaint::render_diff].
+fn render_diff_per_cell(diff_chunks: &PixelCharDiffChunks) -> RenderOps {
+    let mut it = render_ops!();
+    for (position, pixel_char) in diff_chunks.iter() {
+        it.push(RenderOp::MoveCursorPositionAbs(*position));
+        it.push(RenderOp::ResetColor);
+        match pixel_char {
+            PixelChar::Void => continue,
+            PixelChar::Spacer => {
+                it.push(RenderOp::CompositorNoClipTruncPaintTextWithAttributes(
+                    SPACER.into(),
+                    None,
+                ))
+            }
+            PixelChar::PlainText {
+                content,
+                maybe_style,
+            } => {
+                it.push(RenderOp::ApplyColors(*maybe_style));
+                it.push(RenderOp::CompositorNoClipTruncPaintTextWithAttributes(
+                    content.string.clone(),
+                    *maybe_style,
+                ))
+            }
+        }
+    }
+    it
+}
+
+/// Paint the [RenderOps] into a [Vec] using the crossterm backend.
+async fn paint(render_ops: &RenderOps, shared_global_data: &SharedGlobalData) -> Vec<u8> {
+    let mut crossterm_impl = RenderOpImplCrossterm::new(Vec::<u8>::new());
+    let mut local_data = RenderOpsLocalData::default();
+    let mut skip_flush = false;
+    for render_op in render_ops.iter() {
+        crossterm_impl
+            .paint(
+                &mut skip_flush,
+                render_op,
+                shared_global_data,
+                &mut local_data,
+            )
+            .await;
+    }
+    crossterm_impl.writer
+}
+
+fn bench_scenario(bencher: &mut Bencher, name: &str, is_optimized: bool) {
+    let runtime = Runtime::new().unwrap();
+    let shared_global_data: SharedGlobalData =
+        Arc::new(RwLock::new(GlobalData::new_headless(WINDOW_SIZE)));
+    let (before, after) = make_scenario(name);
+    let render_diff = || {
+        if is_optimized {
+            render_diff_optimized(&before, &after)
+        } else {
+            render_diff_per_cell(&get_diff_chunks(&before, &after))
+        }
+    };
+
+    if is_optimized {
+        let per_cell = runtime.block_on(paint(
+            &render_diff_per_cell(&get_diff_chunks(&before, &after)),
+            &shared_global_data,
+        ));
+        let optimized = runtime.block_on(paint(&render_diff(), &shared_global_data));
+        println!(
+            "{name}: {} bytes per cell -> {} bytes optimized",
+            per_cell.len(),
+            optimized.len()
+        );
+    }
+
+    bencher.iter(|| {
+        runtime
+            .block_on(paint(&render_diff(), &shared_global_data))
+            .len()
+    });
+}
+
+#[bench]
+fn keystroke_per_cell(bencher: &mut Bencher) {
+    bench_scenario(bencher, "keystroke", false);
+}
+
+#[bench]
+fn keystroke_optimized(bencher: &mut Bencher) {
+    bench_scenario(bencher, "keystroke", true);
+}
+
+#[bench]
+fn scroll_per_cell(bencher: &mut Bencher) { bench_scenario(bencher, "scroll", false); }
+
+#[bench]
+fn scroll_optimized(bencher: &mut Bencher) { bench_scenario(bencher, "scroll", true); }
+
+#[bench]
+fn clear_per_cell(bencher: &mut Bencher) { bench_scenario(bencher, "clear", false); }
+
+#[bench]
+fn clear_optimized(bencher: &mut Bencher) { bench_scenario(bencher, "clear", true); }
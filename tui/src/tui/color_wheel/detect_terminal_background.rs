@@ -0,0 +1,242 @@
+/*
+ *   Copyright (c) 2023 R3BL LLC
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+//! Detect whether the terminal has a light or dark background. This is used to pick the
+//! [MarkdownTheme](crate::MarkdownTheme) preset. More info:
+//! - <https://invisible-island.net/xterm/ctlseqs/ctlseqs.html#h3-Operating-System-Commands>
+//! - <https://github.com/dalance/termbg>
+//! - <https://github.com/vim/vim/blob/master/src/term.c> (`COLORFGBG` handling)
+
+use std::time::Duration;
+
+use r3bl_rs_utils_core::*;
+
+/// How long to wait for the terminal to respond to the OSC 11 query.
+pub const OSC_11_QUERY_TIMEOUT: Duration = Duration::from_millis(100);
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum TerminalBackground {
+    Light,
+    Dark,
+}
+
+impl TerminalBackground {
+    /// This function is used to determine the background of the current terminal:
+    /// 1. The terminal is asked for its background color using an OSC 11 query. This only works
+    ///    when stdin & stdout are connected to a terminal that responds to the query.
+    /// 2. If that fails, then the `COLORFGBG` environment variable is used.
+    /// 3. If that fails, then [TerminalBackground::Dark] is assumed.
+    ///
+    /// The terminal is put in raw mode while waiting for the response, and the response is read
+    /// from `/dev/tty` (not stdin). So this must be called before any other code starts reading
+    /// input events (eg: before
+    /// [TerminalWindow::main_event_loop](crate::TerminalWindow::main_event_loop) starts its event
+    /// stream).
+    pub fn detect() -> TerminalBackground {
+        detect_terminal_background_impl::try_query_osc_11(OSC_11_QUERY_TIMEOUT)
+            .map(TerminalBackground::from)
+            .unwrap_or_else(TerminalBackground::detect_from_env)
+    }
+
+    /// Same as [TerminalBackground::detect] w/out the OSC 11 query, so no terminal I/O is
+    /// performed.
+    pub fn detect_from_env() -> TerminalBackground {
+        std::env::var("COLORFGBG")
+            .ok()
+            .and_then(|it| parse_colorfgbg(&it))
+            .unwrap_or(TerminalBackground::Dark)
+    }
+}
+
+impl From<RgbValue> for TerminalBackground {
+    /// Uses the relative luminance of the color: <https://www.w3.org/TR/WCAG20/#relativeluminancedef>.
+    fn from(color: RgbValue) -> Self {
+        let luminance = 0.2126 * color.red as f64
+            + 0.7152 * color.green as f64
+            + 0.0722 * color.blue as f64;
+        if luminance > 127.5 {
+            TerminalBackground::Light
+        } else {
+            TerminalBackground::Dark
+        }
+    }
+}
+
+/// Parse the response to an OSC 11 query, eg: `ESC ] 11 ; rgb:1e1e/1e1e/2e2e BEL`. Each
+/// component can have 1 to 4 hex digits, and the response can be terminated by `BEL` or `ESC \`.
+pub fn parse_osc_11_response(response: &str) -> Option<RgbValue> {
+    let start = response.find("]11;")?;
+    let rest = &response[start + "]11;".len()..];
+    let rest = rest
+        .strip_prefix("rgb:")
+        .or_else(|| rest.strip_prefix("rgba:"))?;
+    let end = rest.find(['\x07', '\x1b']).unwrap_or(rest.len());
+
+    let mut components = rest[..end].split('/').map(|it| {
+        if it.is_empty() || it.len() > 4 {
+            return None;
+        }
+        let value = u32::from_str_radix(it, 16).ok()?;
+        let max = (1_u32 << (4 * it.len())) - 1;
+        Some((value * 255 / max) as u8)
+    });
+
+    Some(RgbValue {
+        red: components.next()??,
+        green: components.next()??,
+        blue: components.next()??,
+    })
+}
+
+/// Parse the `COLORFGBG` environment variable, eg: `15;0` or `15;default;0`. The last field is
+/// the ANSI color index of the background. Indices 0-6 & 8 are dark, the rest are light.
+pub fn parse_colorfgbg(value: &str) -> Option<TerminalBackground> {
+    let bg = value.rsplit(';').next()?.trim().parse::<u8>().ok()?;
+    match bg {
+        0..=6 | 8 => Some(TerminalBackground::Dark),
+        7 | 9..=15 => Some(TerminalBackground::Light),
+        _ => None,
+    }
+}
+
+mod detect_terminal_background_impl {
+    use super::*;
+
+    #[cfg(not(unix))]
+    pub fn try_query_osc_11(_timeout: Duration) -> Option<RgbValue> { None }
+
+    /// Sends the OSC 11 query, followed by a primary device attributes (DA1) query. Almost all
+    /// terminals respond to DA1, so once that response arrives, there's no need to wait for the
+    /// timeout (when the terminal doesn't support OSC 11).
+    #[cfg(unix)]
+    pub fn try_query_osc_11(timeout: Duration) -> Option<RgbValue> {
+        use std::{fs::OpenOptions,
+                  io::{stdin, stdout, Read, Write},
+                  os::unix::io::AsRawFd,
+                  time::Instant};
+
+        use crossterm::terminal::{disable_raw_mode,
+                                  enable_raw_mode,
+                                  is_raw_mode_enabled};
+        use is_terminal::IsTerminal;
+
+        if !stdin().is_terminal() || !stdout().is_terminal() {
+            return None;
+        }
+
+        let mut tty = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open("/dev/tty")
+            .ok()?;
+
+        let was_raw_mode_enabled = is_raw_mode_enabled().unwrap_or(false);
+        if !was_raw_mode_enabled {
+            enable_raw_mode().ok()?;
+        }
+
+        let mut response = Vec::<u8>::new();
+        let start = Instant::now();
+        if tty.write_all(b"\x1b]11;?\x1b\\\x1b[c").is_ok() && tty.flush().is_ok() {
+            let mut buffer = [0_u8; 64];
+            while let Some(remaining) = timeout.checked_sub(start.elapsed()) {
+                let mut poll_fd = libc::pollfd {
+                    fd: tty.as_raw_fd(),
+                    events: libc::POLLIN,
+                    revents: 0,
+                };
+                // SAFETY: `poll_fd` is a valid `pollfd` that outlives the call, `nfds` is 1, and
+                // `tty` keeps its file descriptor open.
+                let poll_result = unsafe {
+                    libc::poll(&mut poll_fd, 1, remaining.as_millis() as libc::c_int)
+                };
+                if poll_result <= 0 {
+                    break;
+                }
+                match tty.read(&mut buffer) {
+                    Ok(0) | Err(_) => break,
+                    Ok(count) => response.extend_from_slice(&buffer[..count]),
+                }
+                if is_da1_response_complete(&response) {
+                    break;
+                }
+            }
+        }
+
+        if !was_raw_mode_enabled {
+            disable_raw_mode().ok();
+        }
+
+        parse_osc_11_response(&String::from_utf8_lossy(&response))
+    }
+
+    /// The DA1 response looks like `ESC [ ? 6 2 ; 2 2 c`.
+    #[cfg(unix)]
+    fn is_da1_response_complete(response: &[u8]) -> bool {
+        let Some(start) = response.windows(3).position(|it| it == b"\x1b[?") else {
+            return false;
+        };
+        response[start..].contains(&b'c')
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_osc_11_response() {
+        // Terminated by BEL, 4 hex digits.
+        assert_eq2!(
+            parse_osc_11_response("\x1b]11;rgb:ffff/ffff/ffff\x07"),
+            Some(RgbValue::from_u8(255, 255, 255))
+        );
+        // Terminated by ST, 2 hex digits, followed by the DA1 response.
+        assert_eq2!(
+            parse_osc_11_response("\x1b]11;rgb:1e/1e/2e\x1b\\\x1b[?62;22c"),
+            Some(RgbValue::from_u8(0x1e, 0x1e, 0x2e))
+        );
+        // No OSC 11 response, only the DA1 response.
+        assert_eq2!(parse_osc_11_response("\x1b[?62;22c"), None);
+        // Malformed.
+        assert_eq2!(parse_osc_11_response("\x1b]11;rgb:ff/ff\x07"), None);
+        assert_eq2!(parse_osc_11_response("\x1b]11;rgb:fffff/0/0\x07"), None);
+    }
+
+    #[test]
+    fn test_background_from_color() {
+        assert_eq2!(
+            TerminalBackground::from(RgbValue::from_hex("#fdf6e3")),
+            TerminalBackground::Light
+        );
+        assert_eq2!(
+            TerminalBackground::from(RgbValue::from_hex("#002b36")),
+            TerminalBackground::Dark
+        );
+    }
+
+    #[test]
+    fn test_parse_colorfgbg() {
+        assert_eq2!(parse_colorfgbg("15;0"), Some(TerminalBackground::Dark));
+        assert_eq2!(parse_colorfgbg("0;15"), Some(TerminalBackground::Light));
+        assert_eq2!(
+            parse_colorfgbg("0;default;7"),
+            Some(TerminalBackground::Light)
+        );
+        assert_eq2!(parse_colorfgbg("15;default"), None);
+    }
+}
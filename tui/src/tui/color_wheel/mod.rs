@@ -19,10 +19,12 @@
 pub mod ansi_256_color_gradients;
 pub mod color_wheel_struct;
 pub mod detect_color_support;
+pub mod detect_terminal_background;
 pub mod styled_text;
 
 // Re-export.
 pub use ansi_256_color_gradients::*;
 pub use color_wheel_struct::*;
 pub use detect_color_support::*;
+pub use detect_terminal_background::*;
 pub use styled_text::*;
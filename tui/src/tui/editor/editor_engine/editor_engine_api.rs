@@ -20,7 +20,6 @@ use std::fmt::Debug;
 use crossterm::style::Stylize;
 use r3bl_rs_utils_core::*;
 use r3bl_rs_utils_macro::style;
-use syntect::easy::HighlightLines;
 
 use super::*;
 use crate::*;
@@ -117,7 +116,7 @@ impl EditorEngineApi {
             editor_engine.current_box = current_box.into();
 
             // Create reusable args for render functions.
-            let render_args = RenderArgs {
+            let mut render_args = RenderArgs {
                 editor_buffer,
                 component_registry,
                 editor_engine,
@@ -128,7 +127,7 @@ impl EditorEngineApi {
             } else {
                 let mut render_ops = render_ops!();
 
-                EditorEngineApi::render_content(&render_args, &mut render_ops);
+                EditorEngineApi::render_content(&mut render_args, &mut render_ops);
                 EditorEngineApi::render_selection(&render_args, &mut render_ops);
                 EditorEngineApi::render_caret(&render_args, &mut render_ops);
 
@@ -140,7 +139,7 @@ impl EditorEngineApi {
     }
 
     fn render_content<S, A>(
-        render_args: &RenderArgs<'_, S, A>,
+        render_args: &mut RenderArgs<'_, S, A>,
         render_ops: &mut RenderOps,
     ) where
         S: Debug + Default + Clone + PartialEq + Sync + Send,
@@ -166,29 +165,30 @@ impl EditorEngineApi {
                 editor_buffer,
                 max_display_row_count,
                 render_ops,
-                editor_engine,
+                &*editor_engine,
                 max_display_col_count,
             );
             return;
         }
 
-        // Render using syntect first.
-        syn_hi_syntect_path::render_content(
-            editor_buffer,
-            max_display_row_count,
-            render_ops,
-            editor_engine,
-            max_display_col_count,
-        );
-
-        // Any overrides can be applied here.
-        syn_hi_r3bl_path::render_content(
+        // Render using the syntax highlighter for the file extension, if there is one.
+        if syn_hi_path::render_content(
             editor_buffer,
             max_display_row_count,
             render_ops,
             editor_engine,
             max_display_col_count,
-        );
+        )
+        .is_err()
+        {
+            no_syn_hi_path::render_content(
+                editor_buffer,
+                max_display_row_count,
+                render_ops,
+                &*editor_engine,
+                max_display_col_count,
+            );
+        }
     }
 
     // BM: Render selection
@@ -389,55 +389,68 @@ where
     NotApplied,
 }
 
-mod syn_hi_r3bl_path {
+mod syn_hi_path {
     use super::*;
 
-    /// Try convert [Vec] of [US] to [MdDocument]:
-    /// - Step 1: Get the lines from the buffer using
-    ///           [editor_buffer.get_lines()](EditorBuffer::get_lines()).
-    /// - Step 2: Convert the lines into a [List] of [StyleUSSpanLine] using
-    ///           [try_parse_and_highlight()]. If this fails then take the path of no
-    ///           syntax highlighting else take the path of syntax highlighting.
-    pub fn render_content(
-        editor_buffer: &&EditorBuffer,
-        max_display_row_count: ChUnit,
-        render_ops: &mut RenderOps,
-        editor_engine: &&mut EditorEngine,
-        max_display_col_count: ChUnit,
-    ) {
-        // Try to parse the Vec<US> into an MDDocument & render it.
-        try_render_content(
-            editor_buffer,
-            max_display_row_count,
-            render_ops,
-            editor_engine,
-            max_display_col_count,
-        )
-        .ok();
-    }
-
     /// Path of syntax highlighting:
-    /// - Step 1: Iterate the `List<StyleUSSpanLine>` from: `ch!(@to_usize
+    /// - Step 1: Get the [SyntaxHighlighter] for the file extension of the buffer from
+    ///           [SyntaxHighlighterHolder::get_mut()]. This is re-used in between renders.
+    /// - Step 2: Highlight the rows from: `ch!(@to_usize
     ///           editor_buffer.get_scroll_offset().row_index)` to: `ch!(@to_usize
-    ///           max_display_row_count)`
-    /// - Step 2: For each, call `StyleUSSpanLine::clip()` which returns a `StyledTexts`
-    /// - Step 3: Render the `StyledTexts` into `render_ops`
-    fn try_render_content(
+    ///           max_display_row_count)`, which returns a `List<StyleUSSpanLine>`.
+    /// - Step 3: For each, call `StyleUSSpanLine::clip()` which returns a `StyledTexts`
+    /// - Step 4: Render the `StyledTexts` into `render_ops`
+    ///
+    /// Returns an error if there is no [SyntaxHighlighter] for the buffer, or if highlighting
+    /// failed. In this case nothing is rendered, so the caller can fall back to rendering w/out
+    /// syntax highlighting.
+    pub fn render_content(
         editor_buffer: &&EditorBuffer,
         max_display_row_count: ChUnit,
         render_ops: &mut RenderOps,
-        editor_engine: &&mut EditorEngine,
+        editor_engine: &mut EditorEngine,
         max_display_col_count: ChUnit,
     ) -> CommonResult<()> {
-        let lines = try_parse_and_highlight(
-            editor_buffer.get_lines(),
-            &editor_engine.current_box.get_computed_style(),
-            Some((&editor_engine.syntax_set, &editor_engine.theme)),
-        )?;
+        let EditorEngine {
+            current_box,
+            syntax_set,
+            theme,
+            syntax_highlighter,
+            ..
+        } = editor_engine;
+
+        let Some(syntax_highlighter) = syntax_highlighter
+            .get_mut(editor_buffer.get_maybe_file_extension(), syntax_set)
+        else {
+            return CommonError::new_err_with_only_type(CommonErrorType::DoesNotApply);
+        };
+
+        // Clip the content to max rows.
+        let start_row_index = ch!(@to_usize editor_buffer.get_scroll_offset().row_index);
+        let row_range =
+            start_row_index..start_row_index + ch!(@to_usize max_display_row_count);
+        let lines = match syntax_highlighter.highlight(SyntaxHighlighterArgs {
+            lines: editor_buffer.get_lines(),
+            row_range,
+            maybe_current_box_computed_style: &current_box.get_computed_style(),
+            syntax_set,
+            theme,
+        }) {
+            Ok(lines) => lines,
+            Err(error) => {
+                call_if_true!(DEBUG_TUI_MOD, {
+                    let msg = format!(
+                        "syn_hi_path::render_content -> highlight failed, falling back to no syntax highlighting: {error:?}"
+                    );
+                    log_error(msg);
+                });
+                return Err(error);
+            }
+        };
 
         call_if_true!(DEBUG_TUI_SYN_HI, {
             log_debug(format!(
-                "\n🎯🎯🎯\neditor_buffer.lines.len(): {} vs md_document.lines.len(): {}\n{}\n{}🎯🎯🎯",
+                "\n🎯🎯🎯\neditor_buffer.lines.len(): {} vs highlighted lines.len(): {}\n{}\n{}🎯🎯🎯",
                 ansi_term::Color::Cyan.paint(editor_buffer.get_lines().len().to_string()),
                 ansi_term::Color::Yellow.paint(lines.len().to_string()),
                 ansi_term::Color::Cyan.paint(editor_buffer.get_as_string()),
@@ -445,16 +458,7 @@ mod syn_hi_r3bl_path {
             ));
         });
 
-        for (row_index, line) in lines
-            .iter()
-            .skip(ch!(@to_usize editor_buffer.get_scroll_offset().row_index))
-            .enumerate()
-        {
-            // Clip the content to max rows.
-            if ch!(row_index) > max_display_row_count {
-                break;
-            }
-
+        for (row_index, line) in lines.iter().enumerate() {
             render_single_line(
                 line,
                 editor_buffer,
@@ -471,7 +475,7 @@ mod syn_hi_r3bl_path {
     fn render_single_line(
         line: &List<StyleUSSpan>,
         editor_buffer: &&EditorBuffer,
-        editor_engine: &&mut EditorEngine,
+        editor_engine: &EditorEngine,
         row_index: usize,
         max_display_col_count: ChUnit,
         render_ops: &mut RenderOps,
@@ -481,6 +485,11 @@ mod syn_hi_r3bl_path {
             position! { col_index: 0 , row_index: ch!(@to_usize row_index) },
         ));
         let scroll_offset_col = editor_buffer.get_scroll_offset().col_index;
+        let maybe_rule_line = line.try_fill_horizontal_rule(
+            &editor_engine.current_box.get_computed_style(),
+            scroll_offset_col + max_display_col_count,
+        );
+        let line = maybe_rule_line.as_ref().unwrap_or(line);
         let styled_texts: StyledTexts =
             line.clip(scroll_offset_col, max_display_col_count);
         styled_texts.render_into(render_ops);
@@ -488,116 +497,6 @@ mod syn_hi_r3bl_path {
     }
 }
 
-mod syn_hi_syntect_path {
-    use super::*;
-
-    pub fn render_content(
-        editor_buffer: &&EditorBuffer,
-        max_display_row_count: ChUnit,
-        render_ops: &mut RenderOps,
-        editor_engine: &&mut EditorEngine,
-        max_display_col_count: ChUnit,
-    ) {
-        // Paint each line in the buffer (skipping the scroll_offset.row).
-        // https://doc.rust-lang.org/std/iter/trait.Iterator.html#method.skip
-        for (row_index, line) in editor_buffer
-            .get_lines()
-            .iter()
-            .skip(ch!(@to_usize editor_buffer.get_scroll_offset().row_index))
-            .enumerate()
-        {
-            // Clip the content to max rows.
-            if ch!(row_index) > max_display_row_count {
-                break;
-            }
-
-            render_single_line(
-                render_ops,
-                row_index,
-                editor_engine,
-                editor_buffer,
-                line,
-                max_display_col_count,
-            );
-        }
-    }
-
-    fn render_single_line(
-        render_ops: &mut RenderOps,
-        row_index: usize,
-        editor_engine: &&mut EditorEngine,
-        editor_buffer: &&EditorBuffer,
-        line: &UnicodeString,
-        max_display_col_count: ChUnit,
-    ) {
-        render_ops.push(RenderOp::MoveCursorPositionRelTo(
-            editor_engine.current_box.style_adjusted_origin_pos,
-            position! { col_index: 0 , row_index: ch!(@to_usize row_index) },
-        ));
-
-        let it =
-            try_get_syntect_highlighted_line(editor_engine, editor_buffer, &line.string);
-
-        match it {
-            // If enabled, and we have a SyntaxReference then try and highlight the line.
-            Some(syntect_highlighted_line) => {
-                render_line_with_syntect(
-                    syntect_highlighted_line,
-                    editor_buffer,
-                    max_display_col_count,
-                    render_ops,
-                );
-            }
-            // Otherwise, fallback.
-            None => {
-                no_syn_hi_path::render_line_no_syntax_highlight(
-                    line,
-                    editor_buffer,
-                    max_display_col_count,
-                    render_ops,
-                    editor_engine,
-                );
-            }
-        }
-    }
-
-    fn render_line_with_syntect(
-        syntect_highlighted_line: Vec<(syntect::highlighting::Style, &str)>,
-        editor_buffer: &&EditorBuffer,
-        max_display_col_count: ChUnit,
-        render_ops: &mut RenderOps,
-    ) {
-        let scroll_offset_col = editor_buffer.get_scroll_offset().col_index;
-        let list: List<StyleUSSpan> =
-            syntect_to_styled_text_conversion::from_syntect_to_tui(
-                syntect_highlighted_line,
-            );
-        let styled_texts: StyledTexts =
-            list.clip(scroll_offset_col, max_display_col_count);
-        styled_texts.render_into(render_ops);
-        render_ops.push(RenderOp::ResetColor);
-    }
-
-    /// Try and load syntax highlighting for the current line. It might seem lossy to
-    /// create a new [HighlightLines] for each line, but if this struct is re-used then it
-    /// will not be able to highlight the lines correctly in the editor component. This
-    /// struct is mutated when it is used to highlight a line, so it must be re-created
-    /// for each line.
-    fn try_get_syntect_highlighted_line<'a>(
-        editor_engine: &'a &mut EditorEngine,
-        editor_buffer: &&EditorBuffer,
-        line: &'a str,
-    ) -> Option<Vec<(syntect::highlighting::Style, &'a str)>> {
-        let file_ext = editor_buffer.get_maybe_file_extension()?;
-        let syntax_ref = try_get_syntax_ref(&editor_engine.syntax_set, file_ext)?;
-        let theme = &editor_engine.theme;
-        let mut highlighter = HighlightLines::new(syntax_ref, theme);
-        highlighter
-            .highlight_line(line, &editor_engine.syntax_set)
-            .ok()
-    }
-}
-
 mod no_syn_hi_path {
     use super::*;
 
@@ -617,7 +516,7 @@ mod no_syn_hi_path {
             .enumerate()
         {
             // Clip the content to max rows.
-            if ch!(row_index) > max_display_row_count {
+            if ch!(row_index) >= max_display_row_count {
                 break;
             }
 
@@ -15,11 +15,12 @@
  *   limitations under the License.
  */
 
-use std::fmt::Debug;
+use std::{collections::BTreeMap, fmt::Debug, path::Path};
 
 use r3bl_rs_utils_core::*;
 use serde::*;
-use syntect::{highlighting::Theme, parsing::SyntaxSet};
+use syntect::{highlighting::{Theme, ThemeSet},
+              parsing::SyntaxSet};
 
 use crate::*;
 
@@ -44,6 +45,15 @@ pub struct EditorEngine {
     pub syntax_set: SyntaxSet,
     /// Syntax highlighting support. This is a very heavy object to create, re-use it.
     pub theme: Theme,
+    /// Themes that were loaded by [load_themes_from_dir()](EditorEngine::load_themes_from_dir),
+    /// keyed by name. Use [set_theme_by_name()](EditorEngine::set_theme_by_name) to switch to one.
+    #[serde(skip)]
+    pub loaded_themes: BTreeMap<String, Theme>,
+    /// The [SyntaxHighlighter] for the file extension of the [EditorBuffer]. It is re-used in
+    /// between render calls, so that only the lines that are touched by an edit are highlighted
+    /// again.
+    #[serde(skip)]
+    pub syntax_highlighter: SyntaxHighlighterHolder,
 }
 
 impl Default for EditorEngine {
@@ -59,9 +69,74 @@ impl EditorEngine {
             config_options,
             syntax_set: SyntaxSet::load_defaults_newlines(),
             theme: try_load_r3bl_theme().unwrap_or_else(|_| load_default_theme()),
+            loaded_themes: Default::default(),
+            syntax_highlighter: Default::default(),
         }
     }
 
+    /// Load the `.tmTheme` files in `dir`, so that they can be used w/
+    /// [set_theme_by_name()](EditorEngine::set_theme_by_name). Files that are malformed are
+    /// skipped, and reported in the returned [SyntectAssetsLoadReport].
+    pub fn load_themes_from_dir(
+        &mut self,
+        dir: impl AsRef<Path>,
+    ) -> CommonResult<SyntectAssetsLoadReport> {
+        let (themes, report) = try_load_themes_from_dir(dir)?;
+        self.loaded_themes.extend(themes);
+        Ok(report)
+    }
+
+    /// Replace the [SyntaxSet] w/ the default syntaxes plus the `.sublime-syntax` files in `dir`.
+    /// The compiled [SyntaxSet] is cached in `maybe_cache_file` (if provided), which makes the
+    /// next startup fast. Files that are malformed are skipped, and reported in the returned
+    /// [SyntectAssetsLoadReport].
+    pub fn load_syntaxes_from_dir(
+        &mut self,
+        dir: impl AsRef<Path>,
+        maybe_cache_file: Option<&Path>,
+    ) -> CommonResult<SyntectAssetsLoadReport> {
+        let (syntax_set, report) = try_load_syntax_set_from_dir(dir, maybe_cache_file)?;
+        self.syntax_set = syntax_set;
+        // The syntax highlighter for a file extension depends on the syntaxes that are available.
+        self.syntax_highlighter = Default::default();
+        Ok(report)
+    }
+
+    /// The names of all the themes that can be passed to
+    /// [set_theme_by_name()](EditorEngine::set_theme_by_name).
+    pub fn get_theme_names(&self) -> Vec<String> {
+        let mut acc = self.loaded_themes.keys().cloned().collect::<Vec<_>>();
+        acc.push(R3BL_THEME_NAME.to_string());
+        acc.extend(ThemeSet::load_defaults().themes.into_keys());
+        acc
+    }
+
+    /// Switch the active theme. Anything that was highlighted w/ the old theme is discarded.
+    pub fn set_theme(&mut self, theme: Theme) {
+        self.theme = theme;
+        self.syntax_highlighter.clear();
+    }
+
+    /// Switch the active theme to one of [get_theme_names()](EditorEngine::get_theme_names).
+    /// Themes loaded from a directory take precedence over the built-in ones.
+    pub fn set_theme_by_name(&mut self, name: &str) -> CommonResult<()> {
+        let theme = match self.loaded_themes.get(name) {
+            Some(theme) => theme.clone(),
+            None if name == R3BL_THEME_NAME => try_load_r3bl_theme()?,
+            None => match ThemeSet::load_defaults().themes.remove(name) {
+                Some(theme) => theme,
+                None => {
+                    return CommonError::new(
+                        CommonErrorType::InvalidArguments,
+                        &format!("Unknown theme: {name}"),
+                    );
+                }
+            },
+        };
+        self.set_theme(theme);
+        Ok(())
+    }
+
     pub fn viewport_width(&self) -> ChUnit {
         self.current_box.style_adjusted_bounds_size.col_count
     }
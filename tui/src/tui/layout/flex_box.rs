@@ -82,6 +82,26 @@ mod flex_box_impl {
 
     impl FlexBox {
         pub fn get_computed_style(&self) -> Option<Style> { self.maybe_computed_style }
+
+        /// Returns the [RenderOp] that paints the border that is requested by the computed
+        /// style (w/ its colors), around the edges of this box. Returns `None` if the style
+        /// doesn't have a border.
+        pub fn render_border(
+            &self,
+            maybe_title: Option<BorderTitle>,
+        ) -> Option<RenderOp> {
+            let style = self.maybe_computed_style?;
+            let border_style = style.border?;
+            Some(RenderOp::PaintBorder(
+                self.origin_pos,
+                self.bounds_size,
+                Border {
+                    border_style,
+                    maybe_title,
+                    maybe_style: Some(style),
+                },
+            ))
+        }
     }
 
     impl Debug for FlexBox {
@@ -319,7 +319,7 @@ fn make_root_box_with_style(
     }
 }
 
-/// Adjust `origin` & `bounds_size` based on the `maybe_style`'s padding.
+/// Adjust `origin` & `bounds_size` based on the `maybe_style`'s border & padding.
 fn adjust_with_style(
     maybe_computed_style: &Option<Style>,
     origin_pos: Position,
@@ -329,6 +329,10 @@ fn adjust_with_style(
     let mut style_adjusted_bounds_size = bounds_size;
 
     if let Some(ref style) = maybe_computed_style {
+        if style.border.is_some() {
+            style_adjusted_origin_pos += ch!(1);
+            style_adjusted_bounds_size -= ch!(2);
+        };
         if let Some(padding) = style.padding {
             style_adjusted_origin_pos += padding;
             style_adjusted_bounds_size -= padding * 2;
@@ -341,7 +345,9 @@ fn adjust_with_style(
 fn cascade_styles(parent_box: &FlexBox, self_box_props: &FlexBoxProps) -> Option<Style> {
     let mut style_vec: Vec<Style> = vec![];
 
-    if let Some(parent_style) = parent_box.get_computed_style() {
+    // The border is painted around the parent box, so it isn't inherited (unlike padding).
+    if let Some(mut parent_style) = parent_box.get_computed_style() {
+        parent_style.border = None;
         style_vec.push(parent_style);
     };
 
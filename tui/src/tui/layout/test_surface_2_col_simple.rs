@@ -162,12 +162,24 @@ mod tests {
 
                 assert_eq2!(
                     current_box.style_adjusted_origin_pos,
-                    position!(col_index: 253, row_index: 3)
-                ); // Take padding into account.
+                    position!(col_index: 254, row_index: 4)
+                ); // Take border & padding into account.
                 assert_eq2!(
                     current_box.style_adjusted_bounds_size,
-                    size!(col_count:244, row_count:494)
-                ); // Take padding into account.
+                    size!(col_count:242, row_count:492)
+                ); // Take border & padding into account.
+                assert_eq2!(
+                    current_box.render_border(None),
+                    Some(RenderOp::PaintBorder(
+                        position!(col_index: 250, row_index: 0),
+                        size!(col_count:250, row_count:500),
+                        Border {
+                            border_style: BorderStyle::Rounded,
+                            maybe_title: None,
+                            maybe_style: current_box.get_computed_style(),
+                        }
+                    ))
+                );
 
                 assert_eq2!(
                     current_box.requested_size_percent,
@@ -197,6 +209,7 @@ mod tests {
                 id: 2
                 attrib: [underline, strikethrough]
                 padding: 3
+                border: BorderStyle::Rounded
                 color_fg: TuiColor::Rgb (RgbValue{ red: 0, green: 0, blue: 0 }) /* Black. */
                 color_bg: TuiColor::Rgb (RgbValue{ red: 255, green: 255, blue: 255 }) /* White. */
               }
@@ -0,0 +1,80 @@
+/*
+ *   Copyright (c) 2023 R3BL LLC
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+use constants::*;
+use nom::{branch::*,
+          bytes::complete::*,
+          character::complete::*,
+          combinator::*,
+          sequence::*,
+          IResult};
+
+use crate::*;
+
+/// Matches a footnote definition and consumes the newline (if any). The content is a single line
+/// of markdown text. The [MdLineFragment::FootnoteReference]s w/ the same label refer to it.
+///
+/// Sample inputs:
+/// - "[^1]: Some text.\n"
+/// - "[^note]: Some *formatted* text w/ a [link](https://r3bl.com)."
+#[rustfmt::skip]
+pub fn parse_block_footnote_definition(input: &str) -> IResult<&str, FootnoteDefinitionData> {
+    let (input, (label, content)) = terminated(
+        pair(
+            /* label */ terminated(parse_element_footnote_reference, tag(COLON)),
+            /* content */ preceded(space0, parse_block_markdown_text_opt_eol),
+        ),
+        /* ends with (discarded) */ alt((tag(NEW_LINE), eof)),
+    )(input)?;
+    Ok((input, FootnoteDefinitionData { label, content }))
+}
+
+#[cfg(test)]
+mod tests {
+    use r3bl_rs_utils_core::assert_eq2;
+
+    use super::*;
+
+    #[test]
+    fn test_parse_block_footnote_definition() {
+        assert_eq2!(
+            parse_block_footnote_definition("[^1]: Some *text*.\nfoo"),
+            Ok((
+                "foo",
+                FootnoteDefinitionData {
+                    label: "1",
+                    content: list![
+                        MdLineFragment::Plain("Some "),
+                        MdLineFragment::Italic(list![MdLineFragment::Plain("text")]),
+                        MdLineFragment::Plain("."),
+                    ],
+                }
+            ))
+        );
+        assert_eq2!(
+            parse_block_footnote_definition("[^note]:"),
+            Ok((
+                "",
+                FootnoteDefinitionData {
+                    label: "note",
+                    content: list![],
+                }
+            ))
+        );
+        assert!(parse_block_footnote_definition("[^1] no colon").is_err());
+        assert!(parse_block_footnote_definition("[1]: https://r3bl.com").is_err());
+    }
+}
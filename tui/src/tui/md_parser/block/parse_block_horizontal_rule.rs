@@ -0,0 +1,86 @@
+/*
+ *   Copyright (c) 2023 R3BL LLC
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+use constants::*;
+use nom::{bytes::complete::*, combinator::*, sequence::*, IResult};
+
+use crate::*;
+
+/// Matches a thematic break (aka horizontal rule) and consumes the newline (if any). A line is a
+/// thematic break if it contains 3 or more of the same character (`-`, `*`, or `_`), and nothing
+/// else except for spaces.
+///
+/// Sample inputs:
+/// - "---\n"
+/// - "***\n"
+/// - "___\n"
+/// - "- - -\n"
+/// - "*****\n"
+#[rustfmt::skip]
+pub fn parse_block_horizontal_rule(input: &str) -> IResult<&str, ()> {
+    map(
+        terminated(
+            /* output */ verify(is_not(NEW_LINE), is_horizontal_rule),
+            /* ends with (discarded) */ opt(tag(NEW_LINE)),
+        ),
+        |_| (),
+    )(input)
+}
+
+fn is_horizontal_rule(line: &str) -> bool {
+    let mut chars = line.chars().filter(|it| *it != SPACE_CHAR);
+    let Some(first_char) = chars.next() else {
+        return false;
+    };
+    if !HORIZONTAL_RULE_CHARS.contains(&first_char) {
+        return false;
+    }
+    let mut count = 1;
+    for it in chars {
+        if it != first_char {
+            return false;
+        }
+        count += 1;
+    }
+    count >= HORIZONTAL_RULE_MIN_CHAR_COUNT
+}
+
+#[cfg(test)]
+mod tests {
+    use r3bl_rs_utils_core::assert_eq2;
+
+    use super::*;
+
+    #[test]
+    fn test_parse_block_horizontal_rule() {
+        assert_eq2!(parse_block_horizontal_rule("---\n"), Ok(("", ())));
+        assert_eq2!(parse_block_horizontal_rule("***"), Ok(("", ())));
+        assert_eq2!(parse_block_horizontal_rule("___\nfoo"), Ok(("foo", ())));
+        assert_eq2!(parse_block_horizontal_rule("- - -\n"), Ok(("", ())));
+        assert_eq2!(parse_block_horizontal_rule("  *****  \n"), Ok(("", ())));
+    }
+
+    #[test]
+    fn test_parse_block_horizontal_rule_invalid() {
+        assert!(parse_block_horizontal_rule("--\n").is_err());
+        assert!(parse_block_horizontal_rule("-*-\n").is_err());
+        assert!(parse_block_horizontal_rule("--- foo\n").is_err());
+        assert!(parse_block_horizontal_rule("***bold***\n").is_err());
+        assert!(parse_block_horizontal_rule("\n").is_err());
+        assert!(parse_block_horizontal_rule("").is_err());
+    }
+}
@@ -0,0 +1,102 @@
+/*
+ *   Copyright (c) 2023 R3BL LLC
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+use constants::*;
+use nom::{branch::*,
+          bytes::complete::*,
+          character::complete::*,
+          combinator::*,
+          sequence::*,
+          IResult};
+
+use crate::*;
+
+/// Matches a link reference definition and consumes the newline (if any). Each
+/// [MdLineFragment::ReferenceLink] in the document gets its url from the definition w/ the same
+/// reference (see [resolve_reference_links]). The definition itself is not rendered.
+///
+/// Sample inputs:
+/// - "[r3bl]: https://r3bl.com\n"
+/// - "[r3bl]: https://r3bl.com \"R3BL website\"\n"
+#[rustfmt::skip]
+pub fn parse_block_link_definition(input: &str) -> IResult<&str, LinkDefinitionData> {
+    let (input, (reference, url, title)) = terminated(
+        tuple((
+            /* reference */ delimited(
+                /* start */ tag(LEFT_BRACKET),
+                /* output */ verify(is_not(LINK_DEFINITION_REFERENCE_STOP_CHARS), |it: &str| !it.trim().is_empty()),
+                /* end */ tag(LINK_DEFINITION_SEPARATOR),
+            ),
+            /* url */ preceded(space0, is_not(LINK_DEFINITION_URL_STOP_CHARS)),
+            /* title */ opt(preceded(
+                space1,
+                delimited(
+                    /* start */ tag(QUOTE),
+                    /* output */ is_not(LINK_DEFINITION_TITLE_STOP_CHARS),
+                    /* end */ tag(QUOTE),
+                ),
+            )),
+        )),
+        /* ends with (discarded) */ pair(space0, alt((tag(NEW_LINE), eof))),
+    )(input)?;
+    Ok((input, LinkDefinitionData { reference, url, title }))
+}
+
+#[cfg(test)]
+mod tests {
+    use r3bl_rs_utils_core::assert_eq2;
+
+    use super::*;
+
+    #[test]
+    fn test_parse_block_link_definition() {
+        assert_eq2!(
+            parse_block_link_definition("[r3bl]: https://r3bl.com\nfoo"),
+            Ok((
+                "foo",
+                LinkDefinitionData {
+                    reference: "r3bl",
+                    url: "https://r3bl.com",
+                    title: None,
+                }
+            ))
+        );
+        assert_eq2!(
+            parse_block_link_definition(
+                "[R3BL Site]:https://r3bl.com \"R3BL website\"  "
+            ),
+            Ok((
+                "",
+                LinkDefinitionData {
+                    reference: "R3BL Site",
+                    url: "https://r3bl.com",
+                    title: Some("R3BL website"),
+                }
+            ))
+        );
+    }
+
+    #[test]
+    fn test_parse_block_link_definition_invalid() {
+        assert!(
+            parse_block_link_definition("[r3bl]: https://r3bl.com is a site\n").is_err()
+        );
+        assert!(parse_block_link_definition("[r3bl](https://r3bl.com)\n").is_err());
+        assert!(parse_block_link_definition("[r3bl]:\n").is_err());
+        assert!(parse_block_link_definition("[]: https://r3bl.com\n").is_err());
+        assert!(parse_block_link_definition("[ ]: https://r3bl.com\n").is_err());
+    }
+}
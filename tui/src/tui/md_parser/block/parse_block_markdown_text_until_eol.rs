@@ -62,6 +62,23 @@ pub fn parse_block_markdown_text_opt_eol(
     parse_opt_eol(input, CheckboxParsePolicy::IgnoreCheckbox)
 }
 
+/// Parse a markdown text [FragmentsInOneLine] in the input (no EOL required). Unlike
+/// [parse_block_markdown_text_opt_eol], this never fails. Any text that can't be parsed into a
+/// [MdLineFragment] (eg: an unclosed `*`) is kept as [MdLineFragment::Plain], so that no content is
+/// lost. This is meant for text that has already been isolated by a block parser, like a table
+/// cell or the content of a block quote line.
+pub fn parse_block_markdown_text_lenient(input: &str) -> MdLineFragments {
+    match parse_opt_eol(input, CheckboxParsePolicy::IgnoreCheckbox) {
+        Ok((remainder, mut fragments)) => {
+            if !remainder.is_empty() {
+                fragments.push(MdLineFragment::Plain(remainder));
+            }
+            fragments
+        }
+        Err(_) => list![MdLineFragment::Plain(input)],
+    }
+}
+
 #[rustfmt::skip]
 fn parse_opt_eol(
     input: &str,
@@ -89,8 +106,11 @@ mod test {
                 "here is some plaintext *but what if we italicize?"
             ),
             Ok((
-                "*but what if we italicize?",
-                list![MdLineFragment::Plain("here is some plaintext "),]
+                "",
+                list![
+                    MdLineFragment::Plain("here is some plaintext "),
+                    MdLineFragment::Plain("*but what if we italicize?"),
+                ]
             ))
         );
     }
@@ -110,7 +130,9 @@ mod test {
                 "",
                 list![
                     MdLineFragment::Plain("here is some plaintext "),
-                    MdLineFragment::Italic("but what if we italicize?"),
+                    MdLineFragment::Italic(list![MdLineFragment::Plain(
+                        "but what if we italicize?"
+                    )]),
                 ]
             ))
         );
@@ -120,9 +142,9 @@ mod test {
                 ("",
                 list![
                     MdLineFragment::Plain("here is some plaintext "),
-                    MdLineFragment::Italic("but what if we italicize?"),
+                    MdLineFragment::Italic(list![MdLineFragment::Plain("but what if we italicize?")]),
                     MdLineFragment::Plain(" I guess it doesn't "),
-                    MdLineFragment::Bold("matter"),
+                    MdLineFragment::Bold(list![MdLineFragment::Plain("matter")]),
                     MdLineFragment::Plain(" in my "),
                     MdLineFragment::InlineCode("code"),
                 ])
@@ -136,7 +158,9 @@ mod test {
                 "",
                 list![
                     MdLineFragment::Plain("here is some plaintext "),
-                    MdLineFragment::Italic("but what if we italicize?"),
+                    MdLineFragment::Italic(list![MdLineFragment::Plain(
+                        "but what if we italicize?"
+                    )]),
                 ]
             ))
         );
@@ -0,0 +1,148 @@
+/*
+ *   Copyright (c) 2023 R3BL LLC
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+use constants::*;
+use nom::{bytes::complete::*,
+          character::complete::*,
+          combinator::*,
+          multi::*,
+          sequence::*,
+          IResult};
+
+use crate::*;
+
+/// Sample inputs:
+/// One line:     "> foo\n"
+/// Multi line:   "> foo\n> bar\n"
+/// Nested:       "> foo\n> > bar\n>> baz\n"
+/// Empty line:   ">\n"
+///
+/// All the consecutive lines that start w/ `>` are gathered into a single block. Each line keeps
+/// its own depth, so nested quotes can start and end anywhere inside the block.
+#[rustfmt::skip]
+pub fn parse_block_quote(input: &str) -> IResult<&str, BlockQuoteLines> {
+    let (remainder, lines) = many1(parse_block_quote_line_opt_eol)(input)?;
+    Ok((remainder, List::from(lines)))
+}
+
+#[rustfmt::skip]
+fn parse_block_quote_line_opt_eol(input: &str) -> IResult<&str, BlockQuoteLine> {
+    let (remainder, (depth, content)) = terminated(
+        pair(
+            /* depth */ parse_block_quote_markers,
+            /* content */ opt(is_not(NEW_LINE)),
+        ),
+        /* ends with (discarded) */ opt(tag(NEW_LINE)),
+    )(input)?;
+
+    let content = match content {
+        Some(content) => parse_block_markdown_text_lenient(content),
+        None => list![],
+    };
+
+    Ok((remainder, BlockQuoteLine { depth, content }))
+}
+
+/// Matches one or more `>` chars (each of which can be followed by one space), consumes them, and
+/// outputs how many `>` chars were found.
+#[rustfmt::skip]
+fn parse_block_quote_markers(input: &str) -> IResult<&str, usize> {
+    map(
+        many1(
+            terminated(
+                /* output */ char(BLOCK_QUOTE_CHAR),
+                /* ends with (discarded) */ opt(char(SPACE_CHAR)),
+            )
+        ),
+        |it| it.len(),
+    )(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use r3bl_rs_utils_core::assert_eq2;
+
+    use super::*;
+
+    #[test]
+    fn test_parse_block_quote_markers() {
+        assert_eq2!(parse_block_quote_markers("> foo"), Ok(("foo", 1)));
+        assert_eq2!(parse_block_quote_markers(">foo"), Ok(("foo", 1)));
+        assert_eq2!(parse_block_quote_markers("> > foo"), Ok(("foo", 2)));
+        assert_eq2!(parse_block_quote_markers(">>> foo"), Ok(("foo", 3)));
+        assert!(parse_block_quote_markers("foo").is_err());
+    }
+
+    #[test]
+    fn test_parse_block_quote_single_line() {
+        assert_eq2!(
+            parse_block_quote("> here is *a* quote\nafter"),
+            Ok((
+                "after",
+                list![BlockQuoteLine {
+                    depth: 1,
+                    content: list![
+                        MdLineFragment::Plain("here is "),
+                        MdLineFragment::Italic(list![MdLineFragment::Plain("a")]),
+                        MdLineFragment::Plain(" quote"),
+                    ]
+                }]
+            ))
+        );
+    }
+
+    #[test]
+    fn test_parse_block_quote_nested() {
+        let input = ["> outer", ">", "> > **inner**", ">> inner2", "> *open"].join("\n");
+        assert_eq2!(
+            parse_block_quote(&input),
+            Ok((
+                "",
+                list![
+                    BlockQuoteLine {
+                        depth: 1,
+                        content: list![MdLineFragment::Plain("outer")]
+                    },
+                    BlockQuoteLine {
+                        depth: 1,
+                        content: list![]
+                    },
+                    BlockQuoteLine {
+                        depth: 2,
+                        content: list![MdLineFragment::Bold(list![
+                            MdLineFragment::Plain("inner")
+                        ])]
+                    },
+                    BlockQuoteLine {
+                        depth: 2,
+                        content: list![MdLineFragment::Plain("inner2")]
+                    },
+                    BlockQuoteLine {
+                        depth: 1,
+                        content: list![MdLineFragment::Plain("*open")]
+                    },
+                ]
+            ))
+        );
+    }
+
+    #[test]
+    fn test_parse_block_quote_invalid() {
+        assert!(parse_block_quote("not a quote\n").is_err());
+        assert!(parse_block_quote("").is_err());
+    }
+}
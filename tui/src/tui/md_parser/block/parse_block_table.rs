@@ -0,0 +1,303 @@
+/*
+ *   Copyright (c) 2023 R3BL LLC
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+use constants::*;
+use nom::{branch::*, bytes::complete::*, combinator::*, multi::*, sequence::*, IResult};
+
+use crate::*;
+
+/// Sample input:
+///
+/// ```text
+/// | Name  | Qty |
+/// | :---- | --: |
+/// | apple |   3 |
+/// | pear  |  10 |
+/// ```
+///
+/// 1. The first line is the header row.
+/// 2. The second line is the delimiter row, which holds the [TableColumnAlignment] for each column.
+///    Just like GFM, nothing is a table w/out it. It must have the same number of cells as the
+///    header row too.
+/// 3. All the lines after that (until a line w/out a cell separator is found) are body rows. Body
+///    rows w/ too few cells are padded w/ empty cells, and extra cells are dropped.
+///
+/// A `|` that is escaped (`\|`) or inside inline code is part of the text of a cell, and it is not
+/// a cell separator.
+#[rustfmt::skip]
+pub fn parse_block_table(input: &str) -> IResult<&str, TableData> {
+    let (remainder, (header_line, alignments, body_lines)) = tuple((
+        parse_table_line_opt_eol,
+        map_opt(parse_table_line_opt_eol, parse_table_delimiter_row),
+        many0(parse_table_line_opt_eol),
+    ))(input)?;
+
+    let header_cells = split_table_row_into_cells(header_line);
+    if alignments.len() != header_cells.len() {
+        return Err(nom::Err::Error(nom::error::Error::new(
+            "Table delimiter row must have the same number of cells as the header row",
+            nom::error::ErrorKind::Fail,
+        )));
+    }
+
+    let column_count = alignments.len();
+    let header = convert_into_table_row(header_cells, column_count);
+    let mut rows = List::with_capacity(body_lines.len());
+    for body_line in body_lines {
+        rows.push(convert_into_table_row(
+            split_table_row_into_cells(body_line),
+            column_count,
+        ));
+    }
+
+    Ok((remainder, TableData { header, alignments, rows }))
+}
+
+/// Matches a single line that contains at least one cell separator, and consumes the newline (if
+/// any).
+#[rustfmt::skip]
+fn parse_table_line_opt_eol(input: &str) -> IResult<&str, &str> {
+    terminated(
+        /* output */ verify(is_not(NEW_LINE), |line: &str| {
+            !find_table_cell_separators(line).is_empty()
+        }),
+        /* ends with (discarded) */ opt(tag(NEW_LINE)),
+    )(input)
+}
+
+/// Split a line of a table into its cells. The optional leading and trailing `|` are removed and
+/// each cell is trimmed. See [find_table_cell_separators] for the `|` chars that are not cell
+/// separators.
+///
+/// ## Examples:
+/// | input              | output            |
+/// | ------------------ | ----------------- |
+/// | `"\| a \| b \|"`   | `["a", "b"]`      |
+/// | `"a \| b"`         | `["a", "b"]`      |
+/// | `"\| a \|\|"`      | `["a", ""]`       |
+/// | `"\| a \\\| b \|"` | `["a \\\| b"]`    |
+pub fn split_table_row_into_cells(line: &str) -> Vec<&str> {
+    let line = line.trim();
+    let separators = find_table_cell_separators(line);
+
+    let mut acc = Vec::with_capacity(separators.len() + 1);
+    let mut cell_start = 0;
+    for separator in &separators {
+        acc.push(line[cell_start..*separator].trim());
+        cell_start = separator + TABLE_PIPE.len();
+    }
+    acc.push(line[cell_start..].trim());
+
+    let maybe_last_char_index = line.len().checked_sub(TABLE_PIPE.len());
+    if maybe_last_char_index.is_some()
+        && separators.last().copied() == maybe_last_char_index
+    {
+        acc.pop();
+    }
+    if separators.first() == Some(&0) {
+        acc.remove(0);
+    }
+    acc
+}
+
+/// Returns the byte indices of the `|` chars in `line` that separate its cells. Just like GFM, an
+/// escaped `\|`, or a `|` inside inline code (eg: `` `a|b` ``) is part of the text of a cell.
+pub fn find_table_cell_separators(line: &str) -> Vec<usize> {
+    let mut acc = vec![];
+    let mut rest = line;
+    while let Some(first_char) = rest.chars().next() {
+        let skip_len = match alt((parse_element_escape, parse_element_code))(rest) {
+            Ok((remainder, _)) => rest.len() - remainder.len(),
+            Err(_) => {
+                if first_char == TABLE_PIPE_CHAR {
+                    acc.push(line.len() - rest.len());
+                }
+                first_char.len_utf8()
+            }
+        };
+        rest = &rest[skip_len..];
+    }
+    acc
+}
+
+/// Returns [None] if any of the cells in the delimiter row is not of the form `:?-+:?`.
+fn parse_table_delimiter_row(line: &str) -> Option<List<TableColumnAlignment>> {
+    let mut acc = List::new();
+    for cell in split_table_row_into_cells(line) {
+        let starts_with_colon = cell.starts_with(TABLE_ALIGN_COLON_CHAR);
+        let ends_with_colon = cell.len() > 1 && cell.ends_with(TABLE_ALIGN_COLON_CHAR);
+        let dashes = cell.trim_matches(TABLE_ALIGN_COLON_CHAR);
+        if dashes.is_empty() || !dashes.chars().all(|it| it == TABLE_ALIGN_DASH_CHAR) {
+            return None;
+        }
+        acc.push(match (starts_with_colon, ends_with_colon) {
+            (true, true) => TableColumnAlignment::Center,
+            (true, false) => TableColumnAlignment::Left,
+            (false, true) => TableColumnAlignment::Right,
+            (false, false) => TableColumnAlignment::Default,
+        });
+    }
+    Some(acc)
+}
+
+/// Parse each cell as markdown text, and make sure that there are exactly `column_count` cells.
+fn convert_into_table_row(cells: Vec<&str>, column_count: usize) -> TableRow {
+    let mut acc = List::with_capacity(column_count);
+    for cell in cells.into_iter().take(column_count) {
+        acc.push(parse_block_markdown_text_lenient(cell));
+    }
+    while acc.len() < column_count {
+        acc.push(list![]);
+    }
+    acc
+}
+
+#[cfg(test)]
+mod tests {
+    use r3bl_rs_utils_core::assert_eq2;
+
+    use super::*;
+
+    #[test]
+    fn test_split_table_row_into_cells() {
+        assert_eq2!(split_table_row_into_cells("| a | b |"), vec!["a", "b"]);
+        assert_eq2!(split_table_row_into_cells("a | b"), vec!["a", "b"]);
+        assert_eq2!(split_table_row_into_cells("  | a ||  "), vec!["a", ""]);
+        assert_eq2!(split_table_row_into_cells("|😃|"), vec!["😃"]);
+        assert_eq2!(split_table_row_into_cells("|"), Vec::<&str>::new());
+        assert_eq2!(split_table_row_into_cells(""), vec![""]);
+        assert_eq2!(split_table_row_into_cells("   "), vec![""]);
+    }
+
+    #[test]
+    fn test_split_table_row_into_cells_w_literal_pipes() {
+        assert_eq2!(
+            split_table_row_into_cells("| `a|b` | c |"),
+            vec!["`a|b`", "c"]
+        );
+        assert_eq2!(
+            split_table_row_into_cells("| a \\| b | c |"),
+            vec!["a \\| b", "c"]
+        );
+        assert_eq2!(split_table_row_into_cells("a \\|"), vec!["a \\|"]);
+        // An unclosed back tick doesn't start inline code.
+        assert_eq2!(split_table_row_into_cells("| `a | b |"), vec!["`a", "b"]);
+        // An escaped backslash doesn't escape the `|` after it.
+        assert_eq2!(split_table_row_into_cells("a \\\\| b"), vec!["a \\\\", "b"]);
+    }
+
+    #[test]
+    fn test_parse_table_delimiter_row() {
+        assert_eq2!(
+            parse_table_delimiter_row("| --- | :-- | :-: | --: |"),
+            Some(list![
+                TableColumnAlignment::Default,
+                TableColumnAlignment::Left,
+                TableColumnAlignment::Center,
+                TableColumnAlignment::Right,
+            ])
+        );
+        assert_eq2!(parse_table_delimiter_row("| - | :: |"), None);
+        assert_eq2!(parse_table_delimiter_row("| a | --- |"), None);
+    }
+
+    #[test]
+    fn test_parse_block_table() {
+        let input = [
+            "| Name | *Qty* |",
+            "| :--- | ----: |",
+            "| apple | 3 |",
+            "| pear |",
+            "| fig | 1 | extra |",
+            "after",
+        ]
+        .join("\n");
+
+        let (remainder, table) = parse_block_table(&input).unwrap();
+        assert_eq2!(remainder, "after");
+        assert_eq2!(
+            table,
+            TableData {
+                header: list![
+                    list![MdLineFragment::Plain("Name")],
+                    list![MdLineFragment::Italic(list![MdLineFragment::Plain("Qty")])],
+                ],
+                alignments: list![
+                    TableColumnAlignment::Left,
+                    TableColumnAlignment::Right
+                ],
+                rows: list![
+                    list![
+                        list![MdLineFragment::Plain("apple")],
+                        list![MdLineFragment::Plain("3")],
+                    ],
+                    list![list![MdLineFragment::Plain("pear")], list![]],
+                    list![
+                        list![MdLineFragment::Plain("fig")],
+                        list![MdLineFragment::Plain("1")],
+                    ],
+                ],
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_block_table_unclosed_formatting_in_cell() {
+        let input = "| *a |\n| - |\n";
+        let (remainder, table) = parse_block_table(input).unwrap();
+        assert_eq2!(remainder, "");
+        assert_eq2!(table.header, list![list![MdLineFragment::Plain("*a")]]);
+        assert_eq2!(table.rows.len(), 0);
+    }
+
+    #[test]
+    fn test_parse_block_table_w_literal_pipes() {
+        let input = "| `a|b` | a \\| b |\n| --- | --- |\n| c | d |\n";
+        let (remainder, table) = parse_block_table(input).unwrap();
+        assert_eq2!(remainder, "");
+        assert_eq2!(
+            table.header,
+            list![
+                list![MdLineFragment::InlineCode("a|b")],
+                list![
+                    MdLineFragment::Plain("a "),
+                    MdLineFragment::Escaped("|"),
+                    MdLineFragment::Plain(" b"),
+                ],
+            ]
+        );
+        assert_eq2!(table.rows.len(), 1);
+
+        // Lines whose only `|` chars are literal are not rows.
+        let input = "| a |\n| - |\n`a|b`\na \\| b\n";
+        let (remainder, table) = parse_block_table(input).unwrap();
+        assert_eq2!(remainder, "`a|b`\na \\| b\n");
+        assert_eq2!(table.rows.len(), 0);
+        assert!(parse_block_table("`a|b`\n| - |\n").is_err());
+    }
+
+    #[test]
+    fn test_parse_block_table_invalid() {
+        // No delimiter row.
+        assert!(parse_block_table("| a | b |\n| c | d |\n").is_err());
+        // Mismatched column count.
+        assert!(parse_block_table("| a | b |\n| --- |\n").is_err());
+        // Not a table at all.
+        assert!(parse_block_table("plain text\n").is_err());
+        assert!(parse_block_table("").is_err());
+    }
+}
@@ -0,0 +1,552 @@
+/*
+ *   Copyright (c) 2023 R3BL LLC
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+//! This module is responsible for converting a [MdDocument] into word wrapped, styled ANSI text.
+//! This is useful for output that isn't painted by the TUI engine, eg: printing a Markdown file to
+//! stdout in a CLI app. The styles are the same ones that the [crate::editor] component uses, since
+//! they come from [StyleUSSpanLines::from_block] (and the [md_parser_stylesheet]).
+//!
+//! 1. Text, headings, lists, block quotes, footnote definitions & metadata are word wrapped to the
+//!    given width. The continuation lines of a list item are indented to line up w/ the text after
+//!    the bullet, and the continuation lines of a block quote repeat the quote gutter.
+//! 2. Code blocks, tables, front matter & link definitions are not wrapped. Horizontal rules are
+//!    clipped to the given width.
+//! 3. The colors are degraded based on [r3bl_ansi_color::detect_color_support]. If there is no
+//!    color support (eg: `NO_COLOR` is set, or stdout is not a terminal), then plain text w/out any
+//!    escape sequences is produced.
+
+use std::fmt::{Display, Formatter, Result};
+
+use r3bl_ansi_color::{AnsiStyledText, TransformColor};
+use r3bl_rs_utils_core::*;
+
+use crate::{constants::*, *};
+
+/// A chunk of text w/ the ANSI styles that apply to it. Unlike [AnsiStyledText] this owns its data.
+/// Use [AnsiTextSpan::as_ansi_styled_text] to print it.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AnsiTextSpan {
+    pub text: String,
+    pub style: Vec<r3bl_ansi_color::Style>,
+}
+
+/// A line of output is made up of multiple [AnsiTextSpan]s.
+pub type AnsiTextLine = List<AnsiTextSpan>;
+
+/// The output is made up of multiple [AnsiTextLine]s.
+pub type AnsiTextLines = List<AnsiTextLine>;
+
+/// Parse the given Markdown `input` and convert it into lines of styled ANSI text that are no
+/// wider than `max_display_col_count`. The colors are degraded based on
+/// [r3bl_ansi_color::detect_color_support].
+pub fn try_parse_and_convert_to_ansi_text(
+    input: &str,
+    max_display_col_count: usize,
+) -> CommonResult<AnsiTextLines> {
+    match parse_markdown(input) {
+        Ok((_, document)) => Ok(convert_to_ansi_text(
+            &document,
+            max_display_col_count,
+            r3bl_ansi_color::detect_color_support(),
+        )),
+        Err(_) => CommonError::new_err_with_only_type(CommonErrorType::ParsingError),
+    }
+}
+
+/// Convert the given [MdDocument] into lines of styled ANSI text that are no wider than
+/// `max_display_col_count`, using the given `color_support`.
+pub fn convert_to_ansi_text(
+    document: &MdDocument,
+    max_display_col_count: usize,
+    color_support: r3bl_ansi_color::ColorSupport,
+) -> AnsiTextLines {
+    let mut wrapper = ansi_text_impl::LineWrapper::new(max_display_col_count);
+
+    // The metadata blocks that are populated from the front matter are rendered as part of it.
+    for block in get_source_blocks(document) {
+        let wrap_mode = match block {
+            MdBlockElement::CodeBlock(_)
+            | MdBlockElement::Table(_)
+            | MdBlockElement::FrontMatter(_)
+            | MdBlockElement::LinkDefinition(_) => ansi_text_impl::WrapMode::NoWrap,
+            MdBlockElement::HorizontalRule => ansi_text_impl::WrapMode::Clip,
+            MdBlockElement::SmartList(_) => ansi_text_impl::WrapMode::HangingIndent,
+            MdBlockElement::BlockQuote(_) => ansi_text_impl::WrapMode::RepeatGutter,
+            _ => ansi_text_impl::WrapMode::Wrap,
+        };
+        for line in StyleUSSpanLines::from_block(block, &None, None).iter() {
+            match line.try_fill_horizontal_rule(&None, ch!(max_display_col_count)) {
+                Some(rule_line) => wrapper.add_line(&rule_line, wrap_mode, color_support),
+                None => wrapper.add_line(line, wrap_mode, color_support),
+            }
+        }
+    }
+
+    wrapper.lines
+}
+
+mod ansi_text_span_impl {
+    use super::*;
+
+    impl AnsiTextSpan {
+        pub fn new(text: &str, style: Vec<r3bl_ansi_color::Style>) -> Self {
+            Self {
+                text: text.to_string(),
+                style,
+            }
+        }
+
+        pub fn as_ansi_styled_text(&self) -> AnsiStyledText<'_> {
+            AnsiStyledText {
+                text: &self.text,
+                style: &self.style,
+            }
+        }
+    }
+
+    /// Spans w/out any style are printed as plain text (w/out a trailing reset escape sequence).
+    impl Display for AnsiTextSpan {
+        fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+            if self.style.is_empty() {
+                write!(f, "{}", self.text)
+            } else {
+                write!(f, "{}", self.as_ansi_styled_text())
+            }
+        }
+    }
+
+    impl Display for AnsiTextLine {
+        fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+            for span in self.iter() {
+                write!(f, "{span}")?;
+            }
+            Ok(())
+        }
+    }
+
+    impl Display for AnsiTextLines {
+        fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+            for (index, line) in self.iter().enumerate() {
+                if index > 0 {
+                    writeln!(f)?;
+                }
+                write!(f, "{line}")?;
+            }
+            Ok(())
+        }
+    }
+}
+
+pub mod ansi_text_impl {
+    use super::*;
+
+    /// Convert the given [Style] into ANSI styles for the given `color_support`. When there is no
+    /// color support, no styles are produced at all, so the text is printed as is.
+    pub fn convert_style(
+        style: &Style,
+        color_support: r3bl_ansi_color::ColorSupport,
+    ) -> Vec<r3bl_ansi_color::Style> {
+        let mut acc = vec![];
+
+        if color_support == r3bl_ansi_color::ColorSupport::NoColor {
+            return acc;
+        }
+
+        if let Some(color) = style
+            .color_fg
+            .and_then(|it| convert_color(it, color_support))
+        {
+            acc.push(r3bl_ansi_color::Style::Foreground(color));
+        }
+        if let Some(color) = style
+            .color_bg
+            .and_then(|it| convert_color(it, color_support))
+        {
+            acc.push(r3bl_ansi_color::Style::Background(color));
+        }
+        if style.bold {
+            acc.push(r3bl_ansi_color::Style::Bold);
+        }
+        if style.dim {
+            acc.push(r3bl_ansi_color::Style::Dim);
+        }
+        if style.italic {
+            acc.push(r3bl_ansi_color::Style::Italic);
+        }
+        if style.underline {
+            acc.push(r3bl_ansi_color::Style::Underline);
+        }
+        if style.reverse {
+            acc.push(r3bl_ansi_color::Style::Invert);
+        }
+        if style.hidden {
+            acc.push(r3bl_ansi_color::Style::Hidden);
+        }
+        if style.strikethrough {
+            acc.push(r3bl_ansi_color::Style::Strikethrough);
+        }
+
+        acc
+    }
+
+    /// [TuiColor::Reset] means "use the terminal's default color", so there's nothing to emit.
+    pub fn convert_color(
+        color: TuiColor,
+        color_support: r3bl_ansi_color::ColorSupport,
+    ) -> Option<r3bl_ansi_color::Color> {
+        let color = match color {
+            TuiColor::Reset => return None,
+            TuiColor::Basic(basic_color) => {
+                r3bl_ansi_color::Color::Ansi256(get_ansi256_index(basic_color))
+            }
+            TuiColor::Rgb(RgbValue { red, green, blue }) => {
+                r3bl_ansi_color::Color::Rgb(red, green, blue)
+            }
+            TuiColor::Ansi(AnsiValue { color }) => r3bl_ansi_color::Color::Ansi256(color),
+        };
+
+        match color_support {
+            r3bl_ansi_color::ColorSupport::Ansi256 => {
+                Some(r3bl_ansi_color::Color::Ansi256(color.as_ansi256().index))
+            }
+            _ => Some(color),
+        }
+    }
+
+    /// The first 16 colors of the ANSI 256 palette are the basic colors.
+    #[rustfmt::skip]
+    fn get_ansi256_index(basic_color: ANSIBasicColor) -> u8 {
+        match basic_color {
+            ANSIBasicColor::Black       => 0,
+            ANSIBasicColor::DarkRed     => 1,
+            ANSIBasicColor::DarkGreen   => 2,
+            ANSIBasicColor::DarkYellow  => 3,
+            ANSIBasicColor::DarkBlue    => 4,
+            ANSIBasicColor::DarkMagenta => 5,
+            ANSIBasicColor::DarkCyan    => 6,
+            ANSIBasicColor::Grey        => 7,
+            ANSIBasicColor::DarkGrey    => 8,
+            ANSIBasicColor::Red         => 9,
+            ANSIBasicColor::Green       => 10,
+            ANSIBasicColor::Yellow      => 11,
+            ANSIBasicColor::Blue        => 12,
+            ANSIBasicColor::Magenta     => 13,
+            ANSIBasicColor::Cyan        => 14,
+            ANSIBasicColor::White       => 15,
+        }
+    }
+
+    /// How the lines of a block are fit into the available width.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum WrapMode {
+        /// Word wrap.
+        Wrap,
+        /// Word wrap, and indent continuation lines by the width of the first span (the bullet).
+        HangingIndent,
+        /// Word wrap, and start continuation lines w/ the first span (the quote gutter).
+        RepeatGutter,
+        /// Lines are clipped to the available width.
+        Clip,
+        /// Lines are left as is.
+        NoWrap,
+    }
+
+    /// A single grapheme cluster w/ its style.
+    #[derive(Debug, Clone)]
+    struct Piece {
+        text: String,
+        display_width: usize,
+        style: Vec<r3bl_ansi_color::Style>,
+    }
+
+    /// Greedy word wrapper. Words are runs of non whitespace grapheme clusters, which can span
+    /// multiple styles (eg: `**bold**` is one word made up of 3 spans). Whitespace at a line break
+    /// is dropped, and words that are wider than a line are split at grapheme cluster boundaries.
+    #[derive(Debug)]
+    pub struct LineWrapper {
+        pub lines: AnsiTextLines,
+        max_display_col_count: usize,
+        current_line: AnsiTextLine,
+        current_line_display_width: usize,
+        /// Display width of the non wrappable prefix (bullet, quote gutter) of the current line.
+        line_start_display_width: usize,
+        maybe_continuation_prefix: Option<Piece>,
+        pending_whitespace: Vec<Piece>,
+        pending_word: Vec<Piece>,
+    }
+
+    impl LineWrapper {
+        pub fn new(max_display_col_count: usize) -> Self {
+            Self {
+                lines: AnsiTextLines::default(),
+                max_display_col_count: max_display_col_count.max(1),
+                current_line: AnsiTextLine::default(),
+                current_line_display_width: 0,
+                line_start_display_width: 0,
+                maybe_continuation_prefix: None,
+                pending_whitespace: vec![],
+                pending_word: vec![],
+            }
+        }
+
+        /// Add one line (from the syntax highlighter) which may produce multiple output lines.
+        pub fn add_line(
+            &mut self,
+            line: &StyleUSSpanLine,
+            wrap_mode: WrapMode,
+            color_support: r3bl_ansi_color::ColorSupport,
+        ) {
+            let pieces = line
+                .iter()
+                .map(|span| {
+                    let style = convert_style(&span.style, color_support);
+                    span.text
+                        .vec_segment
+                        .iter()
+                        .map(|segment| Piece {
+                            text: segment.string.clone(),
+                            display_width: ch!(@to_usize segment.unicode_width),
+                            style: style.clone(),
+                        })
+                        .collect::<Vec<_>>()
+                })
+                .collect::<Vec<_>>();
+
+            match wrap_mode {
+                WrapMode::NoWrap => {
+                    for piece in pieces.iter().flatten() {
+                        self.push_piece(piece);
+                    }
+                }
+                WrapMode::Clip => {
+                    for piece in pieces.iter().flatten() {
+                        if self.current_line_display_width + piece.display_width
+                            > self.max_display_col_count
+                        {
+                            break;
+                        }
+                        self.push_piece(piece);
+                    }
+                }
+                WrapMode::Wrap => self.wrap(pieces.iter().flatten()),
+                WrapMode::HangingIndent | WrapMode::RepeatGutter => {
+                    let mut it = pieces.iter();
+                    if let Some(first_span_pieces) = it.next() {
+                        let prefix = Piece {
+                            text: first_span_pieces
+                                .iter()
+                                .map(|piece| piece.text.as_str())
+                                .collect(),
+                            display_width: first_span_pieces
+                                .iter()
+                                .map(|piece| piece.display_width)
+                                .sum(),
+                            style: first_span_pieces
+                                .first()
+                                .map(|piece| piece.style.clone())
+                                .unwrap_or_default(),
+                        };
+                        self.push_piece(&prefix);
+                        self.line_start_display_width = self.current_line_display_width;
+                        self.maybe_continuation_prefix = Some(match wrap_mode {
+                            WrapMode::HangingIndent => Piece {
+                                text: SPACE.repeat(prefix.display_width),
+                                display_width: prefix.display_width,
+                                style: vec![],
+                            },
+                            _ => prefix,
+                        });
+                    }
+                    self.wrap(it.flatten());
+                }
+            }
+
+            self.end_line();
+            self.maybe_continuation_prefix = None;
+            self.line_start_display_width = 0;
+        }
+
+        fn wrap<'a>(&mut self, pieces: impl Iterator<Item = &'a Piece>) {
+            for piece in pieces {
+                let is_whitespace = piece.text.chars().all(char::is_whitespace);
+                if is_whitespace {
+                    self.flush_word();
+                    self.pending_whitespace.push(piece.clone());
+                } else {
+                    self.pending_word.push(piece.clone());
+                }
+            }
+            self.flush_word();
+        }
+
+        fn flush_word(&mut self) {
+            if self.pending_word.is_empty() {
+                return;
+            }
+
+            let whitespace_display_width = get_display_width(&self.pending_whitespace);
+            let word_display_width = get_display_width(&self.pending_word);
+
+            // Move the word to the next line if it doesn't fit on this one.
+            let pending_whitespace = std::mem::take(&mut self.pending_whitespace);
+            if self.current_line_display_width > self.line_start_display_width
+                && self.current_line_display_width
+                    + whitespace_display_width
+                    + word_display_width
+                    > self.max_display_col_count
+            {
+                self.break_line();
+            } else {
+                for piece in pending_whitespace.iter() {
+                    self.push_piece(piece);
+                }
+            }
+
+            // Split the word if it is wider than a line.
+            let pending_word = std::mem::take(&mut self.pending_word);
+            for piece in pending_word.iter() {
+                if self.current_line_display_width > self.line_start_display_width
+                    && self.current_line_display_width + piece.display_width
+                        > self.max_display_col_count
+                {
+                    self.break_line();
+                }
+                self.push_piece(piece);
+            }
+        }
+
+        fn break_line(&mut self) {
+            self.lines.push(std::mem::take(&mut self.current_line));
+            self.current_line_display_width = 0;
+            if let Some(prefix) = self.maybe_continuation_prefix.clone() {
+                self.push_piece(&prefix);
+            }
+        }
+
+        /// Whitespace at the end of a line is dropped.
+        fn end_line(&mut self) {
+            self.flush_word();
+            self.pending_whitespace.clear();
+            self.lines.push(std::mem::take(&mut self.current_line));
+            self.current_line_display_width = 0;
+        }
+
+        /// Adjacent pieces w/ the same style are merged into a single span.
+        fn push_piece(&mut self, piece: &Piece) {
+            self.current_line_display_width += piece.display_width;
+            match self.current_line.last_mut() {
+                Some(span) if span.style == piece.style => {
+                    span.text.push_str(&piece.text)
+                }
+                _ => self
+                    .current_line
+                    .push(AnsiTextSpan::new(&piece.text, piece.style.clone())),
+            }
+        }
+    }
+
+    fn get_display_width(pieces: &[Piece]) -> usize {
+        pieces.iter().map(|piece| piece.display_width).sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use r3bl_ansi_color::ColorSupport;
+    use r3bl_rs_utils_core::*;
+
+    use super::*;
+
+    fn convert(input: &str, width: usize, color_support: ColorSupport) -> AnsiTextLines {
+        let (_, document) = parse_markdown(input).unwrap();
+        convert_to_ansi_text(&document, width, color_support)
+    }
+
+    #[test]
+    fn test_wrap_text_no_color() {
+        let input = "The quick brown fox jumps over the lazy dog\n";
+        let output = convert(input, 16, ColorSupport::NoColor);
+        assert_eq2!(
+            output.to_string(),
+            "The quick brown\nfox jumps over\nthe lazy dog"
+        );
+        assert!(output
+            .iter()
+            .all(|line| line.iter().all(|span| span.style.is_empty())));
+    }
+
+    #[test]
+    fn test_wrap_formatted_words_no_color() {
+        let input = "some **bold** and *italic* text\n";
+        let output = convert(input, 13, ColorSupport::NoColor);
+        assert_eq2!(output.to_string(), "some **bold**\nand *italic*\ntext");
+    }
+
+    #[test]
+    fn test_split_word_wider_than_line() {
+        let input = "abcdefghij\n";
+        let output = convert(input, 4, ColorSupport::NoColor);
+        assert_eq2!(output.to_string(), "abcd\nefgh\nij");
+    }
+
+    #[test]
+    fn test_list_hanging_indent_and_quote_gutter() {
+        let input = "- one two three four\n> one two three\n";
+        let output = convert(input, 10, ColorSupport::NoColor);
+        assert_eq2!(
+            output.to_string(),
+            "─┤one two\n  three\n  four\n┃ one two\n┃ three"
+        );
+    }
+
+    #[test]
+    fn test_code_block_not_wrapped_and_rule_clipped() {
+        let input = "```\nlet a_long_name = 1;\n```\n---\n";
+        let output = convert(input, 8, ColorSupport::NoColor);
+        assert_eq2!(
+            output.to_string(),
+            "```\nlet a_long_name = 1;\n```\n────────"
+        );
+    }
+
+    #[test]
+    fn test_styles_ansi256() {
+        let input = "**bold** text\n";
+        let output = convert(input, 80, ColorSupport::Ansi256);
+        let bold_span = output[0].iter().find(|span| span.text == "bold").unwrap();
+        assert!(bold_span.style.contains(&r3bl_ansi_color::Style::Bold));
+
+        // All the colors are degraded to the ANSI 256 palette.
+        for span in output.iter().flat_map(|line| line.iter()) {
+            for style in span.style.iter() {
+                match style {
+                    r3bl_ansi_color::Style::Foreground(color)
+                    | r3bl_ansi_color::Style::Background(color) => {
+                        assert!(matches!(color, r3bl_ansi_color::Color::Ansi256(_)))
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_plain_span_has_no_escape_sequences() {
+        let span = AnsiTextSpan::new("plain", vec![]);
+        assert_eq2!(span.to_string(), "plain");
+    }
+}
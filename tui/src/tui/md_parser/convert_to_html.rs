@@ -0,0 +1,831 @@
+/*
+ *   Copyright (c) 2023 R3BL LLC
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+//! This module is responsible for converting a [MdDocument] into a standalone HTML document.
+//!
+//! 1. The R3BL metadata blocks ([MdBlockElement::Title], [MdBlockElement::Tags],
+//!    [MdBlockElement::Authors], [MdBlockElement::Date]) are emitted into the `<head>`. So are the
+//!    ones that are populated from [MdBlockElement::FrontMatter].
+//! 2. All the other blocks are emitted into the `<body>` as semantic HTML. Consecutive lines of
+//!    text are joined into a single `<p>`, and consecutive smart list items are joined into
+//!    (nested) `<ul>` or `<ol>` lists based on their indent.
+//! 3. [MdBlockElement::FootnoteDefinition]s are collected into a footnotes `<section>` at the
+//!    end of the `<body>`, which the [MdLineFragment::FootnoteReference]s link to.
+//! 4. All text is escaped using [escape_html].
+
+use crate::*;
+
+/// Convert the given [MdDocument] into a standalone HTML document.
+pub fn convert_to_html(document: &MdDocument) -> String {
+    let mut writer = html_impl::HtmlWriter::default();
+    writer.acc.push_str("<!DOCTYPE html>\n<html>\n");
+    writer.push_head(document);
+    writer.acc.push_str("<body>\n");
+    writer.push_body(document);
+    writer.acc.push_str("</body>\n</html>\n");
+    writer.acc
+}
+
+/// Escape the characters that have special meaning in HTML text & attribute values.
+pub fn escape_html(text: &str) -> String {
+    let mut acc = String::with_capacity(text.len());
+    for character in text.chars() {
+        match character {
+            '&' => acc.push_str("&amp;"),
+            '<' => acc.push_str("&lt;"),
+            '>' => acc.push_str("&gt;"),
+            '"' => acc.push_str("&quot;"),
+            '\'' => acc.push_str("&#39;"),
+            _ => acc.push(character),
+        }
+    }
+    acc
+}
+
+/// Convert the given [MdLineFragments] into inline HTML. Bullets are skipped since the list that
+/// they belong to is emitted by the enclosing block.
+pub fn convert_fragments_to_html(fragments: &MdLineFragments) -> String {
+    let mut acc = String::new();
+    for fragment in fragments.iter() {
+        html_impl::push_fragment(fragment, &mut acc);
+    }
+    acc
+}
+
+mod html_impl {
+    use super::*;
+
+    #[derive(Debug, Default)]
+    pub struct HtmlWriter {
+        pub acc: String,
+        /// Lines of the paragraph that is currently being accumulated.
+        paragraph_lines: Vec<String>,
+        /// Stack of the lists that are currently open, w/ their indent. Each open list always has
+        /// one open `<li>`.
+        open_lists: Vec<(usize, BulletKind)>,
+        /// Footnote definitions are collected here, and emitted at the end of the body.
+        footnotes: Vec<String>,
+    }
+
+    impl HtmlWriter {
+        pub fn push_head(&mut self, document: &MdDocument) {
+            self.acc.push_str("<head>\n<meta charset=\"utf-8\">\n");
+            for block in document.iter() {
+                self.push_metadata(block);
+            }
+            self.acc.push_str("</head>\n");
+        }
+
+        fn push_metadata(&mut self, block: &MdBlockElement) {
+            match block {
+                MdBlockElement::Title(title) => {
+                    self.acc
+                        .push_str(&format!("<title>{}</title>\n", escape_html(title)));
+                }
+                MdBlockElement::Tags(tags) => {
+                    self.push_meta("keywords", &tags.join(", "));
+                }
+                MdBlockElement::Authors(authors) => {
+                    self.push_meta("author", &authors.join(", "));
+                }
+                MdBlockElement::Date(date) => {
+                    self.push_meta("date", date);
+                }
+                _ => {}
+            }
+        }
+
+        fn push_meta(&mut self, name: &str, content: &str) {
+            self.acc.push_str(&format!(
+                "<meta name=\"{}\" content=\"{}\">\n",
+                name,
+                escape_html(content)
+            ));
+        }
+
+        pub fn push_body(&mut self, document: &MdDocument) {
+            for block in document.iter() {
+                // Anything other than a smart list ends the lists that are currently open.
+                if !matches!(block, MdBlockElement::SmartList(_)) {
+                    self.close_lists_deeper_than(None);
+                }
+
+                match block {
+                    MdBlockElement::Text(fragments) => {
+                        if fragments.is_empty() {
+                            self.flush_paragraph();
+                        } else {
+                            self.paragraph_lines
+                                .push(convert_fragments_to_html(fragments));
+                        }
+                    }
+                    MdBlockElement::Heading(heading_data) => {
+                        self.flush_paragraph();
+                        let level = usize::from(heading_data.level);
+                        let text = parse_block_markdown_text_lenient(heading_data.text);
+                        self.acc.push_str(&format!(
+                            "<h{level}>{}</h{level}>\n",
+                            convert_fragments_to_html(&text)
+                        ));
+                    }
+                    MdBlockElement::SmartList((lines, bullet_kind, indent)) => {
+                        self.flush_paragraph();
+                        self.push_list_item(lines, *bullet_kind, *indent);
+                    }
+                    MdBlockElement::CodeBlock(code_block_lines) => {
+                        self.flush_paragraph();
+                        self.push_code_block(code_block_lines);
+                    }
+                    MdBlockElement::Table(table_data) => {
+                        self.flush_paragraph();
+                        self.push_table(table_data);
+                    }
+                    MdBlockElement::BlockQuote(block_quote_lines) => {
+                        self.flush_paragraph();
+                        self.push_block_quote(block_quote_lines);
+                    }
+                    MdBlockElement::HorizontalRule => {
+                        self.flush_paragraph();
+                        self.acc.push_str("<hr>\n");
+                    }
+                    // Metadata goes in the head.
+                    MdBlockElement::Title(_)
+                    | MdBlockElement::Tags(_)
+                    | MdBlockElement::Authors(_)
+                    | MdBlockElement::Date(_)
+                    | MdBlockElement::FrontMatter(_) => {}
+                    // Link definitions are not rendered, they are only used to resolve the
+                    // reference links.
+                    MdBlockElement::LinkDefinition(_) => {}
+                    MdBlockElement::FootnoteDefinition(FootnoteDefinitionData {
+                        label,
+                        content,
+                    }) => {
+                        self.footnotes.push(format!(
+                            "<li id=\"fn-{}\">{}</li>\n",
+                            escape_html(label),
+                            convert_fragments_to_html(content)
+                        ));
+                    }
+                }
+            }
+
+            self.flush_paragraph();
+            self.close_lists_deeper_than(None);
+            self.push_footnotes();
+        }
+
+        fn push_footnotes(&mut self) {
+            if self.footnotes.is_empty() {
+                return;
+            }
+            self.acc.push_str("<section class=\"footnotes\">\n<ol>\n");
+            for footnote in self.footnotes.drain(..) {
+                self.acc.push_str(&footnote);
+            }
+            self.acc.push_str("</ol>\n</section>\n");
+        }
+
+        fn flush_paragraph(&mut self) {
+            if self.paragraph_lines.is_empty() {
+                return;
+            }
+            self.acc
+                .push_str(&format!("<p>{}</p>\n", self.paragraph_lines.join("\n")));
+            self.paragraph_lines.clear();
+        }
+
+        /// Close all the open lists that have an indent greater than `maybe_indent`. If
+        /// `maybe_indent` is [None] then all the open lists are closed.
+        fn close_lists_deeper_than(&mut self, maybe_indent: Option<usize>) {
+            while let Some((open_indent, bullet_kind)) = self.open_lists.last() {
+                if let Some(indent) = maybe_indent {
+                    if *open_indent <= indent {
+                        break;
+                    }
+                }
+                let end_tag = match bullet_kind {
+                    BulletKind::Ordered(_) => "</li>\n</ol>\n",
+                    BulletKind::Unordered => "</li>\n</ul>\n",
+                };
+                self.acc.push_str(end_tag);
+                self.open_lists.pop();
+            }
+        }
+
+        fn push_list_item(
+            &mut self,
+            lines: &Lines,
+            bullet_kind: BulletKind,
+            indent: usize,
+        ) {
+            self.close_lists_deeper_than(Some(indent));
+
+            // An ordered list can't continue as an unordered list (and vice versa).
+            if let Some((open_indent, open_bullet_kind)) = self.open_lists.last() {
+                let is_same_kind = matches!(
+                    (open_bullet_kind, bullet_kind),
+                    (BulletKind::Ordered(_), BulletKind::Ordered(_))
+                        | (BulletKind::Unordered, BulletKind::Unordered)
+                );
+                if *open_indent == indent && !is_same_kind {
+                    self.close_lists_deeper_than(Some(indent.saturating_sub(1)));
+                    if indent == 0 {
+                        self.close_lists_deeper_than(None);
+                    }
+                }
+            }
+
+            match self.open_lists.last() {
+                Some((open_indent, _)) if *open_indent == indent => {
+                    self.acc.push_str("</li>\n");
+                }
+                _ => {
+                    let start_tag = match bullet_kind {
+                        BulletKind::Ordered(1) => "<ol>\n".to_string(),
+                        BulletKind::Ordered(number) => {
+                            format!("<ol start=\"{number}\">\n")
+                        }
+                        BulletKind::Unordered => "<ul>\n".to_string(),
+                    };
+                    self.acc.push_str(&start_tag);
+                    self.open_lists.push((indent, bullet_kind));
+                }
+            }
+
+            let content = lines
+                .iter()
+                .map(convert_fragments_to_html)
+                .collect::<Vec<String>>()
+                .join("\n");
+            self.acc.push_str(&format!("<li>{content}"));
+        }
+
+        fn push_code_block(&mut self, code_block_lines: &CodeBlockLines) {
+            let maybe_lang = code_block_lines
+                .first()
+                .and_then(|code_block_line| code_block_line.language);
+
+            self.acc.push_str(&match maybe_lang {
+                Some(lang) => {
+                    format!("<pre><code class=\"language-{}\">", escape_html(lang))
+                }
+                None => "<pre><code>".to_string(),
+            });
+
+            for code_block_line in code_block_lines.iter() {
+                if let CodeBlockLineContent::Text(text) = code_block_line.content {
+                    self.acc.push_str(&escape_html(text));
+                    self.acc.push('\n');
+                }
+            }
+
+            self.acc.push_str("</code></pre>\n");
+        }
+
+        fn push_table(&mut self, table_data: &TableData) {
+            self.acc.push_str("<table>\n<thead>\n");
+            self.push_table_row(&table_data.header, &table_data.alignments, "th");
+            self.acc.push_str("</thead>\n");
+            if !table_data.rows.is_empty() {
+                self.acc.push_str("<tbody>\n");
+                for row in table_data.rows.iter() {
+                    self.push_table_row(row, &table_data.alignments, "td");
+                }
+                self.acc.push_str("</tbody>\n");
+            }
+            self.acc.push_str("</table>\n");
+        }
+
+        fn push_table_row(
+            &mut self,
+            row: &TableRow,
+            alignments: &List<TableColumnAlignment>,
+            cell_tag: &str,
+        ) {
+            self.acc.push_str("<tr>");
+            for (cell, alignment) in row.iter().zip(alignments.iter()) {
+                let style_attr = match alignment {
+                    TableColumnAlignment::Default => "",
+                    TableColumnAlignment::Left => " style=\"text-align: left\"",
+                    TableColumnAlignment::Center => " style=\"text-align: center\"",
+                    TableColumnAlignment::Right => " style=\"text-align: right\"",
+                };
+                self.acc.push_str(&format!(
+                    "<{cell_tag}{style_attr}>{}</{cell_tag}>",
+                    convert_fragments_to_html(cell)
+                ));
+            }
+            self.acc.push_str("</tr>\n");
+        }
+
+        fn push_block_quote(&mut self, block_quote_lines: &BlockQuoteLines) {
+            let mut depth = 0;
+            for block_quote_line in block_quote_lines.iter() {
+                while depth < block_quote_line.depth {
+                    self.acc.push_str("<blockquote>\n");
+                    depth += 1;
+                }
+                while depth > block_quote_line.depth {
+                    self.acc.push_str("</blockquote>\n");
+                    depth -= 1;
+                }
+                if !block_quote_line.content.is_empty() {
+                    self.acc.push_str(&format!(
+                        "<p>{}</p>\n",
+                        convert_fragments_to_html(&block_quote_line.content)
+                    ));
+                }
+            }
+            for _ in 0..depth {
+                self.acc.push_str("</blockquote>\n");
+            }
+        }
+    }
+
+    pub fn push_fragment(fragment: &MdLineFragment, acc: &mut String) {
+        match fragment {
+            MdLineFragment::OrderedListBullet { .. }
+            | MdLineFragment::UnorderedListBullet { .. } => {}
+            MdLineFragment::Plain(text) => acc.push_str(&escape_html(text)),
+            MdLineFragment::Bold(fragments) => acc.push_str(&format!(
+                "<strong>{}</strong>",
+                convert_fragments_to_html(fragments)
+            )),
+            MdLineFragment::Italic(fragments) => acc.push_str(&format!(
+                "<em>{}</em>",
+                convert_fragments_to_html(fragments)
+            )),
+            MdLineFragment::BoldItalic(fragments) => acc.push_str(&format!(
+                "<strong><em>{}</em></strong>",
+                convert_fragments_to_html(fragments)
+            )),
+            MdLineFragment::Strikethrough(fragments) => acc.push_str(&format!(
+                "<del>{}</del>",
+                convert_fragments_to_html(fragments)
+            )),
+            MdLineFragment::InlineCode(text) => {
+                acc.push_str(&format!("<code>{}</code>", escape_html(text)))
+            }
+            MdLineFragment::Escaped(text) => acc.push_str(&escape_html(text)),
+            MdLineFragment::Link(HyperlinkData { text, url }) => acc.push_str(&format!(
+                "<a href=\"{}\">{}</a>",
+                escape_html(url),
+                escape_html(text)
+            )),
+            MdLineFragment::Image(HyperlinkData { text, url }) => acc.push_str(&format!(
+                "<img src=\"{}\" alt=\"{}\">",
+                escape_html(url),
+                escape_html(text)
+            )),
+            MdLineFragment::WikiLink(wiki_link_data) => acc.push_str(&format!(
+                "<a href=\"{}\" class=\"wiki-link\">{}</a>",
+                escape_html(wiki_link_data.target),
+                escape_html(wiki_link_data.get_text())
+            )),
+            MdLineFragment::AutoLink(url) => acc.push_str(&format!(
+                "<a href=\"{}\">{}</a>",
+                escape_html(url),
+                escape_html(url)
+            )),
+            MdLineFragment::ReferenceLink(reference_link_data) => {
+                match reference_link_data.url {
+                    Some(url) => acc.push_str(&format!(
+                        "<a href=\"{}\">{}</a>",
+                        escape_html(url),
+                        escape_html(reference_link_data.text)
+                    )),
+                    // Unresolved reference links are rendered as they are in the source.
+                    None => acc.push_str(&escape_html(&fragment.pretty_print_debug())),
+                }
+            }
+            MdLineFragment::FootnoteReference(label) => acc.push_str(&format!(
+                "<sup class=\"footnote-ref\"><a href=\"#fn-{}\">{}</a></sup>",
+                escape_html(label),
+                escape_html(label)
+            )),
+            MdLineFragment::Checkbox(is_checked) => acc.push_str(if *is_checked {
+                "<input type=\"checkbox\" disabled checked>"
+            } else {
+                "<input type=\"checkbox\" disabled>"
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use r3bl_rs_utils_core::*;
+
+    use super::*;
+
+    /// Make sure that every start tag has a matching end tag, in the right order. Void elements
+    /// (which don't have an end tag) are skipped.
+    fn assert_tags_balanced(html: &str) {
+        const VOID_ELEMENTS: [&str; 4] = ["meta", "hr", "img", "input"];
+        let mut stack: Vec<&str> = vec![];
+        for tag in html
+            .split('<')
+            .skip(1)
+            .filter_map(|it| it.split('>').next())
+        {
+            if tag.starts_with('!') {
+                continue;
+            }
+            let name = tag
+                .trim_start_matches('/')
+                .split(' ')
+                .next()
+                .unwrap_or_default();
+            if VOID_ELEMENTS.contains(&name) {
+                continue;
+            }
+            if tag.starts_with('/') {
+                assert_eq2!(stack.pop(), Some(name));
+            } else {
+                stack.push(name);
+            }
+        }
+        assert_eq2!(stack.len(), 0);
+    }
+
+    fn get_body(html: &str) -> &str {
+        let start = html.find("<body>\n").unwrap() + "<body>\n".len();
+        let end = html.find("</body>").unwrap();
+        &html[start..end]
+    }
+
+    #[test]
+    fn test_escape_html() {
+        assert_eq2!(
+            escape_html("<a href=\"x\">Tom & Jerry's</a>"),
+            "&lt;a href=&quot;x&quot;&gt;Tom &amp; Jerry&#39;s&lt;/a&gt;"
+        );
+    }
+
+    #[test]
+    fn test_fragments_to_html() {
+        let fragments = list![
+            MdLineFragment::Plain("a < b "),
+            MdLineFragment::Bold(list![MdLineFragment::Plain("bold")]),
+            MdLineFragment::Italic(list![MdLineFragment::Plain("italic")]),
+            MdLineFragment::BoldItalic(list![MdLineFragment::Plain("both")]),
+            MdLineFragment::Strikethrough(list![MdLineFragment::Plain("struck")]),
+            MdLineFragment::InlineCode("x && y"),
+            MdLineFragment::Link(HyperlinkData::new("r3bl", "https://r3bl.com?a=1&b=2")),
+            MdLineFragment::Image(HyperlinkData::new("alt \"text\"", "image.jpg")),
+        ];
+        assert_eq2!(
+            convert_fragments_to_html(&fragments),
+            [
+                "a &lt; b ",
+                "<strong>bold</strong>",
+                "<em>italic</em>",
+                "<strong><em>both</em></strong>",
+                "<del>struck</del>",
+                "<code>x &amp;&amp; y</code>",
+                "<a href=\"https://r3bl.com?a=1&amp;b=2\">r3bl</a>",
+                "<img src=\"image.jpg\" alt=\"alt &quot;text&quot;\">",
+            ]
+            .join("")
+        );
+    }
+
+    #[test]
+    fn test_nested_formatting_and_escapes_to_html() {
+        let (_, document) = parse_markdown(
+            "**bold w/ `code` & *italic* [link](url)** \\*not italic\\* 2 * 3\n",
+        )
+        .unwrap();
+        let html = convert_to_html(&document);
+        assert_tags_balanced(&html);
+        assert_eq2!(
+            get_body(&html),
+            "<p><strong>bold w/ <code>code</code> &amp; <em>italic</em> \
+             <a href=\"url\">link</a></strong> *not italic* 2 * 3</p>\n"
+        );
+    }
+
+    #[test]
+    fn test_footnotes() {
+        let input = [
+            "[^2]: Defined *first*.",
+            "Some text[^1] & more[^2].",
+            "",
+            "[^1]: A <footnote>.",
+        ]
+        .join("\n");
+        let (_, document) = parse_markdown(&input).unwrap();
+        let html = convert_to_html(&document);
+        assert_tags_balanced(&html);
+        assert_eq2!(
+            get_body(&html),
+            [
+                "<p>Some text<sup class=\"footnote-ref\"><a href=\"#fn-1\">1</a></sup> &amp; more\
+                 <sup class=\"footnote-ref\"><a href=\"#fn-2\">2</a></sup>.</p>",
+                "<section class=\"footnotes\">",
+                "<ol>",
+                "<li id=\"fn-2\">Defined <em>first</em>.</li>",
+                "<li id=\"fn-1\">A &lt;footnote&gt;.</li>",
+                "</ol>",
+                "</section>",
+                "",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn test_metadata_goes_in_head() {
+        let input = [
+            "@title: Tom & Jerry",
+            "@tags: cat, mouse",
+            "@authors: Nazmul, Nadia",
+            "@date: 2023-05-01",
+            "Hello",
+            "",
+        ]
+        .join("\n");
+        let (_, document) = parse_markdown(&input).unwrap();
+        let html = convert_to_html(&document);
+        assert_eq2!(
+            html,
+            [
+                "<!DOCTYPE html>",
+                "<html>",
+                "<head>",
+                "<meta charset=\"utf-8\">",
+                "<title>Tom &amp; Jerry</title>",
+                "<meta name=\"keywords\" content=\"cat, mouse\">",
+                "<meta name=\"author\" content=\"Nazmul, Nadia\">",
+                "<meta name=\"date\" content=\"2023-05-01\">",
+                "</head>",
+                "<body>",
+                "<p>Hello</p>",
+                "</body>",
+                "</html>",
+                "",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn test_front_matter_goes_in_head() {
+        let input = [
+            "---",
+            "title: Tom & Jerry",
+            "tags: [cat, mouse]",
+            "draft: true",
+            "---",
+            "Hello",
+            "",
+        ]
+        .join("\n");
+        let (_, document) = parse_markdown(&input).unwrap();
+        let html = convert_to_html(&document);
+        assert!(html.contains(
+            "<head>\n<meta charset=\"utf-8\">\n<title>Tom &amp; Jerry</title>\n\
+             <meta name=\"keywords\" content=\"cat, mouse\">\n</head>\n\
+             <body>\n<p>Hello</p>\n</body>"
+        ));
+    }
+
+    #[test]
+    fn test_links() {
+        let input = [
+            "[[Note|alias]] https://r3bl.com/?a=1&b=2, [site][r3bl] & [missing][]",
+            "[R3BL]: https://r3bl.com",
+            "",
+        ]
+        .join("\n");
+        let (_, document) = parse_markdown(&input).unwrap();
+        let html = convert_to_html(&document);
+        assert_tags_balanced(&html);
+        assert_eq2!(
+            get_body(&html),
+            "<p><a href=\"Note\" class=\"wiki-link\">alias</a> \
+             <a href=\"https://r3bl.com/?a=1&amp;b=2\">https://r3bl.com/?a=1&amp;b=2</a>, \
+             <a href=\"https://r3bl.com\">site</a> &amp; [missing][]</p>\n"
+        );
+    }
+
+    #[test]
+    fn test_nested_lists() {
+        let input = [
+            "- ul1",
+            "  - ul2",
+            "    ul2.1",
+            "- [x] done",
+            "3. ol3",
+            "4. ol4",
+            "end",
+            "",
+        ]
+        .join("\n");
+        let (remainder, document) = parse_markdown(&input).unwrap();
+        assert_eq2!(remainder, "");
+        let html = convert_to_html(&document);
+        assert_tags_balanced(&html);
+        assert_eq2!(
+            get_body(&html),
+            [
+                "<ul>",
+                "<li>ul1<ul>",
+                "<li>ul2\nul2.1</li>",
+                "</ul>",
+                "</li>",
+                "<li><input type=\"checkbox\" disabled checked> done</li>",
+                "</ul>",
+                "<ol start=\"3\">",
+                "<li>ol3</li>",
+                "<li>ol4</li>",
+                "</ol>",
+                "<p>end</p>",
+                "",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn test_blocks() {
+        let input = [
+            "# Tom & *Jerry*",
+            "line 1",
+            "line 2",
+            "",
+            "```rust",
+            "let a = b < c;",
+            "```",
+            "```",
+            "plain",
+            "```",
+            "> quote",
+            "> > nested",
+            "---",
+            "| a | b |",
+            "| :-: | --: |",
+            "| c | d |",
+            "",
+        ]
+        .join("\n");
+        let (remainder, document) = parse_markdown(&input).unwrap();
+        assert_eq2!(remainder, "");
+        let html = convert_to_html(&document);
+        assert_tags_balanced(&html);
+        assert_eq2!(
+            get_body(&html),
+            [
+                "<h1>Tom &amp; <em>Jerry</em></h1>",
+                "<p>line 1\nline 2</p>",
+                "<pre><code class=\"language-rust\">let a = b &lt; c;",
+                "</code></pre>",
+                "<pre><code>plain",
+                "</code></pre>",
+                "<blockquote>",
+                "<p>quote</p>",
+                "<blockquote>",
+                "<p>nested</p>",
+                "</blockquote>",
+                "</blockquote>",
+                "<hr>",
+                "<table>",
+                "<thead>",
+                "<tr><th style=\"text-align: center\">a</th><th style=\"text-align: right\">b</th></tr>",
+                "</thead>",
+                "<tbody>",
+                "<tr><td style=\"text-align: center\">c</td><td style=\"text-align: right\">d</td></tr>",
+                "</tbody>",
+                "</table>",
+                "",
+            ]
+            .join("\n")
+        );
+    }
+
+    /// Round trip the input that is used to test [parse_markdown] thru the HTML exporter.
+    #[test]
+    fn test_round_trip_parser_fixture() {
+        let input = [
+            "@title: Something",
+            "@tags: tag1, tag2, tag3",
+            "# Foobar",
+            "",
+            "Foobar is a Python library for dealing with word pluralization.",
+            "",
+            "```bash",
+            "pip install foobar",
+            "```",
+            "```fish",
+            "```",
+            "```python",
+            "",
+            "```",
+            "## Installation",
+            "",
+            "Use the package manager [pip](https://pip.pypa.io/en/stable/) to install foobar.",
+            "```python",
+            "import foobar",
+            "",
+            "foobar.pluralize('word') # returns 'words'",
+            "foobar.pluralize('goose') # returns 'geese'",
+            "foobar.singularize('phenomena') # returns 'phenomenon'",
+            "```",
+            "- ul1",
+            "- ul2",
+            "1. ol1",
+            "2. ol2",
+            "- [ ] todo",
+            "- [x] done",
+            "end",
+            "",
+        ]
+        .join("\n");
+        let (remainder, document) = parse_markdown(&input).unwrap();
+        assert_eq2!(remainder, "");
+
+        let html = convert_to_html(&document);
+        assert_tags_balanced(&html);
+        assert_eq2!(
+            html,
+            [
+                "<!DOCTYPE html>",
+                "<html>",
+                "<head>",
+                "<meta charset=\"utf-8\">",
+                "<title>Something</title>",
+                "<meta name=\"keywords\" content=\"tag1, tag2, tag3\">",
+                "</head>",
+                "<body>",
+                "<h1>Foobar</h1>",
+                "<p>Foobar is a Python library for dealing with word pluralization.</p>",
+                "<pre><code class=\"language-bash\">pip install foobar",
+                "</code></pre>",
+                "<pre><code class=\"language-fish\"></code></pre>",
+                "<pre><code class=\"language-python\">",
+                "</code></pre>",
+                "<h2>Installation</h2>",
+                "<p>Use the package manager <a href=\"https://pip.pypa.io/en/stable/\">pip</a> to install foobar.</p>",
+                "<pre><code class=\"language-python\">import foobar",
+                "",
+                "foobar.pluralize(&#39;word&#39;) # returns &#39;words&#39;",
+                "foobar.pluralize(&#39;goose&#39;) # returns &#39;geese&#39;",
+                "foobar.singularize(&#39;phenomena&#39;) # returns &#39;phenomenon&#39;",
+                "</code></pre>",
+                "<ul>",
+                "<li>ul1</li>",
+                "<li>ul2</li>",
+                "</ul>",
+                "<ol>",
+                "<li>ol1</li>",
+                "<li>ol2</li>",
+                "</ol>",
+                "<ul>",
+                "<li><input type=\"checkbox\" disabled> todo</li>",
+                "<li><input type=\"checkbox\" disabled checked> done</li>",
+                "</ul>",
+                "<p>end</p>",
+                "</body>",
+                "</html>",
+                "",
+            ]
+            .join("\n")
+        );
+    }
+
+    /// Round trip the test asset that is used by the syntax highlighter. Only the part of the
+    /// document that can be parsed is exported.
+    #[test]
+    fn test_round_trip_valid_content_fixture() {
+        let input = include_str!("../syntax_highlighting/test_assets/valid-content.md");
+        let (_, document) = parse_markdown(input).unwrap();
+
+        let html = convert_to_html(&document);
+        assert_tags_balanced(&html);
+
+        assert!(html.contains("<h1>My Heading</h1>"));
+        assert!(html.contains("<em>emphasis</em>"));
+        assert!(html.contains("<del>strikethrough</del>"));
+        assert!(html.contains("<ul>\n<li>a</li>\n<li>b</li>\n<li>c</li>\n</ul>"));
+        assert!(html.contains("<blockquote>\n<p>my block quote</p>\n</blockquote>"));
+        assert!(html.contains("<pre><code class=\"language-rust\">fn main() {\n"));
+        assert!(
+            html.contains("<img src=\"http://example.com/image.jpg\" alt=\"My Image\">")
+        );
+    }
+}
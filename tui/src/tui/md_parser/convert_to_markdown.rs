@@ -0,0 +1,1007 @@
+/*
+ *   Copyright (c) 2023 R3BL LLC
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+//! This module is responsible for converting a [MdDocument] back into (canonical) Markdown source.
+//! This is what powers "format document". The following things are normalized:
+//!
+//! 1. The R3BL metadata blocks are moved to the top of the document in a fixed order: title, tags,
+//!    authors, date. YAML front matter is kept as is (before them).
+//! 2. Headings have exactly one space between the `#` chars and the heading text.
+//! 3. Unordered lists always use `-` bullets. Ordered lists are renumbered sequentially, starting
+//!    from the number of the first item in the list.
+//! 4. Emphasis always uses `*` (unless the text itself contains a `*`, in which case `_` is used).
+//! 5. Block quotes use `> ` for each level of nesting, and horizontal rules use `---`.
+//! 6. Table columns are padded so that the `|` chars line up.
+//!
+//! Formatting is idempotent, and formatting does not change the meaning of the document:
+//! `parse_markdown(convert_to_markdown(doc)) == doc`, except for the things listed above that are
+//! normalized on purpose, which are the numbers of ordered list items, the position & order of the
+//! metadata blocks, and the whitespace around heading text.
+
+use r3bl_rs_utils_core::*;
+
+use crate::{constants::*, *};
+
+/// Convert the given [MdDocument] into canonical Markdown source.
+pub fn convert_to_markdown(document: &MdDocument) -> String {
+    let mut acc: Vec<String> = vec![];
+
+    // The metadata blocks that are populated from the front matter are emitted as part of it.
+    let blocks = get_source_blocks(document).collect::<Vec<_>>();
+
+    // Front matter is only valid at the start of the document, so it goes first, as is.
+    for block in blocks.iter() {
+        if let MdBlockElement::FrontMatter(front_matter) = block {
+            acc.push(FRONT_MATTER_DELIMITER.to_string());
+            acc.extend(front_matter.lines.iter().map(|line| line.to_string()));
+            acc.push(FRONT_MATTER_DELIMITER.to_string());
+        }
+    }
+
+    // Metadata goes next, in a fixed order.
+    for block in blocks.iter() {
+        if let MdBlockElement::Title(title) = block {
+            acc.push(format!("{TITLE}{COLON}{SPACE}{title}"));
+        }
+    }
+    for block in blocks.iter() {
+        if let MdBlockElement::Tags(tags) = block {
+            acc.push(markdown_impl::format_csv(TAGS, tags));
+        }
+    }
+    for block in blocks.iter() {
+        if let MdBlockElement::Authors(authors) = block {
+            acc.push(markdown_impl::format_csv(AUTHORS, authors));
+        }
+    }
+    for block in blocks.iter() {
+        if let MdBlockElement::Date(date) = block {
+            acc.push(format!("{DATE}{COLON}{SPACE}{date}"));
+        }
+    }
+
+    let mut list_numbering = markdown_impl::ListNumbering::default();
+    for block in blocks.iter() {
+        if !matches!(block, MdBlockElement::SmartList(_)) {
+            list_numbering.reset();
+        }
+
+        match block {
+            MdBlockElement::Heading(HeadingData { level, text }) => {
+                let hashes = HEADING_CHAR.to_string().repeat(usize::from(*level));
+                acc.push(format!("{hashes}{SPACE}{}", text.trim()));
+            }
+            MdBlockElement::SmartList((lines, bullet_kind, indent)) => {
+                let bullet = list_numbering.next_bullet(*bullet_kind, *indent);
+                acc.extend(markdown_impl::format_smart_list(lines, &bullet, *indent));
+            }
+            MdBlockElement::Text(fragments) => {
+                acc.push(convert_fragments_to_markdown(fragments));
+            }
+            MdBlockElement::CodeBlock(code_block_lines) => {
+                acc.extend(markdown_impl::format_code_block(code_block_lines));
+            }
+            MdBlockElement::Table(table_data) => {
+                acc.extend(markdown_impl::format_table(table_data));
+            }
+            MdBlockElement::BlockQuote(block_quote_lines) => {
+                acc.extend(markdown_impl::format_block_quote(block_quote_lines));
+            }
+            MdBlockElement::HorizontalRule => {
+                acc.push(HORIZONTAL_RULE_OUTPUT.to_string());
+            }
+            MdBlockElement::LinkDefinition(LinkDefinitionData {
+                reference,
+                url,
+                title,
+            }) => {
+                let mut it = format!(
+                    "{LEFT_BRACKET}{reference}{LINK_DEFINITION_SEPARATOR}{SPACE}{url}"
+                );
+                if let Some(title) = title {
+                    it.push_str(&format!("{SPACE}{QUOTE}{title}{QUOTE}"));
+                }
+                acc.push(it);
+            }
+            MdBlockElement::FootnoteDefinition(FootnoteDefinitionData {
+                label,
+                content,
+            }) => {
+                let mut it = format!("{FOOTNOTE_START}{label}{RIGHT_BRACKET}{COLON}");
+                if !content.is_empty() {
+                    it.push_str(SPACE);
+                    it.push_str(&convert_fragments_to_markdown(content));
+                }
+                acc.push(it);
+            }
+            // Metadata has already been emitted.
+            MdBlockElement::Title(_)
+            | MdBlockElement::Tags(_)
+            | MdBlockElement::Authors(_)
+            | MdBlockElement::Date(_)
+            | MdBlockElement::FrontMatter(_) => {}
+        }
+    }
+
+    // An empty document is an empty string (and not a single empty line, which would parse as an
+    // empty text block).
+    if acc.is_empty() {
+        return String::new();
+    }
+
+    let mut it = acc.join(NEW_LINE);
+    it.push_str(NEW_LINE);
+    it
+}
+
+/// Parse the given Markdown source and return the formatted source. Anything at the end of the
+/// input that can't be parsed is kept as is, so no content is ever lost.
+pub fn format_markdown(input: &str) -> String {
+    match parse_markdown(input) {
+        Ok((remainder, document)) if !document.is_empty() => {
+            let mut it = convert_to_markdown(&document);
+            it.push_str(remainder);
+            it
+        }
+        _ => input.to_string(),
+    }
+}
+
+/// Convert the given [MdLineFragments] into Markdown source. Bullets are skipped since they are
+/// emitted by the enclosing smart list block.
+pub fn convert_fragments_to_markdown(fragments: &MdLineFragments) -> String {
+    let mut acc = String::new();
+    for fragment in fragments.iter() {
+        markdown_impl::push_fragment(fragment, &mut acc);
+    }
+    acc
+}
+
+mod markdown_impl {
+    use super::*;
+
+    /// Keeps track of the ordered lists that are currently open (at each indent), so that their
+    /// items can be renumbered sequentially.
+    #[derive(Debug, Default)]
+    pub struct ListNumbering {
+        /// Stack of `(indent, next number)`. The next number is [None] for unordered lists.
+        open_lists: Vec<(usize, Option<usize>)>,
+    }
+
+    impl ListNumbering {
+        pub fn reset(&mut self) { self.open_lists.clear(); }
+
+        /// Returns the bullet (w/out the trailing space) for the next list item.
+        pub fn next_bullet(&mut self, bullet_kind: BulletKind, indent: usize) -> String {
+            // Deeper lists have ended.
+            while let Some((open_indent, _)) = self.open_lists.last() {
+                if *open_indent > indent {
+                    self.open_lists.pop();
+                } else {
+                    break;
+                }
+            }
+
+            // Continue the current list if it is the same kind, otherwise start a new one.
+            let maybe_next_number = match self.open_lists.last() {
+                Some((open_indent, maybe_next_number)) if *open_indent == indent => {
+                    let maybe_next_number = *maybe_next_number;
+                    self.open_lists.pop();
+                    maybe_next_number
+                }
+                _ => None,
+            };
+
+            match bullet_kind {
+                BulletKind::Ordered(number) => {
+                    let number = maybe_next_number.unwrap_or(number);
+                    self.open_lists.push((indent, Some(number + 1)));
+                    format!("{number}{PERIOD}")
+                }
+                BulletKind::Unordered => {
+                    self.open_lists.push((indent, None));
+                    UNORDERED_LIST.to_string()
+                }
+            }
+        }
+    }
+
+    pub fn format_csv(tag_name: &str, items: &List<&str>) -> String {
+        format!(
+            "{tag_name}{COLON}{SPACE}{}",
+            items.join(&format!("{COMMA}{SPACE}"))
+        )
+    }
+
+    /// The first line gets the bullet, and the rest of the lines are indented to line up w/ the
+    /// content of the first line.
+    pub fn format_smart_list(lines: &Lines, bullet: &str, indent: usize) -> Vec<String> {
+        let indent_padding = SPACE.repeat(indent);
+        let continuation_padding = SPACE.repeat(bullet.len() + SPACE.len());
+        lines
+            .iter()
+            .enumerate()
+            .map(|(index, line)| {
+                let content = convert_fragments_to_markdown(line);
+                if index == 0 {
+                    format!("{indent_padding}{bullet}{SPACE}{content}")
+                } else {
+                    format!("{indent_padding}{continuation_padding}{content}")
+                }
+            })
+            .collect()
+    }
+
+    pub fn format_code_block(code_block_lines: &CodeBlockLines) -> Vec<String> {
+        code_block_lines
+            .iter()
+            .map(|code_block_line| match code_block_line.content {
+                CodeBlockLineContent::StartTag => format!(
+                    "{CODE_BLOCK_START_PARTIAL}{}",
+                    code_block_line.language.unwrap_or_default()
+                ),
+                CodeBlockLineContent::EndTag => CODE_BLOCK_END.to_string(),
+                CodeBlockLineContent::Text(text) => text.to_string(),
+            })
+            .collect()
+    }
+
+    pub fn format_block_quote(block_quote_lines: &BlockQuoteLines) -> Vec<String> {
+        block_quote_lines
+            .iter()
+            .map(|block_quote_line| {
+                let markers =
+                    format!("{BLOCK_QUOTE_CHAR}{SPACE}").repeat(block_quote_line.depth);
+                let content = convert_fragments_to_markdown(&block_quote_line.content);
+                if content.is_empty() {
+                    markers.trim_end().to_string()
+                } else {
+                    format!("{markers}{content}")
+                }
+            })
+            .collect()
+    }
+
+    /// Every cell in a column is padded to the width of the widest cell in that column. The
+    /// delimiter row is at least [TABLE_DELIMITER_MIN_WIDTH] wide.
+    pub fn format_table(table_data: &TableData) -> Vec<String> {
+        let header: Vec<String> = table_data
+            .header
+            .iter()
+            .map(convert_fragments_to_markdown)
+            .collect();
+        let rows: Vec<Vec<String>> = table_data
+            .rows
+            .iter()
+            .map(|row| row.iter().map(convert_fragments_to_markdown).collect())
+            .collect();
+
+        let mut column_widths =
+            vec![TABLE_DELIMITER_MIN_WIDTH; table_data.column_count()];
+        for row in std::iter::once(&header).chain(rows.iter()) {
+            for (cell, column_width) in row.iter().zip(column_widths.iter_mut()) {
+                *column_width =
+                    (*column_width).max(UnicodeString::str_display_width(cell));
+            }
+        }
+
+        let delimiter: Vec<String> = table_data
+            .alignments
+            .iter()
+            .zip(column_widths.iter())
+            .map(|(alignment, column_width)| {
+                let dash = TABLE_ALIGN_DASH_CHAR.to_string();
+                let colon = TABLE_ALIGN_COLON_CHAR.to_string();
+                match alignment {
+                    TableColumnAlignment::Default => dash.repeat(*column_width),
+                    TableColumnAlignment::Left => {
+                        format!("{colon}{}", dash.repeat(column_width - 1))
+                    }
+                    TableColumnAlignment::Center => {
+                        format!("{colon}{}{colon}", dash.repeat(column_width - 2))
+                    }
+                    TableColumnAlignment::Right => {
+                        format!("{}{colon}", dash.repeat(column_width - 1))
+                    }
+                }
+            })
+            .collect();
+
+        let mut acc = Vec::with_capacity(rows.len() + 2);
+        acc.push(format_table_row(&header, &column_widths));
+        acc.push(format_table_row(&delimiter, &column_widths));
+        for row in rows.iter() {
+            acc.push(format_table_row(row, &column_widths));
+        }
+        acc
+    }
+
+    fn format_table_row(cells: &[String], column_widths: &[usize]) -> String {
+        let mut acc = TABLE_PIPE.to_string();
+        for (cell, column_width) in cells.iter().zip(column_widths.iter()) {
+            let padding =
+                column_width.saturating_sub(UnicodeString::str_display_width(cell));
+            acc.push_str(&format!(
+                "{SPACE}{cell}{}{SPACE}{TABLE_PIPE}",
+                SPACE.repeat(padding)
+            ));
+        }
+        acc
+    }
+
+    /// Use `preferred` to delimit `text`, unless `text` contains the first char of `preferred`,
+    /// in which case `fallback` is used.
+    fn delimit(text: &str, preferred: &str, fallback: &str) -> String {
+        let delimiter = match preferred.chars().next() {
+            Some(it) if text.contains(it) => fallback,
+            _ => preferred,
+        };
+        format!("{delimiter}{text}{delimiter}")
+    }
+
+    pub fn push_fragment(fragment: &MdLineFragment, acc: &mut String) {
+        match fragment {
+            MdLineFragment::OrderedListBullet { .. }
+            | MdLineFragment::UnorderedListBullet { .. } => {}
+            MdLineFragment::Plain(text) => acc.push_str(text),
+            MdLineFragment::Bold(fragments) => {
+                let text = convert_fragments_to_markdown(fragments);
+                acc.push_str(&delimit(&text, BOLD_1, BOLD_2))
+            }
+            MdLineFragment::Italic(fragments) => {
+                let text = convert_fragments_to_markdown(fragments);
+                acc.push_str(&delimit(&text, ITALIC_1, ITALIC_2))
+            }
+            MdLineFragment::BoldItalic(fragments) => {
+                let text = convert_fragments_to_markdown(fragments);
+                acc.push_str(&delimit(&text, BITALIC_1, BITALIC_2))
+            }
+            MdLineFragment::Strikethrough(fragments) => {
+                let text = convert_fragments_to_markdown(fragments);
+                acc.push_str(&format!("{STRIKETHROUGH}{text}{STRIKETHROUGH}"))
+            }
+            MdLineFragment::InlineCode(text) => {
+                acc.push_str(&format!("{BACK_TICK}{text}{BACK_TICK}"))
+            }
+            MdLineFragment::Escaped(text) => acc.push_str(&format!("{BACKSLASH}{text}")),
+            MdLineFragment::Link(HyperlinkData { text, url }) => acc.push_str(&format!(
+                "{LEFT_BRACKET}{text}{RIGHT_BRACKET}{LEFT_PARENTHESIS}{url}{RIGHT_PARENTHESIS}"
+            )),
+            MdLineFragment::Image(HyperlinkData { text, url }) => acc.push_str(&format!(
+                "{LEFT_IMAGE}{text}{RIGHT_IMAGE}{LEFT_PARENTHESIS}{url}{RIGHT_PARENTHESIS}"
+            )),
+            MdLineFragment::WikiLink(WikiLinkData { target, alias }) => {
+                acc.push_str(WIKI_LINK_START);
+                acc.push_str(target);
+                if let Some(alias) = alias {
+                    acc.push_str(WIKI_LINK_ALIAS_SEPARATOR);
+                    acc.push_str(alias);
+                }
+                acc.push_str(WIKI_LINK_END);
+            }
+            MdLineFragment::AutoLink(url) => acc.push_str(url),
+            MdLineFragment::ReferenceLink(ReferenceLinkData {
+                text, reference, ..
+            }) => acc.push_str(&format!(
+                "{LEFT_BRACKET}{text}{RIGHT_BRACKET}{LEFT_BRACKET}{reference}{RIGHT_BRACKET}"
+            )),
+            MdLineFragment::FootnoteReference(label) => {
+                acc.push_str(&format!("{FOOTNOTE_START}{label}{RIGHT_BRACKET}"))
+            }
+            MdLineFragment::Checkbox(is_checked) => {
+                acc.push_str(if *is_checked { CHECKED } else { UNCHECKED })
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Checks that formatting is idempotent, and that the formatted source parses into the same
+    /// document as the (already normalized) input.
+    fn assert_round_trip(input: &str) {
+        let (_, document) = parse_markdown(input).unwrap();
+        let formatted = convert_to_markdown(&document);
+
+        let (remainder, reparsed_document) = parse_markdown(&formatted).unwrap();
+        assert_eq2!(remainder, "");
+        assert_eq2!(reparsed_document, document);
+        assert_eq2!(convert_to_markdown(&reparsed_document), formatted);
+    }
+
+    /// Checks that formatting is idempotent, and that the formatted source parses into the same
+    /// document as the input, once the things that formatting normalizes on purpose (ordered list
+    /// numbers, metadata position & order, and heading text whitespace) are factored out.
+    fn assert_round_trip_modulo_normalization(input: &str) {
+        let (_, document) = parse_markdown(input).unwrap();
+        let formatted = convert_to_markdown(&document);
+
+        let (remainder, reparsed_document) = parse_markdown(&formatted).unwrap();
+        assert_eq2!(remainder, "");
+        assert_eq2!(
+            without_normalization(&reparsed_document),
+            without_normalization(&document)
+        );
+        assert_eq2!(convert_to_markdown(&reparsed_document), formatted);
+    }
+
+    /// Remove everything from the given document that formatting normalizes: ordered list numbers
+    /// are zeroed, heading text is trimmed, and the metadata blocks are (stably) moved to the top
+    /// in the order that they are emitted.
+    fn without_normalization<'a>(document: &MdDocument<'a>) -> MdDocument<'a> {
+        fn metadata_rank(block: &MdBlockElement) -> usize {
+            match block {
+                MdBlockElement::FrontMatter(_) => 0,
+                MdBlockElement::Title(_) => 1,
+                MdBlockElement::Tags(_) => 2,
+                MdBlockElement::Authors(_) => 3,
+                MdBlockElement::Date(_) => 4,
+                _ => 5,
+            }
+        }
+
+        let mut blocks: Vec<MdBlockElement<'a>> = document
+            .iter()
+            .map(|block| match block {
+                MdBlockElement::Heading(HeadingData { level, text }) => {
+                    MdBlockElement::Heading(HeadingData {
+                        level: *level,
+                        text: text.trim(),
+                    })
+                }
+                MdBlockElement::SmartList((lines, bullet_kind, indent)) => {
+                    let bullet_kind = match bullet_kind {
+                        BulletKind::Ordered(_) => BulletKind::Ordered(0),
+                        BulletKind::Unordered => BulletKind::Unordered,
+                    };
+                    let lines = lines
+                        .iter()
+                        .map(|line| {
+                            line.iter()
+                                .map(|fragment| match fragment {
+                                    MdLineFragment::OrderedListBullet {
+                                        indent,
+                                        is_first_line,
+                                        ..
+                                    } => MdLineFragment::OrderedListBullet {
+                                        indent: *indent,
+                                        number: 0,
+                                        is_first_line: *is_first_line,
+                                    },
+                                    _ => fragment.clone(),
+                                })
+                                .collect::<Vec<_>>()
+                                .into()
+                        })
+                        .collect::<Vec<_>>()
+                        .into();
+                    MdBlockElement::SmartList((lines, bullet_kind, *indent))
+                }
+                _ => block.clone(),
+            })
+            .collect();
+        blocks.sort_by_key(metadata_rank);
+        blocks.into()
+    }
+
+    /// The documents that are used as test inputs elsewhere in the `md_parser` module. Each of
+    /// them has to survive a round trip through the formatter.
+    const PARSER_TEST_DOCUMENTS: &[&[&str]] = &[
+        // `test_parse_markdown_with_table` in `parser.rs`.
+        &[
+            "# Fruits",
+            "| Name | Qty |",
+            "| ---- | --: |",
+            "| apple | 3 |",
+            "end",
+            "",
+        ],
+        // `test_parse_markdown_with_quote_and_rule` in `parser.rs`.
+        &[
+            "> quote ~~struck~~",
+            "> > nested",
+            "---",
+            "- - -",
+            "- item",
+            "***",
+            "end",
+            "",
+        ],
+        // `test_parse_markdown_with_front_matter` in `parser.rs`.
+        &[
+            "---",
+            "title: Something",
+            "---",
+            "---",
+            "key: value",
+            "---",
+            "",
+        ],
+        // `test_markdown_invalid` in `parser.rs`.
+        &[
+            "@tags: [foo, bar",
+            "",
+            "```rs",
+            "let a=1;",
+            "```",
+            "",
+            "*italic* **bold** [link](https://example.com)",
+            "",
+            "`inline code`",
+        ],
+        // `test_parse_valid_md_ol_with_indent` in `block/parse_block_smart_list.rs`.
+        &[
+            "start",
+            "1. ol1",
+            "  2. ol2",
+            "     ol2.1",
+            "    3. ol3",
+            "       ol3.1",
+            "       ol3.2",
+            "end",
+            "",
+        ],
+        // `test_parse_valid_md_ul_with_indent` in `block/parse_block_smart_list.rs`.
+        &[
+            "start",
+            "- ul1",
+            "  - ul2",
+            "    ul2.1",
+            "    - ul3",
+            "      ul3.1",
+            "      ul3.2",
+            "end",
+            "",
+        ],
+        // `test_parse_valid_md_multiline_no_indent` in `block/parse_block_smart_list.rs`.
+        &[
+            "start",
+            "- ul1",
+            "- ul2",
+            "  ul2.1",
+            "  ",
+            "- ul3",
+            "  ul3.1",
+            "  ul3.2",
+            "1. ol1",
+            "2. ol2",
+            "   ol2.1",
+            "3. ol3",
+            "   ol3.1",
+            "   ol3.2",
+            "- [ ] todo",
+            "- [x] done",
+            "end",
+            "",
+        ],
+        // `test_parse_valid_md_no_indent` in `block/parse_block_smart_list.rs`.
+        &[
+            "start",
+            "- ul1",
+            "- ul2",
+            "1. ol1",
+            "2. ol2",
+            "- [ ] todo",
+            "- [x] done",
+            "end",
+            "",
+        ],
+        // `test_block_spans` in `source_span.rs`.
+        &[
+            "@title: Something",
+            "# Heading",
+            "",
+            "some *italic* and [link](url) ![img](src.jpg)",
+            "- [x] done",
+            "  more **bold**",
+            "> `code`",
+            "| a | ~~b~~ |",
+            "| - | - |",
+            "```rust",
+            "let a = 1;",
+            "```",
+            "end",
+            "",
+        ],
+        // `test_ordered_list_spans` in `source_span.rs`.
+        &["1. one", "  10. ten", "      ten.1", "- ", ""],
+        // `test_find_md_node_at` in `source_span.rs`.
+        &["# Heading", "some *italic* 😃 `code`", ""],
+        // `test_footnote_spans` in `source_span.rs`.
+        &["Text[^1].", "[^1]: A *note*.", ""],
+        // `test_link_spans` in `source_span.rs`.
+        &["[[a|b]] [c][] https://d.e", "[c]: https://c.com", ""],
+        // `test_resolve_reference_links` in `links.rs`.
+        &[
+            "See [the site][R3BL] & [r3bl][].",
+            "[Missing][nowhere]",
+            "[r3bl]: https://r3bl.com",
+            "[r3bl]: https://ignored.com",
+        ],
+        // `test_extract_links` in `links.rs`.
+        &[
+            "# Links to [[Home]]",
+            "An [inline](https://a.com) & ![image](b.png) link.",
+            "- [[Some Note|alias]] and https://c.com/path(1)).",
+            "> quoted [ref link][ref]",
+            "",
+            "[Ref]:   https://d.com \"Title\"",
+        ],
+        // `test_get_task_stats_by_section` in `task_stats.rs`.
+        &[
+            "- [x] before the first heading",
+            "# Project",
+            "- [ ] plan",
+            "## Backend",
+            "- [x] api",
+            "- [x] db",
+            "  - [ ] migrations",
+            "### Tests",
+            "- [ ] unit",
+            "## Frontend",
+            "Nothing here yet [x] (this isn't a task).",
+            "# Other",
+            "- no checkbox",
+            "",
+        ],
+        // `test_list_hanging_indent_and_quote_gutter` in `convert_to_ansi_text.rs`.
+        &["- one two three four", "> one two three", ""],
+        // `test_code_block_not_wrapped_and_rule_clipped` in `convert_to_ansi_text.rs`.
+        &["```", "let a_long_name = 1;", "```", "---", ""],
+        // `test_footnotes` in `convert_to_html.rs`.
+        &[
+            "[^2]: Defined *first*.",
+            "Some text[^1] & more[^2].",
+            "",
+            "[^1]: A <footnote>.",
+        ],
+        // `test_metadata_goes_in_head` in `convert_to_html.rs`.
+        &[
+            "@title: Tom & Jerry",
+            "@tags: cat, mouse",
+            "@authors: Nazmul, Nadia",
+            "@date: 2023-05-01",
+            "Hello",
+            "",
+        ],
+        // `test_front_matter_goes_in_head` in `convert_to_html.rs`.
+        &[
+            "---",
+            "title: Tom & Jerry",
+            "tags: [cat, mouse]",
+            "draft: true",
+            "---",
+            "Hello",
+            "",
+        ],
+        // `test_links` in `convert_to_html.rs`.
+        &[
+            "[[Note|alias]] https://r3bl.com/?a=1&b=2, [site][r3bl] & [missing][]",
+            "[R3BL]: https://r3bl.com",
+            "",
+        ],
+        // `test_nested_lists` in `convert_to_html.rs`.
+        &[
+            "- ul1",
+            "  - ul2",
+            "    ul2.1",
+            "- [x] done",
+            "3. ol3",
+            "4. ol4",
+            "end",
+            "",
+        ],
+        // `test_blocks` in `convert_to_html.rs`.
+        &[
+            "# Tom & *Jerry*",
+            "line 1",
+            "line 2",
+            "",
+            "```rust",
+            "let a = b < c;",
+            "```",
+            "```",
+            "plain",
+            "```",
+            "> quote",
+            "> > nested",
+            "---",
+            "| a | b |",
+            "| :-: | --: |",
+            "| c | d |",
+            "",
+        ],
+    ];
+
+    /// The Markdown files that are shown by the examples.
+    const EXAMPLE_DOCUMENTS: &[&str] = &[
+        include_str!("../../../examples/demo/ex_pitch/slide1.md"),
+        include_str!("../../../examples/demo/ex_pitch/slide2.md"),
+        include_str!("../../../examples/demo/ex_pitch/slide3.md"),
+        include_str!("../../../examples/demo/ex_pitch/slide3_1.md"),
+        include_str!("../../../examples/demo/ex_pitch/slide4.md"),
+        include_str!("../../../examples/demo/ex_pitch/slide5.md"),
+        include_str!("../../../examples/demo/ex_pitch/slide6.md"),
+        include_str!("../../../examples/demo/ex_pitch/slide7.md"),
+        include_str!("../../../examples/demo/ex_pitch/slide8.md"),
+        include_str!("../../../examples/demo/ex_pitch/slide9.md"),
+        include_str!("../../../examples/demo/ex_pitch/slide10.md"),
+        include_str!("../../../examples/demo/ex_pitch/slide11.md"),
+        include_str!("../../../examples/demo/ex_rc/slide1.md"),
+        include_str!("../../../examples/demo/ex_rc/slide2.md"),
+        include_str!("../../../examples/demo/ex_rc/slide3.md"),
+    ];
+
+    #[test]
+    fn test_round_trip_parser_test_documents() {
+        for lines in PARSER_TEST_DOCUMENTS {
+            assert_round_trip_modulo_normalization(&lines.join("\n"));
+        }
+    }
+
+    #[test]
+    fn test_round_trip_example_documents() {
+        for input in EXAMPLE_DOCUMENTS {
+            assert_round_trip_modulo_normalization(input);
+        }
+    }
+
+    #[test]
+    fn test_round_trip_empty_document() {
+        assert_eq2!(convert_to_markdown(&list![]), "");
+        assert_round_trip("");
+    }
+
+    #[test]
+    fn test_fragments_to_markdown() {
+        let fragments = list![
+            MdLineFragment::Plain("a "),
+            MdLineFragment::Bold(list![MdLineFragment::Plain("bold")]),
+            MdLineFragment::Italic(list![MdLineFragment::Plain("italic")]),
+            MdLineFragment::Italic(list![MdLineFragment::Plain("2*3")]),
+            MdLineFragment::BoldItalic(list![MdLineFragment::Plain("both")]),
+            MdLineFragment::Strikethrough(list![MdLineFragment::Plain("struck")]),
+            MdLineFragment::InlineCode("code"),
+            MdLineFragment::Link(HyperlinkData::new("r3bl", "https://r3bl.com")),
+            MdLineFragment::Image(HyperlinkData::new("alt", "image.jpg")),
+        ];
+        assert_eq2!(
+            convert_fragments_to_markdown(&fragments),
+            "a **bold***italic*_2*3_***both***~~struck~~`code`[r3bl](https://r3bl.com)![alt](image.jpg)"
+        );
+    }
+
+    #[test]
+    fn test_normalize() {
+        let input = [
+            "# Heading",
+            "@date: 2023-05-01",
+            "@authors: Nazmul, Nadia",
+            "@tags: foo, bar",
+            "@title: Something",
+            "#   Foo   ",
+            "_italic_ and __bold__",
+            "3. first",
+            "3. second",
+            "  1. nested",
+            "  7. nested",
+            "3. third",
+            "   more",
+            "- ul",
+            "1. restart",
+            ">> deep",
+            ">",
+            "***",
+            "| a | long column |",
+            "|:-:|-:|",
+            "| 😃 | b |",
+            "",
+        ]
+        .join("\n");
+        let (remainder, document) = parse_markdown(&input).unwrap();
+        assert_eq2!(remainder, "");
+        assert_eq2!(
+            convert_to_markdown(&document),
+            [
+                "@title: Something",
+                "@tags: foo, bar",
+                "@authors: Nazmul, Nadia",
+                "@date: 2023-05-01",
+                "# Heading",
+                "# Foo",
+                "*italic* and **bold**",
+                "3. first",
+                "4. second",
+                "  1. nested",
+                "  2. nested",
+                "5. third",
+                "   more",
+                "- ul",
+                "1. restart",
+                "> > deep",
+                ">",
+                "---",
+                "| a   | long column |",
+                "| :-: | ----------: |",
+                "| 😃  | b           |",
+                "",
+            ]
+            .join("\n")
+        );
+        assert_round_trip_modulo_normalization(&input);
+    }
+
+    #[test]
+    fn test_format_is_idempotent() {
+        let input = [
+            "@tags: foo, bar",
+            "@title: Something",
+            "##    Foo",
+            "1. one",
+            "1. two",
+            "    1. three",
+            "| x |",
+            "| --- |",
+            "",
+        ]
+        .join("\n");
+        let once = format_markdown(&input);
+        let twice = format_markdown(&once);
+        assert_eq2!(once, twice);
+        assert_round_trip_modulo_normalization(&input);
+    }
+
+    #[test]
+    fn test_format_markdown_keeps_unparsed_remainder() {
+        // Text that doesn't end in a newline is not parsed.
+        let input = "#  Foo\nno newline";
+        assert_eq2!(format_markdown(input), "# Foo\nno newline");
+        assert_eq2!(format_markdown(""), "");
+    }
+
+    /// Round trip the input that is used to test [parse_markdown].
+    #[test]
+    fn test_round_trip_parser_fixture() {
+        let input = [
+            "@title: Something",
+            "@tags: tag1, tag2, tag3",
+            "# Foobar",
+            "",
+            "Foobar is a Python library for dealing with word pluralization.",
+            "",
+            "```bash",
+            "pip install foobar",
+            "```",
+            "```fish",
+            "```",
+            "```python",
+            "",
+            "```",
+            "## Installation",
+            "",
+            "Use the package manager [pip](https://pip.pypa.io/en/stable/) to install foobar.",
+            "```python",
+            "import foobar",
+            "",
+            "foobar.pluralize('word') # returns 'words'",
+            "foobar.pluralize('goose') # returns 'geese'",
+            "foobar.singularize('phenomena') # returns 'phenomenon'",
+            "```",
+            "- ul1",
+            "- ul2",
+            "1. ol1",
+            "2. ol2",
+            "- [ ] todo",
+            "- [x] done",
+            "end",
+            "",
+        ]
+        .join("\n");
+        assert_round_trip(&input);
+        assert_eq2!(format_markdown(&input), input);
+    }
+
+    #[test]
+    fn test_round_trip_nested_blocks() {
+        let input = [
+            "start",
+            "1. ol1",
+            "  2. ol2",
+            "     ol2.1",
+            "    3. ol3",
+            "       ol3.1",
+            "- [x] ~~done~~ *and* `code`",
+            "> quote",
+            "> > **nested**",
+            "---",
+            "| Name | *Qty* |",
+            "| :--- | ----: |",
+            "| apple | 3 |",
+            "| pear |",
+            "end",
+            "",
+        ]
+        .join("\n");
+        assert_round_trip(&input);
+    }
+
+    #[test]
+    fn test_round_trip_front_matter() {
+        let input = [
+            "---",
+            "title: Something",
+            "authors:",
+            "  - Alice",
+            "---",
+            "@tags: foo, bar",
+            "text",
+            "",
+        ]
+        .join("\n");
+        assert_round_trip(&input);
+        assert_eq2!(format_markdown(&input), input);
+    }
+
+    #[test]
+    fn test_round_trip_nested_formatting_and_escapes() {
+        let input = [
+            "**bold w/ `code`, *italic* & [link](url)** ~~*gone*~~",
+            "\\*not italic\\*, snake_case_name, 2 * 3 & [not a link",
+            "",
+        ]
+        .join("\n");
+        assert_round_trip(&input);
+        // Bold that contains italic is delimited w/ `__`.
+        assert_eq2!(format_markdown(&input), input.replacen("**", "__", 2));
+    }
+
+    #[test]
+    fn test_round_trip_links() {
+        let input = [
+            "See [[Note]], [[Note|alias]], https://r3bl.com & [site][r3bl] [r3bl][].",
+            "[r3bl]: https://r3bl.com",
+            "[other]: https://example.com \"Title\"",
+            "",
+        ]
+        .join("\n");
+        assert_round_trip(&input);
+        assert_eq2!(format_markdown(&input), input);
+    }
+
+    #[test]
+    fn test_round_trip_footnotes() {
+        let input = [
+            "Some text[^1] & more[^note].",
+            "[^1]: A *formatted* footnote w/ a [link](https://r3bl.com).",
+            "[^note]:",
+            "",
+        ]
+        .join("\n");
+        assert_round_trip(&input);
+        assert_eq2!(format_markdown(&input), input);
+    }
+
+    /// Round trip the test asset that is used by the syntax highlighter. Only the part of the
+    /// document that can be parsed is formatted.
+    #[test]
+    fn test_round_trip_valid_content_fixture() {
+        let input = include_str!("../syntax_highlighting/test_assets/valid-content.md");
+        assert_round_trip(input);
+    }
+}
@@ -62,10 +62,45 @@ impl<'a> PrettyPrintDebug for MdBlockElement<'a> {
                 };
                 format!("code block, line count: {line_count}, lang: {lang}")
             }
+            MdBlockElement::Table(table_data) => {
+                let column_count = table_data.column_count();
+                let row_count = table_data.rows.len();
+                format!("table, column count: {column_count}, row count: {row_count}")
+            }
+            MdBlockElement::BlockQuote(block_quote_lines) => format!(
+                "[  {}  ]",
+                block_quote_lines
+                    .iter()
+                    .map(|block_quote_line| format!(
+                        "┊{}{}┊",
+                        BLOCK_QUOTE_CHAR.to_string().repeat(block_quote_line.depth),
+                        block_quote_line.content.pretty_print_debug()
+                    ))
+                    .collect::<Vec<String>>()
+                    .join(" → ")
+            ),
+            MdBlockElement::HorizontalRule => "horizontal rule".to_string(),
             MdBlockElement::Title(title) => format!("title: {}", title),
             MdBlockElement::Tags(tags) => format!("tags: {}", tags.join(", ")),
             MdBlockElement::Date(date) => format!("title: {}", date),
             MdBlockElement::Authors(authors) => format!("tags: {}", authors.join(", ")),
+            MdBlockElement::FrontMatter(front_matter) => {
+                format!("front matter, line count: {}", front_matter.lines.len())
+            }
+            MdBlockElement::LinkDefinition(LinkDefinitionData {
+                reference, url, ..
+            }) => {
+                format!("link definition: {reference} → {url}")
+            }
+            MdBlockElement::FootnoteDefinition(FootnoteDefinitionData {
+                label,
+                content,
+            }) => {
+                format!(
+                    "footnote definition: {label} → {}",
+                    content.pretty_print_debug()
+                )
+            }
             MdBlockElement::SmartList((list_lines, _bullet_kind, _indent)) => format!(
                 "[  {}  ]",
                 list_lines
@@ -109,10 +144,35 @@ impl PrettyPrintDebug for MdLineFragment<'_> {
                     "{LEFT_IMAGE}{alt_text}{RIGHT_IMAGE}{LEFT_PARENTHESIS}{url}{RIGHT_PARENTHESIS}"
                 )
             }
-            MdLineFragment::Bold(text) => format!("{BOLD_1}{text}{BOLD_1}"),
-            MdLineFragment::Italic(text) => format!("{ITALIC_1}{text}{ITALIC_1}"),
-            MdLineFragment::BoldItalic(text) => format!("{BITALIC_1}{text}{BITALIC_1}"),
+            MdLineFragment::WikiLink(WikiLinkData { target, alias }) => match alias {
+                Some(alias) => format!(
+                    "{WIKI_LINK_START}{target}{WIKI_LINK_ALIAS_SEPARATOR}{alias}{WIKI_LINK_END}"
+                ),
+                None => format!("{WIKI_LINK_START}{target}{WIKI_LINK_END}"),
+            },
+            MdLineFragment::AutoLink(url) => url.to_string(),
+            MdLineFragment::ReferenceLink(ReferenceLinkData {
+                text, reference, ..
+            }) => {
+                format!("{LEFT_BRACKET}{text}{RIGHT_BRACKET}{LEFT_BRACKET}{reference}{RIGHT_BRACKET}")
+            }
+            MdLineFragment::FootnoteReference(label) => {
+                format!("{FOOTNOTE_START}{label}{RIGHT_BRACKET}")
+            }
+            MdLineFragment::Bold(fragments) => {
+                format!("{BOLD_1}{}{BOLD_1}", fragments.pretty_print_debug())
+            }
+            MdLineFragment::Italic(fragments) => {
+                format!("{ITALIC_1}{}{ITALIC_1}", fragments.pretty_print_debug())
+            }
+            MdLineFragment::BoldItalic(fragments) => {
+                format!("{BITALIC_1}{}{BITALIC_1}", fragments.pretty_print_debug())
+            }
+            MdLineFragment::Strikethrough(fragments) => {
+                format!("{STRIKETHROUGH}{}{STRIKETHROUGH}", fragments.pretty_print_debug())
+            }
             MdLineFragment::InlineCode(text) => format!("{BACK_TICK}{text}{BACK_TICK}"),
+            MdLineFragment::Escaped(text) => format!("{BACKSLASH}{text}"),
             MdLineFragment::Checkbox(is_checked) => {
                 (if *is_checked { CHECKED } else { UNCHECKED }).to_string()
             }
@@ -195,17 +255,25 @@ mod to_plain_text_tests {
             "![some image text](https://r3bl.com)"
         );
         assert_eq2!(
-            MdLineFragment::Bold("Hello World").pretty_print_debug(),
+            MdLineFragment::Bold(list![MdLineFragment::Plain("Hello World")])
+                .pretty_print_debug(),
             "**Hello World**"
         );
         assert_eq2!(
-            MdLineFragment::Italic("Hello World").pretty_print_debug(),
+            MdLineFragment::Italic(list![MdLineFragment::Plain("Hello World")])
+                .pretty_print_debug(),
             "*Hello World*"
         );
         assert_eq2!(
-            MdLineFragment::BoldItalic("Hello World").pretty_print_debug(),
+            MdLineFragment::BoldItalic(list![MdLineFragment::Plain("Hello World")])
+                .pretty_print_debug(),
             "***Hello World***"
         );
+        assert_eq2!(
+            MdLineFragment::Strikethrough(list![MdLineFragment::Plain("Hello World")])
+                .pretty_print_debug(),
+            "~~Hello World~~"
+        );
         assert_eq2!(
             MdLineFragment::InlineCode("Hello World").pretty_print_debug(),
             "`Hello World`"
@@ -0,0 +1,305 @@
+/*
+ *   Copyright (c) 2023 R3BL LLC
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+//! This module is responsible for the links in a [MdDocument].
+//!
+//! 1. Reference links (eg: `[text][reference]`) get their url from a link definition (eg:
+//!    `[reference]: https://r3bl.com`) that can be anywhere in the document. So they can only be
+//!    resolved once the whole document has been parsed, which is what [resolve_reference_links]
+//!    does. [parse_markdown] calls it, so there is no need to call it yourself.
+//! 2. Every link in a document (inline, image, wiki, auto, and reference) can be extracted using
+//!    [extract_links]. This is useful for building a backlink index across many documents.
+
+use std::collections::HashMap;
+
+use crate::*;
+
+/// The kind of a [MdLink], which corresponds to the [MdLineFragment] that it came from.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum MdLinkKind {
+    /// [MdLineFragment::Link], eg: `[text](url)`.
+    Inline,
+    /// [MdLineFragment::Image], eg: `![text](url)`.
+    Image,
+    /// [MdLineFragment::WikiLink], eg: `[[target|alias]]`.
+    Wiki,
+    /// [MdLineFragment::AutoLink], eg: `https://r3bl.com`.
+    Auto,
+    /// [MdLineFragment::ReferenceLink], eg: `[text][reference]`.
+    Reference,
+}
+
+/// A link that is found in a [MdDocument] by [extract_links].
+#[derive(Clone, Debug, PartialEq)]
+pub struct MdLink<'a> {
+    pub kind: MdLinkKind,
+    /// The text that is displayed for the link.
+    pub text: &'a str,
+    /// The destination of the link. For [MdLinkKind::Wiki] this is the target (eg: the name of
+    /// another note). For [MdLinkKind::Reference] this is [None] if there is no link definition
+    /// for its reference.
+    pub url: Option<&'a str>,
+    /// Index of the [MdBlockElement] (in the [MdDocument]) that contains the link.
+    pub block_index: usize,
+}
+
+/// Fill in the url of every [MdLineFragment::ReferenceLink] in the document, using the
+/// [MdBlockElement::LinkDefinition]s in it. References are case insensitive, and if there are
+/// multiple definitions w/ the same reference, the first one is used.
+pub fn resolve_reference_links<'a>(document: &mut MdDocument<'a>) {
+    let definitions = collect_link_definitions(document);
+    if definitions.is_empty() {
+        return;
+    }
+
+    let resolve = |fragments: &mut MdLineFragments<'a>| {
+        for fragment in fragments.iter_mut() {
+            if let MdLineFragment::ReferenceLink(reference_link) = fragment {
+                reference_link.url = definitions
+                    .get(&normalize_reference(reference_link.get_reference()))
+                    .copied();
+            }
+        }
+    };
+
+    for block in document.iter_mut() {
+        match block {
+            MdBlockElement::Text(fragments) => resolve(fragments),
+            MdBlockElement::SmartList((lines, _, _)) => {
+                lines.iter_mut().for_each(resolve)
+            }
+            MdBlockElement::BlockQuote(lines) => {
+                lines.iter_mut().for_each(|line| resolve(&mut line.content))
+            }
+            MdBlockElement::Table(table_data) => {
+                table_data.header.iter_mut().for_each(resolve);
+                for row in table_data.rows.iter_mut() {
+                    row.iter_mut().for_each(resolve);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Returns every link in the document, in the order in which they appear. Link definitions are
+/// not links themselves, so they are not returned.
+pub fn extract_links<'a>(document: &MdDocument<'a>) -> List<MdLink<'a>> {
+    let definitions = collect_link_definitions(document);
+    let mut acc = List::new();
+
+    for (block_index, block) in document.iter().enumerate() {
+        let mut extract = |fragments: &MdLineFragments<'a>| {
+            for fragment in fragments.iter() {
+                if let Some(link) =
+                    link_from_fragment(fragment, block_index, &definitions)
+                {
+                    acc.push(link);
+                }
+            }
+        };
+
+        match block {
+            MdBlockElement::Heading(heading_data) => {
+                extract(&parse_block_markdown_text_lenient(heading_data.text))
+            }
+            MdBlockElement::Text(fragments) => extract(fragments),
+            MdBlockElement::SmartList((lines, _, _)) => lines.iter().for_each(extract),
+            MdBlockElement::BlockQuote(lines) => {
+                lines.iter().for_each(|line| extract(&line.content))
+            }
+            MdBlockElement::Table(table_data) => {
+                table_data.header.iter().for_each(&mut extract);
+                for row in table_data.rows.iter() {
+                    row.iter().for_each(&mut extract);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    acc
+}
+
+fn link_from_fragment<'a>(
+    fragment: &MdLineFragment<'a>,
+    block_index: usize,
+    definitions: &HashMap<String, &'a str>,
+) -> Option<MdLink<'a>> {
+    let (kind, text, url) = match fragment {
+        MdLineFragment::Link(hyperlink_data) => (
+            MdLinkKind::Inline,
+            hyperlink_data.text,
+            Some(hyperlink_data.url),
+        ),
+        MdLineFragment::Image(hyperlink_data) => (
+            MdLinkKind::Image,
+            hyperlink_data.text,
+            Some(hyperlink_data.url),
+        ),
+        MdLineFragment::WikiLink(wiki_link_data) => (
+            MdLinkKind::Wiki,
+            wiki_link_data.get_text(),
+            Some(wiki_link_data.target),
+        ),
+        MdLineFragment::AutoLink(url) => (MdLinkKind::Auto, *url, Some(*url)),
+        MdLineFragment::ReferenceLink(reference_link_data) => (
+            MdLinkKind::Reference,
+            reference_link_data.text,
+            reference_link_data.url.or_else(|| {
+                definitions
+                    .get(&normalize_reference(reference_link_data.get_reference()))
+                    .copied()
+            }),
+        ),
+        _ => return None,
+    };
+    Some(MdLink {
+        kind,
+        text,
+        url,
+        block_index,
+    })
+}
+
+/// key: normalized reference, value: url.
+fn collect_link_definitions<'a>(document: &MdDocument<'a>) -> HashMap<String, &'a str> {
+    let mut acc = HashMap::new();
+    for block in document.iter() {
+        if let MdBlockElement::LinkDefinition(link_definition_data) = block {
+            acc.entry(normalize_reference(link_definition_data.reference))
+                .or_insert(link_definition_data.url);
+        }
+    }
+    acc
+}
+
+/// References are matched case insensitively, and any run of whitespace is the same as a single
+/// space.
+fn normalize_reference(reference: &str) -> String {
+    reference
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use r3bl_rs_utils_core::*;
+
+    use super::*;
+
+    #[test]
+    fn test_resolve_reference_links() {
+        let input = [
+            "See [the site][R3BL] & [r3bl][].",
+            "[Missing][nowhere]",
+            "[r3bl]: https://r3bl.com",
+            "[r3bl]: https://ignored.com",
+        ]
+        .join("\n");
+        let (_, document) = parse_markdown(&input).unwrap();
+        assert_eq2!(
+            document[0],
+            MdBlockElement::Text(list![
+                MdLineFragment::Plain("See "),
+                MdLineFragment::ReferenceLink(ReferenceLinkData {
+                    text: "the site",
+                    reference: "R3BL",
+                    url: Some("https://r3bl.com"),
+                }),
+                MdLineFragment::Plain(" & "),
+                MdLineFragment::ReferenceLink(ReferenceLinkData {
+                    text: "r3bl",
+                    reference: "",
+                    url: Some("https://r3bl.com"),
+                }),
+                MdLineFragment::Plain("."),
+            ])
+        );
+        assert_eq2!(
+            document[1],
+            MdBlockElement::Text(list![MdLineFragment::ReferenceLink(
+                ReferenceLinkData::new("Missing", "nowhere")
+            )])
+        );
+        assert_eq2!(
+            document[2],
+            MdBlockElement::LinkDefinition(LinkDefinitionData {
+                reference: "r3bl",
+                url: "https://r3bl.com",
+                title: None,
+            })
+        );
+    }
+
+    #[test]
+    fn test_extract_links() {
+        let input = [
+            "# Links to [[Home]]",
+            "An [inline](https://a.com) & ![image](b.png) link.",
+            "- [[Some Note|alias]] and https://c.com/path(1)).",
+            "> quoted [ref link][ref]",
+            "",
+            "[Ref]:   https://d.com \"Title\"",
+        ]
+        .join("\n");
+        let (_, document) = parse_markdown(&input).unwrap();
+        let links = extract_links(&document);
+        assert_eq2!(
+            links,
+            list![
+                MdLink {
+                    kind: MdLinkKind::Wiki,
+                    text: "Home",
+                    url: Some("Home"),
+                    block_index: 0,
+                },
+                MdLink {
+                    kind: MdLinkKind::Inline,
+                    text: "inline",
+                    url: Some("https://a.com"),
+                    block_index: 1,
+                },
+                MdLink {
+                    kind: MdLinkKind::Image,
+                    text: "image",
+                    url: Some("b.png"),
+                    block_index: 1,
+                },
+                MdLink {
+                    kind: MdLinkKind::Wiki,
+                    text: "alias",
+                    url: Some("Some Note"),
+                    block_index: 2,
+                },
+                MdLink {
+                    kind: MdLinkKind::Auto,
+                    text: "https://c.com/path(1)",
+                    url: Some("https://c.com/path(1)"),
+                    block_index: 2,
+                },
+                MdLink {
+                    kind: MdLinkKind::Reference,
+                    text: "ref link",
+                    url: Some("https://d.com"),
+                    block_index: 3,
+                },
+            ]
+        );
+    }
+}
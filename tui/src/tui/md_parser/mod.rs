@@ -31,25 +31,53 @@
 //!    [MdBlockElement], [MdLineFragment] and all the other intermediate types & enums required for
 //!    parsing.
 //! 3. All the parsers related to parsing metadata specific for R3BL applications which are not
-//!    standard Markdown can be found in [parse_metadata_kv] and [parse_metadata_kcsv].
+//!    standard Markdown can be found in [parse_metadata_kv] and [parse_metadata_kcsv]. YAML front
+//!    matter (which populates the same metadata) is parsed in [parse_metadata_front_matter].
 //! 4. All the parsers that are related to parsing the main "blocks" of Markdown, such as order
-//!    lists, unordered lists, code blocks, text blocks, heading blocks, can be found [block].
+//!    lists, unordered lists, code blocks, tables, text blocks, heading blocks, can be found
+//!    [block].
 //! 5. All the parsers that are related to parsing a single line of Markdown text, such as links,
 //!    bold, italic, etc. can be found [parse_element].
+//! 6. The [MdDocument] can be exported as a standalone HTML document using
+//!    [convert_to_html()], or formatted back into canonical Markdown source using
+//!    [convert_to_markdown()]. It can also be converted into word wrapped, styled ANSI text (eg: to
+//!    print it to stdout) using [convert_to_ansi_text()].
+//! 7. The [SourceSpan] of every block & fragment can be recorded by using
+//!    [parse_markdown_with_spans], and the innermost node at the editor caret can be found using
+//!    [find_md_node_at_caret]. The code for this is in [source_span].
+//! 8. Reference links are resolved against the link definitions in the document, and every link
+//!    in a [MdDocument] can be extracted using [extract_links]. The code for this is in [links].
+//! 9. The tasks (checkboxes) in a [MdDocument] can be counted per heading section using
+//!    [get_task_stats_by_section], eg: to show progress bars. The code for this is in
+//!    [task_stats].
 
 // External use.
 pub mod block;
+pub mod convert_to_ansi_text;
+pub mod convert_to_html;
+pub mod convert_to_markdown;
 pub mod convert_to_plain_text;
+pub mod links;
 pub mod parse_element;
+pub mod parse_metadata_front_matter;
 pub mod parse_metadata_kcsv;
 pub mod parse_metadata_kv;
 pub mod parser;
+pub mod source_span;
+pub mod task_stats;
 pub mod types;
 
 pub use block::*;
+pub use convert_to_ansi_text::*;
+pub use convert_to_html::*;
+pub use convert_to_markdown::*;
 pub use convert_to_plain_text::*;
+pub use links::*;
 pub use parse_element::*;
+pub use parse_metadata_front_matter::*;
 pub use parse_metadata_kcsv::*;
 pub use parse_metadata_kv::*;
 pub use parser::*;
+pub use source_span::*;
+pub use task_stats::*;
 pub use types::*;
@@ -24,34 +24,123 @@ use nom::{branch::*,
           bytes::complete::*,
           character::complete::*,
           combinator::*,
-          multi::*,
           sequence::*,
           IResult};
 
 use crate::*;
 
-#[rustfmt::skip]
-pub fn parse_element_bold_italic(input: &str) -> IResult<&str, &str> {
-    alt((
-        delimited(/* start */ tag(BITALIC_1), /* output */ is_not(BITALIC_1), /* end */ tag(BITALIC_1)),
-        delimited(/* start */ tag(BITALIC_2), /* output */ is_not(BITALIC_2), /* end */ tag(BITALIC_2)),
-    ))(input)
+/// Eg: `***bold italic***` or `___bold italic___`. See [parse_element_delimited_span] for the
+/// rules.
+pub fn parse_element_bold_italic(input: &str) -> IResult<&str, MdLineFragments> {
+    parse_element_delimited_span(input, &[BITALIC_1, BITALIC_2], 0)
 }
 
-#[rustfmt::skip]
-pub fn parse_element_bold(input: &str) -> IResult<&str, &str> {
-    alt((
-        delimited(/* start */ tag(BOLD_1), /* output */ is_not(BOLD_1), /* end */ tag(BOLD_1)),
-        delimited(/* start */ tag(BOLD_2), /* output */ is_not(BOLD_2), /* end */ tag(BOLD_2)),
-    ))(input)
+/// Eg: `**bold**` or `__bold__`. See [parse_element_delimited_span] for the rules.
+pub fn parse_element_bold(input: &str) -> IResult<&str, MdLineFragments> {
+    parse_element_delimited_span(input, &[BOLD_1, BOLD_2], 0)
 }
 
-#[rustfmt::skip]
-pub fn parse_element_italic(input: &str) -> IResult<&str, &str> {
-    alt((
-        delimited(/* start */ tag(ITALIC_1), /* output */ is_not(ITALIC_1), /* end */ tag(ITALIC_1)),
-        delimited(/* start */ tag(ITALIC_2), /* output */ is_not(ITALIC_2), /* end */ tag(ITALIC_2)),
-    ))(input)
+/// Eg: `*italic*` or `_italic_`. See [parse_element_delimited_span] for the rules.
+pub fn parse_element_italic(input: &str) -> IResult<&str, MdLineFragments> {
+    parse_element_delimited_span(input, &[ITALIC_1, ITALIC_2], 0)
+}
+
+/// Eg: `~~strikethrough~~`. See [parse_element_delimited_span] for the rules.
+pub fn parse_element_strikethrough(input: &str) -> IResult<&str, MdLineFragments> {
+    parse_element_delimited_span(input, &[STRIKETHROUGH], 0)
+}
+
+/// Parse a formatting span that starts & ends w/ one of the given `delimiters`. The content is
+/// parsed into [MdLineFragments], so spans can be nested (up to [MAX_INLINE_NESTING_DEPTH]) and
+/// can contain inline code, links, escapes, etc. This follows CommonMark's flanking rules:
+/// 1. The opening delimiter run must be exactly as long as the delimiter (eg: `**` for bold), and
+///    it must not be followed by whitespace.
+/// 2. The closing delimiter must not be preceded by whitespace. If it is preceded by punctuation,
+///    then it must be followed by whitespace, punctuation, or the end of the line.
+/// 3. A closing `_` must not be followed by an alphanumeric char (an opening `_` can't be preceded
+///    by one either, which is taken care of by [parse_element_plaintext]).
+pub fn parse_element_delimited_span<'a>(
+    input: &'a str,
+    delimiters: &[&str],
+    depth: usize,
+) -> IResult<&'a str, MdLineFragments<'a>> {
+    let fail = |input: &'a str, error_kind| {
+        Err(nom::Err::Error(nom::error::Error::new(input, error_kind)))
+    };
+
+    if depth >= MAX_INLINE_NESTING_DEPTH {
+        return fail(input, nom::error::ErrorKind::TooLarge);
+    }
+
+    // Opening delimiter.
+    let Some(delimiter) = delimiters.iter().find(|it| input.starts_with(**it)) else {
+        return fail(input, nom::error::ErrorKind::Tag);
+    };
+    if get_delimiter_run_len(input) != delimiter.len() {
+        return fail(input, nom::error::ErrorKind::Tag);
+    }
+    let content = &input[delimiter.len()..];
+    if !matches!(content.chars().next(), Some(it) if !it.is_whitespace()) {
+        return fail(input, nom::error::ErrorKind::Verify);
+    }
+
+    // Content, until the closing delimiter.
+    let mut fragments = List::new();
+    let mut remainder = content;
+    loop {
+        let maybe_prev_char = content[..content.len() - remainder.len()].chars().last();
+        if let Some(prev_char) = maybe_prev_char {
+            if is_closing_delimiter(prev_char, remainder, delimiter) {
+                return Ok((&remainder[delimiter.len()..], fragments));
+            }
+        }
+        if remainder.is_empty() || remainder.starts_with(NEW_LINE) {
+            return fail(input, nom::error::ErrorKind::Tag);
+        }
+        let (rest, fragment) = parse_element_markdown_inline_at_depth(
+            remainder,
+            CheckboxParsePolicy::IgnoreCheckbox,
+            depth + 1,
+        )?;
+        fragments.push(fragment);
+        remainder = rest;
+    }
+}
+
+/// The number of times that the first char of `input` is repeated at the start of `input`.
+fn get_delimiter_run_len(input: &str) -> usize {
+    let Some(first_char) = input.chars().next() else {
+        return 0;
+    };
+    input.find(|it| it != first_char).unwrap_or(input.len())
+}
+
+fn is_closing_delimiter(prev_char: char, input: &str, delimiter: &str) -> bool {
+    if !input.starts_with(delimiter) || prev_char.is_whitespace() {
+        return false;
+    }
+
+    // A longer run (eg: `***` at the end of `*italic **bold***`) can close more than one span.
+    let run_len = get_delimiter_run_len(input);
+    if run_len != delimiter.len() && run_len < BITALIC_1.len() {
+        return false;
+    }
+
+    let maybe_next_char = input[run_len..].chars().next();
+    let is_next_char_boundary = match maybe_next_char {
+        None => true,
+        Some(it) => it.is_whitespace() || it.is_ascii_punctuation(),
+    };
+    if prev_char.is_ascii_punctuation() && !is_next_char_boundary {
+        return false;
+    }
+    if delimiter.starts_with(ITALIC_2)
+        && matches!(maybe_next_char, Some(it) if it.is_alphanumeric())
+    {
+        return false;
+    }
+
+    true
 }
 
 #[rustfmt::skip]
@@ -68,6 +157,106 @@ pub fn parse_element_link(input: &str) -> IResult<&str, HyperlinkData> {
     Ok((input, HyperlinkData::from(output)))
 }
 
+/// Eg: `[[target]]` or `[[target|alias]]`.
+#[rustfmt::skip]
+pub fn parse_element_wiki_link(input: &str) -> IResult<&str, WikiLinkData> {
+    let (input, output) = delimited(
+        /* start */ tag(WIKI_LINK_START),
+        /* output */ is_not(RIGHT_BRACKET),
+        /* end */ tag(WIKI_LINK_END),
+    )(input)?;
+    let it = match output.split_once(WIKI_LINK_ALIAS_SEPARATOR) {
+        Some((target, alias)) => WikiLinkData { target, alias: Some(alias) },
+        None => WikiLinkData { target: output, alias: None },
+    };
+    Ok((input, it))
+}
+
+/// Eg: `[text][reference]` or `[text][]`. The url is resolved later (at the document level) by
+/// [resolve_reference_links].
+#[rustfmt::skip]
+pub fn parse_element_reference_link(input: &str) -> IResult<&str, ReferenceLinkData> {
+    let (input, (text, reference)) = pair(
+        delimited(/* start */ tag(LEFT_BRACKET), /* output */ is_not(RIGHT_BRACKET), /* end */ tag(RIGHT_BRACKET)),
+        delimited(/* start */ tag(LEFT_BRACKET), /* output */ recognize(opt(is_not(RIGHT_BRACKET))), /* end */ tag(RIGHT_BRACKET)),
+    )(input)?;
+    Ok((input, ReferenceLinkData::new(text, reference)))
+}
+
+/// Eg: `[^1]` or `[^note]`. Returns the label. The label can't contain whitespace.
+#[rustfmt::skip]
+pub fn parse_element_footnote_reference(input: &str) -> IResult<&str, &str> {
+    delimited(
+        /* start */ tag(FOOTNOTE_START),
+        /* output */ is_not(FOOTNOTE_LABEL_STOP_CHARS),
+        /* end */ tag(RIGHT_BRACKET),
+    )(input)
+}
+
+/// A bare url that starts w/ one of the [AUTOLINK_PREFIXES], eg: `https://r3bl.com`. It ends at
+/// whitespace, and any [AUTOLINK_TRAILING_PUNCTUATION] at the end is not part of the url (except
+/// for a `)` that closes a `(` in the url).
+#[rustfmt::skip]
+pub fn parse_element_autolink(input: &str) -> IResult<&str, &str> {
+    let (_, candidate) = recognize(
+        pair(
+            /* prefix */ alt((tag(AUTOLINK_PREFIXES[0]), tag(AUTOLINK_PREFIXES[1]))),
+            /* rest */ take_till1(char::is_whitespace),
+        )
+    )(input)?;
+
+    let mut url = candidate;
+    while let Some(last_char) = url.chars().last() {
+        let is_balanced_parenthesis = last_char == ')'
+            && url.matches(LEFT_PARENTHESIS).count() >= url.matches(RIGHT_PARENTHESIS).count();
+        if !AUTOLINK_TRAILING_PUNCTUATION.contains(&last_char) || is_balanced_parenthesis {
+            break;
+        }
+        url = &url[..url.len() - last_char.len_utf8()];
+    }
+
+    // There has to be something after the prefix.
+    if AUTOLINK_PREFIXES.contains(&url) {
+        return Err(nom::Err::Error(nom::error::Error::new(
+            "Autolink must have something after the prefix.",
+            nom::error::ErrorKind::Fail,
+        )));
+    }
+
+    Ok((&input[url.len()..], url))
+}
+
+/// A backslash followed by an ASCII punctuation char, eg: `\*`. The output is the escaped char.
+/// A backslash that is followed by anything else is just plain text.
+#[rustfmt::skip]
+pub fn parse_element_escape(input: &str) -> IResult<&str, &str> {
+    preceded(
+        /* prefix - discarded */ tag(BACKSLASH),
+        /* output */ recognize(verify(anychar, char::is_ascii_punctuation)),
+    )(input)
+}
+
+/// A run of one of the [UNMATCHED_DELIMITER_CHARS] that doesn't start any other [MdLineFragment],
+/// eg: the `*` in `2 * 3`, or the `[` in `[not a link`. CommonMark treats these as plain text, so
+/// the output is the run, along w/ the plain text that follows it (if any).
+pub fn parse_element_unmatched_delimiter(input: &str) -> IResult<&str, &str> {
+    match input.chars().next() {
+        Some(first_char) if UNMATCHED_DELIMITER_CHARS.contains(&first_char) => {
+            let run_len = get_delimiter_run_len(input);
+            let (rest, _) = opt(parse_element_plaintext)(&input[run_len..])?;
+            Ok((rest, &input[..input.len() - rest.len()]))
+        }
+        _ => Err(nom::Err::Error(nom::error::Error::new(
+            input,
+            if input.is_empty() {
+                nom::error::ErrorKind::Eof
+            } else {
+                nom::error::ErrorKind::Not
+            },
+        ))),
+    }
+}
+
 /// Checkboxes are tricky since they begin with "[" which is also used for hyperlinks and images.
 /// So some extra hint is need from the code calling this parser to let it know whether to parse
 /// a checkbox into plain text, or into a boolean.
@@ -100,65 +289,119 @@ pub fn parse_element_image(input: &str) -> IResult<&str, HyperlinkData> {
 }
 
 /// There must be at least one match. We want to match many things that are not any of our
-/// special tags, but since we have no tools available to match and consume in the negative case
-/// (without regex) we need to match against our (start) tags, then consume one char; we repeat
-/// this until we run into one of our special characters (start tags) then we return this slice.
-#[rustfmt::skip]
+/// special tags, so we check each char to see if one of our special tags (or an escape or an
+/// autolink) starts there, and return the slice up until that char. Unlike the other special tags,
+/// an `_` that is in the middle of a word (eg: `snake_case`) doesn't end the plain text, since it
+/// can't start or end an italic or bold span.
 pub fn parse_element_plaintext(input: &str) -> IResult<&str, &str> {
-    recognize(
-        many1(
-            preceded(
-                /* prefix - discarded */
-                not(
-                    /* starts with special characters */
-                    alt((
-                        tag(BITALIC_1),
-                        tag(BITALIC_2),
-                        tag(BOLD_1),
-                        tag(BOLD_2),
-                        tag(ITALIC_1),
-                        tag(ITALIC_2),
-                        tag(BACK_TICK),
-                        tag(LEFT_BRACKET),
-                        tag(LEFT_IMAGE),
-                        tag(NEW_LINE),
-                    ))
-                ),
-                /* output - keep char */
-                anychar,
-            )
-        )
-    )(input)
+    let mut maybe_prev_char = None;
+    for (index, it) in input.char_indices() {
+        if is_plaintext_end(maybe_prev_char, &input[index..]) {
+            if index == 0 {
+                return Err(nom::Err::Error(nom::error::Error::new(
+                    input,
+                    nom::error::ErrorKind::Not,
+                )));
+            }
+            return Ok((&input[index..], &input[..index]));
+        }
+        maybe_prev_char = Some(it);
+    }
+
+    if input.is_empty() {
+        return Err(nom::Err::Error(nom::error::Error::new(
+            input,
+            nom::error::ErrorKind::Eof,
+        )));
+    }
+    Ok(("", input))
+}
+
+fn is_plaintext_end(maybe_prev_char: Option<char>, input: &str) -> bool {
+    const SPECIAL_TAGS: [&str; 6] = [
+        ITALIC_1,
+        STRIKETHROUGH,
+        BACK_TICK,
+        LEFT_BRACKET,
+        LEFT_IMAGE,
+        NEW_LINE,
+    ];
+
+    if input.starts_with(ITALIC_2) {
+        let is_prev_char_alphanumeric =
+            matches!(maybe_prev_char, Some(it) if it.is_alphanumeric());
+        let is_next_char_alphanumeric = matches!(
+            input[get_delimiter_run_len(input)..].chars().next(),
+            Some(it) if it.is_alphanumeric()
+        );
+        return !(is_prev_char_alphanumeric && is_next_char_alphanumeric);
+    }
+
+    if input.starts_with(BACKSLASH) {
+        return parse_element_escape(input).is_ok();
+    }
+
+    SPECIAL_TAGS.iter().any(|it| input.starts_with(it))
+        || parse_element_autolink(input).is_ok()
 }
 
 /// Parse a single chunk of markdown text (found in a single line of text) into a [MdLineFragment].
-#[rustfmt::skip]
 pub fn parse_element_markdown_inline(
     input: &str,
     checkbox_policy: CheckboxParsePolicy,
 ) -> IResult<&str, MdLineFragment> {
+    parse_element_markdown_inline_at_depth(input, checkbox_policy, 0)
+}
+
+/// Same as [parse_element_markdown_inline], for the content of a formatting span that is nested
+/// `depth` deep.
+#[rustfmt::skip]
+fn parse_element_markdown_inline_at_depth(
+    input: &str,
+    checkbox_policy: CheckboxParsePolicy,
+    depth: usize,
+) -> IResult<&str, MdLineFragment> {
+    let parse_bold_italic = |it| parse_element_delimited_span(it, &[BITALIC_1, BITALIC_2], depth);
+    let parse_bold = |it| parse_element_delimited_span(it, &[BOLD_1, BOLD_2], depth);
+    let parse_italic = |it| parse_element_delimited_span(it, &[ITALIC_1, ITALIC_2], depth);
+    let parse_strikethrough = |it| parse_element_delimited_span(it, &[STRIKETHROUGH], depth);
+
+    // NOTE: The ordering of the parsers below matters. Longer delimiters must come first.
     match checkbox_policy {
         CheckboxParsePolicy::IgnoreCheckbox => alt((
-            map(parse_element_italic, MdLineFragment::Italic),
-            map(parse_element_bold, MdLineFragment::Bold),
-            map(parse_element_bold_italic, MdLineFragment::BoldItalic),
+            map(parse_bold_italic, MdLineFragment::BoldItalic),
+            map(parse_bold, MdLineFragment::Bold),
+            map(parse_italic, MdLineFragment::Italic),
+            map(parse_strikethrough, MdLineFragment::Strikethrough),
             map(parse_element_code, MdLineFragment::InlineCode),
             map(parse_element_image, MdLineFragment::Image),
+            map(parse_element_footnote_reference, MdLineFragment::FootnoteReference),
+            map(parse_element_wiki_link, MdLineFragment::WikiLink),
             map(parse_element_link, MdLineFragment::Link),
             map(parse_element_checkbox_into_str, MdLineFragment::Plain),
+            map(parse_element_reference_link, MdLineFragment::ReferenceLink),
+            map(parse_element_autolink, MdLineFragment::AutoLink),
+            map(parse_element_escape, MdLineFragment::Escaped),
             map(parse_element_plaintext, MdLineFragment::Plain),
+            map(parse_element_unmatched_delimiter, MdLineFragment::Plain),
         ))(input),
         CheckboxParsePolicy::ParseCheckbox => alt((
-            map(parse_element_italic, MdLineFragment::Italic),
-            map(parse_element_bold, MdLineFragment::Bold),
-            map(parse_element_bold_italic, MdLineFragment::BoldItalic),
+            map(parse_bold_italic, MdLineFragment::BoldItalic),
+            map(parse_bold, MdLineFragment::Bold),
+            map(parse_italic, MdLineFragment::Italic),
+            map(parse_strikethrough, MdLineFragment::Strikethrough),
             map(parse_element_code, MdLineFragment::InlineCode),
             map(parse_element_image, MdLineFragment::Image),
+            map(parse_element_footnote_reference, MdLineFragment::FootnoteReference),
+            map(parse_element_wiki_link, MdLineFragment::WikiLink),
             map(parse_element_link, MdLineFragment::Link),
             map(parse_element_checkbox_into_bool, MdLineFragment::Checkbox),
+            map(parse_element_reference_link, MdLineFragment::ReferenceLink),
+            map(parse_element_autolink, MdLineFragment::AutoLink),
+            map(parse_element_escape, MdLineFragment::Escaped),
             map(parse_element_plaintext, MdLineFragment::Plain),
+            map(parse_element_unmatched_delimiter, MdLineFragment::Plain),
         ))(input)
-
     }
 }
 
@@ -200,12 +443,12 @@ mod tests_parse_element {
     fn test_parse_element_italic() {
         assert_eq2!(
             parse_element_italic("*here is italic*"),
-            Ok(("", "here is italic"))
+            Ok(("", list![MdLineFragment::Plain("here is italic")]))
         );
 
         assert_eq2!(
             parse_element_italic("_here is italic_"),
-            Ok(("", "here is italic"))
+            Ok(("", list![MdLineFragment::Plain("here is italic")]))
         );
 
         assert_eq2!(
@@ -236,7 +479,7 @@ mod tests_parse_element {
             parse_element_italic("*"),
             Err(NomErr::Error(Error {
                 input: "*",
-                code: ErrorKind::Tag
+                code: ErrorKind::Verify
             }))
         );
 
@@ -269,7 +512,7 @@ mod tests_parse_element {
     fn test_parse_element_bold_italic() {
         assert_eq2!(
             parse_element_bold_italic("***here is bitalic***"),
-            Ok(("", "here is bitalic"))
+            Ok(("", list![MdLineFragment::Plain("here is bitalic")]))
         );
 
         assert_eq2!(
@@ -290,13 +533,13 @@ mod tests_parse_element {
 
         assert_eq2!(
             parse_element_bold_italic("___here is bitalic___"),
-            Ok(("", "here is bitalic"))
+            Ok(("", list![MdLineFragment::Plain("here is bitalic")]))
         );
 
         assert_eq2!(
             parse_element_bold_italic("___here is bitalic"),
             Err(NomErr::Error(Error {
-                input: "",
+                input: "___here is bitalic",
                 code: ErrorKind::Tag
             }))
         );
@@ -314,12 +557,12 @@ mod tests_parse_element {
     fn test_parse_element_bold() {
         assert_eq2!(
             parse_element_bold("**here is bold**"),
-            Ok(("", "here is bold"))
+            Ok(("", list![MdLineFragment::Plain("here is bold")]))
         );
 
         assert_eq2!(
             parse_element_bold("__here is bold__"),
-            Ok(("", "here is bold"))
+            Ok(("", list![MdLineFragment::Plain("here is bold")]))
         );
 
         assert_eq2!(
@@ -358,7 +601,7 @@ mod tests_parse_element {
             parse_element_bold("**"),
             Err(NomErr::Error(Error {
                 input: "**",
-                code: ErrorKind::Tag
+                code: ErrorKind::Verify
             }))
         );
 
@@ -387,6 +630,173 @@ mod tests_parse_element {
         );
     }
 
+    #[test]
+    fn test_parse_element_strikethrough() {
+        assert_eq2!(
+            parse_element_strikethrough("~~here is strikethrough~~"),
+            Ok(("", list![MdLineFragment::Plain("here is strikethrough")]))
+        );
+
+        assert_eq2!(
+            parse_element_strikethrough("~~here is strikethrough"),
+            Err(NomErr::Error(Error {
+                input: "~~here is strikethrough",
+                code: ErrorKind::Tag
+            }))
+        );
+
+        assert_eq2!(
+            parse_element_strikethrough("~single tilde~"),
+            Err(NomErr::Error(Error {
+                input: "~single tilde~",
+                code: ErrorKind::Tag
+            }))
+        );
+    }
+
+    #[test]
+    fn test_parse_element_nested_formatting() {
+        assert_eq2!(
+            parse_element_bold("**bold w/ `code` & [link](url) inside**"),
+            Ok((
+                "",
+                list![
+                    MdLineFragment::Plain("bold w/ "),
+                    MdLineFragment::InlineCode("code"),
+                    MdLineFragment::Plain(" & "),
+                    MdLineFragment::Link(HyperlinkData::new("link", "url")),
+                    MdLineFragment::Plain(" inside"),
+                ]
+            ))
+        );
+        assert_eq2!(
+            parse_element_italic("*italic **bold** italic*"),
+            Ok((
+                "",
+                list![
+                    MdLineFragment::Plain("italic "),
+                    MdLineFragment::Bold(list![MdLineFragment::Plain("bold")]),
+                    MdLineFragment::Plain(" italic"),
+                ]
+            ))
+        );
+        assert_eq2!(
+            parse_element_bold("**a *b*** c"),
+            Ok((
+                " c",
+                list![
+                    MdLineFragment::Plain("a "),
+                    MdLineFragment::Italic(list![MdLineFragment::Plain("b")]),
+                ]
+            ))
+        );
+        assert_eq2!(
+            parse_element_strikethrough("~~gone *now*~~"),
+            Ok((
+                "",
+                list![
+                    MdLineFragment::Plain("gone "),
+                    MdLineFragment::Italic(list![MdLineFragment::Plain("now")]),
+                ]
+            ))
+        );
+
+        // Nesting is limited to `MAX_INLINE_NESTING_DEPTH` levels, after which delimiters are
+        // kept as plain text.
+        let (_, fragments) = parse_element_italic("*1 **2 ~~3 _4_ 3~~ 2** 1*").unwrap();
+        let MdLineFragment::Bold(bold_fragments) = &fragments[1] else {
+            panic!("expected bold: {fragments:?}");
+        };
+        assert_eq2!(
+            bold_fragments[1],
+            MdLineFragment::Strikethrough(list![
+                MdLineFragment::Plain("3 "),
+                MdLineFragment::Plain("_4"),
+                MdLineFragment::Plain("_ 3"),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_parse_element_flanking_rules() {
+        // An opening delimiter can't be followed by whitespace.
+        assert!(parse_element_italic("* not italic*").is_err());
+        // A closing delimiter can't be preceded by whitespace.
+        assert!(parse_element_italic("*not italic *").is_err());
+        assert_eq2!(
+            parse_element_italic("*a * b*"),
+            Ok((
+                "",
+                list![MdLineFragment::Plain("a "), MdLineFragment::Plain("* b")]
+            ))
+        );
+        // A closing `_` can't be followed by an alphanumeric char.
+        assert!(parse_element_italic("_not_italic").is_err());
+        assert_eq2!(
+            parse_element_italic("_snake_case_"),
+            Ok(("", list![MdLineFragment::Plain("snake_case")]))
+        );
+        // A closing delimiter preceded by punctuation must be followed by a boundary. So the 2nd
+        // `*` can only start a span, which is closed by the 3rd one.
+        assert!(parse_element_italic("*(a)*b*").is_err());
+        assert_eq2!(
+            parse_element_italic("*(a)* b"),
+            Ok((" b", list![MdLineFragment::Plain("(a)")]))
+        );
+        // `*` can be used in the middle of a word.
+        assert_eq2!(
+            parse_element_markdown_inline(
+                "*in*word",
+                CheckboxParsePolicy::IgnoreCheckbox
+            ),
+            Ok((
+                "word",
+                MdLineFragment::Italic(list![MdLineFragment::Plain("in")])
+            ))
+        );
+    }
+
+    #[test]
+    fn test_parse_element_escape() {
+        assert_eq2!(
+            parse_element_escape("\\*not italic"),
+            Ok(("not italic", "*"))
+        );
+        assert_eq2!(parse_element_escape("\\\\"), Ok(("", "\\")));
+        assert!(parse_element_escape("\\a").is_err());
+        assert!(parse_element_escape("\\").is_err());
+        assert_eq2!(
+            parse_element_plaintext("a \\b \\*c"),
+            Ok(("\\*c", "a \\b "))
+        );
+        assert_eq2!(
+            parse_element_italic("*a\\*b*"),
+            Ok((
+                "",
+                list![
+                    MdLineFragment::Plain("a"),
+                    MdLineFragment::Escaped("*"),
+                    MdLineFragment::Plain("b"),
+                ]
+            ))
+        );
+        // Escapes don't work in inline code.
+        assert_eq2!(parse_element_code("`\\*`"), Ok(("", "\\*")));
+    }
+
+    #[test]
+    fn test_parse_element_unmatched_delimiter() {
+        assert_eq2!(
+            parse_element_unmatched_delimiter("** not bold"),
+            Ok(("", "** not bold"))
+        );
+        assert_eq2!(
+            parse_element_unmatched_delimiter("[not a link *a*"),
+            Ok(("*a*", "[not a link "))
+        );
+        assert!(parse_element_unmatched_delimiter("plain").is_err());
+    }
+
     #[test]
     fn test_parse_element_code() {
         assert_eq2!(
@@ -456,6 +866,109 @@ mod tests_parse_element {
         );
     }
 
+    #[test]
+    fn test_parse_element_wiki_link() {
+        assert_eq2!(
+            parse_element_wiki_link("[[Some Note]] foo"),
+            Ok((
+                " foo",
+                WikiLinkData {
+                    target: "Some Note",
+                    alias: None
+                }
+            ))
+        );
+        assert_eq2!(
+            parse_element_wiki_link("[[Some Note|alias]]"),
+            Ok((
+                "",
+                WikiLinkData {
+                    target: "Some Note",
+                    alias: Some("alias")
+                }
+            ))
+        );
+        assert!(parse_element_wiki_link("[[]]").is_err());
+        assert!(parse_element_wiki_link("[[unclosed]").is_err());
+        assert!(parse_element_wiki_link("[title](url)").is_err());
+    }
+
+    #[test]
+    fn test_parse_element_reference_link() {
+        assert_eq2!(
+            parse_element_reference_link("[text][ref] foo"),
+            Ok((" foo", ReferenceLinkData::new("text", "ref")))
+        );
+        assert_eq2!(
+            parse_element_reference_link("[text][]"),
+            Ok(("", ReferenceLinkData::new("text", "")))
+        );
+        assert!(parse_element_reference_link("[text] [ref]").is_err());
+        assert!(parse_element_reference_link("[text](url)").is_err());
+    }
+
+    #[test]
+    fn test_parse_element_footnote_reference() {
+        assert_eq2!(
+            parse_element_footnote_reference("[^1] foo"),
+            Ok((" foo", "1"))
+        );
+        assert_eq2!(
+            parse_element_footnote_reference("[^long-note]: foo"),
+            Ok((": foo", "long-note"))
+        );
+        assert!(parse_element_footnote_reference("[^]").is_err());
+        assert!(parse_element_footnote_reference("[^a b]").is_err());
+        assert!(parse_element_footnote_reference("[1]").is_err());
+
+        assert_eq2!(
+            parse_element_markdown_inline(
+                "[^1](not a link)",
+                CheckboxParsePolicy::IgnoreCheckbox
+            ),
+            Ok(("(not a link)", MdLineFragment::FootnoteReference("1")))
+        );
+    }
+
+    #[test]
+    fn test_parse_element_autolink() {
+        assert_eq2!(
+            parse_element_autolink("https://r3bl.com foo"),
+            Ok((" foo", "https://r3bl.com"))
+        );
+        assert_eq2!(
+            parse_element_autolink("http://r3bl.com/a?b=c."),
+            Ok((".", "http://r3bl.com/a?b=c"))
+        );
+        assert_eq2!(
+            parse_element_autolink("https://en.wikipedia.org/wiki/Rust_(language))."),
+            Ok((").", "https://en.wikipedia.org/wiki/Rust_(language)"))
+        );
+        assert_eq2!(
+            parse_element_autolink("https://r3bl.com**"),
+            Ok(("**", "https://r3bl.com"))
+        );
+        assert!(parse_element_autolink("https://").is_err());
+        assert!(parse_element_autolink("https://.").is_err());
+        assert!(parse_element_autolink("ftp://r3bl.com").is_err());
+    }
+
+    #[test]
+    fn test_parse_element_plaintext_stops_at_links() {
+        assert_eq2!(
+            parse_element_plaintext("see https://r3bl.com"),
+            Ok(("https://r3bl.com", "see "))
+        );
+        assert_eq2!(
+            parse_element_plaintext("https:// is not a link"),
+            Ok(("", "https:// is not a link"))
+        );
+        assert_eq2!(
+            parse_element_plaintext("see [[note]]"),
+            Ok(("[[note]]", "see "))
+        );
+    }
+
     #[test]
     fn test_parse_element_plaintext_unicode() {
         let result = parse_element_plaintext("- straight😃\n");
@@ -483,6 +996,10 @@ mod tests_parse_element {
             parse_element_plaintext("oh my gosh!*"),
             Ok(("*", "oh my gosh!"))
         );
+        assert_eq2!(
+            parse_element_plaintext("oh my ~gosh~~"),
+            Ok(("~~", "oh my ~gosh"))
+        );
         assert_eq2!(
             parse_element_plaintext("*bold baby bold*"),
             Err(NomErr::Error(Error {
@@ -578,14 +1095,20 @@ mod tests_parse_element {
                 "*here is italic*",
                 CheckboxParsePolicy::IgnoreCheckbox
             ),
-            Ok(("", MdLineFragment::Italic("here is italic")))
+            Ok((
+                "",
+                MdLineFragment::Italic(list![MdLineFragment::Plain("here is italic")])
+            ))
         );
         assert_eq2!(
             parse_element_markdown_inline(
                 "**here is bold**",
                 CheckboxParsePolicy::IgnoreCheckbox
             ),
-            Ok(("", MdLineFragment::Bold("here is bold")))
+            Ok((
+                "",
+                MdLineFragment::Bold(list![MdLineFragment::Plain("here is bold")])
+            ))
         );
         assert_eq2!(
             parse_element_markdown_inline(
@@ -594,6 +1117,18 @@ mod tests_parse_element {
             ),
             Ok(("", MdLineFragment::InlineCode("here is code")))
         );
+        assert_eq2!(
+            parse_element_markdown_inline(
+                "~~here is strikethrough~~",
+                CheckboxParsePolicy::IgnoreCheckbox
+            ),
+            Ok((
+                "",
+                MdLineFragment::Strikethrough(list![MdLineFragment::Plain(
+                    "here is strikethrough"
+                )])
+            ))
+        );
         assert_eq2!(
             parse_element_markdown_inline(
                 "[title](https://www.example.com)",
@@ -0,0 +1,198 @@
+/*
+ *   Copyright (c) 2023 R3BL LLC
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+use constants::*;
+use nom::{branch::*, bytes::complete::*, combinator::*, multi::*, sequence::*, IResult};
+use yaml_rust::{Yaml, YamlLoader};
+
+use crate::*;
+
+/// - Parse input: `---\ntitle: Something\ntags: [tag1, tag2]\n---`.
+/// - There may or may not be a newline at the end.
+/// - The lines in between the delimiters must be a YAML mapping (or empty), otherwise this fails,
+///   and the `---` lines are parsed as horizontal rules instead.
+#[rustfmt::skip]
+pub fn parse_front_matter(input: &str) -> IResult<&str, FrontMatterData> {
+    let (remainder, lines) = delimited(
+        /* start */ tuple((tag(FRONT_MATTER_DELIMITER), tag(NEW_LINE))),
+        /* output */ many0(parse_front_matter_line),
+        /* end */ tuple((tag(FRONT_MATTER_DELIMITER), alt((tag(NEW_LINE), eof)))),
+    )(input)?;
+
+    let Some(value) = parse_yaml(&lines.join(NEW_LINE)) else {
+        return Err(nom::Err::Error(nom::error::Error::new(
+            "Front matter must be a YAML mapping.",
+            nom::error::ErrorKind::Fail,
+        )));
+    };
+
+    Ok((remainder, FrontMatterData { lines: List::from(lines), value }))
+}
+
+/// Any line that isn't the closing delimiter.
+#[rustfmt::skip]
+fn parse_front_matter_line(input: &str) -> IResult<&str, &str> {
+    verify(
+        /* output */ terminated(take_until(NEW_LINE), tag(NEW_LINE)),
+        /* verify */ |line: &str| line != FRONT_MATTER_DELIMITER,
+    )(input)
+}
+
+/// Returns [None] if the text is not valid YAML, or if it is not a mapping.
+fn parse_yaml(text: &str) -> Option<FrontMatterValue> {
+    let documents = YamlLoader::load_from_str(text).ok()?;
+    match documents.into_iter().next() {
+        None => Some(FrontMatterValue::Map(List::new())),
+        Some(yaml @ Yaml::Hash(_)) => Some(from_yaml(yaml)),
+        Some(_) => None,
+    }
+}
+
+fn from_yaml(yaml: Yaml) -> FrontMatterValue {
+    match yaml {
+        Yaml::Boolean(value) => FrontMatterValue::Bool(value),
+        Yaml::Integer(value) => FrontMatterValue::Integer(value),
+        Yaml::Real(text) => match text.parse::<f64>() {
+            Ok(value) => FrontMatterValue::Float(value),
+            Err(_) => FrontMatterValue::String(text),
+        },
+        Yaml::String(text) => FrontMatterValue::String(text),
+        Yaml::Array(items) => {
+            let it = items.into_iter().map(from_yaml).collect::<Vec<_>>();
+            FrontMatterValue::List(List::from(it))
+        }
+        Yaml::Hash(entries) => {
+            let it = entries
+                .into_iter()
+                .filter_map(|(key, value)| Some((key_to_string(key)?, from_yaml(value))))
+                .collect::<Vec<_>>();
+            FrontMatterValue::Map(List::from(it))
+        }
+        Yaml::Alias(_) | Yaml::Null | Yaml::BadValue => FrontMatterValue::Null,
+    }
+}
+
+/// Only scalar keys are supported.
+fn key_to_string(key: Yaml) -> Option<String> {
+    match key {
+        Yaml::String(text) | Yaml::Real(text) => Some(text),
+        Yaml::Integer(value) => Some(value.to_string()),
+        Yaml::Boolean(value) => Some(value.to_string()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use r3bl_rs_utils_core::*;
+
+    use super::*;
+
+    #[test]
+    fn test_parse_front_matter() {
+        let input = [
+            "---",
+            "title: Something",
+            "tags: [tag1, tag2]",
+            "authors:",
+            "  - Alice",
+            "  - Bob",
+            "draft: false",
+            "version: 1.5",
+            "nested:",
+            "  count: 3",
+            "  empty:",
+            "---",
+            "# Heading",
+        ]
+        .join("\n");
+        let (remainder, front_matter) = parse_front_matter(&input).unwrap();
+        assert_eq2!(remainder, "# Heading");
+        assert_eq2!(front_matter.lines.len(), 10);
+        assert_eq2!(
+            front_matter.value,
+            FrontMatterValue::Map(list![
+                (
+                    "title".to_string(),
+                    FrontMatterValue::String("Something".to_string())
+                ),
+                (
+                    "tags".to_string(),
+                    FrontMatterValue::List(list![
+                        FrontMatterValue::String("tag1".to_string()),
+                        FrontMatterValue::String("tag2".to_string()),
+                    ])
+                ),
+                (
+                    "authors".to_string(),
+                    FrontMatterValue::List(list![
+                        FrontMatterValue::String("Alice".to_string()),
+                        FrontMatterValue::String("Bob".to_string()),
+                    ])
+                ),
+                ("draft".to_string(), FrontMatterValue::Bool(false)),
+                ("version".to_string(), FrontMatterValue::Float(1.5)),
+                (
+                    "nested".to_string(),
+                    FrontMatterValue::Map(list![
+                        ("count".to_string(), FrontMatterValue::Integer(3)),
+                        ("empty".to_string(), FrontMatterValue::Null),
+                    ])
+                ),
+            ])
+        );
+        assert_eq2!(
+            front_matter.get_metadata_blocks(),
+            list![
+                MdBlockElement::Title("Something"),
+                MdBlockElement::Tags(list!["tag1", "tag2"]),
+                MdBlockElement::Authors(list!["Alice", "Bob"]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_front_matter_empty_and_no_eol() {
+        let (remainder, front_matter) = parse_front_matter("---\n---").unwrap();
+        assert_eq2!(remainder, "");
+        assert_eq2!(front_matter.lines.len(), 0);
+        assert_eq2!(front_matter.value, FrontMatterValue::Map(list![]));
+    }
+
+    #[test]
+    fn test_parse_front_matter_comma_separated_tags() {
+        let input = "---\ntags: tag1, tag2\ndate: 2023-04-05\n---\n";
+        let (_, front_matter) = parse_front_matter(input).unwrap();
+        assert_eq2!(
+            front_matter.get_metadata_blocks(),
+            list![
+                MdBlockElement::Tags(list!["tag1", "tag2"]),
+                MdBlockElement::Date("2023-04-05"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_front_matter_invalid() {
+        // Not closed.
+        assert!(parse_front_matter("---\ntitle: Something\n").is_err());
+        // Not a mapping.
+        assert!(parse_front_matter("---\nJust some text\n---\n").is_err());
+        // Not valid YAML.
+        assert!(parse_front_matter("---\ntitle: [unclosed\n---\n").is_err());
+    }
+}
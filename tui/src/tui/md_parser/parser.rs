@@ -34,9 +34,139 @@ use crate::{constants::*, tui::misc_types::list_of::List, *};
 ///    parsers in [mod@parse_block_smart_list] file handle this.
 /// 5. Code block (which contains string slices of the language & code). The parsers in
 ///    [mod@parse_block_code] file handle this.
-/// 6. line (which contains a [MdLineFragments]). The parsers in [parse_element] file handle this.
+/// 6. Table (which contains a header row, alignments, and body rows of [MdLineFragments]). The
+///    parsers in [mod@parse_block_table] file handle this.
+/// 7. Block quote (which contains a [List] of [BlockQuoteLine]) and horizontal rule. The parsers in
+///    [mod@parse_block_quote] and [mod@parse_block_horizontal_rule] files handle these.
+/// 8. line (which contains a [MdLineFragments]). The parsers in [parse_element] file handle this.
+/// 9. YAML front matter, which is only allowed at the very start of the document. The parsers in
+///    [parse_metadata_front_matter] file handle this. The title, tags, authors & date in it are
+///    emitted right after it as metadata blocks (see [FrontMatterData::get_metadata_blocks]).
+/// 10. Link definition (which contains the reference, url, and optional title). The parsers in
+///     [mod@parse_block_link_definition] file handle this. Once all the blocks are parsed, the
+///     reference links in them are resolved by [resolve_reference_links].
+/// 11. Footnote definition (which contains the label & [MdLineFragments]). The parsers in
+///     [mod@parse_block_footnote_definition] file handle this.
 #[rustfmt::skip]
 pub fn parse_markdown(input: &str) -> IResult<&str, MdDocument> {
+    let (input, maybe_first_block) = opt(parse_markdown_first_block)(input)?;
+    let (input, output) = many0(parse_markdown_block)(input)?;
+    let mut it = List::with_capacity(output.len() + 1);
+    if let Some(first_block) = maybe_first_block {
+        push_first_block(&mut it, first_block);
+    }
+    it += output;
+    resolve_reference_links(&mut it);
+    Ok((input, it))
+}
+
+/// Same as [parse_markdown], except that the [SourceSpan] of every [MdBlockElement] and every
+/// [MdLineFragment] in it is also recorded. There is one [MdBlockSpan] for each [MdBlockElement],
+/// at the same index. Use [find_md_node_at_caret] to find the innermost node at the caret.
+#[rustfmt::skip]
+pub fn parse_markdown_with_spans(input: &str) -> IResult<&str, (MdDocument, MdDocumentSpans)> {
+    let source_locator = SourceLocator::new(input);
+    let mut document = MdDocument::new();
+    let mut block_ranges = Vec::new();
+    let mut remainder = input;
+
+    // This is the same as `many0(parse_markdown_block)`, except that the start & end of each block
+    // is tracked.
+    loop {
+        let parse_block = if remainder.len() == input.len() {
+            parse_markdown_first_block
+        } else {
+            parse_markdown_block
+        };
+        match parse_block(remainder) {
+            Ok((rest, block)) => {
+                // Infinite loop check (the same one that `many0` does).
+                if rest.len() == remainder.len() {
+                    return Err(nom::Err::Error(nom::error::Error::new(
+                        remainder,
+                        nom::error::ErrorKind::Many0,
+                    )));
+                }
+                let start = input.len() - remainder.len();
+                let end = input.len() - rest.len();
+                let block_count = document.len();
+                if block_count == 0 {
+                    push_first_block(&mut document, block);
+                } else {
+                    document.push(block);
+                }
+                // The metadata blocks that are populated from the front matter have the same
+                // span as the front matter itself.
+                for _ in block_count..document.len() {
+                    block_ranges.push((start, end));
+                }
+                remainder = rest;
+            }
+            Err(nom::Err::Error(_)) => break,
+            Err(error) => return Err(error),
+        }
+    }
+
+    // The spans are computed after the reference links are resolved, so that the fragments in
+    // them are the same as the ones in the document.
+    resolve_reference_links(&mut document);
+    let spans = List::from(
+        document
+            .iter()
+            .zip(block_ranges)
+            .map(|(block, (start, end))| source_locator.block_span(block, start, end))
+            .collect::<Vec<_>>(),
+    );
+
+    Ok((remainder, (document, spans)))
+}
+
+/// Push the first block of a document, along w/ the metadata blocks that are populated from it
+/// if it is front matter.
+fn push_first_block<'a>(document: &mut MdDocument<'a>, first_block: MdBlockElement<'a>) {
+    let metadata_blocks = match &first_block {
+        MdBlockElement::FrontMatter(front_matter) => front_matter.get_metadata_blocks(),
+        _ => List::new(),
+    };
+    document.push(first_block);
+    *document += metadata_blocks;
+}
+
+/// The blocks of the `document` that show up in the source, in order. This skips the metadata
+/// blocks that [parse_markdown] populates from the front matter, since they show up in the source
+/// as part of the [MdBlockElement::FrontMatter] block.
+pub fn get_source_blocks<'doc, 'a>(
+    document: &'doc MdDocument<'a>,
+) -> impl Iterator<Item = &'doc MdBlockElement<'a>> {
+    let metadata_block_count = match document.first() {
+        Some(MdBlockElement::FrontMatter(front_matter)) => front_matter
+            .get_metadata_blocks()
+            .iter()
+            .zip(document.iter().skip(1))
+            .take_while(|(metadata_block, block)| metadata_block == block)
+            .count(),
+        _ => 0,
+    };
+    document
+        .iter()
+        .enumerate()
+        .filter(move |(index, _)| !(1..=metadata_block_count).contains(index))
+        .map(|(_, block)| block)
+}
+
+/// Parse the first [MdBlockElement] of a document. This is the only block that can be
+/// [MdBlockElement::FrontMatter].
+#[rustfmt::skip]
+pub fn parse_markdown_first_block(input: &str) -> IResult<&str, MdBlockElement> {
+    alt((
+        map(parse_front_matter, MdBlockElement::FrontMatter),
+        parse_markdown_block,
+    ))(input)
+}
+
+/// Parse a single [MdBlockElement].
+#[rustfmt::skip]
+pub fn parse_markdown_block(input: &str) -> IResult<&str, MdBlockElement> {
     // key: TAGS, value: CSV parser.
     fn parse_tags_list(input: &str) -> IResult<&str, List<&str>>
     {
@@ -61,21 +191,22 @@ pub fn parse_markdown(input: &str) -> IResult<&str, MdDocument> {
         parse_kv_opt_eol(DATE, input)
     }
 
-    let (input, output) = many0(
-        // NOTE: The ordering of the parsers below matters.
-        alt((
-            map(parse_title_value,                   MdBlockElement::Title),
-            map(parse_tags_list,                     MdBlockElement::Tags),
-            map(parse_authors_list,                  MdBlockElement::Authors),
-            map(parse_date_value,                    MdBlockElement::Date),
-            map(parse_block_heading_opt_eol,         MdBlockElement::Heading),
-            map(parse_block_smart_list,              MdBlockElement::SmartList),
-            map(parse_block_code,                    MdBlockElement::CodeBlock),
-            map(parse_block_markdown_text_until_eol, MdBlockElement::Text),
-        )),
-    )(input)?;
-    let it = List::from(output);
-    Ok((input, it))
+    // NOTE: The ordering of the parsers below matters.
+    alt((
+        map(parse_title_value,                   MdBlockElement::Title),
+        map(parse_tags_list,                     MdBlockElement::Tags),
+        map(parse_authors_list,                  MdBlockElement::Authors),
+        map(parse_date_value,                    MdBlockElement::Date),
+        map(parse_block_heading_opt_eol,         MdBlockElement::Heading),
+        map(parse_block_horizontal_rule,         |_| MdBlockElement::HorizontalRule),
+        map(parse_block_quote,                   MdBlockElement::BlockQuote),
+        map(parse_block_smart_list,              MdBlockElement::SmartList),
+        map(parse_block_code,                    MdBlockElement::CodeBlock),
+        map(parse_block_table,                   MdBlockElement::Table),
+        map(parse_block_footnote_definition,     MdBlockElement::FootnoteDefinition),
+        map(parse_block_link_definition,         MdBlockElement::LinkDefinition),
+        map(parse_block_markdown_text_until_eol, MdBlockElement::Text),
+    ))(input)
 }
 
 #[cfg(test)]
@@ -260,6 +391,170 @@ mod tests {
             .for_each(|(lhs, rhs)| assert_eq2!(lhs, rhs));
     }
 
+    #[test]
+    fn test_parse_markdown_with_table() {
+        let input = [
+            "# Fruits",
+            "| Name | Qty |",
+            "| ---- | --: |",
+            "| apple | 3 |",
+            "end",
+            "",
+        ]
+        .join("\n");
+        let (remainder, blocks) = parse_markdown(&input).unwrap();
+        assert_eq2!(remainder, "");
+        assert_eq2!(blocks.len(), 3);
+        assert_eq2!(
+            blocks[1],
+            MdBlockElement::Table(TableData {
+                header: list![
+                    list![MdLineFragment::Plain("Name")],
+                    list![MdLineFragment::Plain("Qty")],
+                ],
+                alignments: list![
+                    TableColumnAlignment::Default,
+                    TableColumnAlignment::Right
+                ],
+                rows: list![list![
+                    list![MdLineFragment::Plain("apple")],
+                    list![MdLineFragment::Plain("3")],
+                ]],
+            })
+        );
+        assert_eq2!(
+            blocks[2],
+            MdBlockElement::Text(list![MdLineFragment::Plain("end")])
+        );
+    }
+
+    #[test]
+    fn test_parse_markdown_with_quote_and_rule() {
+        let input = [
+            "> quote ~~struck~~",
+            "> > nested",
+            "---",
+            "- - -",
+            "- item",
+            "***",
+            "end",
+            "",
+        ]
+        .join("\n");
+        let (remainder, blocks) = parse_markdown(&input).unwrap();
+        assert_eq2!(remainder, "");
+        assert_eq2!(
+            blocks[0],
+            MdBlockElement::BlockQuote(list![
+                BlockQuoteLine {
+                    depth: 1,
+                    content: list![
+                        MdLineFragment::Plain("quote "),
+                        MdLineFragment::Strikethrough(list![MdLineFragment::Plain(
+                            "struck"
+                        )]),
+                    ]
+                },
+                BlockQuoteLine {
+                    depth: 2,
+                    content: list![MdLineFragment::Plain("nested")]
+                },
+            ])
+        );
+        assert_eq2!(blocks[1], MdBlockElement::HorizontalRule);
+        assert_eq2!(blocks[2], MdBlockElement::HorizontalRule);
+        assert!(matches!(blocks[3], MdBlockElement::SmartList(_)));
+        assert_eq2!(blocks[4], MdBlockElement::HorizontalRule);
+        assert_eq2!(
+            blocks[5],
+            MdBlockElement::Text(list![MdLineFragment::Plain("end")])
+        );
+    }
+
+    #[test]
+    fn test_parse_markdown_with_front_matter() {
+        let input = [
+            "---",
+            "title: Something",
+            "---",
+            "---",
+            "key: value",
+            "---",
+            "",
+        ]
+        .join("\n");
+        let (remainder, blocks) = parse_markdown(&input).unwrap();
+        assert_eq2!(remainder, "");
+        let MdBlockElement::FrontMatter(front_matter) = &blocks[0] else {
+            panic!("expected front matter, got {:?}", blocks[0]);
+        };
+        assert_eq2!(front_matter.lines, list!["title: Something"]);
+
+        // The title is emitted into the document, right after the front matter.
+        assert_eq2!(blocks[1], MdBlockElement::Title("Something"));
+
+        // Front matter is only allowed at the start of the document.
+        assert_eq2!(blocks[2], MdBlockElement::HorizontalRule);
+        assert_eq2!(
+            blocks[3],
+            MdBlockElement::Text(list![MdLineFragment::Plain("key: value")])
+        );
+        assert_eq2!(blocks[4], MdBlockElement::HorizontalRule);
+
+        // The title isn't a block of its own in the source.
+        assert_eq2!(get_source_blocks(&blocks).count(), 4);
+
+        // The spans are the same.
+        let (_, (span_blocks, spans)) = parse_markdown_with_spans(&input).unwrap();
+        assert_eq2!(span_blocks, blocks);
+        assert_eq2!(spans[0].span.end.row_index, ch!(2));
+        assert_eq2!(spans[1].span, spans[0].span);
+    }
+
+    #[test]
+    fn test_parse_markdown_with_front_matter_metadata() {
+        let input = [
+            "---",
+            "title: \"Quoted\"",
+            "tags:",
+            "  - tag1",
+            "  - tag2",
+            "authors: Nazmul, Nadia",
+            "date: 2023-05-01",
+            "draft: true",
+            "---",
+            "@title: Explicit",
+            "Text",
+            "",
+        ]
+        .join("\n");
+        let (remainder, blocks) = parse_markdown(&input).unwrap();
+        assert_eq2!(remainder, "");
+        assert_eq2!(
+            blocks[1..],
+            [
+                MdBlockElement::Title("Quoted"),
+                MdBlockElement::Tags(list!["tag1", "tag2"]),
+                MdBlockElement::Authors(list!["Nazmul", "Nadia"]),
+                MdBlockElement::Date("2023-05-01"),
+                MdBlockElement::Title("Explicit"),
+                MdBlockElement::Text(list![MdLineFragment::Plain("Text")]),
+            ]
+        );
+
+        // The metadata blocks are slices of the front matter lines.
+        let MdBlockElement::Title(title) = blocks[1] else {
+            panic!();
+        };
+        let title_offset = title.as_ptr() as usize - input.as_ptr() as usize;
+        assert_eq2!(&input[title_offset - 1..title_offset + 7], "\"Quoted\"");
+
+        assert_eq2!(
+            get_source_blocks(&blocks).collect::<Vec<_>>(),
+            vec![&blocks[0], &blocks[5], &blocks[6]]
+        );
+    }
+
     #[test]
     fn test_markdown_invalid() {
         let input = [
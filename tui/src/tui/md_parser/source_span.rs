@@ -0,0 +1,778 @@
+/*
+ *   Copyright (c) 2023 R3BL LLC
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+//! This module is responsible for computing the [SourceSpan] of every node in a [MdDocument] and
+//! for finding the innermost node at a given caret position.
+//!
+//! The parser is zero copy, so every string slice in the [MdDocument] points into the input that
+//! was parsed. This means that the byte offset of any fragment can be recovered from its string
+//! slice. The parser only has to keep track of where each block starts & ends, which is done by
+//! [parse_markdown_with_spans].
+
+use r3bl_rs_utils_core::*;
+
+use crate::{constants::*, *};
+
+/// Converts byte offsets in the Markdown source into [SourcePosition]s, and computes the
+/// [MdBlockSpan] of each [MdBlockElement] that is parsed from the source.
+#[derive(Debug)]
+pub struct SourceLocator<'a> {
+    input: &'a str,
+    /// The byte offset at which each line starts.
+    line_start_offsets: Vec<usize>,
+}
+
+impl<'a> SourceLocator<'a> {
+    pub fn new(input: &'a str) -> Self {
+        let mut line_start_offsets = vec![0];
+        line_start_offsets.extend(
+            input
+                .match_indices(NEW_LINE)
+                .map(|(offset, _)| offset + NEW_LINE.len()),
+        );
+        Self {
+            input,
+            line_start_offsets,
+        }
+    }
+
+    /// Convert the given byte offset into a [SourcePosition].
+    pub fn position(&self, byte_offset: usize) -> SourcePosition {
+        let row_index = match self.line_start_offsets.binary_search(&byte_offset) {
+            Ok(row_index) => row_index,
+            Err(next_row_index) => next_row_index - 1,
+        };
+        let line_start_offset = self.line_start_offsets[row_index];
+        let col_index =
+            UnicodeString::str_display_width(&self.input[line_start_offset..byte_offset]);
+        SourcePosition {
+            byte_offset,
+            row_index: ch!(row_index),
+            col_index: ch!(col_index),
+        }
+    }
+
+    pub fn span(&self, start: usize, end: usize) -> SourceSpan {
+        SourceSpan {
+            start: self.position(start),
+            end: self.position(end),
+        }
+    }
+
+    /// Returns the byte offset of `slice` in the input, or [None] if `slice` doesn't point into the
+    /// input (eg: it is a `&'static str`).
+    pub fn offset_of(&self, slice: &str) -> Option<usize> {
+        let input_start = self.input.as_ptr() as usize;
+        let slice_start = slice.as_ptr() as usize;
+        if slice_start < input_start
+            || slice_start + slice.len() > input_start + self.input.len()
+        {
+            return None;
+        }
+        Some(slice_start - input_start)
+    }
+
+    /// Compute the [MdBlockSpan] for the given `block`, which was parsed from `input[start..end]`.
+    pub fn block_span(
+        &self,
+        block: &MdBlockElement<'a>,
+        start: usize,
+        end: usize,
+    ) -> MdBlockSpan<'a> {
+        let block_source = &self.input[start..end];
+        let trimmed_end = match block_source.strip_suffix(NEW_LINE) {
+            Some(it) => start + it.len(),
+            None => end,
+        };
+
+        let mut fragments = List::new();
+        match block {
+            MdBlockElement::Text(line) => {
+                self.push_line_fragments(line, start, &mut fragments);
+            }
+            MdBlockElement::SmartList((lines, _, _)) => {
+                for (index, (line, line_start)) in lines
+                    .iter()
+                    .zip(self.line_start_offsets_in(start, end))
+                    .enumerate()
+                {
+                    let content_start =
+                        line_start + self.list_bullet_width(line_start, index == 0);
+                    let mut iter = line.iter();
+                    if let Some(bullet) = iter.next() {
+                        fragments.push(MdFragmentSpan {
+                            fragment: bullet.clone(),
+                            span: self.span(line_start, content_start),
+                        });
+                    }
+                    let content = List::from(iter.cloned().collect::<Vec<_>>());
+                    self.push_line_fragments(&content, content_start, &mut fragments);
+                }
+            }
+            MdBlockElement::BlockQuote(lines) => {
+                for (line, line_start) in
+                    lines.iter().zip(self.line_start_offsets_in(start, end))
+                {
+                    self.push_line_fragments(&line.content, line_start, &mut fragments);
+                }
+            }
+            MdBlockElement::Table(table_data) => {
+                // Skip the delimiter row, which has no fragments.
+                let row_start_offsets = self
+                    .line_start_offsets_in(start, end)
+                    .enumerate()
+                    .filter(|(index, _)| *index != 1)
+                    .map(|(_, line_start)| line_start);
+                let rows =
+                    std::iter::once(&table_data.header).chain(table_data.rows.iter());
+                for (row, row_start) in rows.zip(row_start_offsets) {
+                    let row_source = self.line_at(row_start);
+                    let cell_sources = split_table_row_into_cells(row_source);
+                    for (index, cell) in row.iter().enumerate() {
+                        // Cells that are missing from the source (since the row is too short)
+                        // start at the end of the row.
+                        let cell_start = match cell_sources.get(index) {
+                            Some(cell_source) => self.offset_of(cell_source),
+                            None => None,
+                        }
+                        .unwrap_or(row_start + row_source.len());
+                        self.push_line_fragments(cell, cell_start, &mut fragments);
+                    }
+                }
+            }
+            MdBlockElement::FootnoteDefinition(footnote_definition_data) => {
+                self.push_line_fragments(
+                    &footnote_definition_data.content,
+                    start,
+                    &mut fragments,
+                );
+            }
+            MdBlockElement::Heading(HeadingData { level, text }) => {
+                let text_start = start + usize::from(*level) + SPACE.len();
+                self.push_text_fragments([*text], text_start, &mut fragments);
+            }
+            MdBlockElement::CodeBlock(code_block_lines) => {
+                for (code_block_line, line_start) in code_block_lines
+                    .iter()
+                    .zip(self.line_start_offsets_in(start, end))
+                {
+                    if let CodeBlockLineContent::Text(text) = code_block_line.content {
+                        self.push_text_fragments([text], line_start, &mut fragments);
+                    }
+                }
+            }
+            MdBlockElement::Title(text) | MdBlockElement::Date(text) => {
+                self.push_text_fragments([*text], start, &mut fragments);
+            }
+            MdBlockElement::Tags(items) | MdBlockElement::Authors(items) => {
+                self.push_text_fragments(items.iter().copied(), start, &mut fragments);
+            }
+            MdBlockElement::FrontMatter(front_matter) => {
+                // Skip the `---` line that starts the front matter.
+                for (line, line_start) in front_matter
+                    .lines
+                    .iter()
+                    .zip(self.line_start_offsets_in(start, end).skip(1))
+                {
+                    self.push_text_fragments([*line], line_start, &mut fragments);
+                }
+            }
+            MdBlockElement::HorizontalRule | MdBlockElement::LinkDefinition(_) => {}
+        }
+
+        MdBlockSpan {
+            span: self.span(start, trimmed_end),
+            fragments,
+        }
+    }
+
+    /// The byte offsets at which each line in `input[start..end]` starts.
+    fn line_start_offsets_in(
+        &self,
+        start: usize,
+        end: usize,
+    ) -> impl Iterator<Item = usize> + '_ {
+        self.line_start_offsets
+            .iter()
+            .copied()
+            .filter(move |it| *it >= start && *it < end)
+    }
+
+    /// The line that starts at the given byte offset, w/out its newline.
+    fn line_at(&self, line_start: usize) -> &'a str {
+        let line = &self.input[line_start..];
+        &line[..line.find(NEW_LINE).unwrap_or(line.len())]
+    }
+
+    /// The number of bytes before the content of a smart list line, which includes the indent. The
+    /// first line has a bullet (eg: `"  - "` or `"  10. "`), and the rest of the lines are padded
+    /// w/ spaces to line up w/ the content of the first line.
+    fn list_bullet_width(&self, line_start: usize, is_first_line: bool) -> usize {
+        let line = self.line_at(line_start);
+        let indent = line.len() - line.trim_start_matches(SPACE_CHAR).len();
+        if !is_first_line {
+            return indent;
+        }
+        match line[indent..].find(SPACE_CHAR) {
+            Some(bullet_end) => indent + bullet_end + SPACE.len(),
+            None => line.len(),
+        }
+    }
+
+    /// Fragments that have a string slice (w/ the exception of empty text that isn't in the
+    /// input) have their span computed from it. The rest (eg: [MdLineFragment::Checkbox]) start
+    /// where the previous fragment ended.
+    fn push_line_fragments(
+        &self,
+        line: &MdLineFragments<'a>,
+        content_start: usize,
+        acc: &mut List<MdFragmentSpan<'a>>,
+    ) {
+        let mut cursor = content_start;
+        for fragment in line.iter() {
+            let (start, end) = match self.fragment_range(fragment) {
+                Some(range) => range,
+                None => {
+                    let len = match fragment {
+                        MdLineFragment::Checkbox(true) => CHECKED.len(),
+                        MdLineFragment::Checkbox(false) => UNCHECKED.len(),
+                        _ => 0,
+                    };
+                    (cursor, cursor + len)
+                }
+            };
+            acc.push(MdFragmentSpan {
+                fragment: fragment.clone(),
+                span: self.span(start, end),
+            });
+            cursor = end;
+        }
+    }
+
+    /// Blocks that hold plain text (rather than [MdLineFragments]) have a [MdLineFragment::Plain]
+    /// for each piece of text in them, eg: each tag in [MdBlockElement::Tags].
+    fn push_text_fragments(
+        &self,
+        texts: impl IntoIterator<Item = &'a str>,
+        content_start: usize,
+        acc: &mut List<MdFragmentSpan<'a>>,
+    ) {
+        let line = texts
+            .into_iter()
+            .map(MdLineFragment::Plain)
+            .collect::<Vec<_>>();
+        self.push_line_fragments(&List::from(line), content_start, acc);
+    }
+
+    /// The byte range of a formatting span w/ the given (nested) `fragments`, including its
+    /// delimiters.
+    fn nested_fragments_range(
+        &self,
+        fragments: &MdLineFragments,
+        delimiter_len: usize,
+    ) -> Option<(usize, usize)> {
+        let (start, _) = self.fragment_range(fragments.first()?)?;
+        let (_, end) = self.fragment_range(fragments.last()?)?;
+        Some((start - delimiter_len, end + delimiter_len))
+    }
+
+    /// The byte range of `fragment` in the input, including its delimiters.
+    fn fragment_range(&self, fragment: &MdLineFragment) -> Option<(usize, usize)> {
+        let (text, delimiter_len) = match fragment {
+            MdLineFragment::Plain(text) => (text, 0),
+            MdLineFragment::Bold(fragments) => {
+                return self.nested_fragments_range(fragments, BOLD_1.len());
+            }
+            MdLineFragment::Italic(fragments) => {
+                return self.nested_fragments_range(fragments, ITALIC_1.len());
+            }
+            MdLineFragment::BoldItalic(fragments) => {
+                return self.nested_fragments_range(fragments, BITALIC_1.len());
+            }
+            MdLineFragment::Strikethrough(fragments) => {
+                return self.nested_fragments_range(fragments, STRIKETHROUGH.len());
+            }
+            MdLineFragment::InlineCode(text) => (text, BACK_TICK.len()),
+            MdLineFragment::Escaped(text) => {
+                let start = self.offset_of(text)? - BACKSLASH.len();
+                return Some((start, start + BACKSLASH.len() + text.len()));
+            }
+            MdLineFragment::Link(HyperlinkData { text, url }) => {
+                let start = self.offset_of(text)? - LEFT_BRACKET.len();
+                let end = self.offset_of(url)? + url.len() + RIGHT_PARENTHESIS.len();
+                return Some((start, end));
+            }
+            MdLineFragment::Image(HyperlinkData { text, url }) => {
+                let start = self.offset_of(text)? - LEFT_IMAGE.len();
+                let end = self.offset_of(url)? + url.len() + RIGHT_PARENTHESIS.len();
+                return Some((start, end));
+            }
+            MdLineFragment::WikiLink(WikiLinkData { target, alias }) => {
+                let last = alias.unwrap_or(target);
+                let start = self.offset_of(target)? - WIKI_LINK_START.len();
+                let end = self.offset_of(last)? + last.len() + WIKI_LINK_END.len();
+                return Some((start, end));
+            }
+            MdLineFragment::AutoLink(url) => (url, 0),
+            MdLineFragment::ReferenceLink(ReferenceLinkData {
+                text, reference, ..
+            }) => {
+                let start = self.offset_of(text)? - LEFT_BRACKET.len();
+                let end =
+                    self.offset_of(reference)? + reference.len() + RIGHT_BRACKET.len();
+                return Some((start, end));
+            }
+            MdLineFragment::FootnoteReference(label) => {
+                let start = self.offset_of(label)? - FOOTNOTE_START.len();
+                return Some((
+                    start,
+                    start + FOOTNOTE_START.len() + label.len() + RIGHT_BRACKET.len(),
+                ));
+            }
+            MdLineFragment::Checkbox(_)
+            | MdLineFragment::UnorderedListBullet { .. }
+            | MdLineFragment::OrderedListBullet { .. } => return None,
+        };
+        let start = self.offset_of(text)?;
+        Some((start - delimiter_len, start + text.len() + delimiter_len))
+    }
+}
+
+/// The innermost node in a [MdDocument] at a given position. This is returned by
+/// [find_md_node_at] and [find_md_node_at_caret].
+#[derive(Clone, Debug, PartialEq)]
+pub enum MdNodeAtPosition<'doc, 'a> {
+    Block {
+        block_index: usize,
+        block: &'doc MdBlockElement<'a>,
+        span: SourceSpan,
+    },
+    Fragment {
+        block_index: usize,
+        block: &'doc MdBlockElement<'a>,
+        fragment: &'doc MdLineFragment<'a>,
+        span: SourceSpan,
+    },
+}
+
+/// Find the innermost node (a fragment if there is one, otherwise the block) at the given
+/// position. The `document` & `spans` must come from the same call to
+/// [parse_markdown_with_spans].
+///
+/// - A block contains all the positions from its start up to (and including) the end of its last
+///   line, so a caret that is at the end of a line is still inside of that line's block.
+/// - A fragment contains all the positions from its start up to (but not including) its end.
+pub fn find_md_node_at<'doc, 'a>(
+    document: &'doc MdDocument<'a>,
+    spans: &'doc MdDocumentSpans<'a>,
+    position: Position,
+) -> Option<MdNodeAtPosition<'doc, 'a>> {
+    let position = (position.row_index, position.col_index);
+    let row_col = |it: &SourcePosition| (it.row_index, it.col_index);
+
+    let (block_index, block_span) =
+        spans.iter().enumerate().find(|(_, block_span)| {
+            row_col(&block_span.span.start) <= position
+                && position <= row_col(&block_span.span.end)
+        })?;
+    let block = document.get(block_index)?;
+
+    for fragment_span in block_span.fragments.iter() {
+        if row_col(&fragment_span.span.start) <= position
+            && position < row_col(&fragment_span.span.end)
+        {
+            return Some(MdNodeAtPosition::Fragment {
+                block_index,
+                block,
+                fragment: &fragment_span.fragment,
+                span: fragment_span.span,
+            });
+        }
+    }
+
+    Some(MdNodeAtPosition::Block {
+        block_index,
+        block,
+        span: block_span.span,
+    })
+}
+
+/// Find the innermost node at the caret of the given [EditorBuffer]. The `document` & `spans` must
+/// be parsed from the lines of the `editor_buffer` (joined w/ newlines).
+pub fn find_md_node_at_caret<'doc, 'a>(
+    document: &'doc MdDocument<'a>,
+    spans: &'doc MdDocumentSpans<'a>,
+    editor_buffer: &EditorBuffer,
+) -> Option<MdNodeAtPosition<'doc, 'a>> {
+    find_md_node_at(
+        document,
+        spans,
+        editor_buffer.get_caret(CaretKind::ScrollAdjusted),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns the source text that each fragment span covers.
+    fn get_fragment_sources<'a>(
+        input: &'a str,
+        block_span: &MdBlockSpan,
+    ) -> Vec<&'a str> {
+        block_span
+            .fragments
+            .iter()
+            .map(|it| &input[it.span.start.byte_offset..it.span.end.byte_offset])
+            .collect()
+    }
+
+    #[test]
+    fn test_source_locator_position() {
+        let input = "ab\n😃c\n";
+        let source_locator = SourceLocator::new(input);
+        assert_eq2!(
+            source_locator.position(1),
+            SourcePosition {
+                byte_offset: 1,
+                row_index: ch!(0),
+                col_index: ch!(1)
+            }
+        );
+        // The emoji is 4 bytes long & 2 display columns wide.
+        assert_eq2!(
+            source_locator.position(7),
+            SourcePosition {
+                byte_offset: 7,
+                row_index: ch!(1),
+                col_index: ch!(2)
+            }
+        );
+        assert_eq2!(source_locator.position(input.len()).row_index, ch!(2));
+        assert_eq2!(source_locator.offset_of(&input[3..]), Some(3));
+        assert_eq2!(source_locator.offset_of("ab"), None);
+    }
+
+    #[test]
+    fn test_block_spans() {
+        let input = [
+            "@title: Something",
+            "# Heading",
+            "",
+            "some *italic* and [link](url) ![img](src.jpg)",
+            "- [x] done",
+            "  more **bold**",
+            "> `code`",
+            "| a | ~~b~~ |",
+            "| - | - |",
+            "```rust",
+            "let a = 1;",
+            "```",
+            "end",
+            "",
+        ]
+        .join("\n");
+        let (remainder, (document, spans)) = parse_markdown_with_spans(&input).unwrap();
+        assert_eq2!(remainder, "");
+        assert_eq2!(document.len(), spans.len());
+        assert_eq2!(parse_markdown(&input).unwrap().1, document);
+
+        let block_sources: Vec<&str> = spans
+            .iter()
+            .map(|it| &input[it.span.start.byte_offset..it.span.end.byte_offset])
+            .collect();
+        assert_eq2!(
+            block_sources,
+            vec![
+                "@title: Something",
+                "# Heading",
+                "",
+                "some *italic* and [link](url) ![img](src.jpg)",
+                "- [x] done\n  more **bold**",
+                "> `code`",
+                "| a | ~~b~~ |\n| - | - |",
+                "```rust\nlet a = 1;\n```",
+                "end",
+            ]
+        );
+
+        let rows: Vec<(usize, usize)> = spans
+            .iter()
+            .map(|it| {
+                (
+                    ch!(@to_usize it.span.start.row_index),
+                    ch!(@to_usize it.span.end.row_index),
+                )
+            })
+            .collect();
+        assert_eq2!(
+            rows,
+            vec![
+                (0, 0),
+                (1, 1),
+                (2, 2),
+                (3, 3),
+                (4, 5),
+                (6, 6),
+                (7, 8),
+                (9, 11),
+                (12, 12)
+            ]
+        );
+
+        assert_eq2!(
+            get_fragment_sources(&input, &spans[3]),
+            vec![
+                "some ",
+                "*italic*",
+                " and ",
+                "[link](url)",
+                " ",
+                "![img](src.jpg)"
+            ]
+        );
+        assert_eq2!(
+            get_fragment_sources(&input, &spans[4]),
+            vec!["- ", "[x]", " done", "  ", "more ", "**bold**"]
+        );
+        assert_eq2!(get_fragment_sources(&input, &spans[5]), vec!["`code`"]);
+        assert_eq2!(get_fragment_sources(&input, &spans[0]), vec!["Something"]);
+        assert_eq2!(get_fragment_sources(&input, &spans[1]), vec!["Heading"]);
+        assert_eq2!(get_fragment_sources(&input, &spans[6]), vec!["a", "~~b~~"]);
+        assert_eq2!(get_fragment_sources(&input, &spans[7]), vec!["let a = 1;"]);
+    }
+
+    #[test]
+    fn test_metadata_and_code_block_spans() {
+        let input = [
+            "---",
+            "title: Something",
+            "",
+            "---",
+            "@tags: foo, bar",
+            "@authors: Nazmul",
+            "@date: 2023-05-01",
+            "##   Spaced  ",
+            "```",
+            "",
+            "a",
+            "```",
+            "",
+        ]
+        .join("\n");
+        let (remainder, (_, spans)) = parse_markdown_with_spans(&input).unwrap();
+        assert_eq2!(remainder, "");
+        assert_eq2!(
+            get_fragment_sources(&input, &spans[0]),
+            vec!["title: Something", ""]
+        );
+        // The title that is populated from the front matter.
+        assert_eq2!(get_fragment_sources(&input, &spans[1]), vec!["Something"]);
+        assert_eq2!(get_fragment_sources(&input, &spans[2]), vec!["foo", "bar"]);
+        assert_eq2!(get_fragment_sources(&input, &spans[3]), vec!["Nazmul"]);
+        assert_eq2!(get_fragment_sources(&input, &spans[4]), vec!["2023-05-01"]);
+        assert_eq2!(get_fragment_sources(&input, &spans[5]), vec!["  Spaced  "]);
+        assert_eq2!(get_fragment_sources(&input, &spans[6]), vec!["", "a"]);
+
+        // The empty lines are on their own rows.
+        let rows = |block_span: &MdBlockSpan| {
+            block_span
+                .fragments
+                .iter()
+                .map(|it| ch!(@to_usize it.span.start.row_index))
+                .collect::<Vec<_>>()
+        };
+        assert_eq2!(rows(&spans[0]), vec![1, 2]);
+        assert_eq2!(rows(&spans[1]), vec![1]);
+        assert_eq2!(rows(&spans[6]), vec![9, 10]);
+    }
+
+    #[test]
+    fn test_table_cell_spans() {
+        let input =
+            ["| a | `b|c` |", "| - | - |", "|   | d\\|e |", "| f |", ""].join("\n");
+        let (remainder, (_, spans)) = parse_markdown_with_spans(&input).unwrap();
+        assert_eq2!(remainder, "");
+        assert_eq2!(
+            get_fragment_sources(&input, &spans[0]),
+            vec!["a", "`b|c`", "d", "\\|", "e", "f"]
+        );
+        let rows = spans[0]
+            .fragments
+            .iter()
+            .map(|it| ch!(@to_usize it.span.start.row_index))
+            .collect::<Vec<_>>();
+        assert_eq2!(rows, vec![0, 0, 2, 2, 2, 3]);
+    }
+
+    #[test]
+    fn test_ordered_list_spans() {
+        let input = ["1. one", "  10. ten", "      ten.1", "- ", ""].join("\n");
+        let (remainder, (_, spans)) = parse_markdown_with_spans(&input).unwrap();
+        assert_eq2!(remainder, "");
+        assert_eq2!(get_fragment_sources(&input, &spans[0]), vec!["1. ", "one"]);
+        assert_eq2!(
+            get_fragment_sources(&input, &spans[1]),
+            vec!["  10. ", "ten", "      ", "ten.1"]
+        );
+        // Empty list item.
+        assert_eq2!(get_fragment_sources(&input, &spans[2]), vec!["- ", ""]);
+    }
+
+    #[test]
+    fn test_find_md_node_at() {
+        let input = ["# Heading", "some *italic* 😃 `code`", ""].join("\n");
+        let (_, (document, spans)) = parse_markdown_with_spans(&input).unwrap();
+
+        // On the `#` of the heading, which is not part of its text.
+        let node =
+            find_md_node_at(&document, &spans, position!(col_index: 0, row_index: 0));
+        assert!(matches!(
+            node,
+            Some(MdNodeAtPosition::Block { block_index: 0, .. })
+        ));
+
+        // On the text of the heading.
+        let node =
+            find_md_node_at(&document, &spans, position!(col_index: 3, row_index: 0));
+        assert!(matches!(
+            node,
+            Some(MdNodeAtPosition::Fragment {
+                block_index: 0,
+                fragment: MdLineFragment::Plain("Heading"),
+                ..
+            })
+        ));
+
+        // On the `*` that starts the italic text.
+        let node =
+            find_md_node_at(&document, &spans, position!(col_index: 5, row_index: 1));
+        assert!(matches!(
+            node,
+            Some(MdNodeAtPosition::Fragment {
+                block_index: 1,
+                fragment: MdLineFragment::Italic(fragments),
+                ..
+            }) if fragments[..] == [MdLineFragment::Plain("italic")]
+        ));
+
+        // After the emoji (which is 2 display columns wide).
+        let node =
+            find_md_node_at(&document, &spans, position!(col_index: 17, row_index: 1));
+        assert!(matches!(
+            node,
+            Some(MdNodeAtPosition::Fragment {
+                fragment: MdLineFragment::InlineCode("code"),
+                ..
+            })
+        ));
+
+        // At the end of the line, past the last fragment.
+        let node =
+            find_md_node_at(&document, &spans, position!(col_index: 23, row_index: 1));
+        assert!(matches!(
+            node,
+            Some(MdNodeAtPosition::Block { block_index: 1, .. })
+        ));
+
+        // Past the end of the document.
+        let node =
+            find_md_node_at(&document, &spans, position!(col_index: 0, row_index: 5));
+        assert_eq2!(node, None);
+    }
+
+    #[test]
+    fn test_nested_formatting_spans() {
+        let input = "**a *b*** \\* `c`\n";
+        let (_, (_, spans)) = parse_markdown_with_spans(input).unwrap();
+        let ranges = spans[0]
+            .fragments
+            .iter()
+            .map(|it| it.span.start.byte_offset..it.span.end.byte_offset)
+            .collect::<Vec<_>>();
+        assert_eq2!(ranges, vec![0..9, 9..10, 10..12, 12..13, 13..16]);
+    }
+
+    #[test]
+    fn test_footnote_spans() {
+        let input = "Text[^1].\n[^1]: A *note*.\n";
+        let (_, (_, spans)) = parse_markdown_with_spans(input).unwrap();
+        assert_eq2!(
+            get_fragment_sources(input, &spans[0]),
+            vec!["Text", "[^1]", "."]
+        );
+        assert_eq2!(
+            get_fragment_sources(input, &spans[1]),
+            vec!["A ", "*note*", "."]
+        );
+    }
+
+    #[test]
+    fn test_link_spans() {
+        let input = ["[[a|b]] [c][] https://d.e", "[c]: https://c.com", ""].join("\n");
+        let (_, (document, spans)) = parse_markdown_with_spans(&input).unwrap();
+        let ranges = spans[0]
+            .fragments
+            .iter()
+            .map(|it| {
+                (
+                    ch!(@to_usize it.span.start.col_index),
+                    ch!(@to_usize it.span.end.col_index),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq2!(ranges, vec![(0, 7), (7, 8), (8, 13), (13, 14), (14, 25)]);
+
+        // The fragments in the spans have their reference links resolved too.
+        assert_eq2!(
+            spans[0].fragments[2].fragment,
+            MdLineFragment::ReferenceLink(ReferenceLinkData {
+                text: "c",
+                reference: "",
+                url: Some("https://c.com"),
+            })
+        );
+        assert_eq2!(document.len(), 2);
+    }
+
+    #[test]
+    fn test_find_md_node_at_caret() {
+        let mut editor_buffer = EditorBuffer::new_empty(Some("md"));
+        editor_buffer.set_lines(vec!["- foo".into(), "  **bar**".into()]);
+        {
+            let (_, caret, _, _) = editor_buffer.get_mut();
+            *caret = position!(col_index: 3, row_index: 1);
+        }
+
+        let input = editor_buffer
+            .get_lines()
+            .iter()
+            .map(|it| format!("{}\n", it.string))
+            .collect::<String>();
+        let (_, (document, spans)) = parse_markdown_with_spans(&input).unwrap();
+
+        let node = find_md_node_at_caret(&document, &spans, &editor_buffer);
+        assert!(matches!(
+            node,
+            Some(MdNodeAtPosition::Fragment {
+                block_index: 0,
+                fragment: MdLineFragment::Bold(fragments),
+                ..
+            }) if fragments[..] == [MdLineFragment::Plain("bar")]
+        ));
+    }
+}
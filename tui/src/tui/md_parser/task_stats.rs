@@ -0,0 +1,212 @@
+/*
+ *   Copyright (c) 2023 R3BL LLC
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+//! This module is responsible for counting the tasks (checkboxes) in a [MdDocument], so that an
+//! app can show progress (eg: a progress bar for each heading).
+//!
+//! 1. [get_task_stats] counts all the tasks in the document.
+//! 2. [get_task_stats_by_section] counts the tasks in each heading section. A section starts at
+//!    a heading, and ends at the next heading. Each section also has the totals that include its
+//!    subsections (the sections w/ deeper headings that follow it).
+//!
+//! Only the [MdLineFragment::Checkbox]es in smart lists are tasks, eg: `- [x] done`.
+
+use std::ops::{Add, AddAssign};
+
+use crate::*;
+
+/// The number of tasks (checkboxes) that are checked, out of the total.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct MdTaskStats {
+    pub done: usize,
+    pub total: usize,
+}
+
+impl MdTaskStats {
+    pub fn get_pending(&self) -> usize { self.total - self.done }
+
+    pub fn is_complete(&self) -> bool { self.done == self.total }
+
+    /// Returns the fraction of tasks that are done (between `0.0` and `1.0`), or [None] if there
+    /// are no tasks.
+    pub fn get_done_ratio(&self) -> Option<f64> {
+        if self.total == 0 {
+            None
+        } else {
+            Some(self.done as f64 / self.total as f64)
+        }
+    }
+}
+
+impl Add for MdTaskStats {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self {
+            done: self.done + rhs.done,
+            total: self.total + rhs.total,
+        }
+    }
+}
+
+impl AddAssign for MdTaskStats {
+    fn add_assign(&mut self, rhs: Self) { *self = *self + rhs; }
+}
+
+/// The tasks in a heading section, which is returned by [get_task_stats_by_section].
+#[derive(Clone, Debug, PartialEq)]
+pub struct MdSectionTaskStats<'a> {
+    /// [None] for the blocks before the first heading.
+    pub heading: Option<HeadingData<'a>>,
+    /// Index of the [MdBlockElement] (in the [MdDocument]) where the section starts.
+    pub block_index: usize,
+    /// Only the tasks in the blocks of this section.
+    pub own: MdTaskStats,
+    /// The tasks in this section & all of its subsections.
+    pub total: MdTaskStats,
+}
+
+/// Count all the tasks in the document.
+pub fn get_task_stats(document: &MdDocument) -> MdTaskStats {
+    document
+        .iter()
+        .map(get_task_stats_in_block)
+        .fold(MdTaskStats::default(), Add::add)
+}
+
+/// Count the tasks in each heading section of the document, in the order in which the sections
+/// appear. If there are blocks before the first heading, then the first section has no heading.
+pub fn get_task_stats_by_section<'a>(
+    document: &MdDocument<'a>,
+) -> List<MdSectionTaskStats<'a>> {
+    let mut acc: Vec<MdSectionTaskStats<'a>> = vec![];
+
+    for (block_index, block) in document.iter().enumerate() {
+        match block {
+            MdBlockElement::Heading(heading_data) => acc.push(MdSectionTaskStats {
+                heading: Some(heading_data.clone()),
+                block_index,
+                own: MdTaskStats::default(),
+                total: MdTaskStats::default(),
+            }),
+            _ => {
+                if acc.is_empty() {
+                    acc.push(MdSectionTaskStats {
+                        heading: None,
+                        block_index,
+                        own: MdTaskStats::default(),
+                        total: MdTaskStats::default(),
+                    });
+                }
+                if let Some(section) = acc.last_mut() {
+                    section.own += get_task_stats_in_block(block);
+                }
+            }
+        }
+    }
+
+    // Roll up the subsections into each section.
+    for index in 0..acc.len() {
+        let mut total = acc[index].own;
+        if let Some(heading) = &acc[index].heading {
+            let level = usize::from(heading.level);
+            for subsection in acc[index + 1..].iter() {
+                match &subsection.heading {
+                    Some(it) if usize::from(it.level) > level => total += subsection.own,
+                    _ => break,
+                }
+            }
+        }
+        acc[index].total = total;
+    }
+
+    List::from(acc)
+}
+
+fn get_task_stats_in_block(block: &MdBlockElement) -> MdTaskStats {
+    let mut acc = MdTaskStats::default();
+    if let MdBlockElement::SmartList((lines, _, _)) = block {
+        for fragment in lines.iter().flat_map(|line| line.iter()) {
+            if let MdLineFragment::Checkbox(is_checked) = fragment {
+                acc.total += 1;
+                if *is_checked {
+                    acc.done += 1;
+                }
+            }
+        }
+    }
+    acc
+}
+
+#[cfg(test)]
+mod tests {
+    use r3bl_rs_utils_core::*;
+
+    use super::*;
+
+    #[test]
+    fn test_get_task_stats_by_section() {
+        let input = [
+            "- [x] before the first heading",
+            "# Project",
+            "- [ ] plan",
+            "## Backend",
+            "- [x] api",
+            "- [x] db",
+            "  - [ ] migrations",
+            "### Tests",
+            "- [ ] unit",
+            "## Frontend",
+            "Nothing here yet [x] (this isn't a task).",
+            "# Other",
+            "- no checkbox",
+            "",
+        ]
+        .join("\n");
+        let (_, document) = parse_markdown(&input).unwrap();
+
+        let stats = |done, total| MdTaskStats { done, total };
+        let sections = get_task_stats_by_section(&document)
+            .iter()
+            .map(|it| {
+                (
+                    it.heading.as_ref().map(|heading| heading.text),
+                    it.own,
+                    it.total,
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq2!(
+            sections,
+            vec![
+                (None, stats(1, 1), stats(1, 1)),
+                (Some("Project"), stats(0, 1), stats(2, 5)),
+                (Some("Backend"), stats(2, 3), stats(2, 4)),
+                (Some("Tests"), stats(0, 1), stats(0, 1)),
+                (Some("Frontend"), stats(0, 0), stats(0, 0)),
+                (Some("Other"), stats(0, 0), stats(0, 0)),
+            ]
+        );
+        assert_eq2!(get_task_stats_by_section(&document)[1].block_index, 1);
+
+        let total = get_task_stats(&document);
+        assert_eq2!(total, stats(3, 6));
+        assert_eq2!(total.get_pending(), 3);
+        assert_eq2!(total.get_done_ratio(), Some(0.5));
+        assert_eq2!(MdTaskStats::default().get_done_ratio(), None);
+        assert!(MdTaskStats::default().is_complete());
+    }
+}
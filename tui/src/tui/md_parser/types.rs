@@ -15,6 +15,8 @@
  *   limitations under the License.
  */
 
+use r3bl_rs_utils_core::ChUnit;
+
 use crate::{BulletKind, List};
 
 /// This corresponds to a single Markdown document, which is produced after a successful parse
@@ -51,10 +53,16 @@ pub enum MdBlockElement<'a> {
     SmartList((Lines<'a>, BulletKind, usize)),
     Text(MdLineFragments<'a>),
     CodeBlock(List<CodeBlockLine<'a>>),
+    Table(TableData<'a>),
+    BlockQuote(BlockQuoteLines<'a>),
+    HorizontalRule,
     Title(&'a str),
     Date(&'a str),
     Tags(List<&'a str>),
     Authors(List<&'a str>),
+    FrontMatter(FrontMatterData<'a>),
+    LinkDefinition(LinkDefinitionData<'a>),
+    FootnoteDefinition(FootnoteDefinitionData<'a>),
 }
 
 /// These are things that show up in a single line of Markdown text [MdLineFragments]. They do not
@@ -71,12 +79,23 @@ pub enum MdLineFragment<'a> {
         is_first_line: bool,
     },
     Plain(&'a str),
-    Bold(&'a str),
-    Italic(&'a str),
-    BoldItalic(&'a str),
+    /// Formatting spans can contain other fragments, eg: `**bold w/ *italic* inside**`.
+    Bold(MdLineFragments<'a>),
+    Italic(MdLineFragments<'a>),
+    BoldItalic(MdLineFragments<'a>),
+    Strikethrough(MdLineFragments<'a>),
+    /// The content of inline code is kept as is, so it can't contain formatting or escapes.
     InlineCode(&'a str),
+    /// A backslash escaped ASCII punctuation char, eg: `\*`. This only holds the char (w/out the
+    /// backslash).
+    Escaped(&'a str),
     Link(HyperlinkData<'a>),
     Image(HyperlinkData<'a>),
+    WikiLink(WikiLinkData<'a>),
+    AutoLink(&'a str),
+    ReferenceLink(ReferenceLinkData<'a>),
+    /// Eg: `[^1]`. This only holds the label (w/out the brackets & caret).
+    FootnoteReference(&'a str),
     Checkbox(bool),
 }
 
@@ -98,6 +117,63 @@ mod hyperlink_data_impl {
     }
 }
 
+/// Eg: `[[target]]` or `[[target|alias]]`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct WikiLinkData<'a> {
+    pub target: &'a str,
+    pub alias: Option<&'a str>,
+}
+
+impl<'a> WikiLinkData<'a> {
+    /// The text that is displayed for this link.
+    pub fn get_text(&self) -> &'a str { self.alias.unwrap_or(self.target) }
+}
+
+/// Eg: `[text][reference]` or `[text][]` (in which case `reference` is empty, and `text` is used
+/// as the reference). The `url` is resolved from the [LinkDefinitionData] w/ the same reference
+/// in the document, by [parse_markdown](crate::parse_markdown). It is [None] if there isn't one.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ReferenceLinkData<'a> {
+    pub text: &'a str,
+    pub reference: &'a str,
+    pub url: Option<&'a str>,
+}
+
+impl<'a> ReferenceLinkData<'a> {
+    pub fn new(text: &'a str, reference: &'a str) -> Self {
+        Self {
+            text,
+            reference,
+            url: None,
+        }
+    }
+
+    /// The reference that is used to look up the [LinkDefinitionData].
+    pub fn get_reference(&self) -> &'a str {
+        if self.reference.is_empty() {
+            self.text
+        } else {
+            self.reference
+        }
+    }
+}
+
+/// Eg: `[reference]: https://r3bl.com "Optional title"`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LinkDefinitionData<'a> {
+    pub reference: &'a str,
+    pub url: &'a str,
+    pub title: Option<&'a str>,
+}
+
+/// Eg: `[^1]: Some *text* for the footnote.`. The [MdLineFragment::FootnoteReference]s w/ the
+/// same label refer to this.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FootnoteDefinitionData<'a> {
+    pub label: &'a str,
+    pub content: MdLineFragments<'a>,
+}
+
 #[repr(u8)]
 #[derive(Copy, Clone, Debug, PartialEq)]
 pub enum HeadingLevel {
@@ -160,13 +236,35 @@ pub mod constants {
     pub const BOLD_2: &str = "__";
     pub const ITALIC_1: &str = "*";
     pub const ITALIC_2: &str = "_";
+    pub const STRIKETHROUGH: &str = "~~";
     pub const BACK_TICK: &str = "`";
+    pub const BACKSLASH: &str = "\\";
+    /// Chars that plain text stops at, which are kept as is if they don't start a
+    /// [MdLineFragment] (eg: an unclosed `*`).
+    pub const UNMATCHED_DELIMITER_CHARS: [char; 6] = ['*', '_', '~', '`', '[', '!'];
+    /// How deep formatting spans can be nested, eg: `*italic **bold** italic*` is 2 deep. This
+    /// bounds the backtracking that is done for delimiters that are never closed.
+    pub const MAX_INLINE_NESTING_DEPTH: usize = 3;
     pub const LEFT_BRACKET: &str = "[";
     pub const RIGHT_BRACKET: &str = "]";
     pub const LEFT_PARENTHESIS: &str = "(";
     pub const RIGHT_PARENTHESIS: &str = ")";
     pub const LEFT_IMAGE: &str = "![";
     pub const RIGHT_IMAGE: &str = "]";
+    pub const WIKI_LINK_START: &str = "[[";
+    pub const WIKI_LINK_END: &str = "]]";
+    pub const WIKI_LINK_ALIAS_SEPARATOR: &str = "|";
+    pub const LINK_DEFINITION_SEPARATOR: &str = "]:";
+    pub const LINK_DEFINITION_REFERENCE_STOP_CHARS: &str = "]\n";
+    pub const LINK_DEFINITION_URL_STOP_CHARS: &str = " \t\n";
+    pub const LINK_DEFINITION_TITLE_STOP_CHARS: &str = "\"\n";
+    pub const FOOTNOTE_START: &str = "[^";
+    pub const FOOTNOTE_LABEL_STOP_CHARS: &str = "] \t\n";
+    pub const AUTOLINK_PREFIXES: [&str; 2] = ["https://", "http://"];
+    /// Trailing chars that are not considered part of an autolink. Eg: the period in
+    /// `See https://r3bl.com.`
+    pub const AUTOLINK_TRAILING_PUNCTUATION: [char; 10] =
+        ['.', ',', ':', ';', '!', '?', ')', '*', '_', '~'];
     pub const NEW_LINE: &str = "\n";
     pub const CODE_BLOCK_START_PARTIAL: &str = "```";
     pub const CODE_BLOCK_END: &str = "```";
@@ -174,6 +272,48 @@ pub mod constants {
     pub const UNCHECKED: &str = "[ ]";
     pub const CHECKED_OUTPUT: &str = "┊✔┊";
     pub const UNCHECKED_OUTPUT: &str = "┊┈┊";
+    pub const BLOCK_QUOTE_CHAR: char = '>';
+    pub const HORIZONTAL_RULE_CHARS: [char; 3] = ['-', '*', '_'];
+    pub const HORIZONTAL_RULE_MIN_CHAR_COUNT: usize = 3;
+
+    /// Only for output to Markdown source.
+    pub const HORIZONTAL_RULE_OUTPUT: &str = "---";
+
+    pub const FRONT_MATTER_DELIMITER: &str = "---";
+    pub const FRONT_MATTER_TITLE: &str = "title";
+    pub const FRONT_MATTER_TAGS: &str = "tags";
+    pub const FRONT_MATTER_AUTHORS: &str = "authors";
+    pub const FRONT_MATTER_DATE: &str = "date";
+
+    /// Only for output to terminal.
+    pub const BLOCK_QUOTE_GUTTER_DISPLAY: &str = "┃ ";
+
+    /// Only for output to terminal. This is repeated to fill up the width of the viewport when
+    /// the rule is painted.
+    pub const HORIZONTAL_RULE_DISPLAY: &str = "─";
+
+    pub const TABLE_PIPE: &str = "|";
+    pub const TABLE_PIPE_CHAR: char = '|';
+    pub const TABLE_ALIGN_COLON_CHAR: char = ':';
+    pub const TABLE_ALIGN_DASH_CHAR: char = '-';
+
+    /// Only for output to Markdown source. This is wide enough for a `:-:` delimiter.
+    pub const TABLE_DELIMITER_MIN_WIDTH: usize = 3;
+
+    /// Only for output to terminal.
+    pub const TABLE_CELL_SEPARATOR_DISPLAY: &str = "│";
+
+    /// Only for output to terminal.
+    pub const TABLE_RULE_DISPLAY: &str = "─";
+
+    /// Only for output to terminal.
+    pub const TABLE_RULE_START_DISPLAY: &str = "├";
+
+    /// Only for output to terminal.
+    pub const TABLE_RULE_JUNCTION_DISPLAY: &str = "┼";
+
+    /// Only for output to terminal.
+    pub const TABLE_RULE_END_DISPLAY: &str = "┤";
 }
 
 #[derive(Debug, PartialEq, Clone)]
@@ -191,3 +331,251 @@ pub enum CodeBlockLineContent<'a> {
     StartTag,
     EndTag,
 }
+
+/// One line of a block quote. `depth` is the number of `>` markers at the start of the line, so
+/// `> > foo` and `>> foo` both have a depth of 2.
+#[derive(Debug, PartialEq, Clone)]
+pub struct BlockQuoteLine<'a> {
+    pub depth: usize,
+    pub content: MdLineFragments<'a>,
+}
+
+/// Alias for [List] of [BlockQuoteLine].
+pub type BlockQuoteLines<'a> = List<BlockQuoteLine<'a>>;
+
+/// YAML front matter, which can only be at the very start of a document, in between two `---`
+/// lines. Eg:
+/// ```text
+/// ---
+/// title: Something
+/// tags: [tag1, tag2]
+/// ---
+/// ```
+#[derive(Debug, PartialEq, Clone)]
+pub struct FrontMatterData<'a> {
+    /// The lines in between the `---` delimiters, as they are in the source.
+    pub lines: List<&'a str>,
+    /// The parsed YAML. This is always a [FrontMatterValue::Map].
+    pub value: FrontMatterValue,
+}
+
+/// A typed tree of the values in [FrontMatterData].
+#[derive(Debug, PartialEq, Clone)]
+pub enum FrontMatterValue {
+    Null,
+    Bool(bool),
+    Integer(i64),
+    Float(f64),
+    String(String),
+    List(List<FrontMatterValue>),
+    /// Keys are in the same order as in the source.
+    Map(List<(String, FrontMatterValue)>),
+}
+
+impl FrontMatterValue {
+    /// Returns the value for `key` if this is a [FrontMatterValue::Map].
+    pub fn get(&self, key: &str) -> Option<&FrontMatterValue> {
+        match self {
+            FrontMatterValue::Map(entries) => entries
+                .iter()
+                .find(|(entry_key, _)| entry_key == key)
+                .map(|(_, value)| value),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            FrontMatterValue::String(text) => Some(text),
+            _ => None,
+        }
+    }
+
+    /// A [FrontMatterValue::List] of strings, or a single comma separated string. Eg:
+    /// `[tag1, tag2]` or `tag1, tag2`.
+    pub fn as_str_list(&self) -> Option<List<&str>> {
+        match self {
+            FrontMatterValue::List(items) => {
+                let it = items
+                    .iter()
+                    .filter_map(FrontMatterValue::as_str)
+                    .collect::<Vec<_>>();
+                Some(List::from(it))
+            }
+            FrontMatterValue::String(text) => {
+                let it = text
+                    .split(constants::COMMA)
+                    .map(str::trim)
+                    .filter(|it| !it.is_empty())
+                    .collect::<Vec<_>>();
+                Some(List::from(it))
+            }
+            _ => None,
+        }
+    }
+}
+
+impl<'a> FrontMatterData<'a> {
+    /// The [MdBlockElement::Title], [MdBlockElement::Tags], [MdBlockElement::Authors] and
+    /// [MdBlockElement::Date] that are populated from the front matter (for the keys that are
+    /// present). [parse_markdown] emits these blocks right after the front matter block.
+    ///
+    /// Just like the rest of the [MdDocument], these blocks hold slices of the source. YAML values
+    /// are unescaped, so a value that doesn't show up as is in the source (eg: a quoted string w/
+    /// an escape sequence) is left out.
+    pub fn get_metadata_blocks(&self) -> List<MdBlockElement<'a>> {
+        let mut acc = List::new();
+        if let Some(title) = self.get_str(constants::FRONT_MATTER_TITLE) {
+            acc.push(MdBlockElement::Title(title));
+        }
+        if let Some(tags) = self.get_str_list(constants::FRONT_MATTER_TAGS) {
+            acc.push(MdBlockElement::Tags(tags));
+        }
+        if let Some(authors) = self.get_str_list(constants::FRONT_MATTER_AUTHORS) {
+            acc.push(MdBlockElement::Authors(authors));
+        }
+        if let Some(date) = self.get_str(constants::FRONT_MATTER_DATE) {
+            acc.push(MdBlockElement::Date(date));
+        }
+        acc
+    }
+
+    fn get_str(&self, key: &str) -> Option<&'a str> {
+        let value = self.value.get(key)?.as_str()?;
+        self.get_source_slices(key, &[value]).pop()
+    }
+
+    fn get_str_list(&self, key: &str) -> Option<List<&'a str>> {
+        let values = self.value.get(key)?.as_str_list()?;
+        Some(List::from(self.get_source_slices(key, &values)))
+    }
+
+    /// Find each of the `values` (in order) in the source lines of the top level `key`, which are
+    /// the line that starts w/ the key, and the indented (or `- ` list item) lines after it.
+    fn get_source_slices(&self, key: &str, values: &[&str]) -> Vec<&'a str> {
+        let key_prefix = format!("{key}{}", constants::COLON);
+        let Some(key_line_index) = self
+            .lines
+            .iter()
+            .position(|line| line.starts_with(&key_prefix))
+        else {
+            return vec![];
+        };
+        let is_continuation_line = |line: &str| {
+            line.is_empty()
+                || line.starts_with(constants::SPACE)
+                || line.starts_with(constants::UNORDERED_LIST_PREFIX)
+        };
+        let key_lines = std::iter::once((self.lines[key_line_index], key_prefix.len()))
+            .chain(
+                self.lines[key_line_index + 1..]
+                    .iter()
+                    .take_while(|line| is_continuation_line(line))
+                    .map(|line| (*line, 0)),
+            )
+            .collect::<Vec<_>>();
+
+        // Cursor is the index in `key_lines` & the byte offset in that line.
+        let mut cursor = (0, key_lines[0].1);
+        let mut acc = vec![];
+        for value in values {
+            for (line_index, (line, line_start)) in
+                key_lines.iter().enumerate().skip(cursor.0)
+            {
+                let search_start = if line_index == cursor.0 {
+                    cursor.1
+                } else {
+                    *line_start
+                };
+                if let Some(offset) = line[search_start..].find(value) {
+                    let start = search_start + offset;
+                    acc.push(&line[start..start + value.len()]);
+                    cursor = (line_index, start + value.len());
+                    break;
+                }
+            }
+        }
+        acc
+    }
+}
+
+/// A single cell in a [TableData] row. Each cell is parsed into [MdLineFragments] just like a line
+/// of text.
+pub type TableCell<'a> = MdLineFragments<'a>;
+
+/// Alias for [List] of [TableCell].
+pub type TableRow<'a> = List<TableCell<'a>>;
+
+/// This corresponds to a GFM table. Every row (including the header row) has exactly as many cells
+/// as there are items in `alignments`.
+///
+/// ```text
+/// | Name  | Qty |   <- header
+/// | :---- | --: |   <- alignments
+/// | apple |   3 |   <- rows[0]
+/// ```
+#[derive(Debug, PartialEq, Clone)]
+pub struct TableData<'a> {
+    pub header: TableRow<'a>,
+    pub alignments: List<TableColumnAlignment>,
+    pub rows: List<TableRow<'a>>,
+}
+
+impl TableData<'_> {
+    pub fn column_count(&self) -> usize { self.alignments.len() }
+}
+
+/// Alignment of a table column, which comes from the delimiter row of the table.
+/// - `---`  : [TableColumnAlignment::Default]
+/// - `:--`  : [TableColumnAlignment::Left]
+/// - `:-:`  : [TableColumnAlignment::Center]
+/// - `--:`  : [TableColumnAlignment::Right]
+#[derive(Copy, Clone, Debug, PartialEq, Default)]
+pub enum TableColumnAlignment {
+    #[default]
+    Default,
+    Left,
+    Center,
+    Right,
+}
+
+/// A position in the Markdown source that was parsed. `row_index` & `col_index` use the same
+/// coordinate system as the editor caret (the caret position returned by
+/// [crate::EditorBuffer::get_caret] w/ [crate::CaretKind::ScrollAdjusted]), so `col_index` is a
+/// display column and not a byte or char index.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub struct SourcePosition {
+    pub byte_offset: usize,
+    pub row_index: ChUnit,
+    pub col_index: ChUnit,
+}
+
+/// A half open range `[start, end)` in the Markdown source. Block spans don't include the trailing
+/// newline.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+pub struct SourceSpan {
+    pub start: SourcePosition,
+    pub end: SourcePosition,
+}
+
+/// The [SourceSpan] of a single [MdLineFragment], including its delimiters (eg: the `**` around
+/// [MdLineFragment::Bold]).
+#[derive(Clone, Debug, PartialEq)]
+pub struct MdFragmentSpan<'a> {
+    pub fragment: MdLineFragment<'a>,
+    pub span: SourceSpan,
+}
+
+/// The [SourceSpan] of a single [MdBlockElement], along w/ the spans of all the fragments in it
+/// (in the order in which they show up in the source). The text in blocks that don't hold
+/// [MdLineFragments] (eg: headings, code blocks, metadata & front matter) is reported as
+/// [MdLineFragment::Plain] fragments.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MdBlockSpan<'a> {
+    pub span: SourceSpan,
+    pub fragments: List<MdFragmentSpan<'a>>,
+}
+
+/// Alias for [List] of [MdBlockSpan]. There is one item for each [MdBlockElement] in the
+/// [MdDocument] that was parsed at the same time.
+pub type MdDocumentSpans<'a> = List<MdBlockSpan<'a>>;
@@ -54,6 +54,7 @@ pub mod layout;
 pub mod lolcat;
 pub mod md_parser;
 pub mod misc_types;
+pub mod outline;
 pub mod rsx;
 pub mod syntax_highlighting;
 pub mod terminal_lib_backends;
@@ -68,6 +69,7 @@ pub use layout::*;
 pub use lolcat::*;
 pub use md_parser::*;
 pub use misc_types::*;
+pub use outline::*;
 pub use rsx::*;
 pub use syntax_highlighting::*;
 pub use terminal_lib_backends::*;
@@ -0,0 +1,175 @@
+/*
+ *   Copyright (c) 2023 R3BL LLC
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+use r3bl_rs_utils_core::*;
+
+use crate::{constants::*, *};
+
+/// A heading in a [MdOutline].
+#[derive(Clone, Debug, PartialEq)]
+pub struct MdOutlineItem {
+    pub level: HeadingLevel,
+    /// The text of the heading (w/out the `#`s).
+    pub text: String,
+    /// The row in the [EditorBuffer] where the heading is.
+    pub row_index: ChUnit,
+    /// Index (in [MdOutline::items]) of the closest heading before this one w/ a lower level. This
+    /// is [None] for top level headings.
+    pub maybe_parent_index: Option<usize>,
+}
+
+/// The tree of headings in a Markdown document, in the order in which they appear. Each
+/// [MdOutlineItem] points to its parent, so the items are the pre-order traversal of the tree.
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct MdOutline {
+    pub items: Vec<MdOutlineItem>,
+}
+
+impl MdOutline {
+    /// The text of the [EditorBuffer] is parsed as Markdown, and the heading of each
+    /// [MdBlockElement::Heading] is added to the outline. If the text can't be parsed, then the
+    /// outline is empty.
+    pub fn from_editor_buffer(editor_buffer: &EditorBuffer) -> Self {
+        Self::from_lines(editor_buffer.get_lines())
+    }
+
+    pub fn from_lines(lines: &[US]) -> Self {
+        let mut input = String::new();
+        for line in lines {
+            input.push_str(&line.string);
+            input.push_str(NEW_LINE);
+        }
+
+        match parse_markdown_with_spans(&input) {
+            Ok((_, (document, spans))) => Self::from_document(&document, &spans),
+            Err(_) => Self::default(),
+        }
+    }
+
+    /// The `spans` are the ones that are returned by [parse_markdown_with_spans] along w/ the
+    /// `document`.
+    pub fn from_document(document: &MdDocument, spans: &MdDocumentSpans) -> Self {
+        let mut items: Vec<MdOutlineItem> = vec![];
+
+        for (block, block_span) in document.iter().zip(spans.iter()) {
+            if let MdBlockElement::Heading(heading_data) = block {
+                let level = usize::from(heading_data.level);
+                let maybe_parent_index =
+                    items.iter().rposition(|it| usize::from(it.level) < level);
+                items.push(MdOutlineItem {
+                    level: heading_data.level,
+                    text: heading_data.text.trim().to_string(),
+                    row_index: block_span.span.start.row_index,
+                    maybe_parent_index,
+                });
+            }
+        }
+
+        Self { items }
+    }
+
+    pub fn is_empty(&self) -> bool { self.items.is_empty() }
+
+    pub fn len(&self) -> usize { self.items.len() }
+
+    /// Index of the heading of the section that contains the given row, ie: the last heading
+    /// that starts at or before it. This is [None] for rows before the first heading.
+    pub fn get_item_index_for_row(&self, row_index: ChUnit) -> Option<usize> {
+        self.items.iter().rposition(|it| it.row_index <= row_index)
+    }
+
+    /// Index of the heading of the section that contains the caret of the [EditorBuffer].
+    pub fn get_item_index_at_caret(&self, editor_buffer: &EditorBuffer) -> Option<usize> {
+        let caret = editor_buffer.get_caret(CaretKind::ScrollAdjusted);
+        self.get_item_index_for_row(caret.row_index)
+    }
+
+    /// The indices of the items from the root of the tree down to (and including) the given one.
+    pub fn get_path_to_item(&self, index: usize) -> Vec<usize> {
+        let mut acc = vec![];
+        let mut maybe_index = self.items.get(index).map(|_| index);
+        while let Some(index) = maybe_index {
+            acc.push(index);
+            maybe_index = self.items[index].maybe_parent_index;
+        }
+        acc.reverse();
+        acc
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_lines(lines: &[&str]) -> Vec<US> {
+        lines.iter().map(|it| US::from(*it)).collect()
+    }
+
+    #[test]
+    fn test_from_lines() {
+        let lines = make_lines(&[
+            "@title: Outline",
+            "# One",
+            "text",
+            "```md",
+            "# not a heading",
+            "```",
+            "### One.A (skips a level)",
+            "## One.B",
+            "#### One.B.1",
+            "# Two",
+        ]);
+        let outline = MdOutline::from_lines(&lines);
+
+        let items = outline
+            .items
+            .iter()
+            .map(|it| {
+                (
+                    usize::from(it.level),
+                    it.text.as_str(),
+                    ch!(@to_usize it.row_index),
+                    it.maybe_parent_index,
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq2!(
+            items,
+            vec![
+                (1, "One", 1, None),
+                (3, "One.A (skips a level)", 6, Some(0)),
+                (2, "One.B", 7, Some(0)),
+                (4, "One.B.1", 8, Some(2)),
+                (1, "Two", 9, None),
+            ]
+        );
+        assert_eq2!(outline.get_path_to_item(3), vec![0, 2, 3]);
+        assert_eq2!(outline.get_path_to_item(99), Vec::<usize>::new());
+    }
+
+    #[test]
+    fn test_get_item_index_for_row() {
+        let lines = make_lines(&["intro", "# One", "text", "## Two", "text"]);
+        let outline = MdOutline::from_lines(&lines);
+        assert_eq2!(outline.get_item_index_for_row(ch!(0)), None);
+        assert_eq2!(outline.get_item_index_for_row(ch!(1)), Some(0));
+        assert_eq2!(outline.get_item_index_for_row(ch!(2)), Some(0));
+        assert_eq2!(outline.get_item_index_for_row(ch!(3)), Some(1));
+        assert_eq2!(outline.get_item_index_for_row(ch!(100)), Some(1));
+        assert!(MdOutline::from_lines(&make_lines(&["no headings"])).is_empty());
+    }
+}
@@ -0,0 +1,33 @@
+/*
+ *   Copyright (c) 2023 R3BL LLC
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+//! An outline (table of contents) of the Markdown document in an [EditorBuffer].
+//!
+//! 1. [MdOutline] is the tree of headings that is derived from a [MdDocument].
+//! 2. [OutlineEngine] renders it (eg: in a side panel), highlights the heading of the section that
+//!    contains the editor caret, and handles the keyboard & mouse input.
+//! 3. [OutlineComponent] is the shim that allows the [OutlineEngine] to be used as a [Component].
+
+// Attach.
+pub mod md_outline;
+pub mod outline_component;
+pub mod outline_engine;
+
+// Re-export.
+pub use md_outline::*;
+pub use outline_component::*;
+pub use outline_engine::*;
@@ -0,0 +1,180 @@
+/*
+ *   Copyright (c) 2023 R3BL LLC
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+use std::{fmt::Debug, sync::Arc};
+
+use async_trait::async_trait;
+use r3bl_rs_utils_core::*;
+use tokio::sync::RwLock;
+
+use crate::*;
+
+/// This is a shim which allows the reusable [OutlineEngine] to be used in the context of
+/// [Component] and [r3bl_redux::Store]. It shows the outline (table of contents) of the Markdown
+/// document in the [EditorBuffer] of another component (usually an [EditorComponent]), eg: in a
+/// side panel.
+///
+/// When the user jumps to a heading, the caret & scroll offset of the [EditorBuffer] are changed,
+/// and the new buffer is passed to the `on_outline_jump_handler`. This has the same signature as
+/// the handler of the [EditorComponent], so the same one can be used for both.
+#[derive(Clone, Default)]
+pub struct OutlineComponent<S, A>
+where
+    S: Debug + Default + Clone + PartialEq + Sync + Send,
+    A: Debug + Default + Clone + Sync + Send,
+{
+    pub id: FlexBoxId,
+    /// The id of the component whose [EditorBuffer] the outline is derived from.
+    pub editor_id: FlexBoxId,
+    pub outline_engine: OutlineEngine,
+    /// Make sure to dispatch an action to update the editor buffer w/ `editor_id`.
+    pub on_outline_jump_handler: Option<OnEditorBufferChangeFn<S, A>>,
+}
+
+mod outline_component_impl {
+    use super::*;
+
+    #[async_trait]
+    impl<S, A> Component<S, A> for OutlineComponent<S, A>
+    where
+        S: HasEditorBuffers + Default + Clone + PartialEq + Debug + Sync + Send,
+        A: Debug + Default + Clone + Sync + Send,
+    {
+        fn reset(&mut self) {}
+
+        fn get_id(&self) -> FlexBoxId { self.id }
+
+        /// This shim simply calls [OutlineEngineApi::apply_event](OutlineEngineApi::apply_event)
+        /// w/ the [EditorBuffer] of the editor component (if there is one).
+        async fn handle_event(
+            &mut self,
+            args: ComponentScopeArgs<'_, S, A>,
+            input_event: &InputEvent,
+        ) -> CommonResult<EventPropagation> {
+            throws_with_return!({
+                let ComponentScopeArgs {
+                    shared_store,
+                    state,
+                    ..
+                } = args;
+
+                let Some(editor_buffer) = state.get_editor_buffer(self.editor_id) else {
+                    return Ok(EventPropagation::Propagate);
+                };
+
+                match OutlineEngineApi::apply_event(
+                    &mut self.outline_engine,
+                    editor_buffer,
+                    input_event,
+                ) {
+                    OutlineEngineApplyEventResult::Jumped(new_editor_buffer) => {
+                        if let Some(on_jump_handler) = self.on_outline_jump_handler {
+                            on_jump_handler(
+                                shared_store,
+                                self.editor_id,
+                                new_editor_buffer,
+                            );
+                        }
+                        EventPropagation::Consumed
+                    }
+                    OutlineEngineApplyEventResult::SelectionChanged => {
+                        EventPropagation::ConsumedRender
+                    }
+                    OutlineEngineApplyEventResult::NotApplied => {
+                        EventPropagation::Propagate
+                    }
+                }
+            });
+        }
+
+        /// This shim simply calls
+        /// [OutlineEngineApi::render_engine](OutlineEngineApi::render_engine) w/ the
+        /// [EditorBuffer] of the editor component. If there isn't one yet, then an empty buffer
+        /// is used.
+        async fn render(
+            &mut self,
+            args: ComponentScopeArgs<'_, S, A>,
+            current_box: &FlexBox,
+            _surface_bounds: SurfaceBounds, /* Ignore this. */
+        ) -> CommonResult<RenderPipeline> {
+            let ComponentScopeArgs {
+                state,
+                component_registry,
+                ..
+            } = args;
+
+            let empty_editor_buffer;
+            let editor_buffer = match state.get_editor_buffer(self.editor_id) {
+                Some(it) => it,
+                None => {
+                    empty_editor_buffer = EditorBuffer::new_empty(None);
+                    &empty_editor_buffer
+                }
+            };
+
+            let has_focus = component_registry.has_focus.does_id_have_focus(self.id);
+
+            Ok(OutlineEngineApi::render_engine(
+                &mut self.outline_engine,
+                editor_buffer,
+                current_box,
+                has_focus,
+            ))
+        }
+    }
+}
+
+mod constructor {
+    use super::*;
+
+    impl<S, A> OutlineComponent<S, A>
+    where
+        S: Debug + Default + Clone + PartialEq + Sync + Send,
+        A: Debug + Default + Clone + Sync + Send,
+    {
+        /// The `on_outline_jump` is a lambda that is called w/ the new [EditorBuffer] when the
+        /// user jumps to a heading. Typically this results in a Redux action being created and
+        /// then dispatched to the given store.
+        pub fn new(
+            id: FlexBoxId,
+            editor_id: FlexBoxId,
+            config_options: OutlineEngineConfigOptions,
+            on_outline_jump: OnEditorBufferChangeFn<S, A>,
+        ) -> Self {
+            Self {
+                id,
+                editor_id,
+                outline_engine: OutlineEngine::new(config_options),
+                on_outline_jump_handler: Some(on_outline_jump),
+            }
+        }
+
+        pub fn new_shared(
+            id: FlexBoxId,
+            editor_id: FlexBoxId,
+            config_options: OutlineEngineConfigOptions,
+            on_outline_jump: OnEditorBufferChangeFn<S, A>,
+        ) -> Arc<RwLock<Self>> {
+            Arc::new(RwLock::new(OutlineComponent::new(
+                id,
+                editor_id,
+                config_options,
+                on_outline_jump,
+            )))
+        }
+    }
+}
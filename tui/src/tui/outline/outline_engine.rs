@@ -0,0 +1,531 @@
+/*
+ *   Copyright (c) 2023 R3BL LLC
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+use r3bl_rs_utils_core::*;
+use r3bl_rs_utils_macro::style;
+
+use crate::*;
+
+/// Shown when the document doesn't have any headings.
+pub const OUTLINE_EMPTY_STATE_MSG: &str = "No headings";
+
+/// Holds the data of the outline in between render calls. This is stored in the
+/// [OutlineComponent], and the [EditorBuffer] that the outline is derived from lives in the
+/// [r3bl_redux::Store].
+#[derive(Clone, Debug, Default)]
+pub struct OutlineEngine {
+    pub config_options: OutlineEngineConfigOptions,
+    /// Set by [OutlineEngineApi::render_engine](OutlineEngineApi::render_engine). It is used to
+    /// find the heading that is clicked on.
+    pub current_box: PartialFlexBox,
+    /// Rebuilt by [OutlineEngineApi::update_outline] when the lines of the [EditorBuffer] change.
+    pub outline: MdOutline,
+    /// The lines that `outline` was built from.
+    source_lines: Vec<US>,
+    /// The heading that is selected using the keyboard.
+    pub selected_row_index: ChUnit,
+    pub scroll_offset_row_index: ChUnit,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct OutlineEngineConfigOptions {
+    /// The number of spaces that each [HeadingLevel] (after the first) is indented by.
+    pub indent_width: ChUnit,
+    pub maybe_style_item: Option<Style>,
+    /// Style of the heading of the section that contains the editor caret.
+    pub maybe_style_active_item: Option<Style>,
+    /// Style of the heading that is selected (only shown when the outline has focus).
+    pub maybe_style_selected_item: Option<Style>,
+}
+
+impl Default for OutlineEngineConfigOptions {
+    fn default() -> Self {
+        Self {
+            indent_width: ch!(2),
+            maybe_style_item: None,
+            maybe_style_active_item: Some(style! { attrib: [bold] }),
+            maybe_style_selected_item: Some(style! { attrib: [underline] }),
+        }
+    }
+}
+
+impl OutlineEngine {
+    pub fn new(config_options: OutlineEngineConfigOptions) -> Self {
+        Self {
+            config_options,
+            ..Default::default()
+        }
+    }
+
+    pub fn viewport_height(&self) -> ChUnit {
+        self.current_box.style_adjusted_bounds_size.row_count
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum OutlineEngineApplyEventResult {
+    /// The caret of the [EditorBuffer] has been moved to a heading. This is the new buffer.
+    Jumped(EditorBuffer),
+    /// The selected heading has changed, so the outline has to be rendered again.
+    SelectionChanged,
+    NotApplied,
+}
+
+/// Things you can do w/ an [OutlineEngine].
+pub struct OutlineEngineApi;
+
+impl OutlineEngineApi {
+    /// Rebuild the [MdOutline] if the lines of the `editor_buffer` have changed since the last
+    /// time, and keep the selected heading in range.
+    pub fn update_outline(
+        outline_engine: &mut OutlineEngine,
+        editor_buffer: &EditorBuffer,
+    ) {
+        if outline_engine.source_lines != *editor_buffer.get_lines() {
+            outline_engine.outline = MdOutline::from_editor_buffer(editor_buffer);
+            outline_engine.source_lines = editor_buffer.get_lines().clone();
+        }
+
+        let max_row_index = ch!(outline_engine.outline.len()).max(ch!(1)) - ch!(1);
+        if outline_engine.selected_row_index > max_row_index {
+            outline_engine.selected_row_index = max_row_index;
+        }
+    }
+
+    /// Handles these [InputEvent]s:
+    /// - <kbd>Up</kbd> & <kbd>Down</kbd> change the selected heading.
+    /// - <kbd>Enter</kbd> jumps to the selected heading.
+    /// - A left click on a heading selects it & jumps to it.
+    pub fn apply_event(
+        outline_engine: &mut OutlineEngine,
+        editor_buffer: &EditorBuffer,
+        input_event: &InputEvent,
+    ) -> OutlineEngineApplyEventResult {
+        OutlineEngineApi::update_outline(outline_engine, editor_buffer);
+
+        if outline_engine.outline.is_empty() {
+            return OutlineEngineApplyEventResult::NotApplied;
+        }
+
+        match input_event {
+            InputEvent::Keyboard(KeyPress::Plain {
+                key: Key::SpecialKey(SpecialKey::Up),
+            }) => {
+                if outline_engine.selected_row_index > ch!(0) {
+                    outline_engine.selected_row_index -= 1;
+                }
+                OutlineEngineApi::scroll_selected_into_view(outline_engine);
+                OutlineEngineApplyEventResult::SelectionChanged
+            }
+            InputEvent::Keyboard(KeyPress::Plain {
+                key: Key::SpecialKey(SpecialKey::Down),
+            }) => {
+                if outline_engine.selected_row_index + 1
+                    < ch!(outline_engine.outline.len())
+                {
+                    outline_engine.selected_row_index += 1;
+                }
+                OutlineEngineApi::scroll_selected_into_view(outline_engine);
+                OutlineEngineApplyEventResult::SelectionChanged
+            }
+            InputEvent::Keyboard(KeyPress::Plain {
+                key: Key::SpecialKey(SpecialKey::Enter),
+            }) => OutlineEngineApi::jump_to_selected(outline_engine, editor_buffer),
+            InputEvent::Mouse(MouseInput {
+                pos,
+                kind: MouseInputKind::MouseDown(Button::Left),
+                ..
+            }) => match OutlineEngineApi::get_row_index_at(outline_engine, *pos) {
+                Some(row_index) => {
+                    outline_engine.selected_row_index = row_index;
+                    OutlineEngineApi::jump_to_selected(outline_engine, editor_buffer)
+                }
+                None => OutlineEngineApplyEventResult::NotApplied,
+            },
+            _ => OutlineEngineApplyEventResult::NotApplied,
+        }
+    }
+
+    /// Move the caret to the start of the heading, and scroll the editor so that the heading is
+    /// on its first row.
+    pub fn jump_to_item(
+        editor_buffer: &EditorBuffer,
+        item: &MdOutlineItem,
+    ) -> EditorBuffer {
+        let mut new_editor_buffer = editor_buffer.clone();
+        new_editor_buffer.clear_selection();
+        let (_, caret, scroll_offset, _) = new_editor_buffer.get_mut();
+        *caret = position!(col_index: 0, row_index: 0);
+        *scroll_offset = position!(col_index: 0, row_index: item.row_index);
+        new_editor_buffer
+    }
+
+    fn jump_to_selected(
+        outline_engine: &OutlineEngine,
+        editor_buffer: &EditorBuffer,
+    ) -> OutlineEngineApplyEventResult {
+        let selected_index = ch!(@to_usize outline_engine.selected_row_index);
+        match outline_engine.outline.items.get(selected_index) {
+            Some(item) => OutlineEngineApplyEventResult::Jumped(
+                OutlineEngineApi::jump_to_item(editor_buffer, item),
+            ),
+            None => OutlineEngineApplyEventResult::NotApplied,
+        }
+    }
+
+    /// Index of the heading that is painted at the given (absolute) position, if any.
+    fn get_row_index_at(outline_engine: &OutlineEngine, pos: Position) -> Option<ChUnit> {
+        let origin_pos = outline_engine.current_box.style_adjusted_origin_pos;
+        let bounds_size = outline_engine.current_box.style_adjusted_bounds_size;
+
+        let is_in_box = pos.col_index >= origin_pos.col_index
+            && pos.col_index < origin_pos.col_index + bounds_size.col_count
+            && pos.row_index >= origin_pos.row_index
+            && pos.row_index < origin_pos.row_index + bounds_size.row_count;
+        if !is_in_box {
+            return None;
+        }
+
+        let row_index =
+            pos.row_index - origin_pos.row_index + outline_engine.scroll_offset_row_index;
+        (row_index < ch!(outline_engine.outline.len())).then_some(row_index)
+    }
+
+    fn scroll_selected_into_view(outline_engine: &mut OutlineEngine) {
+        let viewport_height = outline_engine.viewport_height().max(ch!(1));
+        if outline_engine.selected_row_index < outline_engine.scroll_offset_row_index {
+            outline_engine.scroll_offset_row_index = outline_engine.selected_row_index;
+        } else if outline_engine.selected_row_index
+            >= outline_engine.scroll_offset_row_index + viewport_height
+        {
+            outline_engine.scroll_offset_row_index =
+                outline_engine.selected_row_index - viewport_height + ch!(1);
+        }
+    }
+
+    /// Paint the headings, indented by their [HeadingLevel]. The heading of the section that
+    /// contains the caret of the `editor_buffer` is highlighted. If the outline doesn't have
+    /// focus, then the selection follows the caret.
+    pub fn render_engine(
+        outline_engine: &mut OutlineEngine,
+        editor_buffer: &EditorBuffer,
+        current_box: &FlexBox,
+        has_focus: bool,
+    ) -> RenderPipeline {
+        outline_engine.current_box = current_box.into();
+        OutlineEngineApi::update_outline(outline_engine, editor_buffer);
+
+        let maybe_active_index = outline_engine
+            .outline
+            .get_item_index_at_caret(editor_buffer);
+        if !has_focus {
+            if let Some(active_index) = maybe_active_index {
+                outline_engine.selected_row_index = ch!(active_index);
+            }
+        }
+        OutlineEngineApi::scroll_selected_into_view(outline_engine);
+
+        let OutlineEngine {
+            config_options,
+            current_box,
+            outline,
+            selected_row_index,
+            scroll_offset_row_index,
+            ..
+        } = &*outline_engine;
+        let origin_pos = current_box.style_adjusted_origin_pos;
+        let bounds_size = current_box.style_adjusted_bounds_size;
+        let base_style = current_box.get_computed_style().unwrap_or_default()
+            + config_options.maybe_style_item.unwrap_or_default();
+
+        let mut render_ops = render_ops!();
+
+        if outline.is_empty() {
+            let style = base_style + style! { attrib: [dim] };
+            render_ops.push(RenderOp::ResetColor);
+            render_ops.push(RenderOp::MoveCursorPositionRelTo(
+                origin_pos,
+                position!(col_index: 0, row_index: 0),
+            ));
+            render_ops.push(RenderOp::ApplyColors(Some(style)));
+            render_ops.push(RenderOp::PaintTextWithAttributes(
+                US::from(OUTLINE_EMPTY_STATE_MSG)
+                    .clip_to_width(ch!(0), bounds_size.col_count)
+                    .to_string(),
+                Some(style),
+            ));
+            render_ops.push(RenderOp::ResetColor);
+        }
+
+        let visible_items = outline
+            .items
+            .iter()
+            .enumerate()
+            .skip(ch!(@to_usize *scroll_offset_row_index))
+            .take(ch!(@to_usize bounds_size.row_count));
+
+        for (rel_row_index, (index, item)) in visible_items.enumerate() {
+            let mut style = base_style;
+            if maybe_active_index == Some(index) {
+                style += config_options.maybe_style_active_item.unwrap_or_default();
+            }
+            if has_focus && ch!(index) == *selected_row_index {
+                style += config_options.maybe_style_selected_item.unwrap_or_default();
+            }
+
+            let indent = ch!(@to_usize config_options.indent_width)
+                * (usize::from(item.level) - 1);
+            let text = US::from(format!("{}{}", " ".repeat(indent), item.text));
+
+            render_ops.push(RenderOp::ResetColor);
+            render_ops.push(RenderOp::MoveCursorPositionRelTo(
+                origin_pos,
+                position!(col_index: 0, row_index: ch!(rel_row_index)),
+            ));
+            render_ops.push(RenderOp::ApplyColors(Some(style)));
+            render_ops.push(RenderOp::PaintTextWithAttributes(
+                text.clip_to_width(ch!(0), bounds_size.col_count)
+                    .to_string(),
+                Some(style),
+            ));
+            render_ops.push(RenderOp::ResetColor);
+        }
+
+        let mut render_pipeline = render_pipeline!();
+        render_pipeline.push(ZOrder::Normal, render_ops);
+        render_pipeline
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_editor_buffer(lines: &[&str]) -> EditorBuffer {
+        let mut editor_buffer = EditorBuffer::new_empty(Some("md"));
+        editor_buffer.set_lines(lines.iter().map(|it| it.to_string()).collect());
+        editor_buffer
+    }
+
+    fn make_flex_box(row_count: u16) -> FlexBox {
+        FlexBox {
+            style_adjusted_bounds_size: size!( col_count: 12, row_count: row_count ),
+            style_adjusted_origin_pos: position!( col_index: 2, row_index: 1 ),
+            ..Default::default()
+        }
+    }
+
+    /// The text that is painted on each row.
+    fn get_painted_text(
+        render_pipeline: &RenderPipeline,
+    ) -> Vec<(String, Option<Style>)> {
+        render_pipeline
+            .get_all_render_op_in(ZOrder::Normal)
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|it| match it {
+                RenderOp::PaintTextWithAttributes(text, maybe_style) => {
+                    Some((text, maybe_style))
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    const LINES: [&str; 7] = [
+        "# One",
+        "text",
+        "## One.A",
+        "text",
+        "### One.A.i (which is long)",
+        "# Two",
+        "text",
+    ];
+
+    #[test]
+    fn test_render_engine() {
+        let mut editor_buffer = make_editor_buffer(&LINES);
+        editor_buffer.get_mut().1.row_index = ch!(3);
+        let mut outline_engine = OutlineEngine::default();
+
+        let render_pipeline = OutlineEngineApi::render_engine(
+            &mut outline_engine,
+            &editor_buffer,
+            &make_flex_box(3),
+            false,
+        );
+        let item_style = Some(Style::default() + Style::default());
+        let active_style = Some(Style::default() + style! { attrib: [bold] });
+        assert_eq2!(
+            get_painted_text(&render_pipeline),
+            vec![
+                ("One".to_string(), item_style),
+                ("  One.A".to_string(), active_style),
+                ("    One.A.i ".to_string(), item_style),
+            ]
+        );
+        assert_eq2!(outline_engine.selected_row_index, ch!(1));
+
+        // The selection follows the caret (when the outline doesn't have focus), and is
+        // scrolled into view.
+        editor_buffer.get_mut().1.row_index = ch!(6);
+        let render_pipeline = OutlineEngineApi::render_engine(
+            &mut outline_engine,
+            &editor_buffer,
+            &make_flex_box(3),
+            false,
+        );
+        assert_eq2!(
+            get_painted_text(&render_pipeline),
+            vec![
+                ("  One.A".to_string(), item_style),
+                ("    One.A.i ".to_string(), item_style),
+                ("Two".to_string(), active_style),
+            ]
+        );
+        assert_eq2!(outline_engine.selected_row_index, ch!(3));
+        assert_eq2!(outline_engine.scroll_offset_row_index, ch!(1));
+
+        // The selection is only painted when the outline has focus.
+        let render_pipeline = OutlineEngineApi::render_engine(
+            &mut outline_engine,
+            &editor_buffer,
+            &make_flex_box(3),
+            true,
+        );
+        let selected_style = Some(
+            Style::default() + style! { attrib: [bold] } + style! { attrib: [underline] },
+        );
+        assert_eq2!(get_painted_text(&render_pipeline)[2].1, selected_style);
+    }
+
+    #[test]
+    fn test_render_engine_empty() {
+        let editor_buffer = make_editor_buffer(&["no headings"]);
+        let mut outline_engine = OutlineEngine::default();
+        let render_pipeline = OutlineEngineApi::render_engine(
+            &mut outline_engine,
+            &editor_buffer,
+            &make_flex_box(3),
+            false,
+        );
+        let painted_text = get_painted_text(&render_pipeline);
+        assert_eq2!(painted_text.len(), 1);
+        assert_eq2!(painted_text[0].0, OUTLINE_EMPTY_STATE_MSG);
+    }
+
+    #[test]
+    fn test_apply_event_keyboard() {
+        let mut editor_buffer = make_editor_buffer(&LINES);
+        editor_buffer.get_mut().1.row_index = ch!(2);
+        let mut outline_engine = OutlineEngine::default();
+        OutlineEngineApi::render_engine(
+            &mut outline_engine,
+            &editor_buffer,
+            &make_flex_box(2),
+            true,
+        );
+
+        let down = InputEvent::Keyboard(keypress!(@special SpecialKey::Down));
+        let up = InputEvent::Keyboard(keypress!(@special SpecialKey::Up));
+        let enter = InputEvent::Keyboard(keypress!(@special SpecialKey::Enter));
+
+        for _ in 0..5 {
+            assert_eq2!(
+                OutlineEngineApi::apply_event(&mut outline_engine, &editor_buffer, &down),
+                OutlineEngineApplyEventResult::SelectionChanged
+            );
+        }
+        assert_eq2!(outline_engine.selected_row_index, ch!(3));
+        assert_eq2!(outline_engine.scroll_offset_row_index, ch!(2));
+
+        OutlineEngineApi::apply_event(&mut outline_engine, &editor_buffer, &up);
+        assert_eq2!(outline_engine.selected_row_index, ch!(2));
+
+        match OutlineEngineApi::apply_event(&mut outline_engine, &editor_buffer, &enter) {
+            OutlineEngineApplyEventResult::Jumped(new_editor_buffer) => {
+                assert_eq2!(
+                    new_editor_buffer.get_caret(CaretKind::ScrollAdjusted),
+                    position!(col_index: 0, row_index: 4)
+                );
+                assert_eq2!(
+                    new_editor_buffer.get_caret(CaretKind::Raw),
+                    position!(col_index: 0, row_index: 0)
+                );
+                assert_eq2!(new_editor_buffer.get_lines(), editor_buffer.get_lines());
+            }
+            it => panic!("Expected Jumped, got {it:?}"),
+        }
+
+        assert_eq2!(
+            OutlineEngineApi::apply_event(
+                &mut outline_engine,
+                &editor_buffer,
+                &InputEvent::Keyboard(keypress!(@char 'a'))
+            ),
+            OutlineEngineApplyEventResult::NotApplied
+        );
+    }
+
+    #[test]
+    fn test_apply_event_mouse() {
+        let editor_buffer = make_editor_buffer(&LINES);
+        let mut outline_engine = OutlineEngine::default();
+        OutlineEngineApi::render_engine(
+            &mut outline_engine,
+            &editor_buffer,
+            &make_flex_box(3),
+            false,
+        );
+
+        let click = |col_index: u16, row_index: u16| {
+            InputEvent::Mouse(MouseInput {
+                pos: position!(col_index: col_index, row_index: row_index),
+                kind: MouseInputKind::MouseDown(Button::Left),
+                maybe_modifier_keys: None,
+            })
+        };
+
+        // The box starts at row 1, so this is the 2nd heading.
+        match OutlineEngineApi::apply_event(
+            &mut outline_engine,
+            &editor_buffer,
+            &click(5, 2),
+        ) {
+            OutlineEngineApplyEventResult::Jumped(new_editor_buffer) => {
+                assert_eq2!(
+                    new_editor_buffer.get_caret(CaretKind::ScrollAdjusted),
+                    position!(col_index: 0, row_index: 2)
+                );
+            }
+            it => panic!("Expected Jumped, got {it:?}"),
+        }
+        assert_eq2!(outline_engine.selected_row_index, ch!(1));
+
+        // Outside of the box.
+        for (col_index, row_index) in [(0, 2), (14, 2), (5, 0), (5, 4)] {
+            assert_eq2!(
+                OutlineEngineApi::apply_event(
+                    &mut outline_engine,
+                    &editor_buffer,
+                    &click(col_index, row_index)
+                ),
+                OutlineEngineApplyEventResult::NotApplied
+            );
+        }
+    }
+}
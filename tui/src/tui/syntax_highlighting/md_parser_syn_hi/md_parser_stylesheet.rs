@@ -15,220 +15,113 @@
  *   limitations under the License.
  */
 
-//! These are the colors use to highlight the MD document. These are all sensitive to
-//! [ColorSupport] constraints. You can find ANSI colors
-//! [here](https://www.ditig.com/256-colors-cheat-sheet).
+//! These are the styles used to highlight the MD document. They are all read from the
+//! [MarkdownTheme] that is saved in [markdown_theme_global_static], and are sensitive to
+//! [ColorSupport] constraints.
 
 use r3bl_rs_utils_core::*;
-use r3bl_rs_utils_macro::style;
 
 use crate::*;
 
-/// This style is for any selected range in the document.
-pub fn get_selection_style() -> Style {
-    let color_fg = TuiColor::Rgb(RgbValue::from_hex("#dddddd"));
-    let color_bg = TuiColor::Rgb(RgbValue::from_hex("#ff00ff"));
-    style! {
-        color_fg: color_fg
-        color_bg: color_bg
-    }
+/// Get the style for an element from the saved [MarkdownTheme].
+fn get_theme_style(get_element: impl FnOnce(&MarkdownTheme) -> &MdThemeStyle) -> Style {
+    markdown_theme_global_static::with_markdown_theme(|theme| {
+        get_element(theme).to_style(ColorSupport::detect())
+    })
 }
 
+/// This style is for any selected range in the document.
+pub fn get_selection_style() -> Style { get_theme_style(|it| &it.selection) }
+
 /// This style is for the foreground text of the entire document. This is the default
 /// style. It is overridden by other styles like bold, italic, etc. below.
-pub fn get_foreground_style() -> Style {
-    style! {
-        color_fg: match ColorSupport::detect() {
-            ColorSupport::Grayscale => TuiColor::Basic(ANSIBasicColor::White),
-            ColorSupport::Ansi256 => TuiColor::Ansi(AnsiValue::new(244)), // Grey50.
-            ColorSupport::Truecolor => TuiColor::Rgb(RgbValue::from_hex("#c1b3d0")),
-        }
-    }
-}
+pub fn get_foreground_style() -> Style { get_theme_style(|it| &it.foreground) }
 
 /// This style is for things like `[`, `]`, `*`, "`", etc. They are dimmed so that they
 /// don't distract from the main content they are wrapping like a link or inline code
 /// block, etc.
 pub fn get_foreground_dim_style() -> Style {
-    get_foreground_style()
-        + style! {
-            attrib: [dim]
-            color_fg: TuiColor::Rgb(RgbValue::from_hex("#5f5f5f"))
-        }
+    get_foreground_style() + get_theme_style(|it| &it.foreground_dim)
 }
 
 /// This is just for the bold content, not the enclosing `**`.
-pub fn get_bold_style() -> Style {
-    style! {
-        attrib: [bold]
-        color_fg: match ColorSupport::detect() {
-            ColorSupport::Grayscale => TuiColor::Basic(ANSIBasicColor::Yellow),
-            ColorSupport::Ansi256 => TuiColor::Ansi(AnsiValue::new(226)), // Yellow1.
-            ColorSupport::Truecolor => TuiColor::Rgb(RgbValue::from_hex("#dacd24")),
-        }
-    }
-}
+pub fn get_bold_style() -> Style { get_theme_style(|it| &it.bold) }
 
 /// This is just for the bold content, not the enclosing `*`.
-pub fn get_italic_style() -> Style {
-    style! {
-        attrib: [italic]
-        color_fg: match ColorSupport::detect() {
-            ColorSupport::Grayscale => TuiColor::Basic(ANSIBasicColor::DarkYellow),
-            ColorSupport::Ansi256 => TuiColor::Ansi(AnsiValue::new(208)), // DarkOrange.
-            ColorSupport::Truecolor => TuiColor::Rgb(RgbValue::from_hex("#a59e3a")),
-        }
-    }
-}
+pub fn get_italic_style() -> Style { get_theme_style(|it| &it.italic) }
 
 /// This is just for the bold content, not the enclosing `***`.
-pub fn get_bold_italic_style() -> Style {
-    style! {
-        attrib: [bold, italic]
-        color_fg: match ColorSupport::detect() {
-            ColorSupport::Grayscale => TuiColor::Basic(ANSIBasicColor::Yellow),
-            ColorSupport::Ansi256 => TuiColor::Ansi(AnsiValue::new(184)), // Yellow3.
-            ColorSupport::Truecolor => TuiColor::Rgb(RgbValue::from_hex("#dacd24")),
-        }
-    }
+pub fn get_bold_italic_style() -> Style { get_theme_style(|it| &it.bold_italic) }
+
+/// This is just for the struck content, not the enclosing `~~`.
+pub fn get_strikethrough_style() -> Style {
+    get_foreground_style() + get_theme_style(|it| &it.strikethrough)
 }
 
 /// This is just for the bold content, not the enclosing "`".
-pub fn get_inline_code_style() -> Style {
-    style! {
-        color_fg: match ColorSupport::detect() {
-            ColorSupport::Grayscale => TuiColor::Basic(ANSIBasicColor::Magenta),
-            // ColorSupport::Ansi256 => TuiColor::Ansi(AnsiValue::new(126)), //
-            // MediumVioletRed. ColorSupport::Ansi256 =>
-            // TuiColor::Ansi(AnsiValue::new(177)), // Violet.
-            ColorSupport::Ansi256 => TuiColor::Ansi(AnsiValue::new(169)), // HotPink2.
-            ColorSupport::Truecolor => TuiColor::Rgb(RgbValue::from_hex("#ce55b7")),
-        }
-    }
-}
+pub fn get_inline_code_style() -> Style { get_theme_style(|it| &it.inline_code) }
 
 /// This is just for the link text not the enclosing `[` and `]`.
-pub fn get_link_text_style() -> Style {
-    style! {
-        color_fg: match ColorSupport::detect() {
-            ColorSupport::Grayscale => TuiColor::Basic(ANSIBasicColor::Blue),
-            ColorSupport::Ansi256 => TuiColor::Ansi(AnsiValue::new(33)), // DodgerBlue1.
-            ColorSupport::Truecolor => TuiColor::Rgb(RgbValue::from_hex("#4f86ed")),
-        }
-    }
-}
+pub fn get_link_text_style() -> Style { get_theme_style(|it| &it.link_text) }
 
 /// This is just for the link url not the enclosing `(` and `)`.
-pub fn get_link_url_style() -> Style {
-    style! {
-        attrib: [underline]
-        color_fg: match ColorSupport::detect() {
-            ColorSupport::Grayscale => TuiColor::Basic(ANSIBasicColor::Blue),
-            ColorSupport::Ansi256 => TuiColor::Ansi(AnsiValue::new(39)), // DeepSkyBlue1.
-            ColorSupport::Truecolor => TuiColor::Rgb(RgbValue::from_hex("#16adf3")),
-        }
-    }
-}
+pub fn get_link_url_style() -> Style { get_theme_style(|it| &it.link_url) }
 
 /// This is for the entire checkbox span (checked).
 pub fn get_checkbox_checked_style() -> Style {
-    style! {
-        attrib: [bold, dim]
-        color_fg: match ColorSupport::detect() {
-            ColorSupport::Grayscale => TuiColor::Basic(ANSIBasicColor::DarkMagenta),
-            _ => TuiColor::Rgb(RgbValue::from_hex("#14a45b")),
-        }
-    }
+    get_theme_style(|it| &it.checkbox_checked)
 }
 
 /// This is for the entire checkbox span (unchecked).
 pub fn get_checkbox_unchecked_style() -> Style {
-    style! {
-        attrib: [bold]
-        color_fg: match ColorSupport::detect() {
-            ColorSupport::Grayscale => TuiColor::Basic(ANSIBasicColor::Green),
-            _ => TuiColor::Rgb(RgbValue::from_hex("#e1ff2f"))
-        }
-    }
+    get_theme_style(|it| &it.checkbox_unchecked)
 }
 
 /// This is for the bullet or numbered bullet of a list item, not the content.
-pub fn get_list_bullet_style() -> Style {
-    style! {
-        color_fg: match ColorSupport::detect() {
-            ColorSupport::Grayscale => TuiColor::Basic(ANSIBasicColor::Yellow), // There is no equivalent.
-            _ => TuiColor::Rgb(RgbValue::from_hex("#f8f8a6")), // Pale yellow.
-        }
-    }
-}
+pub fn get_list_bullet_style() -> Style { get_theme_style(|it| &it.list_bullet) }
 
 pub fn get_code_block_lang_style() -> Style {
-    get_inline_code_style()
-        + style! {
-            attrib: [italic]
-        }
+    get_inline_code_style() + get_theme_style(|it| &it.code_block_lang)
+}
+
+pub fn get_code_block_content_style() -> Style {
+    get_theme_style(|it| &it.code_block_content)
+}
+
+/// This is for the vertical bar gutter that is drawn for each level of a block quote.
+pub fn get_block_quote_gutter_style() -> Style {
+    get_theme_style(|it| &it.block_quote_gutter)
+}
+
+/// This is added on top of the style of each fragment in a block quote.
+pub fn get_block_quote_text_style() -> Style {
+    get_theme_style(|it| &it.block_quote_text)
 }
 
-pub fn get_code_block_content_style() -> Style { get_inline_code_style() }
+/// This is for the entire horizontal rule.
+pub fn get_horizontal_rule_style() -> Style { get_theme_style(|it| &it.horizontal_rule) }
+
+/// This is for the box drawing characters that are used to draw the grid of a table, not the
+/// content of the cells.
+pub fn get_table_border_style() -> Style { get_theme_style(|it| &it.table_border) }
+
+/// This is added on top of the style of each fragment in the header row of a table.
+pub fn get_table_header_style() -> Style { get_theme_style(|it| &it.table_header) }
 
-/// - Bg color: #4f86ed
-/// - Fg color: black
 pub fn get_metadata_title_marker_style() -> Style {
-    style! {
-        color_fg: TuiColor::Basic(ANSIBasicColor::Black)
-        color_bg: match ColorSupport::detect() {
-            ColorSupport::Grayscale => TuiColor::Basic(ANSIBasicColor::Cyan), // There is no equivalent.
-            ColorSupport::Ansi256 => TuiColor::Ansi(AnsiValue::new(39)), // DeepSkyBlue1.
-            ColorSupport::Truecolor => TuiColor::Rgb(RgbValue::from_hex("#4f86ed")), // Soft blue.
-        }
-    }
+    get_theme_style(|it| &it.metadata_title_marker)
 }
 
-/// - Fg color: #4fcbd4
-/// - Bg color: #444444
 pub fn get_metadata_title_value_style() -> Style {
-    style! {
-        color_fg: match ColorSupport::detect() {
-            ColorSupport::Grayscale => TuiColor::Basic(ANSIBasicColor::Cyan),
-            ColorSupport::Ansi256 => TuiColor::Ansi(AnsiValue::new(51)), // Cyan1.
-            ColorSupport::Truecolor => TuiColor::Rgb(RgbValue::from_hex("#4fcbd4")), // Moderate cyan.
-        }
-        color_bg: match ColorSupport::detect() {
-            ColorSupport::Grayscale => TuiColor::Basic(ANSIBasicColor::DarkGrey),
-            ColorSupport::Ansi256 => TuiColor::Ansi(AnsiValue::new(238)), // Grey27.
-            ColorSupport::Truecolor => TuiColor::Rgb(RgbValue::from_hex("#444444")), // Very dark gray.
-        }
-    }
+    get_theme_style(|it| &it.metadata_title_value)
 }
 
-/// - Bg color: #ad83da
-/// - Fg color: black
 pub fn get_metadata_tags_marker_style() -> Style {
-    style! {
-        color_fg: TuiColor::Basic(ANSIBasicColor::Black)
-        color_bg: match ColorSupport::detect() {
-            ColorSupport::Grayscale => TuiColor::Basic(ANSIBasicColor::Yellow), // There is no equivalent.
-            ColorSupport::Ansi256 => TuiColor::Ansi(AnsiValue::new(133)), // MediumOrchid3. There is no equivalent.
-            ColorSupport::Truecolor => TuiColor::Rgb(RgbValue::from_hex("#ad83da")), // Very soft violet.
-        }
-    }
+    get_theme_style(|it| &it.metadata_tags_marker)
 }
 
-/// - Fg color: #e2a1e3
-/// - Bg color: #303030
 pub fn get_metadata_tags_values_style() -> Style {
-    style! {
-        color_fg: match ColorSupport::detect() {
-            ColorSupport::Grayscale => TuiColor::Basic(ANSIBasicColor::Cyan), // There is no equivalent.
-            ColorSupport::Ansi256 => TuiColor::Ansi(AnsiValue::new(45)), // Turquoise2
-            ColorSupport::Truecolor => TuiColor::Rgb(RgbValue::from_hex("#e2a1e3")), // Soft violet.
-        }
-        color_bg: match ColorSupport::detect() {
-            ColorSupport::Grayscale => TuiColor::Basic(ANSIBasicColor::DarkGrey),
-            ColorSupport::Ansi256 => TuiColor::Ansi(AnsiValue::new(236)), // Grey19.
-            ColorSupport::Truecolor => TuiColor::Rgb(RgbValue::from_hex("#303030")), // Very dark gray.
-        }
-    }
+    get_theme_style(|it| &it.metadata_tags_values)
 }
 
 const SPEED: ColorWheelSpeed = ColorWheelSpeed::Medium;
@@ -236,74 +129,22 @@ const ANSI_SPEED: ColorWheelSpeed = ColorWheelSpeed::Slow;
 const STEPS: usize = 20;
 
 impl ColorWheel {
-    /// More info on gradients: <https://uigradients.com/>.
+    /// The gradients come from the [MarkdownTheme::headings].
     pub fn from_heading_data(heading_data: &HeadingData) -> Self {
-        match heading_data.level {
-            HeadingLevel::Heading1 => ColorWheel::new(vec![
-                ColorWheelConfig::Rgb(
-                    Vec::from(["#01fa22", "#00eef2"].map(String::from)),
-                    SPEED,
-                    STEPS,
-                ),
-                ColorWheelConfig::Ansi256(
-                    Ansi256GradientIndex::LightYellowToWhite,
-                    ANSI_SPEED,
-                ),
-            ]),
-
-            HeadingLevel::Heading2 => ColorWheel::new(vec![
-                ColorWheelConfig::Rgb(
-                    Vec::from(["#fff200", "#de211b"].map(String::from)),
-                    SPEED,
-                    STEPS,
-                ),
-                ColorWheelConfig::Ansi256(Ansi256GradientIndex::GreenToBlue, ANSI_SPEED),
-            ]),
-
-            HeadingLevel::Heading3 => ColorWheel::new(vec![
-                ColorWheelConfig::Rgb(
-                    Vec::from(["#00dbde", "#fc00ff"].map(String::from)),
-                    SPEED,
-                    STEPS,
-                ),
-                ColorWheelConfig::Ansi256(
-                    Ansi256GradientIndex::OrangeToNeonPink,
-                    ANSI_SPEED,
-                ),
-            ]),
-
-            HeadingLevel::Heading4 => ColorWheel::new(vec![
-                ColorWheelConfig::Rgb(
-                    Vec::from(["#ff28a9", "#bd60eb"].map(String::from)),
-                    SPEED,
-                    STEPS,
-                ),
-                ColorWheelConfig::Ansi256(
-                    Ansi256GradientIndex::LightOrangeToLightPurple,
-                    ANSI_SPEED,
-                ),
-            ]),
-
-            HeadingLevel::Heading5 => ColorWheel::new(vec![
-                ColorWheelConfig::Rgb(
-                    Vec::from(["#ff6a00", "#ee0979"].map(String::from)),
-                    SPEED,
-                    STEPS,
-                ),
-                ColorWheelConfig::Ansi256(Ansi256GradientIndex::RustToPurple, ANSI_SPEED),
-            ]),
-
-            HeadingLevel::Heading6 => ColorWheel::new(vec![
-                ColorWheelConfig::Rgb(
-                    Vec::from(["#8470ba", "#12c2e9"].map(String::from)),
-                    SPEED,
-                    STEPS,
-                ),
-                ColorWheelConfig::Ansi256(
-                    Ansi256GradientIndex::DarkOliveGreenToDarkLavender,
-                    ANSI_SPEED,
-                ),
-            ]),
-        }
+        let index = match heading_data.level {
+            HeadingLevel::Heading1 => 0,
+            HeadingLevel::Heading2 => 1,
+            HeadingLevel::Heading3 => 2,
+            HeadingLevel::Heading4 => 3,
+            HeadingLevel::Heading5 => 4,
+            HeadingLevel::Heading6 => 5,
+        };
+        let gradient = markdown_theme_global_static::with_markdown_theme(|theme| {
+            theme.headings[index].clone()
+        });
+        ColorWheel::new(vec![
+            ColorWheelConfig::Rgb(gradient.truecolor_stops, SPEED, STEPS),
+            ColorWheelConfig::Ansi256(gradient.ansi256_gradient, ANSI_SPEED),
+        ])
     }
 }
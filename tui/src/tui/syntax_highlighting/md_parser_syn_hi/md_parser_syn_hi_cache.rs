@@ -0,0 +1,634 @@
+/*
+ *   Copyright (c) 2023 R3BL LLC
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+//! This module holds [MdHighlightCache], which is an incremental version of
+//! [try_parse_and_highlight()]. It produces exactly the same [StyleUSSpanLines], but it only
+//! re-parses & re-highlights the blocks that are touched by an edit, instead of the entire
+//! document.
+//!
+//! Here's how it works:
+//! 1. The lines that were passed in the last time are compared w/ the new ones. Only the range of
+//!    lines between the common prefix and the common suffix has changed.
+//! 2. Parsing restarts at the block that contains the line just before the change (an edit can
+//!    extend the block before it, eg: a list continuation). Blocks w/ unbounded lookahead (eg: an
+//!    unclosed code fence, which might be closed by a fence that is typed later) move the restart
+//!    point back to themselves.
+//! 3. Blocks are parsed one at a time from a window of lines (which grows when needed), until a
+//!    block starts on the same line of the unchanged suffix as an old block did. From that point
+//!    on the input is identical, so the old blocks are reused (w/ their rows shifted).
+//! 4. Code blocks that are re-parsed w/ the same language re-use the highlighted output of the
+//!    lines that did not change. Syntect state can't be cached (it is not [Send]), so it is
+//!    rebuilt by running syntect over the unchanged lines (w/out producing any output) before
+//!    highlighting the first line that changed.
+
+use r3bl_rs_utils_core::*;
+use syntect::{highlighting::Theme, parsing::SyntaxSet};
+
+use crate::{constants::*, *};
+
+/// Lines are joined into a window of at least this many rows in order to parse blocks.
+const WINDOW_MIN_ROW_COUNT: usize = 64;
+
+/// Incremental replacement for [try_parse_and_highlight()]. Call
+/// [update()](MdHighlightCache::update) every time the lines may have changed, and then
+/// [get_lines()](MdHighlightCache::get_lines) to get the [StyleUSSpanLines] for the whole document.
+///
+/// The cache is automatically discarded when the style or the presence of syntect changes. If the
+/// [SyntaxSet] or [Theme] themselves are changed, then [clear()](MdHighlightCache::clear) must be
+/// called.
+#[derive(Clone, Debug, Default)]
+pub struct MdHighlightCache {
+    is_initialized: bool,
+    source_lines: Vec<String>,
+    blocks: Vec<CachedBlock>,
+    output: StyleUSSpanLines,
+    maybe_current_box_computed_style: Option<Style>,
+    is_syntect_enabled: bool,
+    last_update_stats: MdHighlightCacheStats,
+}
+
+/// How much work was done by the last call to [MdHighlightCache::update].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MdHighlightCacheStats {
+    pub blocks_parsed: usize,
+    pub blocks_reused: usize,
+    pub code_lines_highlighted: usize,
+    pub code_lines_reused: usize,
+}
+
+#[derive(Clone, Debug)]
+struct CachedBlock {
+    start_row: usize,
+    start_byte_col: usize,
+    output_line_count: usize,
+    has_unbounded_lookahead: bool,
+    maybe_code_block: Option<CachedCodeBlock>,
+}
+
+/// Only code blocks that are highlighted w/ syntect are cached this way. Their highlighted output
+/// is in [MdHighlightCache::output].
+#[derive(Clone, Debug)]
+struct CachedCodeBlock {
+    language: String,
+    text_lines: Vec<String>,
+}
+
+impl MdHighlightCache {
+    pub fn get_lines(&self) -> &StyleUSSpanLines { &self.output }
+
+    pub fn get_last_update_stats(&self) -> MdHighlightCacheStats {
+        self.last_update_stats
+    }
+
+    pub fn clear(&mut self) { *self = Default::default(); }
+
+    /// Bring the cache up to date w/ `editor_text_lines`. Takes the same arguments as
+    /// [try_parse_and_highlight()].
+    pub fn update(
+        &mut self,
+        editor_text_lines: &[US],
+        maybe_current_box_computed_style: &Option<Style>,
+        maybe_syntect_tuple: Option<(&SyntaxSet, &Theme)>,
+    ) {
+        let is_syntect_enabled = maybe_syntect_tuple.is_some();
+        if self.maybe_current_box_computed_style != *maybe_current_box_computed_style
+            || self.is_syntect_enabled != is_syntect_enabled
+        {
+            self.clear();
+        }
+        self.maybe_current_box_computed_style = *maybe_current_box_computed_style;
+        self.is_syntect_enabled = is_syntect_enabled;
+
+        // Find the range of lines that has changed.
+        let old_len = self.source_lines.len();
+        let new_len = editor_text_lines.len();
+        let prefix = self
+            .source_lines
+            .iter()
+            .zip(editor_text_lines)
+            .take_while(|(old, new)| old.as_str() == new.string.as_str())
+            .count();
+        if self.is_initialized && prefix == old_len && old_len == new_len {
+            self.last_update_stats = MdHighlightCacheStats {
+                blocks_reused: self.blocks.len(),
+                ..Default::default()
+            };
+            return;
+        }
+        let suffix = self.source_lines[prefix..]
+            .iter()
+            .rev()
+            .zip(editor_text_lines[prefix..].iter().rev())
+            .take_while(|(old, new)| old.as_str() == new.string.as_str())
+            .count();
+
+        let restart_block_index = self.find_restart_block_index(prefix);
+        let restart_row = self
+            .blocks
+            .get(restart_block_index)
+            .map(|it| it.start_row)
+            .unwrap_or(0);
+
+        let reparsed = self.reparse(
+            editor_text_lines,
+            restart_row,
+            new_len - suffix,
+            old_len - suffix,
+            prefix,
+            maybe_syntect_tuple,
+        );
+
+        // Splice the re-parsed blocks & their output into the cache.
+        let output_start = sum_output_line_count(&self.blocks[..restart_block_index]);
+        let (blocks_end, output_end) = match reparsed.maybe_resync_block_index {
+            Some(resync_block_index) => (
+                resync_block_index,
+                output_start
+                    + sum_output_line_count(
+                        &self.blocks[restart_block_index..resync_block_index],
+                    ),
+            ),
+            None => (self.blocks.len(), self.output.len()),
+        };
+        for block in self.blocks[blocks_end..].iter_mut() {
+            block.start_row = (block.start_row + new_len) - old_len;
+        }
+        self.last_update_stats = MdHighlightCacheStats {
+            blocks_parsed: reparsed.blocks.len(),
+            blocks_reused: self.blocks.len() - (blocks_end - restart_block_index),
+            ..reparsed.stats
+        };
+        self.blocks
+            .splice(restart_block_index..blocks_end, reparsed.blocks);
+        self.output
+            .items
+            .splice(output_start..output_end, reparsed.output.items);
+        self.source_lines.splice(
+            prefix..old_len - suffix,
+            editor_text_lines[prefix..new_len - suffix]
+                .iter()
+                .map(|it| it.string.clone()),
+        );
+        self.is_initialized = true;
+    }
+
+    /// Parsing has to restart at the block that contains the row just before `prefix`, since the
+    /// edit might extend it. If any block before that has unbounded lookahead, then restart at
+    /// that block instead. Blocks that don't start at the beginning of a line can't be restarted
+    /// from.
+    fn find_restart_block_index(&self, prefix: usize) -> usize {
+        let block_count_before_prefix =
+            self.blocks.partition_point(|it| it.start_row < prefix);
+        let mut restart_block_index = block_count_before_prefix.saturating_sub(1);
+        if let Some(unbounded_block_index) = self.blocks[..restart_block_index]
+            .iter()
+            .position(|it| it.has_unbounded_lookahead)
+        {
+            restart_block_index = unbounded_block_index;
+        }
+        while restart_block_index > 0
+            && self.blocks[restart_block_index].start_byte_col != 0
+        {
+            restart_block_index -= 1;
+        }
+        restart_block_index
+    }
+
+    /// Parse blocks starting at `restart_row`, until a block starts at the same place in the
+    /// unchanged suffix (`new_suffix_start_row` & `old_suffix_start_row`) as an old block did, or
+    /// the end of the document is reached.
+    fn reparse(
+        &self,
+        editor_text_lines: &[US],
+        restart_row: usize,
+        new_suffix_start_row: usize,
+        old_suffix_start_row: usize,
+        prefix: usize,
+        maybe_syntect_tuple: Option<(&SyntaxSet, &Theme)>,
+    ) -> Reparsed {
+        let mut acc = Reparsed::default();
+        let new_len = editor_text_lines.len();
+        let mut window_row_count = WINDOW_MIN_ROW_COUNT;
+        let mut row = restart_row;
+        let mut byte_col = 0;
+
+        'window: while row < new_len {
+            let window_end_row = new_len.min(row + window_row_count);
+            let is_end_of_document = window_end_row == new_len;
+            let window = {
+                let mut it = String::new();
+                for line in &editor_text_lines[row..window_end_row] {
+                    it.push_str(&line.string);
+                    it.push_str(NEW_LINE);
+                }
+                it
+            };
+            let mut input = &window[byte_col..];
+
+            loop {
+                // Resync w/ an old block in the unchanged suffix. The first block of the
+                // document is parsed differently (it can be front matter), so it can only be
+                // resynced w/ the old first block.
+                if byte_col == 0 && row >= new_suffix_start_row {
+                    let old_row = (row + old_suffix_start_row) - new_suffix_start_row;
+                    if (old_row == 0) == (row == 0) {
+                        if let Ok(block_index) =
+                            self.blocks.binary_search_by_key(&(old_row, 0), |it| {
+                                (it.start_row, it.start_byte_col)
+                            })
+                        {
+                            acc.maybe_resync_block_index = Some(block_index);
+                            return acc;
+                        }
+                    }
+                }
+
+                if input.is_empty() {
+                    break;
+                }
+
+                let is_first_block = row == 0 && byte_col == 0;
+                let parse_block = if is_first_block {
+                    parse_markdown_first_block
+                } else {
+                    parse_markdown_block
+                };
+                let Ok((remainder, block)) = parse_block(input) else {
+                    // Same as `parse_markdown()`, the rest of the document is not rendered.
+                    return acc;
+                };
+                if remainder.len() == input.len() {
+                    return acc;
+                }
+                let consumed = &input[..input.len() - remainder.len()];
+                let has_unbounded_lookahead =
+                    has_unbounded_lookahead(&block, consumed, is_first_block);
+
+                // The block might have been parsed differently w/ more lines after it. Grow the
+                // window & parse it again.
+                if !is_end_of_document
+                    && (has_unbounded_lookahead
+                        || !has_complete_line_after(consumed, remainder))
+                {
+                    window_row_count *= 2;
+                    continue 'window;
+                }
+
+                let (output, maybe_code_block) = match &block {
+                    MdBlockElement::CodeBlock(code_block_lines) => self
+                        .highlight_code_block(
+                            code_block_lines,
+                            row,
+                            byte_col,
+                            prefix,
+                            maybe_syntect_tuple,
+                            &mut acc.stats,
+                        ),
+                    _ => (
+                        StyleUSSpanLines::from_block(
+                            &block,
+                            &self.maybe_current_box_computed_style,
+                            maybe_syntect_tuple,
+                        ),
+                        None,
+                    ),
+                };
+
+                acc.blocks.push(CachedBlock {
+                    start_row: row,
+                    start_byte_col: byte_col,
+                    output_line_count: output.len(),
+                    has_unbounded_lookahead,
+                    maybe_code_block,
+                });
+                acc.output.items.extend(output.items);
+
+                // Move past the consumed input.
+                match consumed.rfind(NEW_LINE) {
+                    Some(last_new_line_index) => {
+                        row += consumed.matches(NEW_LINE).count();
+                        byte_col = consumed.len() - (last_new_line_index + 1);
+                    }
+                    None => byte_col += consumed.len(),
+                }
+                input = remainder;
+            }
+
+            if is_end_of_document {
+                break;
+            }
+        }
+
+        acc
+    }
+
+    /// Same output as [StyleUSSpanLines::from_block_codeblock], but re-uses the lines of the old
+    /// code block at the same position (if there is one), as long as they haven't changed.
+    fn highlight_code_block(
+        &self,
+        code_block_lines: &CodeBlockLines,
+        row: usize,
+        byte_col: usize,
+        prefix: usize,
+        maybe_syntect_tuple: Option<(&SyntaxSet, &Theme)>,
+        stats: &mut MdHighlightCacheStats,
+    ) -> (StyleUSSpanLines, Option<CachedCodeBlock>) {
+        let style = &self.maybe_current_box_computed_style;
+
+        let (Some(language), Some((syntax_set, theme))) = (
+            code_block_lines.first().and_then(|it| it.language),
+            maybe_syntect_tuple,
+        ) else {
+            return (
+                StyleUSSpanLines::from_block_codeblock(code_block_lines, style, None),
+                None,
+            );
+        };
+        let Some(mut highlighter) =
+            CodeBlockHighlighter::try_new(language, syntax_set, theme)
+        else {
+            return (
+                StyleUSSpanLines::from_block_codeblock(code_block_lines, style, None),
+                None,
+            );
+        };
+
+        // Find the old code block that starts at the same position (before the edit).
+        let maybe_old = if row < prefix {
+            self.blocks
+                .binary_search_by_key(&(row, byte_col), |it| {
+                    (it.start_row, it.start_byte_col)
+                })
+                .ok()
+                .and_then(|block_index| {
+                    let old_code_block =
+                        self.blocks[block_index].maybe_code_block.as_ref()?;
+                    (old_code_block.language == language).then(|| {
+                        (
+                            old_code_block,
+                            sum_output_line_count(&self.blocks[..block_index]),
+                        )
+                    })
+                })
+        } else {
+            None
+        };
+
+        let mut acc_lines_output = StyleUSSpanLines::default();
+        let mut cached_code_block = CachedCodeBlock {
+            language: language.to_string(),
+            text_lines: vec![],
+        };
+        let mut is_reusing = maybe_old.is_some();
+
+        for (line_index, code_block_line) in code_block_lines.iter().enumerate() {
+            let CodeBlockLineContent::Text(text) = code_block_line.content else {
+                acc_lines_output +=
+                    StyleUSSpanLine::from_code_block_line(code_block_line, style, None);
+                continue;
+            };
+
+            let text_line_index = cached_code_block.text_lines.len();
+            if let (true, Some((old_code_block, old_output_start))) =
+                (is_reusing, maybe_old)
+            {
+                if old_code_block
+                    .text_lines
+                    .get(text_line_index)
+                    .map(String::as_str)
+                    == Some(text)
+                {
+                    acc_lines_output
+                        .push(self.output[old_output_start + line_index].clone());
+                    cached_code_block.text_lines.push(text.to_string());
+                    // Keep the syntect state up to date for the first line that changed.
+                    highlighter.skip_line(text);
+                    stats.code_lines_reused += 1;
+                    continue;
+                }
+                is_reusing = false;
+            }
+
+            acc_lines_output += StyleUSSpanLine::from_code_block_line(
+                code_block_line,
+                style,
+                Some(&mut highlighter),
+            );
+            cached_code_block.text_lines.push(text.to_string());
+            stats.code_lines_highlighted += 1;
+        }
+
+        (acc_lines_output, Some(cached_code_block))
+    }
+}
+
+#[derive(Default)]
+struct Reparsed {
+    blocks: Vec<CachedBlock>,
+    output: StyleUSSpanLines,
+    /// Index of the first old block that is re-used.
+    maybe_resync_block_index: Option<usize>,
+    stats: MdHighlightCacheStats,
+}
+
+fn sum_output_line_count(blocks: &[CachedBlock]) -> usize {
+    blocks.iter().map(|it| it.output_line_count).sum()
+}
+
+/// Whether there is at least one more complete line in `remainder` after the line that `consumed`
+/// ends on. Parsers only peek at the line after the block, so if it is there the block is final.
+fn has_complete_line_after(consumed: &str, remainder: &str) -> bool {
+    if consumed.ends_with(NEW_LINE) {
+        !remainder.is_empty()
+    } else {
+        match remainder.find(NEW_LINE) {
+            Some(new_line_index) => new_line_index + 1 < remainder.len(),
+            None => false,
+        }
+    }
+}
+
+/// These blocks are parsed depending on lines that are arbitrarily far after them:
+/// - an unclosed code fence (it becomes a code block if a closing fence shows up later),
+/// - `@title: ` or `@date: ` w/ no value (this consumes the rest of the document in order to
+///   check that it does not contain another title),
+/// - a `---` line at the start of the document (it might be the start of front matter that has
+///   not been closed yet, or front matter that is not valid YAML yet).
+fn has_unbounded_lookahead(
+    block: &MdBlockElement,
+    consumed: &str,
+    is_first_block: bool,
+) -> bool {
+    let first_line = consumed.split(NEW_LINE).next().unwrap_or_default();
+    let is_unclosed_code_fence = matches!(block, MdBlockElement::Text(_))
+        && first_line.starts_with(CODE_BLOCK_START_PARTIAL);
+    let is_empty_kv = [TITLE, DATE]
+        .iter()
+        .any(|key| first_line == format!("{key}{COLON}{SPACE}"));
+    let is_front_matter_start = is_first_block && first_line == FRONT_MATTER_DELIMITER;
+    is_unclosed_code_fence || is_empty_kv || is_front_matter_start
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn to_lines(text: &str) -> Vec<US> { text.lines().map(US::from).collect() }
+
+    /// Apply each of the `edits` (in order) & check that the cache output matches the output of
+    /// [try_parse_and_highlight()].
+    fn assert_same_as_full_parse(
+        cache: &mut MdHighlightCache,
+        edits: &[&str],
+        maybe_syntect_tuple: Option<(&SyntaxSet, &Theme)>,
+    ) {
+        for edit in edits {
+            let lines = to_lines(edit);
+            cache.update(&lines, &None, maybe_syntect_tuple);
+            let expected =
+                try_parse_and_highlight(&lines, &None, maybe_syntect_tuple).unwrap();
+            assert_eq2!(cache.get_lines(), &expected);
+        }
+    }
+
+    const DOCUMENT: &str = "@title: Something
+@tags: tag1, tag2
+# Heading
+
+Some *text* here.
+- item 1
+- item 2
+```rs
+fn main() {
+    /* comment
+    */
+    let x = 1;
+}
+```
+> quote
+
+Last line.";
+
+    #[test]
+    fn test_edits_match_full_parse() {
+        let syntax_set = SyntaxSet::load_defaults_newlines();
+        let theme = load_default_theme();
+        for maybe_syntect_tuple in [None, Some((&syntax_set, &theme))] {
+            let mut cache = MdHighlightCache::default();
+            assert_same_as_full_parse(
+                &mut cache,
+                &[
+                    DOCUMENT,
+                    // Type in a paragraph.
+                    &DOCUMENT.replace("Some *text* here.", "Some *text* here!"),
+                    // List continuation.
+                    &DOCUMENT.replace("- item 2\n", "- item 2\n  more\n"),
+                    // Change a line inside a code block comment.
+                    &DOCUMENT.replace("    */", "    still a comment */"),
+                    // Remove the closing fence, and add it back.
+                    &DOCUMENT.replace("}\n```\n", "}\n"),
+                    DOCUMENT,
+                    // Delete & append lines.
+                    &DOCUMENT.replace("# Heading\n\n", ""),
+                    &format!("{DOCUMENT}\n- new item\n```\n"),
+                    // Links, and a link definition.
+                    &format!(
+                        "{DOCUMENT}\nSee [[note]], https://r3bl.com & [a][b].\n[b]: https://r3bl.com"
+                    ),
+                    "",
+                    DOCUMENT,
+                ],
+                maybe_syntect_tuple,
+            );
+        }
+    }
+
+    #[test]
+    fn test_unclosed_fence_is_closed_later() {
+        let mut cache = MdHighlightCache::default();
+        let unclosed = "```bash\necho hi\n\nmore text\n- list";
+        assert_same_as_full_parse(
+            &mut cache,
+            &[
+                unclosed,
+                &format!("{unclosed}\n```"),
+                &format!("{unclosed}\n``"),
+                &format!("{unclosed}\n```\n# After"),
+            ],
+            None,
+        );
+    }
+
+    #[test]
+    fn test_front_matter_edits() {
+        let mut cache = MdHighlightCache::default();
+        let front_matter =
+            "---\ntitle: Something\ntags: [tag1, tag2]\n---\n# Heading\nText";
+        assert_same_as_full_parse(
+            &mut cache,
+            &[
+                front_matter,
+                // Not closed yet.
+                &front_matter.replacen("---\n#", "#", 1),
+                // Not valid YAML yet.
+                &front_matter.replace("[tag1, tag2]", "[tag1, tag2"),
+                front_matter,
+                // Not at the start of the document.
+                &format!("Text\n{front_matter}"),
+                front_matter,
+            ],
+            None,
+        );
+    }
+
+    #[test]
+    fn test_only_touched_blocks_are_reparsed() {
+        let syntax_set = SyntaxSet::load_defaults_newlines();
+        let theme = load_default_theme();
+        let maybe_syntect_tuple = Some((&syntax_set, &theme));
+
+        let document = (0..1_000)
+            .map(|it| format!("Paragraph {it}."))
+            .chain(
+                ["```rs", "let a = 1;", "let b = 2;", "let c = 3;", "```"]
+                    .map(String::from),
+            )
+            .collect::<Vec<_>>()
+            .join("\n");
+        let mut cache = MdHighlightCache::default();
+        assert_same_as_full_parse(&mut cache, &[&document], maybe_syntect_tuple);
+        assert_eq2!(cache.get_last_update_stats().blocks_parsed, 1_001);
+
+        // Nothing changed.
+        assert_same_as_full_parse(&mut cache, &[&document], maybe_syntect_tuple);
+        assert_eq2!(cache.get_last_update_stats().blocks_parsed, 0);
+
+        // Edit a paragraph in the middle.
+        let edited = document.replace("Paragraph 500.", "Paragraph 500!");
+        assert_same_as_full_parse(&mut cache, &[&edited], maybe_syntect_tuple);
+        let stats = cache.get_last_update_stats();
+        assert!(stats.blocks_parsed <= 3, "{stats:?}");
+        assert!(stats.blocks_reused >= 998, "{stats:?}");
+
+        // Edit the last line of the code block. Only that line is highlighted again.
+        let edited = edited.replace("let c = 3;", "let c = 4;");
+        assert_same_as_full_parse(&mut cache, &[&edited], maybe_syntect_tuple);
+        let stats = cache.get_last_update_stats();
+        assert_eq2!(stats.code_lines_reused, 2);
+        assert_eq2!(stats.code_lines_highlighted, 1);
+    }
+}
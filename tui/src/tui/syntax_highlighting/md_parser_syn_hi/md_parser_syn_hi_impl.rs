@@ -19,7 +19,7 @@
 
 use r3bl_rs_utils_core::*;
 use r3bl_rs_utils_macro::style;
-use syntect::{easy::HighlightLines, highlighting::Theme, parsing::SyntaxSet};
+use syntect::{highlighting::Theme, parsing::SyntaxSet};
 
 use crate::{constants::*, *};
 
@@ -117,7 +117,8 @@ impl StyleUSSpanLines {
         maybe_syntect_tuple: Option<(&SyntaxSet, &Theme)>,
     ) -> Self {
         let mut lines = StyleUSSpanLines::default();
-        for block in document.iter() {
+        // The metadata blocks that are populated from the front matter are rendered as part of it.
+        for block in get_source_blocks(document) {
             let block_to_lines = StyleUSSpanLines::from_block(
                 block,
                 maybe_current_box_computed_style,
@@ -143,147 +144,60 @@ impl StyleUSSpanLines {
     ///
     /// Case 2: Syntect
     /// - 1st line        : "```": `get_foreground_dim_style()`, lang: `get_code_block_lang_style()`
-    /// - 2nd line .. end : use syntect to highlight
+    /// - 2nd line .. end : use syntect to highlight (state is carried from one line to the next)
     /// - last line       : "```": `get_foreground_dim_style()`
     pub fn from_block_codeblock(
         code_block_lines: &CodeBlockLines,
         maybe_current_box_computed_style: &Option<Style>,
         maybe_syntect_tuple: Option<(&SyntaxSet, &Theme)>,
     ) -> Self {
-        mod inner {
-            use super::*;
-
-            pub fn try_use_syntect(
-                code_block_lines: &CodeBlockLines,
-                maybe_current_box_computed_style: &Option<Style>,
-                maybe_syntect_tuple: Option<(&SyntaxSet, &Theme)>,
-            ) -> Option<StyleUSSpanLines> {
-                let mut acc_lines_output = StyleUSSpanLines::default();
-
-                // Process each line in code_block_lines.
-                for code_block_line in code_block_lines.iter() {
-                    let mut acc_line_output = StyleUSSpanLine::default();
-
-                    let maybe_lang =
-                        if let Some(code_block_line) = code_block_lines.items.first() {
-                            code_block_line.language
-                        } else {
-                            None
-                        };
-                    let syntax_set = maybe_syntect_tuple?.0;
-                    let lang = maybe_lang?;
-                    let theme = maybe_syntect_tuple?.1;
-                    let syntax_ref = try_get_syntax_ref(syntax_set, lang)?;
-
-                    let mut highlighter = HighlightLines::new(syntax_ref, theme);
-
-                    match code_block_line.content {
-                        CodeBlockLineContent::Text(line_of_text) => {
-                            let syntect_highlighted_line: Vec<(
-                                syntect::highlighting::Style,
-                                &str,
-                            )> = highlighter
-                                .highlight_line(line_of_text, syntax_set)
-                                .ok()?;
-
-                            let line_converted_to_tui: List<StyleUSSpan> =
-                                syntect_to_styled_text_conversion::from_syntect_to_tui(
-                                    syntect_highlighted_line,
-                                );
-
-                            acc_line_output += line_converted_to_tui;
-                        }
-
-                        // Same as fallback.
-                        CodeBlockLineContent::StartTag => {
-                            acc_line_output += StyleUSSpan::new(
-                                maybe_current_box_computed_style.unwrap_or_default()
-                                    + get_foreground_dim_style(),
-                                US::from(CODE_BLOCK_START_PARTIAL),
-                            );
-                            if let Some(language) = code_block_line.language {
-                                acc_line_output += StyleUSSpan::new(
-                                    maybe_current_box_computed_style.unwrap_or_default()
-                                        + get_code_block_lang_style(),
-                                    US::from(language),
-                                );
-                            }
-                        }
-
-                        // Same as fallback.
-                        CodeBlockLineContent::EndTag => {
-                            acc_line_output += StyleUSSpan::new(
-                                maybe_current_box_computed_style.unwrap_or_default()
-                                    + get_foreground_dim_style(),
-                                US::from(CODE_BLOCK_START_PARTIAL),
-                            );
-                        }
-                    }
-
-                    acc_lines_output += acc_line_output;
-                }
-
-                Some(acc_lines_output)
+        let mut maybe_highlighter = match (
+            code_block_lines.first().and_then(|it| it.language),
+            maybe_syntect_tuple,
+        ) {
+            (Some(language), Some((syntax_set, theme))) => {
+                CodeBlockHighlighter::try_new(language, syntax_set, theme)
             }
+            _ => None,
+        };
 
-            pub fn use_fallback(
-                code_block_lines: &CodeBlockLines,
-                maybe_current_box_computed_style: &Option<Style>,
-            ) -> StyleUSSpanLines {
-                let mut acc_lines_output = StyleUSSpanLines::default();
-
-                // Process each line in code_block_lines.
-                for code_block_line in code_block_lines.iter() {
-                    let mut acc_line_output = StyleUSSpanLine::default();
-
-                    match code_block_line.content {
-                        CodeBlockLineContent::StartTag => {
-                            acc_line_output += StyleUSSpan::new(
-                                maybe_current_box_computed_style.unwrap_or_default()
-                                    + get_foreground_dim_style(),
-                                US::from(CODE_BLOCK_START_PARTIAL),
-                            );
-                            if let Some(language) = code_block_line.language {
-                                acc_line_output += StyleUSSpan::new(
-                                    maybe_current_box_computed_style.unwrap_or_default()
-                                        + get_code_block_lang_style(),
-                                    US::from(language),
-                                );
-                            }
-                        }
-
-                        CodeBlockLineContent::EndTag => {
-                            acc_line_output += StyleUSSpan::new(
-                                maybe_current_box_computed_style.unwrap_or_default()
-                                    + get_foreground_dim_style(),
-                                US::from(CODE_BLOCK_START_PARTIAL),
-                            );
-                        }
-
-                        CodeBlockLineContent::Text(content) => {
-                            acc_line_output += StyleUSSpan::new(
-                                maybe_current_box_computed_style.unwrap_or_default()
-                                    + get_code_block_content_style(),
-                                US::from(content),
-                            );
-                        }
-                    }
-
-                    acc_lines_output += acc_line_output;
-                }
-
-                acc_lines_output
-            }
+        let mut acc_lines_output = StyleUSSpanLines::default();
+        for code_block_line in code_block_lines.iter() {
+            acc_lines_output += StyleUSSpanLine::from_code_block_line(
+                code_block_line,
+                maybe_current_box_computed_style,
+                maybe_highlighter.as_mut(),
+            );
         }
+        acc_lines_output
+    }
 
-        match inner::try_use_syntect(
-            code_block_lines,
-            maybe_current_box_computed_style,
-            maybe_syntect_tuple,
-        ) {
-            Some(syntect_output) => syntect_output,
-            _ => inner::use_fallback(code_block_lines, maybe_current_box_computed_style),
+    /// - 1st & last line : "---": `get_foreground_dim_style()`
+    /// - other lines     : [StyleUSSpanLine::from_front_matter_line]
+    pub fn from_block_front_matter(
+        front_matter: &FrontMatterData,
+        maybe_current_box_computed_style: &Option<Style>,
+    ) -> Self {
+        let delimiter_line = {
+            let mut it = StyleUSSpanLine::default();
+            it += StyleUSSpan::new(
+                maybe_current_box_computed_style.unwrap_or_default()
+                    + get_foreground_dim_style(),
+                US::from(FRONT_MATTER_DELIMITER),
+            );
+            it
+        };
+
+        let mut lines = StyleUSSpanLines::default();
+        lines.push(delimiter_line.clone());
+        for line in front_matter.lines.iter() {
+            lines.push(StyleUSSpanLine::from_front_matter_line(
+                line,
+                maybe_current_box_computed_style,
+            ));
         }
+        lines.push(delimiter_line);
+        lines
     }
 
     pub fn from_block_smart_list(
@@ -309,6 +223,124 @@ impl StyleUSSpanLines {
         acc_lines_output
     }
 
+    /// Each line of the block quote is converted into one line of output. The `>` markers are
+    /// replaced w/ a vertical bar gutter (one bar for each level of nesting), and the content of
+    /// the line is rendered in italic (on top of whatever formatting it already has).
+    ///
+    /// ```text
+    /// > foo           ┃ foo
+    /// > > bar   =>    ┃ ┃ bar
+    /// ```
+    pub fn from_block_quote(
+        block_quote_lines: &BlockQuoteLines,
+        maybe_current_box_computed_style: &Option<Style>,
+    ) -> Self {
+        let mut acc_lines_output = StyleUSSpanLines::default();
+
+        for block_quote_line in block_quote_lines.iter() {
+            let mut acc_line_output = StyleUSSpanLine::default();
+
+            acc_line_output += StyleUSSpan::new(
+                maybe_current_box_computed_style.unwrap_or_default()
+                    + get_block_quote_gutter_style(),
+                US::from(BLOCK_QUOTE_GUTTER_DISPLAY.repeat(block_quote_line.depth)),
+            );
+
+            let mut content = StyleUSSpanLine::from_fragments(
+                &block_quote_line.content,
+                maybe_current_box_computed_style,
+            );
+            content.add_style(get_block_quote_text_style());
+            acc_line_output += content;
+
+            acc_lines_output += acc_line_output;
+        }
+
+        acc_lines_output
+    }
+
+    /// Each line of the table source is converted into exactly one line of output, so that the
+    /// rendered table lines up w/ the lines in the editor buffer. Every column is padded to the
+    /// display width of its widest cell (which takes wide graphemes into account) and aligned
+    /// based on its [TableColumnAlignment].
+    ///
+    /// ```text
+    /// | Name | Qty |        │ Name  │ Qty │
+    /// | :--- | --: |   =>   ├───────┼─────┤
+    /// | apple | 3 |         │ apple │   3 │
+    /// ```
+    ///
+    /// Since every output line has the same display width, a table that is wider than the
+    /// viewport is clipped by [StyleUSSpanLine::clip] at the same column for every row, so the grid
+    /// stays intact as it is scrolled horizontally.
+    pub fn from_block_table(
+        table_data: &TableData,
+        maybe_current_box_computed_style: &Option<Style>,
+    ) -> Self {
+        let mut acc_lines_output = StyleUSSpanLines::default();
+
+        let border_style = maybe_current_box_computed_style.unwrap_or_default()
+            + get_table_border_style();
+
+        let header_row: Vec<StyleUSSpanLine> = table_data
+            .header
+            .iter()
+            .map(|cell| {
+                let mut it = StyleUSSpanLine::from_fragments(
+                    cell,
+                    maybe_current_box_computed_style,
+                );
+                it.add_style(get_table_header_style());
+                it
+            })
+            .collect();
+
+        let body_rows: Vec<Vec<StyleUSSpanLine>> = table_data
+            .rows
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|cell| {
+                        StyleUSSpanLine::from_fragments(
+                            cell,
+                            maybe_current_box_computed_style,
+                        )
+                    })
+                    .collect()
+            })
+            .collect();
+
+        // Each column is as wide as its widest cell.
+        let mut column_widths = vec![1; table_data.column_count()];
+        for row in std::iter::once(&header_row).chain(body_rows.iter()) {
+            for (column_width, cell) in column_widths.iter_mut().zip(row.iter()) {
+                *column_width = (*column_width).max(ch!(@to_usize cell.display_width()));
+            }
+        }
+
+        acc_lines_output += table_impl::render_row(
+            header_row,
+            &column_widths,
+            &table_data.alignments,
+            maybe_current_box_computed_style,
+            border_style,
+        );
+
+        acc_lines_output += table_impl::render_rule(&column_widths, border_style);
+
+        for row in body_rows {
+            acc_lines_output += table_impl::render_row(
+                row,
+                &column_widths,
+                &table_data.alignments,
+                maybe_current_box_computed_style,
+                border_style,
+            );
+        }
+
+        acc_lines_output
+    }
+
     /// Each [MdBlockElement] needs to be translated into a line. The [MdBlockElement::CodeBlock] is
     /// the only block that needs to be translated into multiple lines. This is why the return type
     /// is a [StyleUSSpanLines] (and not a single line).
@@ -366,6 +398,12 @@ impl StyleUSSpanLines {
                     maybe_current_box_computed_style,
                 );
             }
+            MdBlockElement::FrontMatter(front_matter) => {
+                lines += StyleUSSpanLines::from_block_front_matter(
+                    front_matter,
+                    maybe_current_box_computed_style,
+                );
+            }
             MdBlockElement::CodeBlock(code_block_lines) => {
                 lines += StyleUSSpanLines::from_block_codeblock(
                     code_block_lines,
@@ -373,12 +411,108 @@ impl StyleUSSpanLines {
                     maybe_syntect_tuple,
                 );
             }
+            MdBlockElement::Table(table_data) => {
+                lines += StyleUSSpanLines::from_block_table(
+                    table_data,
+                    maybe_current_box_computed_style,
+                );
+            }
+            MdBlockElement::BlockQuote(block_quote_lines) => {
+                lines += StyleUSSpanLines::from_block_quote(
+                    block_quote_lines,
+                    maybe_current_box_computed_style,
+                );
+            }
+            MdBlockElement::LinkDefinition(link_definition_data) => {
+                lines.push(StyleUSSpanLine::from_link_definition(
+                    link_definition_data,
+                    maybe_current_box_computed_style,
+                ));
+            }
+            MdBlockElement::FootnoteDefinition(footnote_definition_data) => {
+                lines.push(StyleUSSpanLine::from_footnote_definition(
+                    footnote_definition_data,
+                    maybe_current_box_computed_style,
+                ));
+            }
+            MdBlockElement::HorizontalRule => {
+                // The width of the viewport isn't known here, so this is filled in when the rule
+                // is painted, see [StyleUSSpanLine::try_fill_horizontal_rule].
+                lines += list![StyleUSSpan::new(
+                    maybe_current_box_computed_style.unwrap_or_default()
+                        + get_horizontal_rule_style(),
+                    US::from(HORIZONTAL_RULE_DISPLAY),
+                )];
+            }
         }
 
         lines
     }
 }
 
+mod table_impl {
+    use super::*;
+
+    /// Eg: "│ apple │   3 │"
+    pub fn render_row(
+        cells: Vec<StyleUSSpanLine>,
+        column_widths: &[usize],
+        alignments: &List<TableColumnAlignment>,
+        maybe_current_box_computed_style: &Option<Style>,
+        border_style: Style,
+    ) -> StyleUSSpanLine {
+        let pad_style = maybe_current_box_computed_style.unwrap_or_default();
+        let mut acc_line_output = StyleUSSpanLine::default();
+
+        acc_line_output +=
+            StyleUSSpan::new(border_style, US::from(TABLE_CELL_SEPARATOR_DISPLAY));
+
+        for ((cell, column_width), alignment) in cells
+            .into_iter()
+            .zip(column_widths.iter())
+            .zip(alignments.iter())
+        {
+            let cell_width = ch!(@to_usize cell.display_width());
+            let extra_width = column_width.saturating_sub(cell_width);
+            let (left_pad_width, right_pad_width) = match alignment {
+                TableColumnAlignment::Default | TableColumnAlignment::Left => {
+                    (0, extra_width)
+                }
+                TableColumnAlignment::Right => (extra_width, 0),
+                TableColumnAlignment::Center => {
+                    (extra_width / 2, extra_width - extra_width / 2)
+                }
+            };
+
+            acc_line_output +=
+                StyleUSSpan::new(pad_style, US::from(SPACE.repeat(left_pad_width + 1)));
+            acc_line_output += cell;
+            acc_line_output +=
+                StyleUSSpan::new(pad_style, US::from(SPACE.repeat(right_pad_width + 1)));
+            acc_line_output +=
+                StyleUSSpan::new(border_style, US::from(TABLE_CELL_SEPARATOR_DISPLAY));
+        }
+
+        acc_line_output
+    }
+
+    /// Eg: "├───────┼─────┤"
+    pub fn render_rule(column_widths: &[usize], border_style: Style) -> StyleUSSpanLine {
+        let rule = column_widths
+            .iter()
+            .map(|column_width| TABLE_RULE_DISPLAY.repeat(column_width + 2))
+            .collect::<Vec<String>>()
+            .join(TABLE_RULE_JUNCTION_DISPLAY);
+
+        list![StyleUSSpan::new(
+            border_style,
+            US::from(format!(
+                "{TABLE_RULE_START_DISPLAY}{rule}{TABLE_RULE_END_DISPLAY}"
+            )),
+        )]
+    }
+}
+
 enum HyperlinkType {
     Image,
     Link,
@@ -426,6 +560,116 @@ impl StyleUSSpan {
         ]
     }
 
+    /// A formatting span (eg: bold) w/ the given `delimiter` around its (nested) `fragments`. The
+    /// `content_style` is added to the current style for all the nested fragments, so nested
+    /// styles are combined, eg: italic inside bold is both bold & italic. Plain text gets the
+    /// combined style as is.
+    fn format_nested_fragments(
+        fragments: &MdLineFragments,
+        delimiter: &str,
+        content_style: Style,
+        maybe_current_box_computed_style: &Option<Style>,
+    ) -> Vec<Self> {
+        let delimiter_style = maybe_current_box_computed_style.unwrap_or_default()
+            + get_foreground_dim_style();
+        let nested_style =
+            maybe_current_box_computed_style.unwrap_or_default() + content_style;
+
+        let mut it = vec![StyleUSSpan::new(delimiter_style, US::from(delimiter))];
+        for fragment in fragments.iter() {
+            match fragment {
+                MdLineFragment::Plain(plain_text) => {
+                    it.push(StyleUSSpan::new(nested_style, US::from(*plain_text)))
+                }
+                _ => it.extend(Self::from_fragment(fragment, &Some(nested_style))),
+            }
+        }
+        it.push(StyleUSSpan::new(delimiter_style, US::from(delimiter)));
+        it
+    }
+
+    /// `[[target]]` or `[[target|alias]]`. The alias is what is displayed, so it gets the link text
+    /// style, and the target gets the link url style.
+    fn format_wiki_link_data(
+        wiki_link_data: &WikiLinkData,
+        maybe_current_box_computed_style: &Option<Style>,
+    ) -> Vec<Self> {
+        let base_style = maybe_current_box_computed_style.unwrap_or_default()
+            + get_foreground_dim_style();
+
+        let link_text_style =
+            maybe_current_box_computed_style.unwrap_or_default() + get_link_text_style();
+
+        let link_url_style =
+            maybe_current_box_computed_style.unwrap_or_default() + get_link_url_style();
+
+        let mut it = vec![StyleUSSpan::new(base_style, US::from(WIKI_LINK_START))];
+        match wiki_link_data.alias {
+            Some(alias) => {
+                it.push(StyleUSSpan::new(
+                    link_url_style,
+                    US::from(wiki_link_data.target),
+                ));
+                it.push(StyleUSSpan::new(
+                    base_style,
+                    US::from(WIKI_LINK_ALIAS_SEPARATOR),
+                ));
+                it.push(StyleUSSpan::new(link_text_style, US::from(alias)));
+            }
+            None => {
+                it.push(StyleUSSpan::new(
+                    link_text_style,
+                    US::from(wiki_link_data.target),
+                ));
+            }
+        }
+        it.push(StyleUSSpan::new(base_style, US::from(WIKI_LINK_END)));
+        it
+    }
+
+    /// `[text][reference]`. This doesn't depend on whether the reference has been resolved, since
+    /// the link definition might be in a part of the document that hasn't been parsed (eg: by
+    /// [MdHighlightCache]).
+    fn format_reference_link_data(
+        reference_link_data: &ReferenceLinkData,
+        maybe_current_box_computed_style: &Option<Style>,
+    ) -> Vec<Self> {
+        let base_style = maybe_current_box_computed_style.unwrap_or_default()
+            + get_foreground_dim_style();
+
+        let link_text_style =
+            maybe_current_box_computed_style.unwrap_or_default() + get_link_text_style();
+
+        let link_url_style =
+            maybe_current_box_computed_style.unwrap_or_default() + get_link_url_style();
+
+        vec![
+            // [text]
+            StyleUSSpan::new(base_style, US::from(LEFT_BRACKET)),
+            StyleUSSpan::new(link_text_style, US::from(reference_link_data.text)),
+            StyleUSSpan::new(base_style, US::from(RIGHT_BRACKET)),
+            // [reference]
+            StyleUSSpan::new(base_style, US::from(LEFT_BRACKET)),
+            StyleUSSpan::new(link_url_style, US::from(reference_link_data.reference)),
+            StyleUSSpan::new(base_style, US::from(RIGHT_BRACKET)),
+        ]
+    }
+
+    /// `[^label]`.
+    fn format_footnote_label(
+        label: &str,
+        closing_delimiter: &str,
+        maybe_current_box_computed_style: &Option<Style>,
+    ) -> Vec<Self> {
+        let base_style = maybe_current_box_computed_style.unwrap_or_default();
+        let dim_style = base_style + get_foreground_dim_style();
+        vec![
+            StyleUSSpan::new(dim_style, US::from(FOOTNOTE_START)),
+            StyleUSSpan::new(base_style + get_link_text_style(), US::from(label)),
+            StyleUSSpan::new(dim_style, US::from(closing_delimiter)),
+        ]
+    }
+
     /// Each [MdLineFragment] needs to be translated into a [StyleUSSpan] or [Vec] of
     /// [StyleUSSpan]s.
     ///
@@ -470,59 +714,44 @@ impl StyleUSSpan {
                 US::from(*plain_text),
             )],
 
-            MdLineFragment::Bold(bold_text) => {
-                vec![
-                    StyleUSSpan::new(
-                        maybe_current_box_computed_style.unwrap_or_default()
-                            + get_foreground_dim_style(),
-                        US::from(BOLD_1),
-                    ),
-                    StyleUSSpan::new(
-                        maybe_current_box_computed_style.unwrap_or_default()
-                            + get_bold_style(),
-                        US::from(*bold_text),
-                    ),
-                    StyleUSSpan::new(
-                        maybe_current_box_computed_style.unwrap_or_default()
-                            + get_foreground_dim_style(),
-                        US::from(BOLD_1),
-                    ),
-                ]
-            }
+            MdLineFragment::Bold(fragments) => Self::format_nested_fragments(
+                fragments,
+                BOLD_1,
+                get_bold_style(),
+                maybe_current_box_computed_style,
+            ),
 
-            MdLineFragment::Italic(italic_text) => vec![
-                StyleUSSpan::new(
-                    maybe_current_box_computed_style.unwrap_or_default()
-                        + get_foreground_dim_style(),
-                    US::from(ITALIC_1),
-                ),
-                StyleUSSpan::new(
-                    maybe_current_box_computed_style.unwrap_or_default()
-                        + get_italic_style(),
-                    US::from(*italic_text),
-                ),
-                StyleUSSpan::new(
-                    maybe_current_box_computed_style.unwrap_or_default()
-                        + get_foreground_dim_style(),
-                    US::from(ITALIC_1),
-                ),
-            ],
+            MdLineFragment::Italic(fragments) => Self::format_nested_fragments(
+                fragments,
+                ITALIC_1,
+                get_italic_style(),
+                maybe_current_box_computed_style,
+            ),
 
-            MdLineFragment::BoldItalic(bitalic_text) => vec![
+            MdLineFragment::BoldItalic(fragments) => Self::format_nested_fragments(
+                fragments,
+                BITALIC_1,
+                get_bold_italic_style(),
+                maybe_current_box_computed_style,
+            ),
+
+            MdLineFragment::Strikethrough(fragments) => Self::format_nested_fragments(
+                fragments,
+                STRIKETHROUGH,
+                get_strikethrough_style(),
+                maybe_current_box_computed_style,
+            ),
+
+            MdLineFragment::Escaped(escaped_text) => vec![
                 StyleUSSpan::new(
                     maybe_current_box_computed_style.unwrap_or_default()
                         + get_foreground_dim_style(),
-                    US::from(BITALIC_1),
+                    US::from(BACKSLASH),
                 ),
                 StyleUSSpan::new(
                     maybe_current_box_computed_style.unwrap_or_default()
-                        + get_bold_italic_style(),
-                    US::from(*bitalic_text),
-                ),
-                StyleUSSpan::new(
-                    maybe_current_box_computed_style.unwrap_or_default()
-                        + get_foreground_dim_style(),
-                    US::from(BITALIC_1),
+                        + get_foreground_style(),
+                    US::from(*escaped_text),
                 ),
             ],
 
@@ -556,6 +785,30 @@ impl StyleUSSpan {
                 HyperlinkType::Image,
             ),
 
+            MdLineFragment::WikiLink(wiki_link_data) => Self::format_wiki_link_data(
+                wiki_link_data,
+                maybe_current_box_computed_style,
+            ),
+
+            MdLineFragment::AutoLink(url) => vec![StyleUSSpan::new(
+                maybe_current_box_computed_style.unwrap_or_default()
+                    + get_link_url_style(),
+                US::from(*url),
+            )],
+
+            MdLineFragment::ReferenceLink(reference_link_data) => {
+                Self::format_reference_link_data(
+                    reference_link_data,
+                    maybe_current_box_computed_style,
+                )
+            }
+
+            MdLineFragment::FootnoteReference(label) => Self::format_footnote_label(
+                label,
+                RIGHT_BRACKET,
+                maybe_current_box_computed_style,
+            ),
+
             MdLineFragment::Checkbox(done) => {
                 vec![if *done {
                     StyleUSSpan::new(
@@ -588,6 +841,215 @@ impl PrettyPrintDebug for StyleUSSpanLine {
 }
 
 impl StyleUSSpanLine {
+    /// A [MdBlockElement::HorizontalRule] is highlighted as a single [HORIZONTAL_RULE_DISPLAY].
+    /// If this line is one, then return the rule repeated to fill up `display_col_count`.
+    /// Otherwise return [None].
+    pub fn try_fill_horizontal_rule(
+        &self,
+        maybe_current_box_computed_style: &Option<Style>,
+        display_col_count: ChUnit,
+    ) -> Option<StyleUSSpanLine> {
+        let rule_style = maybe_current_box_computed_style.unwrap_or_default()
+            + get_horizontal_rule_style();
+        match self.items.as_slice() {
+            [span]
+                if span.style == rule_style
+                    && span.text.string == HORIZONTAL_RULE_DISPLAY =>
+            {
+                Some(list![StyleUSSpan::new(
+                    rule_style,
+                    US::from(
+                        HORIZONTAL_RULE_DISPLAY.repeat(ch!(@to_usize display_col_count))
+                    ),
+                )])
+            }
+            _ => None,
+        }
+    }
+
+    /// Eg: `[reference]: https://r3bl.com "title"`.
+    /// - `[`, `]: ` & quotes : `get_foreground_dim_style()`
+    /// - reference          : `get_link_text_style()`
+    /// - url                : `get_link_url_style()`
+    /// - title              : `get_foreground_style()`
+    pub fn from_link_definition(
+        link_definition_data: &LinkDefinitionData,
+        maybe_current_box_computed_style: &Option<Style>,
+    ) -> Self {
+        let base_style = maybe_current_box_computed_style.unwrap_or_default();
+        let dim_style = base_style + get_foreground_dim_style();
+
+        let mut acc_line_output = list![
+            StyleUSSpan::new(dim_style, US::from(LEFT_BRACKET)),
+            StyleUSSpan::new(
+                base_style + get_link_text_style(),
+                US::from(link_definition_data.reference),
+            ),
+            StyleUSSpan::new(
+                dim_style,
+                US::from(format!("{LINK_DEFINITION_SEPARATOR}{SPACE}")),
+            ),
+            StyleUSSpan::new(
+                base_style + get_link_url_style(),
+                US::from(link_definition_data.url),
+            ),
+        ];
+
+        if let Some(title) = link_definition_data.title {
+            acc_line_output +=
+                StyleUSSpan::new(dim_style, US::from(format!("{SPACE}{QUOTE}")));
+            acc_line_output +=
+                StyleUSSpan::new(base_style + get_foreground_style(), US::from(title));
+            acc_line_output += StyleUSSpan::new(dim_style, US::from(QUOTE));
+        }
+
+        acc_line_output
+    }
+
+    /// Eg: `[^label]: Some *text*.`.
+    /// - `[^` & `]: ` : `get_foreground_dim_style()`
+    /// - label       : `get_link_text_style()`
+    /// - content     : same as [MdBlockElement::Text]
+    pub fn from_footnote_definition(
+        footnote_definition_data: &FootnoteDefinitionData,
+        maybe_current_box_computed_style: &Option<Style>,
+    ) -> Self {
+        let mut acc_line_output = List::from(StyleUSSpan::format_footnote_label(
+            footnote_definition_data.label,
+            &format!("{RIGHT_BRACKET}{COLON}{SPACE}"),
+            maybe_current_box_computed_style,
+        ));
+        acc_line_output += StyleUSSpanLine::from_fragments(
+            &footnote_definition_data.content,
+            maybe_current_box_computed_style,
+        );
+        acc_line_output
+    }
+
+    /// Style a single line of YAML front matter like the R3BL metadata:
+    /// - indent & `- ` list markers & comments: `get_foreground_dim_style()`
+    /// - `key: `                             : key: `get_metadata_title_marker_style()`
+    /// - scalar values                       : `get_metadata_title_value_style()`
+    /// - list items                          : `get_metadata_tags_values_style()`
+    pub fn from_front_matter_line(
+        line: &str,
+        maybe_current_box_computed_style: &Option<Style>,
+    ) -> Self {
+        let base_style = maybe_current_box_computed_style.unwrap_or_default();
+        let mut acc_line_output = StyleUSSpanLine::default();
+
+        let content = line.trim_start();
+        let indent = &line[..line.len() - content.len()];
+        if !indent.is_empty() {
+            acc_line_output += StyleUSSpan::new(
+                base_style + get_foreground_dim_style(),
+                US::from(indent),
+            );
+        }
+
+        // List item, eg: `- item`.
+        let (content, value_style) = match content.strip_prefix(UNORDERED_LIST_PREFIX) {
+            Some(item) => {
+                acc_line_output += StyleUSSpan::new(
+                    base_style + get_foreground_dim_style(),
+                    US::from(UNORDERED_LIST_PREFIX),
+                );
+                (item, get_metadata_tags_values_style())
+            }
+            None => (content, get_metadata_title_value_style()),
+        };
+
+        // Comment, eg: `# comment`.
+        if content.starts_with(HEADING_CHAR) {
+            acc_line_output += StyleUSSpan::new(
+                base_style + get_foreground_dim_style(),
+                US::from(content),
+            );
+            return acc_line_output;
+        }
+
+        // Key value pair, eg: `key: value` or `key:`.
+        let maybe_key_value = match content.split_once(COLON) {
+            Some((key, value))
+                if !key.is_empty() && (value.is_empty() || value.starts_with(SPACE)) =>
+            {
+                Some((key, value))
+            }
+            _ => None,
+        };
+        let value = match maybe_key_value {
+            Some((key, value)) => {
+                acc_line_output += StyleUSSpan::new(
+                    base_style + get_metadata_title_marker_style(),
+                    US::from(key),
+                );
+                acc_line_output += StyleUSSpan::new(
+                    base_style + get_foreground_dim_style(),
+                    US::from(COLON),
+                );
+                value
+            }
+            None => content,
+        };
+        if !value.is_empty() {
+            acc_line_output +=
+                StyleUSSpan::new(base_style + value_style, US::from(value));
+        }
+
+        acc_line_output
+    }
+
+    /// Convert a single line of a code block. The text lines are highlighted using
+    /// `maybe_highlighter` if it is provided (and syntect is able to parse the line), otherwise
+    /// [get_code_block_content_style] is used.
+    pub fn from_code_block_line(
+        code_block_line: &CodeBlockLine,
+        maybe_current_box_computed_style: &Option<Style>,
+        maybe_highlighter: Option<&mut CodeBlockHighlighter>,
+    ) -> Self {
+        let mut acc_line_output = StyleUSSpanLine::default();
+
+        match code_block_line.content {
+            CodeBlockLineContent::StartTag => {
+                acc_line_output += StyleUSSpan::new(
+                    maybe_current_box_computed_style.unwrap_or_default()
+                        + get_foreground_dim_style(),
+                    US::from(CODE_BLOCK_START_PARTIAL),
+                );
+                if let Some(language) = code_block_line.language {
+                    acc_line_output += StyleUSSpan::new(
+                        maybe_current_box_computed_style.unwrap_or_default()
+                            + get_code_block_lang_style(),
+                        US::from(language),
+                    );
+                }
+            }
+
+            CodeBlockLineContent::EndTag => {
+                acc_line_output += StyleUSSpan::new(
+                    maybe_current_box_computed_style.unwrap_or_default()
+                        + get_foreground_dim_style(),
+                    US::from(CODE_BLOCK_END),
+                );
+            }
+
+            CodeBlockLineContent::Text(content) => {
+                match maybe_highlighter.and_then(|it| it.highlight_line(content)) {
+                    Some(syntect_output) => acc_line_output += syntect_output,
+                    None => {
+                        acc_line_output += StyleUSSpan::new(
+                            maybe_current_box_computed_style.unwrap_or_default()
+                                + get_code_block_content_style(),
+                            US::from(content),
+                        );
+                    }
+                }
+            }
+        }
+
+        acc_line_output
+    }
+
     pub fn from_fragments(
         fragments_in_one_line: &FragmentsInOneLine,
         maybe_current_box_computed_style: &Option<Style>,
@@ -921,7 +1383,7 @@ mod tests_style_us_span_lines_from {
 
         #[test]
         fn test_italic() {
-            let fragment = MdLineFragment::Italic("Foobar");
+            let fragment = MdLineFragment::Italic(list![MdLineFragment::Plain("Foobar")]);
             let style = style! {
                 color_bg: TuiColor::Basic(ANSIBasicColor::Red)
             };
@@ -946,7 +1408,7 @@ mod tests_style_us_span_lines_from {
 
         #[test]
         fn test_bold() {
-            let fragment = MdLineFragment::Bold("Foobar");
+            let fragment = MdLineFragment::Bold(list![MdLineFragment::Plain("Foobar")]);
             let style = style! {
                 color_bg: TuiColor::Basic(ANSIBasicColor::Red)
             };
@@ -969,9 +1431,40 @@ mod tests_style_us_span_lines_from {
             );
         }
 
+        #[test]
+        fn test_italic_inside_bold() {
+            let fragment = MdLineFragment::Bold(list![
+                MdLineFragment::Plain("a "),
+                MdLineFragment::Italic(list![MdLineFragment::Plain("b")]),
+            ]);
+            let style = style! {
+                color_bg: TuiColor::Basic(ANSIBasicColor::Red)
+            };
+
+            let actual = StyleUSSpan::from_fragment(&fragment, &Some(style));
+
+            assert_eq2!(actual.len(), 6);
+            assert_eq2!(
+                actual[1],
+                StyleUSSpan::new(style + get_bold_style(), US::from("a "))
+            );
+            assert_eq2!(
+                actual[3],
+                StyleUSSpan::new(
+                    style + get_bold_style() + get_italic_style(),
+                    US::from("b")
+                )
+            );
+            assert_eq2!(
+                actual[5],
+                StyleUSSpan::new(style + get_foreground_dim_style(), US::from("**"))
+            );
+        }
+
         #[test]
         fn test_bold_italic() {
-            let fragment = MdLineFragment::BoldItalic("Foobar");
+            let fragment =
+                MdLineFragment::BoldItalic(list![MdLineFragment::Plain("Foobar")]);
             let style = style! {
                 color_bg: TuiColor::Basic(ANSIBasicColor::Red)
             };
@@ -994,6 +1487,31 @@ mod tests_style_us_span_lines_from {
             );
         }
 
+        #[test]
+        fn test_strikethrough() {
+            let fragment =
+                MdLineFragment::Strikethrough(list![MdLineFragment::Plain("Foobar")]);
+            let style = style! {
+                color_bg: TuiColor::Basic(ANSIBasicColor::Red)
+            };
+
+            let actual = StyleUSSpan::from_fragment(&fragment, &Some(style));
+
+            assert_eq2!(
+                actual[0],
+                StyleUSSpan::new(style + get_foreground_dim_style(), US::from("~~"),)
+            );
+            assert_eq2!(
+                actual[1],
+                StyleUSSpan::new(style + get_strikethrough_style(), US::from("Foobar"),)
+            );
+            assert_eq2!(actual[1].style.strikethrough, true);
+            assert_eq2!(
+                actual[2],
+                StyleUSSpan::new(style + get_foreground_dim_style(), US::from("~~"),)
+            );
+        }
+
         #[test]
         fn test_plain() {
             let fragment = MdLineFragment::Plain("Foobar");
@@ -1244,6 +1762,232 @@ mod tests_style_us_span_lines_from {
             Ok(())
         }
 
+        #[test]
+        fn test_block_quote() -> CommonResult<()> {
+            let style = style! {
+                color_bg: TuiColor::Basic(ANSIBasicColor::Red)
+            };
+            let (_, doc) = parse_markdown("> foo\n> > **bar**\n")?;
+            let lines = StyleUSSpanLines::from_block(&doc[0], &Some(style), None);
+
+            assert_eq2!(lines.len(), 2);
+            assert_eq2!(lines[0].get_plain_text(), "┃ foo");
+            assert_eq2!(lines[1].get_plain_text(), "┃ ┃ **bar**");
+
+            assert_eq2!(
+                lines[0][0],
+                StyleUSSpan::new(style + get_block_quote_gutter_style(), US::from("┃ "))
+            );
+            assert_eq2!(
+                lines[0][1],
+                StyleUSSpan::new(
+                    style + get_foreground_style() + get_block_quote_text_style(),
+                    US::from("foo")
+                )
+            );
+            assert_eq2!(
+                lines[1][2],
+                StyleUSSpan::new(
+                    style + get_bold_style() + get_block_quote_text_style(),
+                    US::from("bar")
+                )
+            );
+
+            Ok(())
+        }
+
+        #[test]
+        fn test_block_front_matter() {
+            let input = "---\ntitle: Something\ntags:\n  - tag1\n# comment\n---\n";
+            let (_, front_matter) = parse_front_matter(input).unwrap();
+            let lines = StyleUSSpanLines::from_block(
+                &MdBlockElement::FrontMatter(front_matter),
+                &None,
+                None,
+            );
+            assert_eq2!(lines.len(), 6);
+
+            let dim = Style::default() + get_foreground_dim_style();
+            assert_eq2!(lines[0], list![StyleUSSpan::new(dim, US::from("---"))]);
+            assert_eq2!(
+                lines[1],
+                list![
+                    StyleUSSpan::new(
+                        Style::default() + get_metadata_title_marker_style(),
+                        US::from("title")
+                    ),
+                    StyleUSSpan::new(dim, US::from(":")),
+                    StyleUSSpan::new(
+                        Style::default() + get_metadata_title_value_style(),
+                        US::from(" Something")
+                    ),
+                ]
+            );
+            assert_eq2!(
+                lines[3],
+                list![
+                    StyleUSSpan::new(dim, US::from("  ")),
+                    StyleUSSpan::new(dim, US::from("- ")),
+                    StyleUSSpan::new(
+                        Style::default() + get_metadata_tags_values_style(),
+                        US::from("tag1")
+                    ),
+                ]
+            );
+            assert_eq2!(
+                lines[4],
+                list![StyleUSSpan::new(dim, US::from("# comment"))]
+            );
+            assert_eq2!(lines[5], lines[0]);
+
+            // The title & tags that are populated from the front matter are not rendered again.
+            let editor_text_lines = input.lines().map(US::from).collect::<Vec<_>>();
+            let document_lines =
+                try_parse_and_highlight(&editor_text_lines, &None, None).unwrap();
+            assert_eq2!(document_lines, lines);
+        }
+
+        #[test]
+        fn test_block_link_definition() {
+            let lines = StyleUSSpanLines::from_block(
+                &MdBlockElement::LinkDefinition(LinkDefinitionData {
+                    reference: "r3bl",
+                    url: "https://r3bl.com",
+                    title: Some("R3BL"),
+                }),
+                &None,
+                None,
+            );
+            assert_eq2!(lines.len(), 1);
+            assert_eq2!(
+                lines[0].get_plain_text_clipped(ch!(0), ch!(100)),
+                "[r3bl]: https://r3bl.com \"R3BL\""
+            );
+            assert_eq2!(lines[0][1].style, Style::default() + get_link_text_style());
+            assert_eq2!(lines[0][3].style, Style::default() + get_link_url_style());
+        }
+
+        #[test]
+        fn test_block_links() {
+            let (_, document) =
+                parse_markdown("[[a|b]] https://c.d [e][f]\n[f]: https://f.g").unwrap();
+            let lines = StyleUSSpanLines::from_block(&document[0], &None, None);
+            assert_eq2!(lines.len(), 1);
+            assert_eq2!(
+                lines[0].get_plain_text_clipped(ch!(0), ch!(100)),
+                "[[a|b]] https://c.d [e][f]"
+            );
+            // Wiki link target, alias, & the autolink.
+            assert_eq2!(lines[0][1].style, Style::default() + get_link_url_style());
+            assert_eq2!(lines[0][3].style, Style::default() + get_link_text_style());
+            assert_eq2!(lines[0][6].style, Style::default() + get_link_url_style());
+        }
+
+        #[test]
+        fn test_block_footnotes() {
+            let (_, document) = parse_markdown("Text[^1]\n[^1]: A *note*").unwrap();
+
+            let lines = StyleUSSpanLines::from_block(&document[0], &None, None);
+            assert_eq2!(
+                lines[0].get_plain_text_clipped(ch!(0), ch!(100)),
+                "Text[^1]"
+            );
+            assert_eq2!(
+                lines[0][1].style,
+                Style::default() + get_foreground_dim_style()
+            );
+            assert_eq2!(lines[0][2].style, Style::default() + get_link_text_style());
+
+            let lines = StyleUSSpanLines::from_block(&document[1], &None, None);
+            assert_eq2!(lines.len(), 1);
+            assert_eq2!(
+                lines[0].get_plain_text_clipped(ch!(0), ch!(100)),
+                "[^1]: A *note*"
+            );
+            assert_eq2!(lines[0][1].style, Style::default() + get_link_text_style());
+            assert_eq2!(lines[0][5].style, Style::default() + get_italic_style());
+        }
+
+        #[test]
+        fn test_block_horizontal_rule() {
+            let lines = StyleUSSpanLines::from_block(
+                &MdBlockElement::HorizontalRule,
+                &None,
+                None,
+            );
+            assert_eq2!(lines.len(), 1);
+            assert_eq2!(
+                lines[0][0].style,
+                Style::default() + get_horizontal_rule_style()
+            );
+
+            // The rule fills up the entire width of the viewport when it is painted.
+            let line = lines[0].try_fill_horizontal_rule(&None, ch!(10)).unwrap();
+            assert_eq2!(line.get_plain_text_clipped(ch!(0), ch!(20)), "──────────");
+            assert_eq2!(line[0].style, lines[0][0].style);
+
+            // Other lines are not rules.
+            let (_, document) = parse_markdown("─\n").unwrap();
+            let lines = StyleUSSpanLines::from_block(&document[0], &None, None);
+            assert_eq2!(lines[0].try_fill_horizontal_rule(&None, ch!(10)), None);
+        }
+
+        #[test]
+        fn test_block_table() -> CommonResult<()> {
+            let style = style! {
+                color_bg: TuiColor::Basic(ANSIBasicColor::Red)
+            };
+            let (_, doc) = parse_markdown(
+                "| Name | Qty | 😃 |\n| :-- | --: | :-: |\n| apple | 3 | x |\n",
+            )?;
+            let lines = StyleUSSpanLines::from_block(&doc[0], &Some(style), None);
+
+            // One output line per line of source.
+            assert_eq2!(lines.len(), 3);
+            assert_eq2!(lines[0].get_plain_text(), "│ Name  │ Qty │ 😃 │");
+            assert_eq2!(lines[1].get_plain_text(), "├───────┼─────┼────┤");
+            assert_eq2!(lines[2].get_plain_text(), "│ apple │   3 │ x  │");
+
+            // Every line has the same display width.
+            assert_eq2!(lines[0].display_width(), lines[1].display_width());
+            assert_eq2!(lines[0].display_width(), lines[2].display_width());
+
+            // Header cells are bold, body cells are not.
+            assert_eq2!(
+                lines[0][2],
+                StyleUSSpan::new(
+                    style + get_foreground_style() + get_table_header_style(),
+                    US::from("Name")
+                )
+            );
+            assert_eq2!(
+                lines[2][2],
+                StyleUSSpan::new(style + get_foreground_style(), US::from("apple"))
+            );
+            assert_eq2!(lines[1][0].style, style + get_table_border_style());
+
+            Ok(())
+        }
+
+        #[test]
+        fn test_block_table_clipped_to_viewport() -> CommonResult<()> {
+            let (_, doc) = parse_markdown(
+                "| a | a very wide column |\n| - | - |\n| 😃😃😃 | b |\n",
+            )?;
+            let lines = StyleUSSpanLines::from_block(&doc[0], &None, None);
+
+            // All the rows are clipped at the same column, so the grid stays aligned.
+            let clipped: Vec<String> = lines
+                .iter()
+                .map(|line| line.get_plain_text_clipped(ch!(0), ch!(12)))
+                .collect();
+            assert_eq2!(clipped[0], "│ a      │ a");
+            assert_eq2!(clipped[1], "├────────┼──");
+            assert_eq2!(clipped[2], "│ 😃😃😃 │ b");
+
+            Ok(())
+        }
+
         #[test]
         fn test_block_text() {
             let text_block = MdBlockElement::Text(list![MdLineFragment::Plain("Foobar")]);
@@ -0,0 +1,587 @@
+/*
+ *   Copyright (c) 2023 R3BL LLC
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+//! The [MarkdownTheme] holds the styles that are used to highlight every element of a MD
+//! document. It can be loaded from JSON, and there are presets for light & dark terminal
+//! backgrounds ([MarkdownTheme::light] & [MarkdownTheme::dark]).
+//!
+//! The theme that is used for highlighting is stored in
+//! [markdown_theme_global_static](crate::markdown_theme_global_static), and the functions in
+//! [md_parser_stylesheet](crate::md_parser_stylesheet) read from it. You can find ANSI colors
+//! [here](https://www.ditig.com/256-colors-cheat-sheet).
+
+use r3bl_rs_utils_core::*;
+use serde::{Deserialize, Serialize};
+
+use crate::*;
+
+/// A color w/ a value for each [ColorSupport]. In JSON it looks like this:
+/// `{ "truecolor": "#c1b3d0", "ansi256": 244, "grayscale": "White" }`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct MdThemeColor {
+    /// Hex color, eg: `#c1b3d0`. Used for [ColorSupport::Truecolor].
+    pub truecolor: String,
+    /// Used for [ColorSupport::Ansi256]. If not set, then `truecolor` is used.
+    #[serde(default)]
+    pub ansi256: Option<u8>,
+    /// Used for [ColorSupport::Grayscale]. If not set, then `truecolor` is used.
+    #[serde(default)]
+    pub grayscale: Option<ANSIBasicColor>,
+}
+
+impl MdThemeColor {
+    pub fn new(
+        truecolor: &str,
+        ansi256: Option<u8>,
+        grayscale: Option<ANSIBasicColor>,
+    ) -> Self {
+        Self {
+            truecolor: truecolor.to_string(),
+            ansi256,
+            grayscale,
+        }
+    }
+
+    /// Invalid hex colors are reported by [MarkdownTheme::try_from_json], so this just falls
+    /// back to [TuiColor::Reset].
+    pub fn to_tui_color(&self, color_support: ColorSupport) -> TuiColor {
+        let truecolor = || match RgbValue::try_from_hex_color(&self.truecolor) {
+            Ok(it) => TuiColor::Rgb(it),
+            Err(_) => TuiColor::Reset,
+        };
+        match color_support {
+            ColorSupport::Grayscale => self.grayscale.map(TuiColor::Basic),
+            ColorSupport::Ansi256 => {
+                self.ansi256.map(|it| TuiColor::Ansi(AnsiValue::new(it)))
+            }
+            ColorSupport::Truecolor => None,
+        }
+        .unwrap_or_else(truecolor)
+    }
+}
+
+/// The style of a single MD element. All the fields are optional in JSON, eg:
+/// `{ "bold": true, "color_fg": { "truecolor": "#dacd24" } }`.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+#[serde(default)]
+pub struct MdThemeStyle {
+    pub bold: bool,
+    pub italic: bool,
+    pub dim: bool,
+    pub underline: bool,
+    pub strikethrough: bool,
+    pub color_fg: Option<MdThemeColor>,
+    pub color_bg: Option<MdThemeColor>,
+}
+
+impl MdThemeStyle {
+    pub fn to_style(&self, color_support: ColorSupport) -> Style {
+        Style {
+            id: u8::MAX,
+            bold: self.bold,
+            italic: self.italic,
+            dim: self.dim,
+            underline: self.underline,
+            strikethrough: self.strikethrough,
+            color_fg: self
+                .color_fg
+                .as_ref()
+                .map(|it| it.to_tui_color(color_support)),
+            color_bg: self
+                .color_bg
+                .as_ref()
+                .map(|it| it.to_tui_color(color_support)),
+            ..Default::default()
+        }
+    }
+}
+
+/// The gradient that is used to colorize a heading w/ a [ColorWheel].
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct MdThemeHeadingGradient {
+    /// Hex colors. Used for [ColorSupport::Truecolor].
+    pub truecolor_stops: Vec<String>,
+    /// Used for [ColorSupport::Ansi256] & [ColorSupport::Grayscale].
+    pub ansi256_gradient: Ansi256GradientIndex,
+}
+
+impl MdThemeHeadingGradient {
+    fn new(truecolor_stops: [&str; 2], ansi256_gradient: Ansi256GradientIndex) -> Self {
+        Self {
+            truecolor_stops: Vec::from(truecolor_stops.map(String::from)),
+            ansi256_gradient,
+        }
+    }
+}
+
+/// The styles for every element of a MD document. Some styles are applied on top of others, which
+/// is noted in their docs. The presets are [MarkdownTheme::dark] (the default) and
+/// [MarkdownTheme::light]. Use [MarkdownTheme::from] a [TerminalBackground] to pick one.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct MarkdownTheme {
+    /// Any selected range in the document.
+    pub selection: MdThemeStyle,
+    /// The foreground text of the entire document. It is overridden by the other styles.
+    pub foreground: MdThemeStyle,
+    /// Applied on top of `foreground` for things like `[`, `]`, `*`, "`", etc.
+    pub foreground_dim: MdThemeStyle,
+    pub bold: MdThemeStyle,
+    pub italic: MdThemeStyle,
+    pub bold_italic: MdThemeStyle,
+    /// Applied on top of `foreground`.
+    pub strikethrough: MdThemeStyle,
+    pub inline_code: MdThemeStyle,
+    pub link_text: MdThemeStyle,
+    pub link_url: MdThemeStyle,
+    pub checkbox_checked: MdThemeStyle,
+    pub checkbox_unchecked: MdThemeStyle,
+    pub list_bullet: MdThemeStyle,
+    /// Applied on top of `inline_code`.
+    pub code_block_lang: MdThemeStyle,
+    pub code_block_content: MdThemeStyle,
+    pub block_quote_gutter: MdThemeStyle,
+    /// Applied on top of the style of each fragment in a block quote.
+    pub block_quote_text: MdThemeStyle,
+    pub horizontal_rule: MdThemeStyle,
+    pub table_border: MdThemeStyle,
+    /// Applied on top of the style of each fragment in the header row of a table.
+    pub table_header: MdThemeStyle,
+    pub metadata_title_marker: MdThemeStyle,
+    pub metadata_title_value: MdThemeStyle,
+    pub metadata_tags_marker: MdThemeStyle,
+    pub metadata_tags_values: MdThemeStyle,
+    /// One gradient for each heading level, from `#` to `######`.
+    pub headings: [MdThemeHeadingGradient; 6],
+}
+
+impl Default for MarkdownTheme {
+    fn default() -> Self { Self::dark() }
+}
+
+impl From<TerminalBackground> for MarkdownTheme {
+    fn from(terminal_background: TerminalBackground) -> Self {
+        match terminal_background {
+            TerminalBackground::Light => Self::light(),
+            TerminalBackground::Dark => Self::dark(),
+        }
+    }
+}
+
+impl MarkdownTheme {
+    /// Pick the preset that matches [TerminalBackground::detect].
+    pub fn detect() -> Self { Self::from(TerminalBackground::detect()) }
+
+    /// Returns an error if the JSON is malformed, or if it contains an invalid hex color.
+    pub fn try_from_json(json: &str) -> CommonResult<Self> {
+        let theme: MarkdownTheme = match serde_json::from_str(json) {
+            Ok(it) => it,
+            Err(error) => {
+                return CommonError::new(
+                    CommonErrorType::ParsingError,
+                    &format!("Invalid markdown theme: {error}"),
+                );
+            }
+        };
+
+        for hex_color in markdown_theme_impl::get_hex_colors(&theme) {
+            if RgbValue::try_from_hex_color(hex_color).is_err() {
+                return CommonError::new(
+                    CommonErrorType::InvalidHexColorFormat,
+                    &format!("Invalid hex color in markdown theme: {hex_color}"),
+                );
+            }
+        }
+
+        Ok(theme)
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap_or_default()
+    }
+
+    /// The preset for terminals w/ a dark background.
+    pub fn dark() -> Self {
+        use markdown_theme_impl::*;
+        use ANSIBasicColor::*;
+
+        Self {
+            selection: fg_bg(
+                MdThemeColor::new("#dddddd", None, None),
+                MdThemeColor::new("#ff00ff", None, None),
+            ),
+            foreground: fg(color("#c1b3d0", 244, White)), // Grey50.
+            foreground_dim: MdThemeStyle {
+                dim: true,
+                ..fg(MdThemeColor::new("#5f5f5f", None, None))
+            },
+            bold: MdThemeStyle {
+                bold: true,
+                ..fg(color("#dacd24", 226, Yellow)) // Yellow1.
+            },
+            italic: MdThemeStyle {
+                italic: true,
+                ..fg(color("#a59e3a", 208, DarkYellow)) // DarkOrange.
+            },
+            bold_italic: MdThemeStyle {
+                bold: true,
+                italic: true,
+                ..fg(color("#dacd24", 184, Yellow)) // Yellow3.
+            },
+            strikethrough: MdThemeStyle {
+                strikethrough: true,
+                ..Default::default()
+            },
+            inline_code: fg(color("#ce55b7", 169, Magenta)), // HotPink2.
+            link_text: fg(color("#4f86ed", 33, Blue)),       // DodgerBlue1.
+            link_url: MdThemeStyle {
+                underline: true,
+                ..fg(color("#16adf3", 39, Blue)) // DeepSkyBlue1.
+            },
+            checkbox_checked: MdThemeStyle {
+                bold: true,
+                dim: true,
+                ..fg(MdThemeColor::new("#14a45b", None, Some(DarkMagenta)))
+            },
+            checkbox_unchecked: MdThemeStyle {
+                bold: true,
+                ..fg(MdThemeColor::new("#e1ff2f", None, Some(Green)))
+            },
+            list_bullet: fg(MdThemeColor::new("#f8f8a6", None, Some(Yellow))), // Pale yellow.
+            code_block_lang: MdThemeStyle {
+                italic: true,
+                ..Default::default()
+            },
+            code_block_content: fg(color("#ce55b7", 169, Magenta)), // HotPink2.
+            block_quote_gutter: MdThemeStyle {
+                bold: true,
+                ..fg(color("#5fafaf", 73, DarkCyan)) // CadetBlue.
+            },
+            block_quote_text: MdThemeStyle {
+                italic: true,
+                ..Default::default()
+            },
+            horizontal_rule: fg(color("#5f5f5f", 240, DarkGrey)), // Grey35.
+            table_border: fg(color("#6c5f8a", 60, DarkGrey)),     // MediumPurple4.
+            table_header: MdThemeStyle {
+                bold: true,
+                ..Default::default()
+            },
+            metadata_title_marker: fg_bg(
+                color("#000000", 16, Black),
+                color("#4f86ed", 39, Cyan), // Soft blue, DeepSkyBlue1.
+            ),
+            metadata_title_value: fg_bg(
+                color("#4fcbd4", 51, Cyan),      // Moderate cyan, Cyan1.
+                color("#444444", 238, DarkGrey), // Very dark gray, Grey27.
+            ),
+            metadata_tags_marker: fg_bg(
+                color("#000000", 16, Black),
+                color("#ad83da", 133, Yellow), // Very soft violet, MediumOrchid3.
+            ),
+            metadata_tags_values: fg_bg(
+                color("#e2a1e3", 45, Cyan),      // Soft violet, Turquoise2.
+                color("#303030", 236, DarkGrey), // Very dark gray, Grey19.
+            ),
+            // More info on gradients: <https://uigradients.com/>.
+            headings: [
+                MdThemeHeadingGradient::new(
+                    ["#01fa22", "#00eef2"],
+                    Ansi256GradientIndex::LightYellowToWhite,
+                ),
+                MdThemeHeadingGradient::new(
+                    ["#fff200", "#de211b"],
+                    Ansi256GradientIndex::GreenToBlue,
+                ),
+                MdThemeHeadingGradient::new(
+                    ["#00dbde", "#fc00ff"],
+                    Ansi256GradientIndex::OrangeToNeonPink,
+                ),
+                MdThemeHeadingGradient::new(
+                    ["#ff28a9", "#bd60eb"],
+                    Ansi256GradientIndex::LightOrangeToLightPurple,
+                ),
+                MdThemeHeadingGradient::new(
+                    ["#ff6a00", "#ee0979"],
+                    Ansi256GradientIndex::RustToPurple,
+                ),
+                MdThemeHeadingGradient::new(
+                    ["#8470ba", "#12c2e9"],
+                    Ansi256GradientIndex::DarkOliveGreenToDarkLavender,
+                ),
+            ],
+        }
+    }
+
+    /// The preset for terminals w/ a light background. The colors are darker versions of the ones
+    /// in [MarkdownTheme::dark], so that they are readable on a white background.
+    pub fn light() -> Self {
+        use markdown_theme_impl::*;
+        use ANSIBasicColor::*;
+
+        Self {
+            selection: fg_bg(
+                MdThemeColor::new("#ffffff", None, None),
+                MdThemeColor::new("#c000c0", None, None),
+            ),
+            foreground: fg(color("#3c3744", 238, Black)), // Grey27.
+            foreground_dim: MdThemeStyle {
+                dim: true,
+                ..fg(MdThemeColor::new("#9e9e9e", None, None))
+            },
+            bold: MdThemeStyle {
+                bold: true,
+                ..fg(color("#8a6d00", 136, DarkYellow)) // DarkGoldenrod.
+            },
+            italic: MdThemeStyle {
+                italic: true,
+                ..fg(color("#9a5b13", 130, DarkYellow)) // DarkOrange3.
+            },
+            bold_italic: MdThemeStyle {
+                bold: true,
+                italic: true,
+                ..fg(color("#8a6d00", 136, DarkYellow)) // DarkGoldenrod.
+            },
+            strikethrough: MdThemeStyle {
+                strikethrough: true,
+                ..Default::default()
+            },
+            inline_code: fg(color("#a3218e", 126, DarkMagenta)), // MediumVioletRed.
+            link_text: fg(color("#1f5fbf", 25, DarkBlue)),       // DeepSkyBlue4.
+            link_url: MdThemeStyle {
+                underline: true,
+                ..fg(color("#00709e", 31, DarkBlue)) // DeepSkyBlue3.
+            },
+            checkbox_checked: MdThemeStyle {
+                bold: true,
+                dim: true,
+                ..fg(MdThemeColor::new("#14a45b", None, Some(DarkGreen)))
+            },
+            checkbox_unchecked: MdThemeStyle {
+                bold: true,
+                ..fg(MdThemeColor::new("#b35900", None, Some(DarkYellow)))
+            },
+            list_bullet: fg(MdThemeColor::new("#8a6d00", None, Some(DarkYellow))),
+            code_block_lang: MdThemeStyle {
+                italic: true,
+                ..Default::default()
+            },
+            code_block_content: fg(color("#a3218e", 126, DarkMagenta)), // MediumVioletRed.
+            block_quote_gutter: MdThemeStyle {
+                bold: true,
+                ..fg(color("#2f8f8f", 30, DarkCyan)) // DarkCyan.
+            },
+            block_quote_text: MdThemeStyle {
+                italic: true,
+                ..Default::default()
+            },
+            horizontal_rule: fg(color("#b0b0b0", 249, Grey)), // Grey70.
+            table_border: fg(color("#9a8fb8", 103, Grey)),    // LightSlateGrey.
+            table_header: MdThemeStyle {
+                bold: true,
+                ..Default::default()
+            },
+            metadata_title_marker: fg_bg(
+                color("#ffffff", 231, White),
+                color("#1f5fbf", 25, DarkBlue), // DeepSkyBlue4.
+            ),
+            metadata_title_value: fg_bg(
+                color("#005f87", 24, DarkBlue), // DeepSkyBlue4.
+                color("#e4e4e4", 254, Grey),    // Grey89.
+            ),
+            metadata_tags_marker: fg_bg(
+                color("#ffffff", 231, White),
+                color("#7d4fb0", 97, DarkMagenta), // MediumPurple3.
+            ),
+            metadata_tags_values: fg_bg(
+                color("#7d2a80", 90, DarkMagenta), // DarkMagenta.
+                color("#eeeeee", 255, Grey),       // Grey93.
+            ),
+            headings: [
+                MdThemeHeadingGradient::new(
+                    ["#00801a", "#007a80"],
+                    Ansi256GradientIndex::MediumGreenToMediumBlue,
+                ),
+                MdThemeHeadingGradient::new(
+                    ["#b36b00", "#b01a15"],
+                    Ansi256GradientIndex::DarkRedToDarkMagenta,
+                ),
+                MdThemeHeadingGradient::new(
+                    ["#00878a", "#a000a8"],
+                    Ansi256GradientIndex::RedToBrightPink,
+                ),
+                MdThemeHeadingGradient::new(
+                    ["#c2006f", "#7a2fb0"],
+                    Ansi256GradientIndex::DarkRedToDarkMagenta,
+                ),
+                MdThemeHeadingGradient::new(
+                    ["#c25100", "#b3065b"],
+                    Ansi256GradientIndex::RustToPurple,
+                ),
+                MdThemeHeadingGradient::new(
+                    ["#5b4a8a", "#0b7f99"],
+                    Ansi256GradientIndex::BackgroundDarkGreenToDarkBlue,
+                ),
+            ],
+        }
+    }
+}
+
+mod markdown_theme_impl {
+    use super::*;
+
+    pub fn color(
+        truecolor: &str,
+        ansi256: u8,
+        grayscale: ANSIBasicColor,
+    ) -> MdThemeColor {
+        MdThemeColor::new(truecolor, Some(ansi256), Some(grayscale))
+    }
+
+    pub fn fg(color_fg: MdThemeColor) -> MdThemeStyle {
+        MdThemeStyle {
+            color_fg: Some(color_fg),
+            ..Default::default()
+        }
+    }
+
+    pub fn fg_bg(color_fg: MdThemeColor, color_bg: MdThemeColor) -> MdThemeStyle {
+        MdThemeStyle {
+            color_fg: Some(color_fg),
+            color_bg: Some(color_bg),
+            ..Default::default()
+        }
+    }
+
+    pub fn get_hex_colors(theme: &MarkdownTheme) -> Vec<&str> {
+        let styles = [
+            &theme.selection,
+            &theme.foreground,
+            &theme.foreground_dim,
+            &theme.bold,
+            &theme.italic,
+            &theme.bold_italic,
+            &theme.strikethrough,
+            &theme.inline_code,
+            &theme.link_text,
+            &theme.link_url,
+            &theme.checkbox_checked,
+            &theme.checkbox_unchecked,
+            &theme.list_bullet,
+            &theme.code_block_lang,
+            &theme.code_block_content,
+            &theme.block_quote_gutter,
+            &theme.block_quote_text,
+            &theme.horizontal_rule,
+            &theme.table_border,
+            &theme.table_header,
+            &theme.metadata_title_marker,
+            &theme.metadata_title_value,
+            &theme.metadata_tags_marker,
+            &theme.metadata_tags_values,
+        ];
+
+        let mut acc = styles
+            .iter()
+            .flat_map(|it| [&it.color_fg, &it.color_bg])
+            .flatten()
+            .map(|it| it.truecolor.as_str())
+            .collect::<Vec<_>>();
+        acc.extend(
+            theme
+                .headings
+                .iter()
+                .flat_map(|it| it.truecolor_stops.iter().map(|it| it.as_str())),
+        );
+        acc
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_json_round_trip() {
+        for theme in [MarkdownTheme::dark(), MarkdownTheme::light()] {
+            let json = theme.to_json();
+            assert_eq2!(MarkdownTheme::try_from_json(&json).unwrap(), theme);
+        }
+    }
+
+    #[test]
+    fn test_invalid_json_is_an_error() {
+        assert!(MarkdownTheme::try_from_json("{").is_err());
+
+        let json = MarkdownTheme::dark()
+            .to_json()
+            .replace("#dacd24", "not_a_color");
+        assert!(MarkdownTheme::try_from_json(&json).is_err());
+    }
+
+    #[test]
+    fn test_style_for_each_color_support() {
+        let bold = MarkdownTheme::dark().bold;
+        let style = bold.to_style(ColorSupport::Truecolor);
+        assert!(style.bold);
+        assert_eq2!(
+            style.color_fg,
+            Some(TuiColor::Rgb(RgbValue::from_hex("#dacd24")))
+        );
+        assert_eq2!(
+            bold.to_style(ColorSupport::Ansi256).color_fg,
+            Some(TuiColor::Ansi(AnsiValue::new(226)))
+        );
+        assert_eq2!(
+            bold.to_style(ColorSupport::Grayscale).color_fg,
+            Some(TuiColor::Basic(ANSIBasicColor::Yellow))
+        );
+
+        // Fall back to the truecolor value.
+        let list_bullet = MarkdownTheme::dark().list_bullet;
+        assert_eq2!(
+            list_bullet.to_style(ColorSupport::Ansi256).color_fg,
+            Some(TuiColor::Rgb(RgbValue::from_hex("#f8f8a6")))
+        );
+    }
+
+    #[test]
+    fn test_partial_style_json() {
+        let style: MdThemeStyle = serde_json::from_str(
+            r##"{ "bold": true, "color_fg": { "truecolor": "#123456" } }"##,
+        )
+        .unwrap();
+        assert!(style.bold);
+        assert!(!style.italic);
+        assert_eq2!(style.color_bg, None);
+        assert_eq2!(
+            style.to_style(ColorSupport::Grayscale).color_fg,
+            Some(TuiColor::Rgb(RgbValue::from_hex("#123456")))
+        );
+    }
+
+    #[test]
+    fn test_preset_from_terminal_background() {
+        assert_eq2!(
+            MarkdownTheme::from(TerminalBackground::Light),
+            MarkdownTheme::light()
+        );
+        assert_eq2!(
+            MarkdownTheme::from(TerminalBackground::Dark),
+            MarkdownTheme::default()
+        );
+    }
+}
@@ -24,8 +24,12 @@
 
 // Attach.
 pub mod md_parser_stylesheet;
+pub mod md_parser_syn_hi_cache;
 pub mod md_parser_syn_hi_impl;
+pub mod md_parser_theme;
 
 // Re-export.
 pub use md_parser_stylesheet::*;
+pub use md_parser_syn_hi_cache::*;
 pub use md_parser_syn_hi_impl::*;
+pub use md_parser_theme::*;
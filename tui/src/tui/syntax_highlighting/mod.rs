@@ -20,14 +20,22 @@ pub mod intermediate_types;
 pub mod md_parser_syn_hi;
 pub mod pattern_matcher;
 pub mod r3bl_syntect_theme;
+pub mod syntax_highlighter;
+pub mod syntect_assets;
 pub mod syntect_to_styled_text_conversion;
+#[cfg(feature = "syn_hi_tree_sitter")]
+pub mod tree_sitter_syn_hi;
 
 // Re-export
 pub use intermediate_types::*;
 pub use md_parser_syn_hi::*;
 pub use pattern_matcher::*;
 pub use r3bl_syntect_theme::*;
+pub use syntax_highlighter::*;
+pub use syntect_assets::*;
 pub use syntect_to_styled_text_conversion::*;
+#[cfg(feature = "syn_hi_tree_sitter")]
+pub use tree_sitter_syn_hi::*;
 
 // Tests.
 mod test_r3bl_syntect_theme;
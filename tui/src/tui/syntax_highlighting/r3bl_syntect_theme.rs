@@ -19,6 +19,9 @@ use std::io::{BufReader, Cursor};
 
 use syntect::highlighting::*;
 
+/// The name of the theme that is loaded by [try_load_r3bl_theme()].
+pub const R3BL_THEME_NAME: &str = "r3bl";
+
 pub fn try_load_r3bl_theme() -> std::io::Result<Theme> {
     // Load bytes from file asset.
     let theme_bytes = include_bytes!("assets/r3bl.tmTheme");
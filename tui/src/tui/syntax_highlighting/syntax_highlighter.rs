@@ -0,0 +1,270 @@
+/*
+ *   Copyright (c) 2023 R3BL LLC
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+//! This module holds the [SyntaxHighlighter] trait, which is used by the [EditorEngine] to
+//! highlight the content of an [EditorBuffer]. The implementation is selected based on the file
+//! extension of the buffer by [try_create_syntax_highlighter()]:
+//! 1. [MdSyntaxHighlighter] for Markdown files. This uses the custom R3BL md_parser, and only
+//!    re-parses the blocks that are touched by an edit (using [MdHighlightCache]).
+//! 2. `TreeSitterSyntaxHighlighter` for Rust, TOML, JSON & shell files, when the
+//!    `syn_hi_tree_sitter` feature is enabled. This re-parses & re-highlights incrementally.
+//! 3. [SyntectSyntaxHighlighter] for all the other file extensions that syntect knows about.
+//!
+//! If no implementation is found, then the content is rendered w/out syntax highlighting.
+
+use std::{fmt::Debug, ops::Range};
+
+use r3bl_rs_utils_core::*;
+use syntect::{easy::HighlightLines, highlighting::Theme, parsing::SyntaxSet};
+
+use crate::*;
+
+/// The arguments that are passed to [SyntaxHighlighter::highlight].
+#[derive(Debug)]
+pub struct SyntaxHighlighterArgs<'a> {
+    /// All the lines in the [EditorBuffer].
+    pub lines: &'a [US],
+    /// The rows that are visible in the viewport, which have to be highlighted.
+    pub row_range: Range<usize>,
+    pub maybe_current_box_computed_style: &'a Option<Style>,
+    /// Syntax highlighting support. This is a very heavy object to create, re-use it.
+    pub syntax_set: &'a SyntaxSet,
+    /// Syntax highlighting support. This is a very heavy object to create, re-use it.
+    pub theme: &'a Theme,
+}
+
+/// Implementations can keep state in between calls to [highlight()](SyntaxHighlighter::highlight),
+/// so that only the lines that have changed since the last call are highlighted again.
+pub trait SyntaxHighlighter: Debug + Send + Sync {
+    /// Returns one [StyleUSSpanLine] for each row in `args.row_range` (that exists in
+    /// `args.lines`).
+    fn highlight(
+        &mut self,
+        args: SyntaxHighlighterArgs<'_>,
+    ) -> CommonResult<StyleUSSpanLines>;
+
+    /// Discard any state that is kept in between calls, eg: when the [Theme] has changed.
+    fn clear(&mut self);
+}
+
+/// Select the [SyntaxHighlighter] for the given `file_extension`. Returns [None] if there isn't
+/// one.
+pub fn try_create_syntax_highlighter(
+    file_extension: &str,
+    syntax_set: &SyntaxSet,
+) -> Option<Box<dyn SyntaxHighlighter>> {
+    if file_extension == DEFAULT_SYN_HI_FILE_EXT {
+        return Some(Box::<MdSyntaxHighlighter>::default());
+    }
+
+    #[cfg(feature = "syn_hi_tree_sitter")]
+    if let Some(it) = TreeSitterSyntaxHighlighter::try_new(file_extension) {
+        return Some(Box::new(it));
+    }
+
+    try_get_syntax_ref(syntax_set, file_extension)?;
+    Some(Box::new(SyntectSyntaxHighlighter::new(file_extension)))
+}
+
+/// Holds the [SyntaxHighlighter] for the file extension of the [EditorBuffer] that was rendered
+/// last. It is (re)created lazily when the file extension changes. This is stored in the
+/// [EditorEngine].
+///
+/// Cloning this does not clone the [SyntaxHighlighter], since any state that it holds is only used
+/// to speed up highlighting (it is created again on the next render).
+#[derive(Debug, Default)]
+pub struct SyntaxHighlighterHolder {
+    is_initialized: bool,
+    maybe_file_extension: Option<String>,
+    maybe_syntax_highlighter: Option<Box<dyn SyntaxHighlighter>>,
+}
+
+mod syntax_highlighter_holder_impl {
+    use super::*;
+
+    impl Clone for SyntaxHighlighterHolder {
+        fn clone(&self) -> Self { Self::default() }
+    }
+
+    impl SyntaxHighlighterHolder {
+        pub fn get_mut(
+            &mut self,
+            maybe_file_extension: Option<&str>,
+            syntax_set: &SyntaxSet,
+        ) -> Option<&mut Box<dyn SyntaxHighlighter>> {
+            if !self.is_initialized
+                || self.maybe_file_extension.as_deref() != maybe_file_extension
+            {
+                self.is_initialized = true;
+                self.maybe_file_extension = maybe_file_extension.map(|it| it.to_string());
+                self.maybe_syntax_highlighter = maybe_file_extension
+                    .and_then(|it| try_create_syntax_highlighter(it, syntax_set));
+            }
+            self.maybe_syntax_highlighter.as_mut()
+        }
+
+        pub fn clear(&mut self) {
+            if let Some(it) = self.maybe_syntax_highlighter.as_mut() {
+                it.clear();
+            }
+        }
+    }
+}
+
+/// Returns the rows in `row_range` from `lines`.
+fn get_rows(lines: &StyleUSSpanLines, row_range: Range<usize>) -> StyleUSSpanLines {
+    lines
+        .iter()
+        .skip(row_range.start)
+        .take(row_range.len())
+        .cloned()
+        .collect::<Vec<_>>()
+        .into()
+}
+
+/// Highlights Markdown w/ the custom R3BL md_parser. Code blocks are highlighted w/ syntect.
+#[derive(Debug, Default)]
+pub struct MdSyntaxHighlighter {
+    pub md_highlight_cache: MdHighlightCache,
+}
+
+impl SyntaxHighlighter for MdSyntaxHighlighter {
+    fn highlight(
+        &mut self,
+        args: SyntaxHighlighterArgs<'_>,
+    ) -> CommonResult<StyleUSSpanLines> {
+        self.md_highlight_cache.update(
+            args.lines,
+            args.maybe_current_box_computed_style,
+            Some((args.syntax_set, args.theme)),
+        );
+        Ok(get_rows(
+            self.md_highlight_cache.get_lines(),
+            args.row_range,
+        ))
+    }
+
+    fn clear(&mut self) { self.md_highlight_cache.clear(); }
+}
+
+/// Highlights each visible line w/ syntect. It might seem lossy to create a new [HighlightLines]
+/// for each line, but since only the visible lines are highlighted, the state at the start of a
+/// line isn't known, so each line is highlighted on its own.
+#[derive(Debug)]
+pub struct SyntectSyntaxHighlighter {
+    pub file_extension: String,
+}
+
+impl SyntectSyntaxHighlighter {
+    pub fn new(file_extension: &str) -> Self {
+        Self {
+            file_extension: file_extension.to_string(),
+        }
+    }
+}
+
+impl SyntaxHighlighter for SyntectSyntaxHighlighter {
+    fn highlight(
+        &mut self,
+        args: SyntaxHighlighterArgs<'_>,
+    ) -> CommonResult<StyleUSSpanLines> {
+        let Some(syntax_ref) = try_get_syntax_ref(args.syntax_set, &self.file_extension)
+        else {
+            return CommonError::new_err_with_only_type(CommonErrorType::DoesNotApply);
+        };
+
+        let mut acc = StyleUSSpanLines::default();
+        for line in args
+            .lines
+            .iter()
+            .skip(args.row_range.start)
+            .take(args.row_range.len())
+        {
+            let mut highlighter = HighlightLines::new(syntax_ref, args.theme);
+            acc += match highlighter.highlight_line(&line.string, args.syntax_set) {
+                Ok(syntect_highlighted_line) => {
+                    syntect_to_styled_text_conversion::from_syntect_to_tui(
+                        syntect_highlighted_line,
+                    )
+                }
+                Err(_) => list![StyleUSSpan::new(
+                    args.maybe_current_box_computed_style.unwrap_or_default(),
+                    line.clone(),
+                )],
+            };
+        }
+        Ok(acc)
+    }
+
+    fn clear(&mut self) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_select_syntax_highlighter() {
+        let syntax_set = SyntaxSet::load_defaults_newlines();
+
+        let md = try_create_syntax_highlighter("md", &syntax_set).unwrap();
+        assert!(format!("{md:?}").starts_with("MdSyntaxHighlighter"));
+
+        let java = try_create_syntax_highlighter("java", &syntax_set).unwrap();
+        assert!(format!("{java:?}").starts_with("SyntectSyntaxHighlighter"));
+
+        assert!(try_create_syntax_highlighter("not_a_file_ext", &syntax_set).is_none());
+    }
+
+    #[test]
+    fn test_holder_recreates_highlighter_when_file_extension_changes() {
+        let syntax_set = SyntaxSet::load_defaults_newlines();
+        let mut holder = SyntaxHighlighterHolder::default();
+
+        assert!(holder.get_mut(Some("md"), &syntax_set).is_some());
+        assert!(holder.get_mut(None, &syntax_set).is_none());
+        let it = holder.get_mut(Some("java"), &syntax_set).unwrap();
+        assert!(format!("{it:?}").starts_with("SyntectSyntaxHighlighter"));
+    }
+
+    #[test]
+    fn test_highlight_visible_rows_only() {
+        let syntax_set = SyntaxSet::load_defaults_newlines();
+        let theme = load_default_theme();
+        let lines = vec![US::from("# one"), US::from("two"), US::from("three")];
+
+        for file_extension in ["md", "java"] {
+            let mut it =
+                try_create_syntax_highlighter(file_extension, &syntax_set).unwrap();
+            let output = it
+                .highlight(SyntaxHighlighterArgs {
+                    lines: &lines,
+                    row_range: 1..5,
+                    maybe_current_box_computed_style: &None,
+                    syntax_set: &syntax_set,
+                    theme: &theme,
+                })
+                .unwrap();
+            assert_eq2!(output.len(), 2);
+            let text = output[0]
+                .iter()
+                .map(|span| span.text.string.as_str())
+                .collect::<String>();
+            assert_eq2!(text, "two");
+        }
+    }
+}
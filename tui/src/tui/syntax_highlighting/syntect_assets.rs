@@ -0,0 +1,294 @@
+/*
+ *   Copyright (c) 2023 R3BL LLC
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+//! This module is responsible for loading syntect themes (`.tmTheme` files) and syntax definitions
+//! (`.sublime-syntax` files) from a directory at runtime. The [EditorEngine] uses these functions
+//! to let users bring their own themes & syntaxes.
+//!
+//! 1. Files that can't be loaded (eg: malformed XML or YAML) are skipped, and reported in the
+//!    [SyntectAssetsLoadReport]. Only errors that prevent the directory from being read at all are
+//!    returned as an [Err].
+//! 2. Compiling a [SyntaxSet] is slow, so it can be cached as a binary dump in a file. The cache is
+//!    used as long as it is newer than all the syntax definitions in the directory.
+
+use std::{collections::BTreeMap,
+          fs,
+          path::{Path, PathBuf},
+          time::SystemTime};
+
+use r3bl_rs_utils_core::*;
+use syntect::{dumps::{dump_to_uncompressed_file, from_uncompressed_dump_file},
+              highlighting::{Theme, ThemeSet},
+              parsing::{SyntaxDefinition, SyntaxSet}};
+
+pub const THEME_FILE_EXT: &str = "tmTheme";
+pub const SYNTAX_FILE_EXT: &str = "sublime-syntax";
+
+/// A theme or syntax definition file that could not be loaded.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SyntectAssetError {
+    pub path: PathBuf,
+    pub message: String,
+}
+
+/// The result of loading the themes or syntax definitions in a directory.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SyntectAssetsLoadReport {
+    /// The names of the themes or syntaxes that were loaded.
+    pub loaded: Vec<String>,
+    /// The files that could not be loaded.
+    pub errors: Vec<SyntectAssetError>,
+    /// The [SyntaxSet] was loaded from the binary dump, so no syntax definitions were compiled.
+    pub is_from_cache: bool,
+}
+
+/// Load all the `.tmTheme` files in `dir` (not recursively). The themes are keyed by file name
+/// (w/out the extension).
+pub fn try_load_themes_from_dir(
+    dir: impl AsRef<Path>,
+) -> CommonResult<(BTreeMap<String, Theme>, SyntectAssetsLoadReport)> {
+    let mut themes = BTreeMap::new();
+    let mut report = SyntectAssetsLoadReport::default();
+
+    for path in syntect_assets_impl::try_get_files_with_ext(dir.as_ref(), THEME_FILE_EXT)?
+    {
+        match ThemeSet::get_theme(&path) {
+            Ok(theme) => {
+                let name = syntect_assets_impl::get_file_stem(&path);
+                report.loaded.push(name.clone());
+                themes.insert(name, theme);
+            }
+            Err(error) => report.errors.push(SyntectAssetError {
+                path,
+                message: error.to_string(),
+            }),
+        }
+    }
+
+    Ok((themes, report))
+}
+
+/// Load all the `.sublime-syntax` files in `dir` (not recursively) on top of the default syntaxes
+/// that come w/ syntect.
+///
+/// If `maybe_cache_file` is provided, then the compiled [SyntaxSet] is loaded from it when it is
+/// newer than all the syntax definitions in `dir`. Otherwise the syntax definitions are compiled,
+/// and the result is written to it (failing to write the cache is not an error).
+pub fn try_load_syntax_set_from_dir(
+    dir: impl AsRef<Path>,
+    maybe_cache_file: Option<&Path>,
+) -> CommonResult<(SyntaxSet, SyntectAssetsLoadReport)> {
+    let paths =
+        syntect_assets_impl::try_get_files_with_ext(dir.as_ref(), SYNTAX_FILE_EXT)?;
+
+    // Try the cache first.
+    if let Some(cache_file) = maybe_cache_file {
+        if syntect_assets_impl::is_cache_fresh(cache_file, &paths) {
+            if let Ok(syntax_set) =
+                from_uncompressed_dump_file::<SyntaxSet, _>(cache_file)
+            {
+                let report = SyntectAssetsLoadReport {
+                    is_from_cache: true,
+                    ..Default::default()
+                };
+                return Ok((syntax_set, report));
+            }
+        }
+    }
+
+    let mut report = SyntectAssetsLoadReport::default();
+    let mut builder = SyntaxSet::load_defaults_newlines().into_builder();
+    for path in paths {
+        let fallback_name = syntect_assets_impl::get_file_stem(&path);
+        let result = fs::read_to_string(&path)
+            .map_err(|error| error.to_string())
+            .and_then(|content| {
+                SyntaxDefinition::load_from_str(&content, true, Some(&fallback_name))
+                    .map_err(|error| error.to_string())
+            });
+        match result {
+            Ok(syntax_definition) => {
+                report.loaded.push(syntax_definition.name.clone());
+                builder.add(syntax_definition);
+            }
+            Err(message) => report.errors.push(SyntectAssetError { path, message }),
+        }
+    }
+    let syntax_set = builder.build();
+
+    if let Some(cache_file) = maybe_cache_file {
+        dump_to_uncompressed_file(&syntax_set, cache_file).ok();
+    }
+
+    Ok((syntax_set, report))
+}
+
+mod syntect_assets_impl {
+    use super::*;
+
+    /// Returns the files in `dir` that have the extension `ext`, sorted by path.
+    pub fn try_get_files_with_ext(dir: &Path, ext: &str) -> CommonResult<Vec<PathBuf>> {
+        let entries = match fs::read_dir(dir) {
+            Ok(it) => it,
+            Err(error) => {
+                return CommonError::new(
+                    CommonErrorType::IOError,
+                    &format!("Can't read directory {}: {error}", dir.display()),
+                );
+            }
+        };
+
+        let mut acc = entries
+            .filter_map(|entry| entry.ok().map(|it| it.path()))
+            .filter(|path| {
+                path.is_file()
+                    && path
+                        .extension()
+                        .is_some_and(|it| it.to_string_lossy().eq_ignore_ascii_case(ext))
+            })
+            .collect::<Vec<_>>();
+        acc.sort();
+        Ok(acc)
+    }
+
+    pub fn get_file_stem(path: &Path) -> String {
+        path.file_stem()
+            .map(|it| it.to_string_lossy().to_string())
+            .unwrap_or_default()
+    }
+
+    fn get_modified_time(path: &Path) -> Option<SystemTime> {
+        fs::metadata(path).and_then(|it| it.modified()).ok()
+    }
+
+    /// The cache is fresh if it exists, and none of the `paths` were modified after it.
+    pub fn is_cache_fresh(cache_file: &Path, paths: &[PathBuf]) -> bool {
+        let Some(cache_modified_time) = get_modified_time(cache_file) else {
+            return false;
+        };
+        paths.iter().all(|path| {
+            get_modified_time(path).is_some_and(|it| it <= cache_modified_time)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::*;
+
+    const VALID_SYNTAX: &str = r#"%YAML 1.2
+---
+name: R3BL Test
+file_extensions: [r3bltest]
+scope: source.r3bltest
+contexts:
+  main:
+    - match: '\blet\b'
+      scope: keyword.r3bltest
+"#;
+
+    /// Creates an empty temporary directory for a test.
+    fn create_temp_dir(test_name: &str) -> PathBuf {
+        let dir = std::env::temp_dir()
+            .join(format!("r3bl_tui_{test_name}_{}", std::process::id()));
+        fs::remove_dir_all(&dir).ok();
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_load_themes_reports_malformed_files() {
+        let dir = create_temp_dir("test_load_themes");
+        fs::write(
+            dir.join("mine.tmTheme"),
+            include_bytes!("assets/r3bl.tmTheme"),
+        )
+        .unwrap();
+        fs::write(dir.join("broken.tmTheme"), "<plist>not a theme").unwrap();
+        fs::write(dir.join("ignored.txt"), "not a theme").unwrap();
+
+        let (themes, report) = try_load_themes_from_dir(&dir).unwrap();
+        assert_eq2!(themes.keys().collect::<Vec<_>>(), vec!["mine"]);
+        assert_eq2!(themes["mine"], try_load_r3bl_theme().unwrap());
+        assert_eq2!(report.loaded, vec!["mine".to_string()]);
+        assert_eq2!(report.errors.len(), 1);
+        assert_eq2!(report.errors[0].path, dir.join("broken.tmTheme"));
+
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_editor_engine_switch_theme() {
+        let dir = create_temp_dir("test_editor_engine_switch_theme");
+        fs::write(
+            dir.join("mine.tmTheme"),
+            include_bytes!("assets/r3bl.tmTheme"),
+        )
+        .unwrap();
+
+        let mut editor_engine = EditorEngine::default();
+        editor_engine.load_themes_from_dir(&dir).unwrap();
+        assert!(editor_engine
+            .get_theme_names()
+            .contains(&"mine".to_string()));
+
+        editor_engine
+            .set_theme_by_name("base16-ocean.dark")
+            .unwrap();
+        assert_eq2!(editor_engine.theme, load_default_theme());
+        editor_engine.set_theme_by_name("mine").unwrap();
+        assert_eq2!(editor_engine.theme, try_load_r3bl_theme().unwrap());
+        assert!(editor_engine.set_theme_by_name("not_a_theme").is_err());
+
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_load_from_missing_dir_is_an_error() {
+        let dir = std::env::temp_dir().join("r3bl_tui_this_dir_does_not_exist");
+        assert!(try_load_themes_from_dir(&dir).is_err());
+        assert!(try_load_syntax_set_from_dir(&dir, None).is_err());
+    }
+
+    #[test]
+    fn test_load_syntaxes_w_cache() {
+        let dir = create_temp_dir("test_load_syntaxes");
+        let cache_file = dir.join("syntax_set.packdump");
+        fs::write(dir.join("valid.sublime-syntax"), VALID_SYNTAX).unwrap();
+        fs::write(dir.join("broken.sublime-syntax"), "contexts: [").unwrap();
+
+        // 1st load compiles the syntaxes & writes the cache.
+        let (syntax_set, report) =
+            try_load_syntax_set_from_dir(&dir, Some(&cache_file)).unwrap();
+        assert!(syntax_set.find_syntax_by_extension("r3bltest").is_some());
+        assert!(syntax_set.find_syntax_by_extension("rs").is_some());
+        assert_eq2!(report.loaded, vec!["R3BL Test".to_string()]);
+        assert_eq2!(report.errors.len(), 1);
+        assert_eq2!(report.errors[0].path, dir.join("broken.sublime-syntax"));
+        assert_eq2!(report.is_from_cache, false);
+        assert!(cache_file.exists());
+
+        // 2nd load uses the cache.
+        let (syntax_set, report) =
+            try_load_syntax_set_from_dir(&dir, Some(&cache_file)).unwrap();
+        assert!(syntax_set.find_syntax_by_extension("r3bltest").is_some());
+        assert_eq2!(report.is_from_cache, true);
+
+        fs::remove_dir_all(&dir).ok();
+    }
+}
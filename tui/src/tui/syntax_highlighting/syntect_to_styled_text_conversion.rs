@@ -29,7 +29,8 @@
 //! 2. Then convert [StyleUSSpanLine] into a [StyledTexts].
 
 use r3bl_rs_utils_core::*;
-use syntect::parsing::SyntaxSet;
+use syntect::{highlighting::{HighlightIterator, HighlightState, Highlighter, Theme},
+              parsing::{ParseState, ScopeStack, SyntaxSet}};
 
 use crate::*;
 
@@ -61,6 +62,77 @@ pub fn from_syntect_to_tui(
     it
 }
 
+/// Highlights the lines of a single code block w/ syntect, one line at a time. State is carried
+/// from one line to the next, so constructs that span multiple lines (eg: block comments) are
+/// highlighted correctly.
+pub struct CodeBlockHighlighter<'a> {
+    highlighter: Highlighter<'a>,
+    syntax_set: &'a SyntaxSet,
+    highlight_state: HighlightState,
+    parse_state: ParseState,
+}
+
+impl<'a> CodeBlockHighlighter<'a> {
+    /// Returns [None] if there is no syntax for the given language.
+    pub fn try_new(
+        language: &str,
+        syntax_set: &'a SyntaxSet,
+        theme: &'a Theme,
+    ) -> Option<Self> {
+        let syntax_ref = try_get_syntax_ref(syntax_set, language)?;
+        let highlighter = Highlighter::new(theme);
+        let highlight_state = HighlightState::new(&highlighter, ScopeStack::new());
+        let parse_state = ParseState::new(syntax_ref);
+        Some(Self {
+            highlighter,
+            syntax_set,
+            highlight_state,
+            parse_state,
+        })
+    }
+
+    /// Update the state w/ the next line of code, w/out producing any output. This is used to
+    /// catch up to the first line of a code block that has to be highlighted again, when the
+    /// output of the lines before it is already known.
+    pub fn skip_line(&mut self, line: &str) {
+        let line_with_newline = format!("{line}\n");
+        if let Ok(ops) = self
+            .parse_state
+            .parse_line(&line_with_newline, self.syntax_set)
+        {
+            HighlightIterator::new(
+                &mut self.highlight_state,
+                &ops[..],
+                &line_with_newline,
+                &self.highlighter,
+            )
+            .for_each(drop);
+        }
+    }
+
+    /// Highlight the next line of code. `line` must not contain a newline. Returns [None] if
+    /// syntect fails to parse the line.
+    pub fn highlight_line(&mut self, line: &str) -> Option<StyleUSSpanLine> {
+        // The syntaxes are loaded w/ newlines, so the newline must be passed to syntect in order
+        // for the state to be correct for the next line.
+        let line_with_newline = format!("{line}\n");
+        let ops = self
+            .parse_state
+            .parse_line(&line_with_newline, self.syntax_set)
+            .ok()?;
+        let syntect_highlighted_line: SyntectStyleStrSpanLine = HighlightIterator::new(
+            &mut self.highlight_state,
+            &ops[..],
+            &line_with_newline,
+            &self.highlighter,
+        )
+        .map(|(style, text)| (style, text.strip_suffix('\n').unwrap_or(text)))
+        .filter(|(_, text)| !text.is_empty())
+        .collect();
+        Some(from_syntect_to_tui(syntect_highlighted_line))
+    }
+}
+
 mod syntect_support {
     use super::*;
 
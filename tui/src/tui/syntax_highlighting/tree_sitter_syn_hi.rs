@@ -0,0 +1,510 @@
+/*
+ *   Copyright (c) 2023 R3BL LLC
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+//! This module holds [TreeSitterSyntaxHighlighter], which is a [SyntaxHighlighter] that uses
+//! tree-sitter. It is only available when the `syn_hi_tree_sitter` feature is enabled. The
+//! languages that are supported are listed in [TreeSitterLanguage].
+//!
+//! Here's how it works:
+//! 1. The syntax tree is kept in between calls. The lines that were passed in the last time are
+//!    compared w/ the new ones, and the range of lines between the common prefix and the common
+//!    suffix is passed to tree-sitter as an edit. The tree is then re-parsed incrementally.
+//! 2. The highlighted output of each row is cached. The rows that are touched by the edit, and the
+//!    rows whose syntax nodes changed as a result (eg: when a block comment is opened), are marked
+//!    as dirty.
+//! 3. Only the dirty rows that are visible are highlighted again, by running the highlights query
+//!    of the language on those rows. The capture names (eg: `keyword`, `string`) are mapped to
+//!    TextMate scopes, so that the colors come from the same syntect [Theme] as everything else.
+
+use std::{fmt::{Debug, Formatter},
+          ops::Range};
+
+use r3bl_rs_utils_core::*;
+use streaming_iterator::StreamingIterator;
+use syntect::highlighting::{Highlighter, Theme};
+use tree_sitter::{InputEdit, Language, Parser, Point, Query, QueryCursor, Tree};
+
+use crate::*;
+
+/// The languages that [TreeSitterSyntaxHighlighter] supports.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TreeSitterLanguage {
+    Rust,
+    Toml,
+    Json,
+    Shell,
+}
+
+mod tree_sitter_language_impl {
+    use super::*;
+
+    impl TreeSitterLanguage {
+        pub fn try_from_file_extension(file_extension: &str) -> Option<Self> {
+            match file_extension {
+                "rs" => Some(TreeSitterLanguage::Rust),
+                "toml" => Some(TreeSitterLanguage::Toml),
+                "json" => Some(TreeSitterLanguage::Json),
+                "sh" | "bash" | "zsh" => Some(TreeSitterLanguage::Shell),
+                _ => None,
+            }
+        }
+
+        pub fn get_language(&self) -> Language {
+            match self {
+                TreeSitterLanguage::Rust => tree_sitter_rust::LANGUAGE.into(),
+                TreeSitterLanguage::Toml => tree_sitter_toml_ng::LANGUAGE.into(),
+                TreeSitterLanguage::Json => tree_sitter_json::LANGUAGE.into(),
+                TreeSitterLanguage::Shell => tree_sitter_bash::LANGUAGE.into(),
+            }
+        }
+
+        pub fn get_highlights_query(&self) -> &'static str {
+            match self {
+                TreeSitterLanguage::Rust => tree_sitter_rust::HIGHLIGHTS_QUERY,
+                TreeSitterLanguage::Toml => tree_sitter_toml_ng::HIGHLIGHTS_QUERY,
+                TreeSitterLanguage::Json => tree_sitter_json::HIGHLIGHTS_QUERY,
+                TreeSitterLanguage::Shell => tree_sitter_bash::HIGHLIGHT_QUERY,
+            }
+        }
+    }
+}
+
+/// Maps tree-sitter capture names to TextMate scopes (which are used by syntect [Theme]s). The
+/// first entry that matches the capture name, or a prefix of it (eg: `string` matches
+/// `string.special.key`), is used.
+#[rustfmt::skip]
+const CAPTURE_NAME_TO_SCOPE: &[(&str, &str)] = &[
+    ("comment",            "comment"),
+    ("string.special",     "string.regexp"),
+    ("string",             "string"),
+    ("escape",             "constant.character.escape"),
+    ("number",             "constant.numeric"),
+    ("boolean",            "constant.language"),
+    ("constant.builtin",   "constant.language"),
+    ("constant",           "constant"),
+    ("keyword",            "keyword"),
+    ("operator",           "keyword.operator"),
+    ("function.macro",     "entity.name.function.macro"),
+    ("function",           "entity.name.function"),
+    ("constructor",        "entity.name.type"),
+    ("type.builtin",       "storage.type"),
+    ("type",               "entity.name.type"),
+    ("attribute",          "entity.other.attribute-name"),
+    ("label",              "entity.name.label"),
+    ("property",           "variable.other.member"),
+    ("variable.parameter", "variable.parameter"),
+    ("variable.builtin",   "variable.language"),
+    ("variable",           "variable"),
+    ("punctuation",        "punctuation"),
+    ("embedded",           "meta.embedded"),
+];
+
+/// How much work was done by the last call to [TreeSitterSyntaxHighlighter::highlight].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TreeSitterHighlightStats {
+    pub is_full_parse: bool,
+    pub rows_highlighted: usize,
+}
+
+/// Highlights the languages in [TreeSitterLanguage] w/ tree-sitter, incrementally.
+pub struct TreeSitterSyntaxHighlighter {
+    language: TreeSitterLanguage,
+    parser: Parser,
+    query: Query,
+    maybe_tree: Option<Tree>,
+    /// Each line is terminated by a new line, so that the byte offset of the start of a line is
+    /// easy to find (even for the line after the last one).
+    source: String,
+    source_lines: Vec<String>,
+    /// [None] for rows that have to be highlighted (again).
+    output: Vec<Option<StyleUSSpanLine>>,
+    maybe_current_box_computed_style: Option<Style>,
+    last_update_stats: TreeSitterHighlightStats,
+}
+
+impl Debug for TreeSitterSyntaxHighlighter {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TreeSitterSyntaxHighlighter")
+            .field("language", &self.language)
+            .field("source_lines.len()", &self.source_lines.len())
+            .field("last_update_stats", &self.last_update_stats)
+            .finish()
+    }
+}
+
+impl TreeSitterSyntaxHighlighter {
+    /// Returns [None] if the `file_extension` isn't one of the [TreeSitterLanguage]s.
+    pub fn try_new(file_extension: &str) -> Option<Self> {
+        let language = TreeSitterLanguage::try_from_file_extension(file_extension)?;
+        let mut parser = Parser::new();
+        parser.set_language(&language.get_language()).ok()?;
+        let query =
+            Query::new(&language.get_language(), language.get_highlights_query()).ok()?;
+        Some(Self {
+            language,
+            parser,
+            query,
+            maybe_tree: None,
+            source: String::new(),
+            source_lines: vec![],
+            output: vec![],
+            maybe_current_box_computed_style: None,
+            last_update_stats: Default::default(),
+        })
+    }
+
+    pub fn get_language(&self) -> TreeSitterLanguage { self.language }
+
+    pub fn get_last_update_stats(&self) -> TreeSitterHighlightStats {
+        self.last_update_stats
+    }
+
+    /// Bring the syntax tree up to date w/ `lines`, and mark the rows that have to be highlighted
+    /// again.
+    fn update_tree(&mut self, lines: &[US]) {
+        let new_source_lines = lines
+            .iter()
+            .map(|line| line.string.clone())
+            .collect::<Vec<_>>();
+        let new_source = get_source(&new_source_lines);
+
+        let Some(mut tree) = self.maybe_tree.take() else {
+            self.maybe_tree = self.parser.parse(&new_source, None);
+            self.output = vec![None; new_source_lines.len()];
+            self.source = new_source;
+            self.source_lines = new_source_lines;
+            self.last_update_stats.is_full_parse = true;
+            return;
+        };
+
+        // Find the range of lines that has changed.
+        let old_len = self.source_lines.len();
+        let new_len = new_source_lines.len();
+        let prefix = self
+            .source_lines
+            .iter()
+            .zip(new_source_lines.iter())
+            .take_while(|(old, new)| old == new)
+            .count();
+        if prefix == old_len && old_len == new_len {
+            self.maybe_tree = Some(tree);
+            return;
+        }
+        let suffix = self.source_lines[prefix..]
+            .iter()
+            .rev()
+            .zip(new_source_lines[prefix..].iter().rev())
+            .take_while(|(old, new)| old == new)
+            .count();
+
+        tree.edit(&InputEdit {
+            start_byte: get_byte_offset(&self.source_lines, prefix),
+            old_end_byte: get_byte_offset(&self.source_lines, old_len - suffix),
+            new_end_byte: get_byte_offset(&new_source_lines, new_len - suffix),
+            start_position: Point::new(prefix, 0),
+            old_end_position: Point::new(old_len - suffix, 0),
+            new_end_position: Point::new(new_len - suffix, 0),
+        });
+        self.maybe_tree = self.parser.parse(&new_source, Some(&tree));
+
+        // Mark the rows that were edited, and the rows whose syntax nodes have changed, as dirty.
+        self.output.splice(
+            prefix..old_len - suffix,
+            vec![None; new_len - suffix - prefix],
+        );
+        if let Some(new_tree) = self.maybe_tree.as_ref() {
+            for range in tree.changed_ranges(new_tree) {
+                // A range that ends at the start of a row doesn't touch that row.
+                let end_row = match range.end_point.column {
+                    0 if range.end_point.row > range.start_point.row => {
+                        range.end_point.row
+                    }
+                    _ => range.end_point.row + 1,
+                }
+                .min(new_len);
+                for row in range.start_point.row..end_row {
+                    self.output[row] = None;
+                }
+            }
+        }
+
+        self.source = new_source;
+        self.source_lines = new_source_lines;
+    }
+
+    /// Returns the highlighted output for each row in `row_range` (which must only contain rows
+    /// that exist). If there is no syntax tree, the rows are not highlighted.
+    fn highlight_rows(
+        &self,
+        row_range: Range<usize>,
+        theme: &Theme,
+        maybe_current_box_computed_style: &Option<Style>,
+    ) -> Vec<StyleUSSpanLine> {
+        let Some(tree) = self.maybe_tree.as_ref() else {
+            return self.source_lines[row_range]
+                .iter()
+                .map(|line| {
+                    list![StyleUSSpan::new(
+                        maybe_current_box_computed_style.unwrap_or_default(),
+                        US::from(line.as_str()),
+                    )]
+                })
+                .collect();
+        };
+
+        // For each byte in each row, the capture index & byte length of the innermost node that
+        // is captured (for the same node, the first pattern wins).
+        let mut row_captures = self.source_lines[row_range.clone()]
+            .iter()
+            .map(|line| vec![None; line.len()])
+            .collect::<Vec<Vec<Option<(usize, usize)>>>>();
+
+        let mut cursor = QueryCursor::new();
+        cursor.set_point_range(
+            Point::new(row_range.start, 0)..Point::new(row_range.end, 0),
+        );
+        let mut captures =
+            cursor.captures(&self.query, tree.root_node(), self.source.as_bytes());
+        while let Some((query_match, capture_index)) = captures.next() {
+            let capture = query_match.captures[*capture_index];
+            let node = capture.node;
+            let node_byte_len = node.end_byte() - node.start_byte();
+            let start = node.start_position();
+            let end = node.end_position();
+            let end_row = (end.row + 1).min(row_range.end);
+            for row in start.row.max(row_range.start)..end_row {
+                let line_len = self.source_lines[row].len();
+                let start_col = if row == start.row { start.column } else { 0 };
+                let end_col = if row == end.row {
+                    end.column.min(line_len)
+                } else {
+                    line_len
+                };
+                for slot in
+                    row_captures[row - row_range.start][start_col..end_col].iter_mut()
+                {
+                    match slot {
+                        Some((_, byte_len)) if *byte_len <= node_byte_len => {}
+                        _ => *slot = Some((capture.index as usize, node_byte_len)),
+                    }
+                }
+            }
+        }
+
+        // Convert the captures into spans.
+        let highlighter = Highlighter::new(theme);
+        let default_style = highlighter.get_default();
+        let capture_styles = self
+            .query
+            .capture_names()
+            .iter()
+            .map(
+                |capture_name| match get_scope_for_capture_name(capture_name) {
+                    Some(scope) => highlighter.style_for_stack(&[scope]),
+                    None => default_style,
+                },
+            )
+            .collect::<Vec<_>>();
+
+        row_range
+            .zip(row_captures)
+            .map(|(row, captures)| {
+                let line = self.source_lines[row].as_str();
+                let mut acc: SyntectStyleStrSpanLine = vec![];
+                let mut span_start = 0;
+                for byte_index in 1..=line.len() {
+                    if byte_index == line.len()
+                        || captures[byte_index].map(|it| it.0)
+                            != captures[span_start].map(|it| it.0)
+                    {
+                        let style = match captures[span_start] {
+                            Some((capture_index, _)) => capture_styles[capture_index],
+                            None => default_style,
+                        };
+                        acc.push((style, &line[span_start..byte_index]));
+                        span_start = byte_index;
+                    }
+                }
+                syntect_to_styled_text_conversion::from_syntect_to_tui(acc)
+            })
+            .collect()
+    }
+}
+
+impl SyntaxHighlighter for TreeSitterSyntaxHighlighter {
+    fn highlight(
+        &mut self,
+        args: SyntaxHighlighterArgs<'_>,
+    ) -> CommonResult<StyleUSSpanLines> {
+        if self.maybe_current_box_computed_style != *args.maybe_current_box_computed_style
+        {
+            self.clear();
+        }
+        self.maybe_current_box_computed_style = *args.maybe_current_box_computed_style;
+        self.last_update_stats = Default::default();
+
+        self.update_tree(args.lines);
+
+        // Highlight the visible rows that are dirty.
+        let row_range = args.row_range.start.min(self.output.len())
+            ..args.row_range.end.min(self.output.len());
+        let dirty_rows = row_range
+            .clone()
+            .filter(|row| self.output[*row].is_none())
+            .collect::<Vec<_>>();
+        if let (Some(first), Some(last)) = (dirty_rows.first(), dirty_rows.last()) {
+            let highlighted = self.highlight_rows(
+                *first..*last + 1,
+                args.theme,
+                args.maybe_current_box_computed_style,
+            );
+            for (row, line) in (*first..*last + 1).zip(highlighted) {
+                if self.output[row].is_none() {
+                    self.output[row] = Some(line);
+                    self.last_update_stats.rows_highlighted += 1;
+                }
+            }
+        }
+
+        Ok(self.output[row_range]
+            .iter()
+            .map(|it| it.clone().unwrap_or_default())
+            .collect::<Vec<_>>()
+            .into())
+    }
+
+    fn clear(&mut self) {
+        self.maybe_tree = None;
+        self.source.clear();
+        self.source_lines.clear();
+        self.output.clear();
+    }
+}
+
+fn get_source(lines: &[String]) -> String {
+    let mut acc = String::new();
+    for line in lines {
+        acc.push_str(line);
+        acc.push('\n');
+    }
+    acc
+}
+
+/// Returns the byte offset of the start of the line at `row_index` in [get_source()].
+fn get_byte_offset(lines: &[String], row_index: usize) -> usize {
+    lines[..row_index].iter().map(|line| line.len() + 1).sum()
+}
+
+fn get_scope_for_capture_name(capture_name: &str) -> Option<syntect::parsing::Scope> {
+    CAPTURE_NAME_TO_SCOPE
+        .iter()
+        .find(|(name, _)| {
+            capture_name == *name
+                || capture_name
+                    .strip_prefix(*name)
+                    .is_some_and(|rest| rest.starts_with('.'))
+        })
+        .and_then(|(_, scope)| syntect::parsing::Scope::new(scope).ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use syntect::parsing::SyntaxSet;
+
+    use super::*;
+
+    fn highlight(
+        highlighter: &mut TreeSitterSyntaxHighlighter,
+        lines: &[&str],
+    ) -> StyleUSSpanLines {
+        let lines = lines.iter().map(|it| US::from(*it)).collect::<Vec<_>>();
+        highlighter
+            .highlight(SyntaxHighlighterArgs {
+                lines: &lines,
+                row_range: 0..lines.len(),
+                maybe_current_box_computed_style: &None,
+                syntax_set: &SyntaxSet::load_defaults_newlines(),
+                theme: &load_default_theme(),
+            })
+            .unwrap()
+    }
+
+    fn get_text(line: &StyleUSSpanLine) -> String {
+        line.iter().map(|span| span.text.string.as_str()).collect()
+    }
+
+    #[test]
+    fn test_select_tree_sitter_for_supported_file_extensions() {
+        let syntax_set = SyntaxSet::load_defaults_newlines();
+        for file_extension in ["rs", "toml", "json", "sh"] {
+            let it = try_create_syntax_highlighter(file_extension, &syntax_set).unwrap();
+            assert!(format!("{it:?}").starts_with("TreeSitterSyntaxHighlighter"));
+        }
+    }
+
+    #[test]
+    fn test_highlight_each_language() {
+        for (file_extension, line) in [
+            ("rs", "fn main() { let a = 1; }"),
+            ("toml", "name = \"r3bl\""),
+            ("json", "{\"a\": [1, true]}"),
+            ("sh", "echo \"hello\" # comment"),
+        ] {
+            let mut it = TreeSitterSyntaxHighlighter::try_new(file_extension).unwrap();
+            let output = highlight(&mut it, &[line]);
+            assert_eq2!(output.len(), 1);
+            assert_eq2!(get_text(&output[0]), line);
+            assert!(output[0].len() > 1, "{file_extension} is not highlighted");
+        }
+    }
+
+    #[test]
+    fn test_incremental_edit_only_highlights_changed_rows() {
+        let mut it = TreeSitterSyntaxHighlighter::try_new("rs").unwrap();
+
+        highlight(&mut it, &["let a = 1;", "let b = 2;", "let c = 3;"]);
+        assert_eq2!(
+            it.get_last_update_stats(),
+            TreeSitterHighlightStats {
+                is_full_parse: true,
+                rows_highlighted: 3
+            }
+        );
+
+        let output = highlight(&mut it, &["let a = 1;", "let bb = 2;", "let c = 3;"]);
+        assert_eq2!(
+            it.get_last_update_stats(),
+            TreeSitterHighlightStats {
+                is_full_parse: false,
+                rows_highlighted: 1
+            }
+        );
+        assert_eq2!(get_text(&output[1]), "let bb = 2;");
+    }
+
+    #[test]
+    fn test_incremental_edit_highlights_rows_whose_nodes_changed() {
+        let mut it = TreeSitterSyntaxHighlighter::try_new("rs").unwrap();
+        highlight(&mut it, &["let a = 1;", "let b = 2;", "*/"]);
+
+        // Opening a block comment turns the lines below it into a comment.
+        let output = highlight(&mut it, &["/* let a = 1;", "let b = 2;", "*/"]);
+        assert_eq2!(it.get_last_update_stats().rows_highlighted, 3);
+        assert_eq2!(output[1].len(), 1);
+        assert_eq2!(output[1][0].style, output[2][0].style);
+    }
+}
@@ -70,20 +70,38 @@ use r3bl_rs_utils_core::*;
 
 use crate::*;
 
-pub struct AsyncEventStream {
-    event_stream: EventStream,
+/// Reads [InputEvent]s asynchronously using the [TerminalLibBackend] that is in use when this is
+/// created.
+pub enum AsyncEventStream {
+    Crossterm(EventStream),
+    #[cfg(all(unix, feature = "termion"))]
+    Termion(TermionEventStream),
 }
 
 impl AsyncEventStream {
     pub async fn try_to_get_input_event(&mut self) -> Option<InputEvent> {
-        self.event_stream.try_to_get_input_event().await
+        match self {
+            AsyncEventStream::Crossterm(event_stream) => {
+                event_stream.try_to_get_input_event().await
+            }
+            #[cfg(all(unix, feature = "termion"))]
+            AsyncEventStream::Termion(event_stream) => {
+                event_stream.try_to_get_input_event().await
+            }
+        }
     }
 }
 
 impl Default for AsyncEventStream {
     fn default() -> Self {
-        Self {
-            event_stream: EventStream::new(),
+        match TerminalLibBackend::get() {
+            TerminalLibBackend::Crossterm => {
+                AsyncEventStream::Crossterm(EventStream::new())
+            }
+            #[cfg(all(unix, feature = "termion"))]
+            TerminalLibBackend::Termion => {
+                AsyncEventStream::Termion(TermionEventStream::default())
+            }
         }
     }
 }
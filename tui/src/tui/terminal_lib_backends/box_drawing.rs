@@ -0,0 +1,338 @@
+/*
+ *   Copyright (c) 2023 R3BL LLC
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+//! [RenderOp::PaintBorder](crate::RenderOp::PaintBorder) paints a rectangle using one of the
+//! [BorderStyle] sets of box drawing characters, w/ an optional title. Eg:
+//!
+//! ```rust
+//! use r3bl_rs_utils_core::*;
+//! use r3bl_tui::*;
+//!
+//! let render_ops = render_ops!(
+//!   @new
+//!   RenderOp::PaintBorder(
+//!     position!(col_index: 0, row_index: 0),
+//!     size!(col_count: 20, row_count: 5),
+//!     Border {
+//!       border_style: BorderStyle::Rounded,
+//!       maybe_title: Some(BorderTitle::new("Title", BorderTitleAlignment::Center)),
+//!       maybe_style: None,
+//!     },
+//!   ),
+//! );
+//! ```
+//!
+//! When the compositor paints a border over a cell that already has a box drawing character w/
+//! the same line weight in it (eg: where the borders of 2 boxes that share an edge meet), the
+//! lines of both are merged into a junction. Eg: `┐` painted over `┌` results in `┬`.
+//!
+//! ```text
+//! ┌───┬───┐
+//! │   │   │
+//! ├───┼───┤
+//! │   │   │
+//! └───┴───┘
+//! ```
+
+use r3bl_rs_utils_core::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Default)]
+pub enum BorderTitleAlignment {
+    #[default]
+    Left,
+    Center,
+    Right,
+}
+
+/// The title is painted in the top edge of a [Border], between its corners. It is truncated if
+/// it doesn't fit.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, Default)]
+pub struct BorderTitle {
+    pub text: String,
+    pub alignment: BorderTitleAlignment,
+}
+
+impl BorderTitle {
+    pub fn new(text: &str, alignment: BorderTitleAlignment) -> Self {
+        BorderTitle {
+            text: text.to_string(),
+            alignment,
+        }
+    }
+
+    /// Returns the column offset of the title (from the first column after the top left corner),
+    /// and the (truncated) title text, for a border w/ the given inner width.
+    pub fn get_offset_and_text(&self, inner_col_count: ChUnit) -> (ChUnit, String) {
+        let unicode_string = self.text.unicode_string();
+        let text = unicode_string.truncate_end_to_fit_width(inner_col_count);
+        let text_display_width = ch!(UnicodeString::str_display_width(text));
+        let offset = match self.alignment {
+            BorderTitleAlignment::Left => ch!(0),
+            BorderTitleAlignment::Center => (inner_col_count - text_display_width) / 2,
+            BorderTitleAlignment::Right => inner_col_count - text_display_width,
+        };
+        (offset, text.to_string())
+    }
+}
+
+/// Everything that [RenderOp::PaintBorder](crate::RenderOp::PaintBorder) needs (other than its
+/// position & size). The colors & attributes of `maybe_style` are used for the border & its
+/// title. If it is `None`, then the colors that are currently set are used (just like
+/// [RenderOp::PaintTextWithAttributes](crate::RenderOp::PaintTextWithAttributes)).
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, Default)]
+pub struct Border {
+    pub border_style: BorderStyle,
+    pub maybe_title: Option<BorderTitle>,
+    pub maybe_style: Option<Style>,
+}
+
+/// The weight of the lines of a box drawing character. [BorderStyle::Single] &
+/// [BorderStyle::Rounded] have the same weight, so they can be merged.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineWeight {
+    Light,
+    Heavy,
+    Double,
+    Ascii,
+}
+
+impl From<BorderStyle> for LineWeight {
+    fn from(border_style: BorderStyle) -> Self {
+        match border_style {
+            BorderStyle::Single | BorderStyle::Rounded => LineWeight::Light,
+            BorderStyle::Double => LineWeight::Double,
+            BorderStyle::Thick => LineWeight::Heavy,
+            BorderStyle::Ascii => LineWeight::Ascii,
+        }
+    }
+}
+
+/// A box drawing character, described by the lines that go from its center to each of its 4
+/// sides.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BoxGlyph {
+    pub weight: LineWeight,
+    pub is_rounded: bool,
+    pub up: bool,
+    pub down: bool,
+    pub left: bool,
+    pub right: bool,
+}
+
+mod box_glyph_impl {
+    use super::*;
+
+    // These are indexed by the bits of the lines: up = 1, down = 2, left = 4, right = 8.
+    const LIGHT: [&str; 16] = [
+        " ", "╵", "╷", "│", "╴", "┘", "┐", "┤", "╶", "└", "┌", "├", "─", "┴", "┬", "┼",
+    ];
+    const HEAVY: [&str; 16] = [
+        " ", "╹", "╻", "┃", "╸", "┛", "┓", "┫", "╺", "┗", "┏", "┣", "━", "┻", "┳", "╋",
+    ];
+    const DOUBLE: [&str; 16] = [
+        " ", "║", "║", "║", "═", "╝", "╗", "╣", "═", "╚", "╔", "╠", "═", "╩", "╦", "╬",
+    ];
+    const ASCII: [&str; 16] = [
+        " ", "|", "|", "|", "-", "+", "+", "+", "-", "+", "+", "+", "-", "+", "+", "+",
+    ];
+    /// Only the corners are rounded, the rest are the same as [LIGHT].
+    const ROUNDED_CORNERS: [(usize, &str); 4] = [(5, "╯"), (6, "╮"), (9, "╰"), (10, "╭")];
+
+    fn get_table(weight: LineWeight) -> &'static [&'static str; 16] {
+        match weight {
+            LineWeight::Light => &LIGHT,
+            LineWeight::Heavy => &HEAVY,
+            LineWeight::Double => &DOUBLE,
+            LineWeight::Ascii => &ASCII,
+        }
+    }
+
+    impl BoxGlyph {
+        pub fn new(
+            border_style: BorderStyle,
+            up: bool,
+            down: bool,
+            left: bool,
+            right: bool,
+        ) -> Self {
+            BoxGlyph {
+                weight: border_style.into(),
+                is_rounded: border_style == BorderStyle::Rounded,
+                up,
+                down,
+                left,
+                right,
+            }
+        }
+
+        fn get_index(&self) -> usize {
+            (self.up as usize)
+                | (self.down as usize) << 1
+                | (self.left as usize) << 2
+                | (self.right as usize) << 3
+        }
+
+        fn from_index(weight: LineWeight, is_rounded: bool, index: usize) -> Self {
+            BoxGlyph {
+                weight,
+                is_rounded,
+                up: index & 1 != 0,
+                down: index & 2 != 0,
+                left: index & 4 != 0,
+                right: index & 8 != 0,
+            }
+        }
+
+        /// Returns true if there are no lines (it would be painted as a space).
+        pub fn is_empty(&self) -> bool { self.get_index() == 0 }
+
+        pub fn as_str(&self) -> &'static str {
+            let index = self.get_index();
+            if self.is_rounded {
+                if let Some((_, glyph)) =
+                    ROUNDED_CORNERS.iter().find(|(it, _)| *it == index)
+                {
+                    return glyph;
+                }
+            }
+            get_table(self.weight)[index]
+        }
+
+        /// Returns `None` if `text` isn't a box drawing character that is painted by
+        /// [RenderOp::PaintBorder](crate::RenderOp::PaintBorder). The table is searched from the
+        /// end, so that a glyph that is used for more than 1 combination of lines (eg: `║` or
+        /// `+`) gets all of them.
+        pub fn try_from_str(text: &str) -> Option<Self> {
+            if let Some((index, _)) = ROUNDED_CORNERS.iter().find(|(_, it)| *it == text) {
+                return Some(BoxGlyph::from_index(LineWeight::Light, true, *index));
+            }
+            for weight in [
+                LineWeight::Light,
+                LineWeight::Heavy,
+                LineWeight::Double,
+                LineWeight::Ascii,
+            ] {
+                if let Some(index) =
+                    get_table(weight).iter().skip(1).rposition(|it| *it == text)
+                {
+                    return Some(BoxGlyph::from_index(weight, false, index + 1));
+                }
+            }
+            None
+        }
+
+        /// Paint `other` over `self`. If they have the same weight, then their lines are merged
+        /// (eg: into a junction), otherwise `other` replaces `self`.
+        pub fn merge(&self, other: &BoxGlyph) -> BoxGlyph {
+            if self.weight != other.weight {
+                return *other;
+            }
+            BoxGlyph {
+                weight: other.weight,
+                is_rounded: other.is_rounded,
+                up: self.up || other.up,
+                down: self.down || other.down,
+                left: self.left || other.left,
+                right: self.right || other.right,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_box_glyph_round_trip() {
+        for border_style in [
+            BorderStyle::Single,
+            BorderStyle::Double,
+            BorderStyle::Rounded,
+            BorderStyle::Thick,
+        ] {
+            for (up, down, left, right) in [
+                (false, true, false, true),
+                (true, true, false, false),
+                (true, true, true, true),
+                (false, true, true, true),
+            ] {
+                let glyph = BoxGlyph::new(border_style, up, down, left, right);
+                let parsed_glyph = BoxGlyph::try_from_str(glyph.as_str()).unwrap();
+                assert_eq2!((parsed_glyph.up, parsed_glyph.down), (up, down));
+                assert_eq2!((parsed_glyph.left, parsed_glyph.right), (left, right));
+                assert_eq2!(parsed_glyph.as_str(), glyph.as_str());
+            }
+        }
+        assert_eq2!(BoxGlyph::try_from_str("a"), None);
+        assert_eq2!(BoxGlyph::try_from_str(" "), None);
+    }
+
+    #[test]
+    fn test_box_glyph_merge() {
+        let top_right = BoxGlyph::new(BorderStyle::Single, false, true, true, false);
+        let top_left = BoxGlyph::new(BorderStyle::Single, false, true, false, true);
+        assert_eq2!(top_right.merge(&top_left).as_str(), "┬");
+
+        let vertical = BoxGlyph::try_from_str("║").unwrap();
+        let horizontal = BoxGlyph::try_from_str("═").unwrap();
+        assert_eq2!(vertical.merge(&horizontal).as_str(), "╬");
+
+        // Rounded & single corners have the same weight.
+        let rounded = BoxGlyph::new(BorderStyle::Rounded, false, true, false, true);
+        assert_eq2!(rounded.as_str(), "╭");
+        assert_eq2!(
+            BoxGlyph::try_from_str("│")
+                .unwrap()
+                .merge(&rounded)
+                .as_str(),
+            "├"
+        );
+
+        // Different weights aren't merged.
+        let thick = BoxGlyph::new(BorderStyle::Thick, false, false, true, true);
+        assert_eq2!(vertical.merge(&thick).as_str(), "━");
+
+        let ascii = BoxGlyph::new(BorderStyle::Ascii, true, true, false, false);
+        assert_eq2!(
+            BoxGlyph::try_from_str("-").unwrap().merge(&ascii).as_str(),
+            "+"
+        );
+    }
+
+    #[test]
+    fn test_border_title_offset() {
+        let title = BorderTitle::new("abc", BorderTitleAlignment::Center);
+        assert_eq2!(
+            title.get_offset_and_text(ch!(8)),
+            (ch!(2), "abc".to_string())
+        );
+
+        let title = BorderTitle::new("abc", BorderTitleAlignment::Right);
+        assert_eq2!(
+            title.get_offset_and_text(ch!(8)),
+            (ch!(5), "abc".to_string())
+        );
+
+        let title = BorderTitle::new("abcdef", BorderTitleAlignment::Left);
+        assert_eq2!(
+            title.get_offset_and_text(ch!(4)),
+            (ch!(0), "abcd".to_string())
+        );
+    }
+}
@@ -0,0 +1,242 @@
+/*
+ *   Copyright (c) 2023 R3BL LLC
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+//! [RenderOp::PushClipRect] & [RenderOp::PushTranslate] let a component render into a virtual
+//! canvas that is larger than its [FlexBox], which is scrolled & clipped to the box. Eg:
+//!
+//! ```rust
+//! use r3bl_rs_utils_core::*;
+//! use r3bl_tui::*;
+//!
+//! let box_origin = position!(col_index: 10, row_index: 2);
+//! let box_size = size!(col_count: 20, row_count: 5);
+//! let scroll_offset = position!(col_index: 0, row_index: 100);
+//! let render_ops = render_ops!(
+//!   @new
+//!   RenderOp::PushClipRect(box_origin, box_size),
+//!   RenderOp::PushTranslate(box_origin, scroll_offset),
+//!   // This is painted in the first row of the box.
+//!   RenderOp::MoveCursorPositionAbs(position!(col_index: 0, row_index: 100)),
+//!   RenderOp::PaintTextWithAttributes("line 100".into(), None),
+//!   RenderOp::PopTranslate,
+//!   RenderOp::PopClipRect,
+//! );
+//! ```
+//!
+//! They can be nested (eg: for a scrollable viewport inside another one). The compositor keeps
+//! track of them in [ClipState] when it converts a [RenderPipeline] to an [OffscreenBuffer].
+
+use r3bl_rs_utils_core::*;
+
+use crate::*;
+
+/// A rectangle in window coordinates. The start is inclusive, and the end is exclusive. These
+/// are signed, since a translated rectangle can start above or to the left of the window.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClipRect {
+    pub col_start: isize,
+    pub row_start: isize,
+    pub col_end: isize,
+    pub row_end: isize,
+}
+
+impl ClipRect {
+    pub fn new(col_start: isize, row_start: isize, size: Size) -> Self {
+        ClipRect {
+            col_start,
+            row_start,
+            col_end: col_start + ch!(@to_usize size.col_count) as isize,
+            row_end: row_start + ch!(@to_usize size.row_count) as isize,
+        }
+    }
+
+    /// The entire window.
+    pub fn from_window_size(window_size: Size) -> Self {
+        ClipRect::new(0, 0, window_size)
+    }
+
+    /// The result is empty (nothing is painted in it) if the rectangles don't overlap.
+    pub fn intersect(&self, other: &ClipRect) -> ClipRect {
+        ClipRect {
+            col_start: self.col_start.max(other.col_start),
+            row_start: self.row_start.max(other.row_start),
+            col_end: self.col_end.min(other.col_end),
+            row_end: self.row_end.min(other.row_end),
+        }
+    }
+
+    pub fn contains_col(&self, col_index: isize) -> bool {
+        col_index >= self.col_start && col_index < self.col_end
+    }
+
+    pub fn contains_row(&self, row_index: isize) -> bool {
+        row_index >= self.row_start && row_index < self.row_end
+    }
+}
+
+/// The clip rects & translations that are in effect while the compositor processes a
+/// [RenderOps], and the cursor position in window coordinates (which can be outside the window
+/// when it is translated). The clip rects & translations only apply to the [RenderOps] that they
+/// are pushed in, so every push should be matched by a pop in the same [RenderOps].
+#[derive(Debug, Clone, Default)]
+pub struct ClipState {
+    clip_rect_stack: Vec<ClipRect>,
+    /// Each entry is the sum of all the `(col, row)` offsets that are pushed so far.
+    translate_stack: Vec<(isize, isize)>,
+    cursor_col_index: isize,
+    cursor_row_index: isize,
+}
+
+impl ClipState {
+    /// Returns true if there are any clip rects or translations in effect.
+    pub fn is_active(&self) -> bool {
+        !self.clip_rect_stack.is_empty() || !self.translate_stack.is_empty()
+    }
+
+    /// Pop all the clip rects & translations, w/out moving the cursor.
+    pub fn clear(&mut self) {
+        self.clip_rect_stack.clear();
+        self.translate_stack.clear();
+    }
+
+    fn get_translate_offset(&self) -> (isize, isize) {
+        self.translate_stack.last().copied().unwrap_or_default()
+    }
+
+    /// The `origin` is translated, and the clip rect is intersected w/ the one it is nested in.
+    pub fn push_clip_rect(&mut self, origin: Position, size: Size) {
+        let (col_offset, row_offset) = self.get_translate_offset();
+        let clip_rect = ClipRect::new(
+            ch!(@to_usize origin.col_index) as isize + col_offset,
+            ch!(@to_usize origin.row_index) as isize + row_offset,
+            size,
+        );
+        let clip_rect = match self.clip_rect_stack.last() {
+            Some(parent) => parent.intersect(&clip_rect),
+            None => clip_rect,
+        };
+        self.clip_rect_stack.push(clip_rect);
+    }
+
+    pub fn pop_clip_rect(&mut self) { self.clip_rect_stack.pop(); }
+
+    /// After this, the position `scroll_offset` (in the canvas) is painted at `origin` (which is
+    /// translated by the translations that are already in effect).
+    pub fn push_translate(&mut self, origin: Position, scroll_offset: ScrollOffset) {
+        let (col_offset, row_offset) = self.get_translate_offset();
+        self.translate_stack.push((
+            col_offset + ch!(@to_usize origin.col_index) as isize
+                - ch!(@to_usize scroll_offset.col_index) as isize,
+            row_offset + ch!(@to_usize origin.row_index) as isize
+                - ch!(@to_usize scroll_offset.row_index) as isize,
+        ));
+    }
+
+    pub fn pop_translate(&mut self) { self.translate_stack.pop(); }
+
+    /// The innermost clip rect, intersected w/ the window.
+    pub fn get_clip_rect(&self, window_size: Size) -> ClipRect {
+        let window_rect = ClipRect::from_window_size(window_size);
+        match self.clip_rect_stack.last() {
+            Some(clip_rect) => clip_rect.intersect(&window_rect),
+            None => window_rect,
+        }
+    }
+
+    /// `position` is translated.
+    pub fn move_cursor_to(&mut self, position: Position) {
+        let (col_offset, row_offset) = self.get_translate_offset();
+        self.cursor_col_index = ch!(@to_usize position.col_index) as isize + col_offset;
+        self.cursor_row_index = ch!(@to_usize position.row_index) as isize + row_offset;
+    }
+
+    pub fn move_cursor_forward(&mut self, col_count: isize) {
+        self.cursor_col_index += col_count;
+    }
+
+    pub fn get_cursor(&self) -> (isize, isize) {
+        (self.cursor_col_index, self.cursor_row_index)
+    }
+
+    /// The cursor position, w/ the parts that are above or to the left of the window clamped to
+    /// `0`.
+    pub fn get_cursor_position(&self) -> Position {
+        position!(
+            col_index: self.cursor_col_index.max(0) as usize,
+            row_index: self.cursor_row_index.max(0) as usize
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nested_clip_rects_and_translations() {
+        let window_size = size!(col_count: 80, row_count: 24);
+        let mut clip_state = ClipState::default();
+        assert_eq2!(clip_state.is_active(), false);
+
+        // Outer viewport at (10, 2), scrolled down by 100 rows.
+        clip_state.push_clip_rect(
+            position!(col_index: 10, row_index: 2),
+            size!(col_count: 20, row_count: 5),
+        );
+        clip_state.push_translate(
+            position!(col_index: 10, row_index: 2),
+            position!(col_index: 0, row_index: 100),
+        );
+        clip_state.move_cursor_to(position!(col_index: 1, row_index: 99));
+        assert_eq2!(clip_state.get_cursor(), (11, 1));
+
+        // Inner viewport at (15, 101) in the outer canvas, which is cut by the outer one.
+        clip_state.push_clip_rect(
+            position!(col_index: 15, row_index: 101),
+            size!(col_count: 10, row_count: 10),
+        );
+        clip_state.push_translate(
+            position!(col_index: 15, row_index: 101),
+            position!(col_index: 3, row_index: 0),
+        );
+        assert_eq2!(
+            clip_state.get_clip_rect(window_size),
+            ClipRect {
+                col_start: 25,
+                row_start: 3,
+                col_end: 30,
+                row_end: 7,
+            }
+        );
+        clip_state.move_cursor_to(position!(col_index: 0, row_index: 0));
+        assert_eq2!(clip_state.get_cursor(), (22, 3));
+
+        clip_state.pop_translate();
+        clip_state.pop_clip_rect();
+        assert_eq2!(
+            clip_state.get_clip_rect(window_size),
+            ClipRect::new(10, 2, size!(col_count: 20, row_count: 5))
+        );
+
+        clip_state.clear();
+        assert_eq2!(clip_state.is_active(), false);
+        assert_eq2!(
+            clip_state.get_clip_rect(window_size),
+            ClipRect::from_window_size(window_size)
+        );
+    }
+}
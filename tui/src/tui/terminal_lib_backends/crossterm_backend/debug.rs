@@ -59,6 +59,32 @@ impl DebugFormatRenderOp for CrosstermDebugFormatRenderOp {
                     Some(style) => format!("ApplyColors({style:?})"),
                     None => "ApplyColors(None)".into(),
                 },
+                RenderOp::MoveCursorToColumn(col) =>
+                    format!("MoveCursorToColumn({col:?})"),
+                RenderOp::MoveCursorForward(count) =>
+                    format!("MoveCursorForward({count:?})"),
+                RenderOp::MoveCursorBackward(count) =>
+                    format!("MoveCursorBackward({count:?})"),
+                RenderOp::MoveCursorUp(count) => format!("MoveCursorUp({count:?})"),
+                RenderOp::MoveCursorDown(count) => format!("MoveCursorDown({count:?})"),
+                RenderOp::MoveCursorToStartOfLine => "MoveCursorToStartOfLine".into(),
+                RenderOp::MoveCursorToStartOfNextLine(count) =>
+                    format!("MoveCursorToStartOfNextLine({count:?})"),
+                RenderOp::ClearToEndOfLine => "ClearToEndOfLine".into(),
+                RenderOp::ApplyAttributes(style) => format!("ApplyAttributes({style:?})"),
+                RenderOp::SetScrollRegion(top, bottom) =>
+                    format!("SetScrollRegion({top:?}, {bottom:?})"),
+                RenderOp::ResetScrollRegion => "ResetScrollRegion".into(),
+                RenderOp::InsertLines(count) => format!("InsertLines({count:?})"),
+                RenderOp::DeleteLines(count) => format!("DeleteLines({count:?})"),
+                RenderOp::PushClipRect(origin, size) =>
+                    format!("PushClipRect({origin:?}, {size:?})"),
+                RenderOp::PopClipRect => "PopClipRect".into(),
+                RenderOp::PushTranslate(origin, scroll_offset) =>
+                    format!("PushTranslate({origin:?}, {scroll_offset:?})"),
+                RenderOp::PopTranslate => "PopTranslate".into(),
+                RenderOp::PaintBorder(origin, size, border) =>
+                    format!("PaintBorder({origin:?}, {size:?}, {border:?})"),
                 RenderOp::CompositorNoClipTruncPaintTextWithAttributes(
                     text,
                     maybe_style,
@@ -32,6 +32,12 @@ impl OffscreenBufferPaint for OffscreenBufferPaintImplCrossterm {
     ) {
         let mut skip_flush = false;
 
+        // Hold off on updating the screen until the entire frame is written.
+        let is_synchronized_output_enabled = SynchronizedOutput::is_enabled();
+        if is_synchronized_output_enabled {
+            RenderOp::default().begin_synchronized_update();
+        }
+
         if let FlushKind::ClearBeforeFlush = flush_kind {
             RenderOp::default().clear_before_flush();
         }
@@ -41,6 +47,10 @@ impl OffscreenBufferPaint for OffscreenBufferPaintImplCrossterm {
             .execute_all(&mut skip_flush, shared_global_data)
             .await;
 
+        if is_synchronized_output_enabled {
+            RenderOp::default().end_synchronized_update();
+        }
+
         // Flush everything to the terminal.
         if !skip_flush {
             RenderOp::default().flush()
@@ -62,11 +72,21 @@ impl OffscreenBufferPaint for OffscreenBufferPaintImplCrossterm {
     ) {
         let mut skip_flush = false;
 
+        // Hold off on updating the screen until the entire frame is written.
+        let is_synchronized_output_enabled = SynchronizedOutput::is_enabled();
+        if is_synchronized_output_enabled {
+            RenderOp::default().begin_synchronized_update();
+        }
+
         // Execute each RenderOp.
         render_ops
             .execute_all(&mut skip_flush, shared_global_data)
             .await;
 
+        if is_synchronized_output_enabled {
+            RenderOp::default().end_synchronized_update();
+        }
+
         // Flush everything to the terminal.
         if !skip_flush {
             RenderOp::default().flush()
@@ -170,40 +190,20 @@ impl OffscreenBufferPaint for OffscreenBufferPaintImplCrossterm {
         context.render_ops
     }
 
-    async fn render_diff(&mut self, diff_chunks: &PixelCharDiffChunks) -> RenderOps {
+    /// The [RenderOps] that are generated from the diff don't depend on the backend. See
+    /// [render_diff_to_render_ops] for how the number of bytes that are painted is minimized.
+    async fn render_diff(
+        &mut self,
+        diff_chunks: &PixelCharDiffChunks,
+        window_size: Size,
+    ) -> RenderOps {
         call_if_true!(DEBUG_TUI_COMPOSITOR, {
             let msg = format!("🎨 offscreen_buffer_paint_impl_crossterm::render_diff() ok ✅: \ndiff_chunks: \n{}",
             diff_chunks.pretty_print());
             log_info(msg);
         });
 
-        let mut it = render_ops!();
-
-        for (position, pixel_char) in diff_chunks.iter() {
-            it.push(RenderOp::MoveCursorPositionAbs(*position));
-            it.push(RenderOp::ResetColor);
-            match pixel_char {
-                PixelChar::Void => continue,
-                PixelChar::Spacer => {
-                    it.push(RenderOp::CompositorNoClipTruncPaintTextWithAttributes(
-                        SPACER.into(),
-                        None,
-                    ))
-                }
-                PixelChar::PlainText {
-                    content,
-                    maybe_style,
-                } => {
-                    it.push(RenderOp::ApplyColors(*maybe_style));
-                    it.push(RenderOp::CompositorNoClipTruncPaintTextWithAttributes(
-                        content.string.clone(),
-                        *maybe_style,
-                    ))
-                }
-            }
-        }
-
-        it
+        render_diff_to_render_ops(diff_chunks, window_size)
     }
 }
 
@@ -359,10 +359,29 @@ mod tests {
     async fn test_render_plain_text() {
         let my_offscreen_buffer = make_offscreen_buffer_plain_text().await;
         // println!("my_offscreen_buffer: \n{:#?}", my_offscreen_buffer);
-        let mut paint = OffscreenBufferPaintImplCrossterm {};
-        let render_ops = paint.render(&my_offscreen_buffer).await;
-        // println!("render_ops: {:#?}", render_ops);
+        for backend in TerminalLibBackend::get_all() {
+            let mut paint = make_offscreen_buffer_paint(backend);
+            let render_ops = paint.render(&my_offscreen_buffer).await;
+            // println!("render_ops: {:#?}", render_ops);
+            assert_render_plain_text(render_ops);
+        }
+    }
+
+    /// The [RenderOps] that are generated from the same [OffscreenBuffer] must be the same for all
+    /// the backends.
+    fn make_offscreen_buffer_paint(
+        backend: TerminalLibBackend,
+    ) -> Box<dyn OffscreenBufferPaint> {
+        match backend {
+            TerminalLibBackend::Crossterm => {
+                Box::new(OffscreenBufferPaintImplCrossterm {})
+            }
+            #[cfg(all(unix, feature = "termion"))]
+            TerminalLibBackend::Termion => Box::new(OffscreenBufferPaintImplTermion {}),
+        }
+    }
 
+    fn assert_render_plain_text(render_ops: RenderOps) {
         // Output:
         // render_ops:
         // - RenderOps.len(): 10
@@ -16,7 +16,7 @@
  */
 
 use std::{borrow::Cow,
-          io::{stderr, stdout, Write}};
+          io::{stderr, stdout, Stdout, Write}};
 
 use async_trait::async_trait;
 use crossterm::{cursor::*,
@@ -28,15 +28,26 @@ use r3bl_rs_utils_core::*;
 
 use crate::*;
 
-/// Struct representing the implementation of [RenderOp] for crossterm terminal backend. This empty
-/// struct is needed since the [Flush] trait needs to be implemented.
-pub struct RenderOpImplCrossterm;
+/// Struct representing the implementation of [RenderOp] for crossterm terminal backend. Everything
+/// is written to `writer`, which is [stdout] by default (use [RenderOpImplCrossterm::new] to paint
+/// into something else, eg: to count the bytes that are painted).
+pub struct RenderOpImplCrossterm<W: Write + Send> {
+    pub writer: W,
+}
+
+impl Default for RenderOpImplCrossterm<Stdout> {
+    fn default() -> Self { Self { writer: stdout() } }
+}
+
+impl<W: Write + Send> RenderOpImplCrossterm<W> {
+    pub fn new(writer: W) -> Self { Self { writer } }
+}
 
 mod render_op_impl_crossterm_impl_trait_paint_render_op {
     use super::*;
 
     #[async_trait]
-    impl PaintRenderOp for RenderOpImplCrossterm {
+    impl<W: Write + Send> PaintRenderOp for RenderOpImplCrossterm<W> {
         async fn paint(
             &mut self,
             skip_flush: &mut bool,
@@ -47,14 +58,13 @@ mod render_op_impl_crossterm_impl_trait_paint_render_op {
             match command_ref {
                 RenderOp::Noop => {}
                 RenderOp::EnterRawMode => {
-                    RenderOpImplCrossterm::raw_mode_enter(skip_flush, shared_global_data)
-                        .await;
+                    self.raw_mode_enter(skip_flush, shared_global_data).await;
                 }
                 RenderOp::ExitRawMode => {
-                    RenderOpImplCrossterm::raw_mode_exit(skip_flush);
+                    self.raw_mode_exit(skip_flush);
                 }
                 RenderOp::MoveCursorPositionAbs(abs_pos) => {
-                    RenderOpImplCrossterm::move_cursor_position_abs(
+                    self.move_cursor_position_abs(
                         abs_pos,
                         shared_global_data,
                         local_data,
@@ -62,7 +72,7 @@ mod render_op_impl_crossterm_impl_trait_paint_render_op {
                     .await;
                 }
                 RenderOp::MoveCursorPositionRelTo(box_origin_pos, content_rel_pos) => {
-                    RenderOpImplCrossterm::move_cursor_position_rel_to(
+                    self.move_cursor_position_rel_to(
                         box_origin_pos,
                         content_rel_pos,
                         shared_global_data,
@@ -70,29 +80,132 @@ mod render_op_impl_crossterm_impl_trait_paint_render_op {
                     )
                     .await;
                 }
+                RenderOp::MoveCursorToColumn(col) => {
+                    exec_render_op!(
+                        queue!(self.writer, MoveToColumn(**col)),
+                        format!("MoveCursorToColumn({})", **col)
+                    );
+                    self.save_rel_move(command_ref, shared_global_data, local_data)
+                        .await;
+                }
+                RenderOp::MoveCursorForward(count) => {
+                    exec_render_op!(
+                        queue!(self.writer, MoveRight(**count)),
+                        format!("MoveCursorForward({})", **count)
+                    );
+                    self.save_rel_move(command_ref, shared_global_data, local_data)
+                        .await;
+                }
+                RenderOp::MoveCursorBackward(count) => {
+                    exec_render_op!(
+                        queue!(self.writer, MoveLeft(**count)),
+                        format!("MoveCursorBackward({})", **count)
+                    );
+                    self.save_rel_move(command_ref, shared_global_data, local_data)
+                        .await;
+                }
+                RenderOp::MoveCursorUp(count) => {
+                    exec_render_op!(
+                        queue!(self.writer, MoveUp(**count)),
+                        format!("MoveCursorUp({})", **count)
+                    );
+                    self.save_rel_move(command_ref, shared_global_data, local_data)
+                        .await;
+                }
+                RenderOp::MoveCursorDown(count) => {
+                    exec_render_op!(
+                        queue!(self.writer, MoveDown(**count)),
+                        format!("MoveCursorDown({})", **count)
+                    );
+                    self.save_rel_move(command_ref, shared_global_data, local_data)
+                        .await;
+                }
+                RenderOp::MoveCursorToStartOfLine => {
+                    exec_render_op!(
+                        queue!(self.writer, Print('\r')),
+                        "MoveCursorToStartOfLine"
+                    );
+                    self.save_rel_move(command_ref, shared_global_data, local_data)
+                        .await;
+                }
+                RenderOp::MoveCursorToStartOfNextLine(count) => {
+                    exec_render_op!(
+                        queue!(self.writer, MoveToNextLine(**count)),
+                        format!("MoveCursorToStartOfNextLine({})", **count)
+                    );
+                    self.save_rel_move(command_ref, shared_global_data, local_data)
+                        .await;
+                }
+                RenderOp::SetScrollRegion(top, bottom) => {
+                    // crossterm has no command for DECSTBM, and the rows are 1 based.
+                    exec_render_op!(
+                        queue!(
+                            self.writer,
+                            Print(format!("\x1b[{};{}r", **top + 1, **bottom + 1))
+                        ),
+                        format!("SetScrollRegion({}, {})", **top, **bottom)
+                    );
+                    self.save_rel_move(command_ref, shared_global_data, local_data)
+                        .await;
+                }
+                RenderOp::ResetScrollRegion => {
+                    exec_render_op!(
+                        queue!(self.writer, Print("\x1b[r")),
+                        "ResetScrollRegion"
+                    );
+                    self.save_rel_move(command_ref, shared_global_data, local_data)
+                        .await;
+                }
+                RenderOp::InsertLines(count) => {
+                    // crossterm has no command for this.
+                    exec_render_op!(
+                        queue!(self.writer, Print(format!("\x1b[{}L", **count))),
+                        format!("InsertLines({})", **count)
+                    );
+                    self.save_rel_move(command_ref, shared_global_data, local_data)
+                        .await;
+                }
+                RenderOp::DeleteLines(count) => {
+                    // crossterm has no command for this.
+                    exec_render_op!(
+                        queue!(self.writer, Print(format!("\x1b[{}M", **count))),
+                        format!("DeleteLines({})", **count)
+                    );
+                    self.save_rel_move(command_ref, shared_global_data, local_data)
+                        .await;
+                }
                 RenderOp::ClearScreen => {
                     exec_render_op!(
-                        queue!(stdout(), Clear(ClearType::All)),
+                        queue!(self.writer, Clear(ClearType::All)),
                         "ClearScreen"
                     )
                 }
+                RenderOp::ClearToEndOfLine => {
+                    exec_render_op!(
+                        queue!(self.writer, Clear(ClearType::UntilNewLine)),
+                        "ClearToEndOfLine"
+                    )
+                }
                 RenderOp::SetFgColor(color) => {
-                    RenderOpImplCrossterm::set_fg_color(color);
+                    self.set_fg_color(color);
                 }
                 RenderOp::SetBgColor(color) => {
-                    RenderOpImplCrossterm::set_bg_color(color);
+                    self.set_bg_color(color);
                 }
                 RenderOp::ResetColor => {
-                    exec_render_op!(queue!(stdout(), ResetColor), "ResetColor")
+                    exec_render_op!(queue!(self.writer, ResetColor), "ResetColor")
                 }
                 RenderOp::ApplyColors(style) => {
-                    RenderOpImplCrossterm::apply_colors(style);
+                    self.apply_colors(style);
+                }
+                RenderOp::ApplyAttributes(style) => {
+                    perform_paint::apply_attributes(&mut self.writer, style);
                 }
                 RenderOp::CompositorNoClipTruncPaintTextWithAttributes(
                     text,
                     maybe_style,
                 ) => {
-                    RenderOpImplCrossterm::paint_text_with_attributes(
+                    self.paint_text_with_attributes(
                         text,
                         maybe_style,
                         shared_global_data,
@@ -105,6 +218,14 @@ mod render_op_impl_crossterm_impl_trait_paint_render_op {
                     // buffer first, then that is diff'd and then painted via calls to
                     // CompositorNoClipTruncPaintTextWithAttributes.
                 }
+                RenderOp::PushClipRect(..)
+                | RenderOp::PopClipRect
+                | RenderOp::PushTranslate(..)
+                | RenderOp::PopTranslate
+                | RenderOp::PaintBorder(..) => {
+                    // These are only honored by the compositor, when it renders to an offscreen
+                    // buffer.
+                }
             }
         }
     }
@@ -113,43 +234,56 @@ mod render_op_impl_crossterm_impl_trait_paint_render_op {
 pub mod render_op_impl_crossterm_impl_trait_flush {
     use super::*;
 
-    impl Flush for RenderOpImplCrossterm {
-        fn flush(&mut self) { flush(); }
-        fn clear_before_flush(&mut self) { clear_before_flush(); }
-    }
+    impl<W: Write + Send> Flush for RenderOpImplCrossterm<W> {
+        fn flush(&mut self) {
+            exec_render_op!(self.writer.flush(), "flush() -> writer");
+            exec_render_op!(stderr().flush(), "flush() -> stderr");
+        }
 
-    fn clear_before_flush() {
-        exec_render_op! {
-          queue!(stdout(),
-            ResetColor,
-            Clear(ClearType::All),
-          ),
-        "flush() -> after ResetColor, Clear"
+        fn clear_before_flush(&mut self) {
+            exec_render_op! {
+              queue!(self.writer,
+                ResetColor,
+                Clear(ClearType::All),
+              ),
+            "flush() -> after ResetColor, Clear"
+            }
         }
-    }
 
-    pub fn flush() {
-        exec_render_op!(stdout().flush(), "flush() -> stdout");
-        exec_render_op!(stderr().flush(), "flush() -> stderr");
+        fn begin_synchronized_update(&mut self) {
+            exec_render_op!(
+                queue!(self.writer, BeginSynchronizedUpdate),
+                "BeginSynchronizedUpdate"
+            )
+        }
+
+        fn end_synchronized_update(&mut self) {
+            exec_render_op!(
+                queue!(self.writer, EndSynchronizedUpdate),
+                "EndSynchronizedUpdate"
+            )
+        }
     }
 }
 
 mod render_op_impl_crossterm_impl {
     use super::*;
 
-    impl RenderOpImplCrossterm {
+    impl<W: Write + Send> RenderOpImplCrossterm<W> {
         pub async fn move_cursor_position_rel_to(
+            &mut self,
             box_origin_pos: &Position,
             content_rel_pos: &Position,
             shared_global_data: &SharedGlobalData,
             local_data: &mut RenderOpsLocalData,
         ) {
             let new_abs_pos = *box_origin_pos + *content_rel_pos;
-            Self::move_cursor_position_abs(&new_abs_pos, shared_global_data, local_data)
+            self.move_cursor_position_abs(&new_abs_pos, shared_global_data, local_data)
                 .await;
         }
 
         pub async fn move_cursor_position_abs(
+            &mut self,
             abs_pos: &Position,
             shared_global_data: &SharedGlobalData,
             local_data: &mut RenderOpsLocalData,
@@ -160,26 +294,43 @@ mod render_op_impl_crossterm_impl {
             } = sanitize_and_save_abs_position(*abs_pos, shared_global_data, local_data)
                 .await;
             exec_render_op!(
-                queue!(stdout(), MoveTo(*col, *row)),
+                queue!(self.writer, MoveTo(*col, *row)),
                 format!("MoveCursorPosition(col: {}, row: {})", *col, *row)
             )
         }
 
-        pub fn raw_mode_exit(skip_flush: &mut bool) {
+        /// Save the cursor position after a relative cursor move (eg:
+        /// [RenderOp::MoveCursorForward]) to [RenderOpsLocalData].
+        pub async fn save_rel_move(
+            &mut self,
+            render_op: &RenderOp,
+            shared_global_data: &SharedGlobalData,
+            local_data: &mut RenderOpsLocalData,
+        ) {
+            if let Some(new_pos) =
+                render_op.get_cursor_position_after_rel_move(local_data.cursor_position)
+            {
+                sanitize_and_save_abs_position(new_pos, shared_global_data, local_data)
+                    .await;
+            }
+        }
+
+        pub fn raw_mode_exit(&mut self, skip_flush: &mut bool) {
             exec_render_op! {
-              queue!(stdout(),
+              queue!(self.writer,
                 Show,
                 LeaveAlternateScreen,
                 DisableMouseCapture
               ),
               "ExitRawMode -> Show, LeaveAlternateScreen, DisableMouseCapture"
             };
-            render_op_impl_crossterm_impl_trait_flush::flush();
+            self.flush();
             exec_render_op! {terminal::disable_raw_mode(), "ExitRawMode -> disable_raw_mode()"}
             *skip_flush = true;
         }
 
         pub async fn raw_mode_enter(
+            &mut self,
             skip_flush: &mut bool,
             _shared_global_data: &SharedGlobalData,
         ) {
@@ -188,7 +339,7 @@ mod render_op_impl_crossterm_impl {
               "EnterRawMode -> enable_raw_mode()"
             };
             exec_render_op! {
-              queue!(stdout(),
+              queue!(self.writer,
                 EnableMouseCapture,
                 EnterAlternateScreen,
                 MoveTo(0,0),
@@ -197,28 +348,29 @@ mod render_op_impl_crossterm_impl {
               ),
             "EnterRawMode -> EnableMouseCapture, EnterAlternateScreen, MoveTo(0,0), Clear(ClearType::All), Hide"
             }
-            render_op_impl_crossterm_impl_trait_flush::flush();
+            self.flush();
             *skip_flush = true;
         }
 
-        pub fn set_fg_color(color: &TuiColor) {
+        pub fn set_fg_color(&mut self, color: &TuiColor) {
             let color = color_converter::to_crossterm_color(*color);
             exec_render_op!(
-                queue!(stdout(), SetForegroundColor(color)),
+                queue!(self.writer, SetForegroundColor(color)),
                 format!("SetFgColor({color:?})")
             )
         }
 
-        pub fn set_bg_color(color: &TuiColor) {
+        pub fn set_bg_color(&mut self, color: &TuiColor) {
             let color: crossterm::style::Color =
                 color_converter::to_crossterm_color(*color);
             exec_render_op!(
-                queue!(stdout(), SetBackgroundColor(color)),
+                queue!(self.writer, SetBackgroundColor(color)),
                 format!("SetBgColor({color:?})")
             )
         }
 
         pub async fn paint_text_with_attributes(
+            &mut self,
             text_arg: &String,
             maybe_style: &Option<Style>,
             shared_global_data: &SharedGlobalData,
@@ -241,19 +393,25 @@ mod render_op_impl_crossterm_impl {
             let needs_reset = Cow::Owned(false);
 
             // Paint plain_text.
-            paint_style_and_text(&mut paint_args, needs_reset, local_data).await;
+            paint_style_and_text(
+                &mut self.writer,
+                &mut paint_args,
+                needs_reset,
+                local_data,
+            )
+            .await;
         }
 
         /// Use [crossterm::style::Color] to set crossterm Colors.
         /// Docs: <https://docs.rs/crossterm/latest/crossterm/style/index.html#colors>
-        pub fn apply_colors(maybe_style: &Option<Style>) {
+        pub fn apply_colors(&mut self, maybe_style: &Option<Style>) {
             if let Some(style) = maybe_style {
                 // Handle background color.
                 if let Some(tui_color_bg) = style.color_bg {
                     let color_bg: crossterm::style::Color =
                         color_converter::to_crossterm_color(tui_color_bg);
                     exec_render_op!(
-                        queue!(stdout(), SetBackgroundColor(color_bg)),
+                        queue!(self.writer, SetBackgroundColor(color_bg)),
                         format!("ApplyColors -> SetBgColor({color_bg:?})")
                     )
                 }
@@ -263,7 +421,7 @@ mod render_op_impl_crossterm_impl {
                     let color_fg: crossterm::style::Color =
                         color_converter::to_crossterm_color(tui_color_fg);
                     exec_render_op!(
-                        queue!(stdout(), SetForegroundColor(color_fg)),
+                        queue!(self.writer, SetForegroundColor(color_fg)),
                         format!("ApplyColors -> SetFgColor({color_fg:?})")
                     )
                 }
@@ -309,9 +467,20 @@ mod perform_paint {
         it
     }
 
+    /// Set the crossterm [Attributes] of the [Style] w/out resetting them afterwards.
+    pub fn apply_attributes(writer: &mut impl Write, style: &Style) {
+        style_to_attribute(style).iter().for_each(|attr| {
+            exec_render_op!(
+                queue!(writer, SetAttribute(*attr)),
+                format!("ApplyAttributes -> SetAttribute({attr:?})")
+            );
+        });
+    }
+
     /// Use [Style] to set crossterm [Attributes] ([docs](
     /// https://docs.rs/crossterm/latest/crossterm/style/index.html#attributes)).
     pub async fn paint_style_and_text<'a>(
+        writer: &mut impl Write,
         paint_args: &mut PaintArgs<'a>,
         mut needs_reset: Cow<'_, bool>,
         local_data: &mut RenderOpsLocalData,
@@ -322,24 +491,25 @@ mod perform_paint {
             let attrib_vec = style_to_attribute(style);
             attrib_vec.iter().for_each(|attr| {
                 exec_render_op!(
-                    queue!(stdout(), SetAttribute(*attr)),
+                    queue!(writer, SetAttribute(*attr)),
                     format!("PaintWithAttributes -> SetAttribute({attr:?})")
                 );
                 needs_reset = Cow::Owned(true);
             });
         }
 
-        paint_text(paint_args, local_data).await;
+        paint_text(writer, paint_args, local_data).await;
 
         if *needs_reset {
             exec_render_op!(
-                queue!(stdout(), SetAttribute(Attribute::Reset)),
+                queue!(writer, SetAttribute(Attribute::Reset)),
                 format!("PaintWithAttributes -> SetAttribute(Reset))")
             );
         }
     }
 
     pub async fn paint_text<'a>(
+        writer: &mut impl Write,
         paint_args: &PaintArgs<'a>,
         local_data: &mut RenderOpsLocalData,
     ) {
@@ -358,7 +528,7 @@ mod perform_paint {
             let text = Cow::Borrowed(text);
             let log_msg: &str = log_msg;
             exec_render_op!(
-                queue!(stdout(), Print(&text)),
+                queue!(writer, Print(&text)),
                 format!("Print( {} {log_msg})", &text)
             );
         };
@@ -391,7 +561,8 @@ macro_rules! exec_render_op {
         // when there are issues accessing the log file for whatever reason.
         use $crate::tui::DEBUG_TUI_SHOW_TERMINAL_BACKEND;
 
-        let _fn_wrap_for_logging_err = || -> CommonResult<()> {
+        #[allow(unused_mut)]
+        let mut _fn_wrap_for_logging_err = || -> CommonResult<()> {
             throws!({
                 // Execute the command.
                 if let Err(err) = $arg_cmd {
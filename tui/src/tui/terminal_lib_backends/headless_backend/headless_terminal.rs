@@ -0,0 +1,67 @@
+/*
+ *   Copyright (c) 2022 R3BL LLC
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+use r3bl_rs_utils_core::*;
+
+use crate::*;
+
+/// The input to [TerminalWindow::main_event_loop_headless].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HeadlessTerminal {
+    /// The size of the (fake) terminal window. [InputEvent::Resize] can be used to change it.
+    pub size: Size,
+    /// These are processed in order. The main event loop exits when they run out, or when one of
+    /// the exit keys is pressed.
+    pub input_events: Vec<InputEvent>,
+    /// [None] (the default) paints every render request right away, so that every frame is
+    /// captured. Otherwise the requests are coalesced by the [FrameScheduler] (just like when
+    /// there is a terminal), and the paint that it defers is flushed before the frame after each
+    /// input event is captured.
+    pub maybe_max_fps: Option<u16>,
+}
+
+impl HeadlessTerminal {
+    pub fn new(size: Size, input_events: Vec<InputEvent>) -> Self {
+        Self {
+            size,
+            input_events,
+            maybe_max_fps: None,
+        }
+    }
+}
+
+/// The output of [TerminalWindow::main_event_loop_headless].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HeadlessOutput {
+    /// The frame that is on screen after the first render (before any input events).
+    pub initial_frame: OffscreenBuffer,
+    /// The frame that is on screen after each input event has been processed. The frame at index
+    /// `i` corresponds to [HeadlessTerminal::input_events] at index `i`. Input events that come
+    /// after an exit key are not processed, so this can be shorter than the input events.
+    pub frames_after_each_event: Vec<OffscreenBuffer>,
+    /// Every frame that was painted, in order. An input event can cause zero or more paints.
+    pub all_frames: Vec<OffscreenBuffer>,
+}
+
+impl HeadlessOutput {
+    /// The frame that is on screen when the main event loop exits.
+    pub fn get_last_frame(&self) -> &OffscreenBuffer {
+        self.frames_after_each_event
+            .last()
+            .unwrap_or(&self.initial_frame)
+    }
+}
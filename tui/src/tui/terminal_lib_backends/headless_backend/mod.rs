@@ -0,0 +1,37 @@
+/*
+ *   Copyright (c) 2022 R3BL LLC
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+//! The headless backend runs an [App] w/out a terminal, which is useful for integration tests. It
+//! takes a fixed [Size] & a scripted list of [InputEvent]s (in a [HeadlessTerminal]), and captures
+//! every [OffscreenBuffer] frame that would have been painted (in a [HeadlessOutput]). Tests can
+//! then assert on the rendered [PixelChar]s (text & [Style](r3bl_rs_utils_core::Style)) after each
+//! event.
+//!
+//! Unlike the crossterm & termion backends, it isn't selected using [TerminalLibBackend]. It is
+//! used by [TerminalWindow::main_event_loop_headless], which stores the captured frames in
+//! [GlobalData::maybe_headless_frames], so multiple headless apps can run at the same time (eg: in
+//! tests that run in parallel). It is only available w/ the `headless` feature (which is usually
+//! enabled in `dev-dependencies`).
+
+// Attach.
+pub mod headless_terminal;
+
+// Re-export.
+pub use headless_terminal::*;
+
+// Tests.
+mod test_headless;
@@ -0,0 +1,296 @@
+/*
+ *   Copyright (c) 2022 R3BL LLC
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use async_trait::async_trait;
+    use r3bl_redux::*;
+    use r3bl_rs_utils_core::*;
+    use r3bl_rs_utils_macro::style;
+    use tokio::sync::RwLock;
+
+    use crate::*;
+
+    const COUNTER_ID: u8 = 1;
+
+    #[derive(Clone, PartialEq, Default, Debug)]
+    struct State {
+        count: i32,
+    }
+
+    #[derive(Clone, Default, Debug)]
+    enum Action {
+        Increment,
+        #[default]
+        Noop,
+    }
+
+    #[derive(Default)]
+    struct Reducer;
+
+    #[async_trait]
+    impl AsyncReducer<State, Action> for Reducer {
+        async fn run(&self, action: &Action, state: &mut State) {
+            if let Action::Increment = action {
+                state.count += 1;
+            }
+        }
+    }
+
+    /// Paints the count, and increments it (w/ a spawned action) when `+` is pressed.
+    struct CounterComponent;
+
+    #[async_trait]
+    impl Component<State, Action> for CounterComponent {
+        fn reset(&mut self) {}
+
+        fn get_id(&self) -> FlexBoxId { FlexBoxId::from(COUNTER_ID) }
+
+        async fn handle_event(
+            &mut self,
+            args: ComponentScopeArgs<'_, State, Action>,
+            input_event: &InputEvent,
+        ) -> CommonResult<EventPropagation> {
+            throws_with_return!({
+                let ComponentScopeArgs { shared_store, .. } = args;
+                let mut event_consumed = false;
+                if input_event.matches_keypress(keypress! { @char '+' }) {
+                    spawn_and_consume_event!(
+                        event_consumed,
+                        shared_store,
+                        Action::Increment
+                    );
+                }
+                if event_consumed {
+                    EventPropagation::Consumed
+                } else {
+                    EventPropagation::Propagate
+                }
+            });
+        }
+
+        async fn render(
+            &mut self,
+            args: ComponentScopeArgs<'_, State, Action>,
+            current_box: &FlexBox,
+            _surface_bounds: SurfaceBounds,
+        ) -> CommonResult<RenderPipeline> {
+            throws_with_return!({
+                let ComponentScopeArgs { state, .. } = args;
+                render_pipeline!(@new ZOrder::Normal =>
+                    RenderOp::MoveCursorPositionAbs(current_box.style_adjusted_origin_pos),
+                    RenderOp::PaintTextWithAttributes(
+                        format!("count: {}", state.count),
+                        Some(style! { attrib: [bold] }),
+                    )
+                )
+            });
+        }
+    }
+
+    #[derive(Default)]
+    struct CounterApp {
+        component_registry: ComponentRegistry<State, Action>,
+    }
+
+    #[async_trait]
+    impl App<State, Action> for CounterApp {
+        fn init(&mut self) {
+            let id = FlexBoxId::from(COUNTER_ID);
+            self.component_registry
+                .put(id, Arc::new(RwLock::new(CounterComponent)));
+            self.component_registry.has_focus.set_id(id);
+        }
+
+        fn get_component_registry(&mut self) -> &mut ComponentRegistry<State, Action> {
+            &mut self.component_registry
+        }
+
+        async fn app_handle_event(
+            &mut self,
+            args: GlobalScopeArgs<'_, State, Action>,
+            input_event: &InputEvent,
+        ) -> CommonResult<EventPropagation> {
+            let GlobalScopeArgs {
+                state,
+                shared_store,
+                shared_global_data,
+                window_size,
+            } = args;
+            ComponentRegistry::route_event_to_focused_component(
+                &mut self.component_registry,
+                input_event,
+                state,
+                shared_store,
+                shared_global_data,
+                window_size,
+            )
+            .await
+        }
+
+        async fn app_render(
+            &mut self,
+            args: GlobalScopeArgs<'_, State, Action>,
+        ) -> CommonResult<RenderPipeline> {
+            throws_with_return!({
+                let GlobalScopeArgs {
+                    state,
+                    shared_store,
+                    shared_global_data,
+                    window_size,
+                } = args;
+                let mut it = surface!(stylesheet: stylesheet! {});
+                let surface = &mut it;
+                surface.surface_start(SurfaceProps {
+                    pos: position!(col_index: 0, row_index: 0),
+                    size: *window_size,
+                })?;
+                box_start! (
+                    in:                     surface,
+                    id:                     FlexBoxId::from(COUNTER_ID),
+                    dir:                    LayoutDirection::Vertical,
+                    requested_size_percent: requested_size_percent!(width: 100, height: 100),
+                    styles:                 []
+                );
+                render_component_in_current_box!(
+                    in:                 surface,
+                    component_id:       FlexBoxId::from(COUNTER_ID),
+                    from:               self.component_registry,
+                    state:              state,
+                    shared_store:       shared_store,
+                    shared_global_data: shared_global_data,
+                    window_size:        window_size
+                );
+                box_end!(in: surface);
+                surface.surface_end()?;
+                it.render_pipeline
+            });
+        }
+    }
+
+    async fn run_counter_app(input_events: Vec<InputEvent>) -> HeadlessOutput {
+        run_counter_app_w_max_fps(input_events, None).await
+    }
+
+    async fn run_counter_app_w_max_fps(
+        input_events: Vec<InputEvent>,
+        maybe_max_fps: Option<u16>,
+    ) -> HeadlessOutput {
+        let mut store = Store::<State, Action>::default();
+        store.add_reducer(Reducer::new()).await;
+        TerminalWindow::main_event_loop_headless(
+            CounterApp::new_shared(),
+            store,
+            vec![InputEvent::Keyboard(keypress! { @char 'x' })],
+            HeadlessTerminal {
+                maybe_max_fps,
+                ..HeadlessTerminal::new(size!(col_count: 70, row_count: 12), input_events)
+            },
+        )
+        .await
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_headless_captures_frame_after_each_event() {
+        let output = run_counter_app(vec![
+            InputEvent::Keyboard(keypress! { @char '+' }),
+            InputEvent::Keyboard(keypress! { @char '+' }),
+            // Not handled by the app, so nothing changes.
+            InputEvent::Keyboard(keypress! { @char 'a' }),
+        ])
+        .await;
+
+        assert_eq2!(
+            output.initial_frame.get_row_text(0).unwrap().trim_end(),
+            "count: 0"
+        );
+        assert_eq2!(output.frames_after_each_event.len(), 3);
+        let row_texts = output
+            .frames_after_each_event
+            .iter()
+            .map(|it| it.get_row_text(0).unwrap().trim_end().to_string())
+            .collect::<Vec<_>>();
+        assert_eq2!(row_texts, vec!["count: 1", "count: 2", "count: 2"]);
+
+        // Check the style of a cell.
+        match output
+            .get_last_frame()
+            .get_pixel_char(position!(col_index: 7, row_index: 0))
+        {
+            Some(PixelChar::PlainText {
+                content,
+                maybe_style: Some(style),
+            }) => {
+                assert_eq2!(content.string, "2");
+                assert_eq2!(style.bold, true);
+            }
+            it => panic!("unexpected pixel char: {it:?}"),
+        }
+
+        // The initial render, and one for each action that was dispatched.
+        assert_eq2!(output.all_frames.len(), 3);
+
+        // Lock in the entire frame.
+        assert_snapshot!("headless_counter_app_count_2", output.get_last_frame());
+    }
+
+    #[tokio::test]
+    async fn test_headless_flushes_deferred_paint_before_capturing_frame() {
+        // Every render request after the first one is deferred, since they all arrive within
+        // the 1s frame interval.
+        let output = run_counter_app_w_max_fps(
+            vec![
+                InputEvent::Keyboard(keypress! { @char '+' }),
+                InputEvent::Keyboard(keypress! { @char '+' }),
+            ],
+            Some(1),
+        )
+        .await;
+
+        let row_texts = output
+            .frames_after_each_event
+            .iter()
+            .map(|it| it.get_row_text(0).unwrap().trim_end().to_string())
+            .collect::<Vec<_>>();
+        assert_eq2!(row_texts, vec!["count: 1", "count: 2"]);
+        assert_eq2!(output.all_frames.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_headless_exit_keys_and_resize() {
+        let output = run_counter_app(vec![
+            InputEvent::Resize(size!(col_count: 80, row_count: 15)),
+            InputEvent::Keyboard(keypress! { @char 'x' }),
+            // Never processed, since 'x' exits the main event loop.
+            InputEvent::Keyboard(keypress! { @char '+' }),
+        ])
+        .await;
+
+        assert_eq2!(output.frames_after_each_event.len(), 2);
+        assert_eq2!(
+            output.get_last_frame().window_size,
+            size!(col_count: 80, row_count: 15)
+        );
+        assert_eq2!(
+            output.get_last_frame().get_row_text(0).unwrap().trim_end(),
+            "count: 0"
+        );
+    }
+}
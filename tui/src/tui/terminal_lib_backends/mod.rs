@@ -47,48 +47,117 @@
 //! - Sodium:
 //!   - repo: <https://github.com/redox-os/sodium>
 
+use crate::terminal_lib_backend_global_static::{clear_terminal_lib_backend_override,
+                                                get_terminal_lib_backend_override,
+                                                set_terminal_lib_backend_override};
+
+/// The terminal library that is used to paint [RenderOps], enter & exit raw mode, get the size of
+/// the terminal, and read [InputEvent]s. Use [TerminalLibBackend::get] to find out which one is
+/// in use.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum TerminalLibBackend {
     Crossterm,
+    /// Only available on Unix, when the `termion` feature is enabled.
+    #[cfg(all(unix, feature = "termion"))]
     Termion,
 }
 
-pub const TERMINAL_LIB_BACKEND: TerminalLibBackend = TerminalLibBackend::Crossterm;
+impl TerminalLibBackend {
+    pub fn set_override(backend: TerminalLibBackend) {
+        set_terminal_lib_backend_override(backend);
+    }
+
+    pub fn clear_override() { clear_terminal_lib_backend_override(); }
+
+    /// Returns the override if it is set (using [set_override](TerminalLibBackend::set_override)).
+    /// Otherwise [DEFAULT_TERMINAL_LIB_BACKEND] is used.
+    ///
+    /// The override should be set before [TerminalWindow::main_event_loop] is called, and not
+    /// changed while it is running.
+    pub fn get() -> TerminalLibBackend {
+        if let Some(backend) = get_terminal_lib_backend_override() {
+            return backend;
+        }
+        DEFAULT_TERMINAL_LIB_BACKEND
+    }
+
+    /// All the backends that are available in this build.
+    pub fn get_all() -> Vec<TerminalLibBackend> {
+        vec![
+            TerminalLibBackend::Crossterm,
+            #[cfg(all(unix, feature = "termion"))]
+            TerminalLibBackend::Termion,
+        ]
+    }
+}
+
+/// [TerminalLibBackend::Termion] on Unix if the `termion` feature is enabled, and
+/// [TerminalLibBackend::Crossterm] otherwise.
+#[cfg(not(all(unix, feature = "termion")))]
+pub const DEFAULT_TERMINAL_LIB_BACKEND: TerminalLibBackend =
+    TerminalLibBackend::Crossterm;
+/// [TerminalLibBackend::Termion] on Unix if the `termion` feature is enabled, and
+/// [TerminalLibBackend::Crossterm] otherwise.
+#[cfg(all(unix, feature = "termion"))]
+pub const DEFAULT_TERMINAL_LIB_BACKEND: TerminalLibBackend = TerminalLibBackend::Termion;
+
+#[deprecated(note = "Use `TerminalLibBackend::get()` to get the backend that is in use, or \
+                     `DEFAULT_TERMINAL_LIB_BACKEND` to get the default one")]
+pub const TERMINAL_LIB_BACKEND: TerminalLibBackend = DEFAULT_TERMINAL_LIB_BACKEND;
 
 // Attach source files.
 pub mod async_event_stream_ext;
+pub mod box_drawing;
+pub mod clip_rect;
 pub mod color_converter;
 pub mod crossterm_backend;
 pub mod enhanced_keys;
+pub mod headless_backend;
 pub mod input_event;
 pub mod keypress;
 pub mod modifier_keys_mask;
 pub mod mouse_input;
 pub mod offscreen_buffer;
+pub mod offscreen_buffer_export;
+pub mod offscreen_buffer_snapshot;
 pub mod paint;
 pub mod raw_mode;
+pub mod render_diff;
 pub mod render_op;
 pub mod render_pipeline;
 pub mod render_pipeline_to_offscreen_buffer;
+pub mod synchronized_output;
 pub mod terminal_lib_operations;
+#[cfg(all(unix, feature = "termion"))]
 pub mod termion_backend;
+pub mod vertical_shift;
 
 // Re-export.
 pub use async_event_stream_ext::*;
+pub use box_drawing::*;
+pub use clip_rect::*;
 pub use color_converter::*;
 pub use crossterm_backend::*;
 pub use enhanced_keys::*;
+pub use headless_backend::*;
 pub use input_event::*;
 pub use keypress::*;
 pub use modifier_keys_mask::*;
 pub use mouse_input::*;
 pub use offscreen_buffer::*;
+pub use offscreen_buffer_export::*;
+pub use offscreen_buffer_snapshot::*;
 pub use paint::*;
 pub use raw_mode::*;
+pub use render_diff::*;
 pub use render_op::*;
 pub use render_pipeline::*;
 pub use render_pipeline_to_offscreen_buffer::*;
+pub use synchronized_output::*;
 pub use terminal_lib_operations::*;
+#[cfg(all(unix, feature = "termion"))]
 pub use termion_backend::*;
+pub use vertical_shift::*;
 
 // Tests.
 mod test_input_event;
@@ -124,6 +124,28 @@ mod offscreen_buffer_impl {
             }
         }
 
+        /// Returns [None] if `pos` is outside the buffer.
+        pub fn get_pixel_char(&self, pos: Position) -> Option<&PixelChar> {
+            self.buffer
+                .get(ch!(@to_usize pos.row_index))?
+                .get(ch!(@to_usize pos.col_index))
+        }
+
+        /// Returns the text that is painted in the given row, w/out any styles. A [PixelChar::Spacer]
+        /// is a space, and a [PixelChar::Void] (which follows a wide grapheme cluster, eg: "😃") is
+        /// skipped.
+        pub fn get_row_text(&self, row_index: usize) -> Option<String> {
+            let mut it = String::new();
+            for pixel_char in self.buffer.get(row_index)?.iter() {
+                match pixel_char {
+                    PixelChar::Void => {}
+                    PixelChar::Spacer => it.push(' '),
+                    PixelChar::PlainText { content, .. } => it.push_str(&content.string),
+                }
+            }
+            Some(it)
+        }
+
         // Make sure each line is full of empty chars.
         pub fn clear(&mut self) {
             self.buffer = PixelCharLines::new_with_capacity_initialized(self.window_size);
@@ -416,7 +438,11 @@ mod pixel_char_impl {
 pub trait OffscreenBufferPaint {
     async fn render(&mut self, offscreen_buffer: &OffscreenBuffer) -> RenderOps;
 
-    async fn render_diff(&mut self, diff_chunks: &PixelCharDiffChunks) -> RenderOps;
+    async fn render_diff(
+        &mut self,
+        diff_chunks: &PixelCharDiffChunks,
+        window_size: Size,
+    ) -> RenderOps;
 
     async fn paint(
         &mut self,
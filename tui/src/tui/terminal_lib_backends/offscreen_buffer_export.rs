@@ -0,0 +1,430 @@
+/*
+ *   Copyright (c) 2023 R3BL LLC
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+//! Export an [OffscreenBuffer] to a standalone SVG image, or to an HTML `<pre>` element, eg: to
+//! generate screenshots of an app in CI, using the frames that are captured by
+//! [TerminalWindow::main_event_loop_headless](crate::TerminalWindow::main_event_loop_headless).
+//!
+//! - The [Style] of each [PixelChar] is converted to inline colors & font attributes.
+//!   [TuiColor::Reset] & missing colors use the defaults in [OffscreenBufferExportOptions].
+//! - Wide grapheme clusters (eg: "😃") span the [PixelChar::Void] cells that follow them.
+
+use std::fmt::Write;
+
+use r3bl_rs_utils_core::*;
+
+use crate::*;
+
+/// The font & colors that are used by [OffscreenBuffer::to_svg] & [OffscreenBuffer::to_html].
+#[derive(Debug, Clone, PartialEq)]
+pub struct OffscreenBufferExportOptions {
+    pub font_family: String,
+    /// In pixels.
+    pub font_size: f64,
+    /// In pixels. The width of each cell in the monospace grid.
+    pub cell_width: f64,
+    /// In pixels. The height of each cell in the monospace grid (ie, the line height).
+    pub cell_height: f64,
+    pub default_fg_color: RgbValue,
+    pub default_bg_color: RgbValue,
+}
+
+impl Default for OffscreenBufferExportOptions {
+    fn default() -> Self {
+        Self {
+            font_family: "Menlo, Monaco, 'DejaVu Sans Mono', 'Courier New', monospace"
+                .to_string(),
+            font_size: 14.0,
+            cell_width: 8.4,
+            cell_height: 17.0,
+            default_fg_color: RgbValue::from_hex("#c0c0c0"),
+            default_bg_color: RgbValue::from_hex("#1e1e2e"),
+        }
+    }
+}
+
+impl OffscreenBuffer {
+    /// Returns a standalone SVG document w/ a monospace grid of
+    /// [window_size](OffscreenBuffer::window_size) cells.
+    pub fn to_svg(&self, options: &OffscreenBufferExportOptions) -> String {
+        let col_count = ch!(@to_usize self.window_size.col_count);
+        let row_count = ch!(@to_usize self.window_size.row_count);
+        let width = fmt_px(col_count as f64 * options.cell_width);
+        let height = fmt_px(row_count as f64 * options.cell_height);
+
+        let mut it = String::new();
+        let _ = writeln!(
+            it,
+            concat!(
+                r#"<svg xmlns="http://www.w3.org/2000/svg" "#,
+                r#"width="{0}" height="{1}" viewBox="0 0 {0} {1}">"#
+            ),
+            width, height
+        );
+        let _ = writeln!(
+            it,
+            r#"<style>text {{ font-family: {}; font-size: {}px; white-space: pre; }}</style>"#,
+            escape_xml(&options.font_family),
+            fmt_px(options.font_size)
+        );
+        let _ = writeln!(
+            it,
+            r#"<rect width="100%" height="100%" fill="{}"/>"#,
+            to_hex(options.default_bg_color)
+        );
+
+        for (row_index, line) in self.buffer.iter().enumerate() {
+            let y = row_index as f64 * options.cell_height;
+            let cells = get_cells(line, options);
+
+            // Backgrounds (consecutive cells w/ the same color are merged).
+            let mut col_index = 0;
+            while col_index < cells.len() {
+                let bg_color = cells[col_index].look.bg_color;
+                let start = col_index;
+                while col_index < cells.len()
+                    && cells[col_index].look.bg_color == bg_color
+                {
+                    col_index += cells[col_index].width;
+                }
+                if bg_color != options.default_bg_color {
+                    let _ = writeln!(
+                        it,
+                        r#"<rect x="{}" y="{}" width="{}" height="{}" fill="{}"/>"#,
+                        fmt_px(cells[start].col_index as f64 * options.cell_width),
+                        fmt_px(y),
+                        fmt_px((col_index - start) as f64 * options.cell_width),
+                        fmt_px(options.cell_height),
+                        to_hex(bg_color)
+                    );
+                }
+            }
+
+            // Text (each grapheme cluster is placed in its own cell, so the grid is exact).
+            let baseline_y = y + (options.cell_height + options.font_size) / 2.0 - 2.0;
+            for cell in cells.iter() {
+                if cell.text.trim().is_empty() {
+                    continue;
+                }
+                let _ = write!(
+                    it,
+                    r#"<text x="{}" y="{}" fill="{}""#,
+                    fmt_px(cell.col_index as f64 * options.cell_width),
+                    fmt_px(baseline_y),
+                    to_hex(cell.look.fg_color)
+                );
+                if cell.look.bold {
+                    it.push_str(r#" font-weight="bold""#);
+                }
+                if cell.look.italic {
+                    it.push_str(r#" font-style="italic""#);
+                }
+                if let Some(text_decoration) = cell.look.get_text_decoration() {
+                    let _ = write!(it, r#" text-decoration="{text_decoration}""#);
+                }
+                let _ = writeln!(it, ">{}</text>", escape_xml(cell.text));
+            }
+        }
+
+        it.push_str("</svg>\n");
+        it
+    }
+
+    /// Returns an HTML `<pre>` element w/ inline styles. Each row is a line of text, and
+    /// consecutive cells w/ the same style are merged into one `<span>`.
+    pub fn to_html(&self, options: &OffscreenBufferExportOptions) -> String {
+        let mut it = format!(
+            concat!(
+                r#"<pre style="font-family: {}; font-size: {}px; line-height: {}px; "#,
+                r#"color: {}; background-color: {}; margin: 0;">"#
+            ),
+            escape_xml(&options.font_family),
+            fmt_px(options.font_size),
+            fmt_px(options.cell_height),
+            to_hex(options.default_fg_color),
+            to_hex(options.default_bg_color)
+        );
+
+        for (row_index, line) in self.buffer.iter().enumerate() {
+            if row_index > 0 {
+                it.push('\n');
+            }
+            let cells = get_cells(line, options);
+            for run in cells.chunk_by(|lhs, rhs| lhs.look == rhs.look) {
+                let text = run.iter().map(|cell| cell.text).collect::<String>();
+                let css = run[0].look.to_css(options);
+                if css.is_empty() {
+                    it.push_str(&escape_xml(&text));
+                } else {
+                    let _ =
+                        write!(it, r#"<span style="{css}">{}</span>"#, escape_xml(&text));
+                }
+            }
+        }
+
+        it.push_str("</pre>\n");
+        it
+    }
+}
+
+/// A [PixelChar] w/ its [Style] resolved to colors (using [OffscreenBufferExportOptions]).
+#[derive(Debug)]
+struct Cell<'a> {
+    col_index: usize,
+    /// 1 + the number of [PixelChar::Void] cells that follow this one.
+    width: usize,
+    text: &'a str,
+    look: CellLook,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct CellLook {
+    fg_color: RgbValue,
+    bg_color: RgbValue,
+    bold: bool,
+    italic: bool,
+    underline: bool,
+    strikethrough: bool,
+}
+
+impl CellLook {
+    fn new(maybe_style: Option<&Style>, options: &OffscreenBufferExportOptions) -> Self {
+        let mut it = CellLook {
+            fg_color: options.default_fg_color,
+            bg_color: options.default_bg_color,
+            bold: false,
+            italic: false,
+            underline: false,
+            strikethrough: false,
+        };
+        let Some(style) = maybe_style else {
+            return it;
+        };
+
+        if let Some(color) = style.color_fg.and_then(to_rgb) {
+            it.fg_color = color;
+        }
+        if let Some(color) = style.color_bg.and_then(to_rgb) {
+            it.bg_color = color;
+        }
+        if style.reverse {
+            std::mem::swap(&mut it.fg_color, &mut it.bg_color);
+        }
+        if style.dim {
+            it.fg_color = blend(it.fg_color, it.bg_color);
+        }
+        if style.hidden {
+            it.fg_color = it.bg_color;
+        }
+        it.bold = style.bold;
+        it.italic = style.italic;
+        it.underline = style.underline;
+        it.strikethrough = style.strikethrough;
+        it
+    }
+
+    fn get_text_decoration(&self) -> Option<&'static str> {
+        match (self.underline, self.strikethrough) {
+            (true, true) => Some("underline line-through"),
+            (true, false) => Some("underline"),
+            (false, true) => Some("line-through"),
+            (false, false) => None,
+        }
+    }
+
+    /// Only the properties that are different from the defaults are included.
+    fn to_css(self, options: &OffscreenBufferExportOptions) -> String {
+        let mut it: Vec<String> = vec![];
+        if self.fg_color != options.default_fg_color {
+            it.push(format!("color: {}", to_hex(self.fg_color)));
+        }
+        if self.bg_color != options.default_bg_color {
+            it.push(format!("background-color: {}", to_hex(self.bg_color)));
+        }
+        if self.bold {
+            it.push("font-weight: bold".to_string());
+        }
+        if self.italic {
+            it.push("font-style: italic".to_string());
+        }
+        if let Some(text_decoration) = self.get_text_decoration() {
+            it.push(format!("text-decoration: {text_decoration}"));
+        }
+        it.join("; ")
+    }
+}
+
+fn get_cells<'a>(
+    line: &'a PixelCharLine,
+    options: &OffscreenBufferExportOptions,
+) -> Vec<Cell<'a>> {
+    let mut it: Vec<Cell<'a>> = vec![];
+    for (col_index, pixel_char) in line.iter().enumerate() {
+        match pixel_char {
+            PixelChar::Void => match it.last_mut() {
+                Some(cell) => cell.width += 1,
+                // A Void w/out a grapheme cluster before it is treated like a Spacer.
+                None => it.push(Cell {
+                    col_index,
+                    width: 1,
+                    text: " ",
+                    look: CellLook::new(None, options),
+                }),
+            },
+            PixelChar::Spacer => it.push(Cell {
+                col_index,
+                width: 1,
+                text: " ",
+                look: CellLook::new(None, options),
+            }),
+            PixelChar::PlainText {
+                content,
+                maybe_style,
+            } => it.push(Cell {
+                col_index,
+                width: 1,
+                text: &content.string,
+                look: CellLook::new(maybe_style.as_ref(), options),
+            }),
+        }
+    }
+    it
+}
+
+/// [TuiColor::Reset] returns [None], so the default color is used.
+fn to_rgb(color: TuiColor) -> Option<RgbValue> {
+    match color {
+        TuiColor::Reset => None,
+        TuiColor::Ansi(ansi_value) => Some(RgbValue::from(ansi_value)),
+        _ => RgbValue::try_from_tui_color(color).ok(),
+    }
+}
+
+fn blend(lhs: RgbValue, rhs: RgbValue) -> RgbValue {
+    let mix = |lhs: u8, rhs: u8| ((lhs as u16 + rhs as u16) / 2) as u8;
+    RgbValue::from_u8(
+        mix(lhs.red, rhs.red),
+        mix(lhs.green, rhs.green),
+        mix(lhs.blue, rhs.blue),
+    )
+}
+
+fn to_hex(color: RgbValue) -> String {
+    format!("#{:02x}{:02x}{:02x}", color.red, color.green, color.blue)
+}
+
+/// Round to 2 decimal places, w/out trailing zeros, eg: `8.4`, `17`.
+fn fmt_px(value: f64) -> String {
+    let it = format!("{value:.2}");
+    it.trim_end_matches('0').trim_end_matches('.').to_string()
+}
+
+fn escape_xml(input: &str) -> String {
+    let mut it = String::with_capacity(input.len());
+    for ch in input.chars() {
+        match ch {
+            '&' => it.push_str("&amp;"),
+            '<' => it.push_str("&lt;"),
+            '>' => it.push_str("&gt;"),
+            '"' => it.push_str("&quot;"),
+            '\'' => it.push_str("&#39;"),
+            _ => it.push(ch),
+        }
+    }
+    it
+}
+
+#[cfg(test)]
+mod tests {
+    use r3bl_rs_utils_macro::style;
+
+    use super::*;
+
+    /// `|<b>😃|` (w/ a red background), followed by spacers.
+    fn make_offscreen_buffer() -> OffscreenBuffer {
+        let mut it = OffscreenBuffer::new_with_capacity_initialized(
+            size!(col_count: 6, row_count: 2),
+        );
+        let bold = Some(style! { attrib: [bold, underline] color_fg: color!(0, 255, 0) });
+        let red_bg = Some(style! { color_bg: color!(@red) });
+        for (col_index, (text, maybe_style)) in
+            [("<", bold), ("b", bold), (">", None), ("😃", red_bg)]
+                .into_iter()
+                .enumerate()
+        {
+            it.buffer[0][col_index] = PixelChar::PlainText {
+                content: GraphemeClusterSegment::from(text),
+                maybe_style,
+            };
+        }
+        it.buffer[0][4] = PixelChar::Void;
+        it
+    }
+
+    #[test]
+    fn test_to_svg() {
+        let svg =
+            make_offscreen_buffer().to_svg(&OffscreenBufferExportOptions::default());
+        assert!(svg.starts_with(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="50.4" height="34" viewBox="0 0 50.4 34">"#
+        ));
+        assert!(svg.ends_with("</svg>\n"));
+        // The wide emoji's background spans 2 cells.
+        assert!(svg.contains(
+            r##"<rect x="25.2" y="0" width="16.8" height="17" fill="#ff0000"/>"##
+        ));
+        assert!(svg.contains(
+            r##"<text x="0" y="13.5" fill="#00ff00" font-weight="bold" text-decoration="underline">&lt;</text>"##
+        ));
+        assert!(svg.contains(r##"<text x="16.8" y="13.5" fill="#c0c0c0">&gt;</text>"##));
+        assert!(svg.contains(r##"<text x="25.2" y="13.5" fill="#c0c0c0">😃</text>"##));
+        // Spacers aren't painted.
+        assert_eq2!(svg.matches("<text ").count(), 4);
+    }
+
+    #[test]
+    fn test_to_html() {
+        let html =
+            make_offscreen_buffer().to_html(&OffscreenBufferExportOptions::default());
+        let expected_rows = concat!(
+            r#"<span style="color: #00ff00; font-weight: bold; text-decoration: underline">&lt;b</span>"#,
+            r#"&gt;<span style="background-color: #ff0000">😃</span> "#,
+            "\n      </pre>\n"
+        );
+        assert!(html.starts_with("<pre style=\"font-family: "));
+        assert!(html.ends_with(expected_rows), "{html}");
+    }
+
+    #[test]
+    fn test_resolve_style() {
+        let options = OffscreenBufferExportOptions::default();
+        let style = style! {
+            attrib: [reverse, dim]
+            color_fg: color!(@white)
+            color_bg: TuiColor::Ansi(AnsiValue::new(16))
+        };
+        let look = CellLook::new(Some(&style), &options);
+        assert_eq2!(look.bg_color, RgbValue::from_u8(255, 255, 255));
+        assert_eq2!(look.fg_color, RgbValue::from_u8(127, 127, 127));
+
+        let reset = style! { color_fg: TuiColor::Reset };
+        assert_eq2!(
+            CellLook::new(Some(&reset), &options).fg_color,
+            options.default_fg_color
+        );
+    }
+}
@@ -0,0 +1,606 @@
+/*
+ *   Copyright (c) 2023 R3BL LLC
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+//! A stable, human readable text serialization of an [OffscreenBuffer], which is used for snapshot
+//! testing. Here's an example of a 10x2 buffer:
+//!
+//! ```text
+//! size: col_count=10 row_count=2
+//! text:
+//! |hi 😃 ok  |
+//! |          |
+//! styles:
+//! |aa.b~.aa  |
+//! |          |
+//! legend:
+//! a: bold fg=#00ff00
+//! b: fg=ansi(208) bg=DarkBlue
+//! ```
+//!
+//! - The text layer has the content of each [PixelChar], w/out styles.
+//! - The style layer has one char for each cell: `' '` is a [PixelChar::Spacer], `'~'` is a
+//!   [PixelChar::Void] (which follows a wide grapheme cluster, eg: "😃"), `'.'` is text w/out a
+//!   style, and any other char is a key into the legend, which holds the [Style] of the text.
+//!
+//! Only the `window_size` & the cells of the buffer are serialized. Use
+//! [OffscreenBuffer::to_snapshot_string] & [OffscreenBuffer::try_from_snapshot_string] to convert,
+//! and [assert_snapshot!] to compare an [OffscreenBuffer] against a `.snap` file.
+
+use std::{collections::HashMap, fmt::Write, path::Path};
+
+use r3bl_rs_utils_core::*;
+
+use crate::*;
+
+/// When this environment variable is set (to anything), [assert_offscreen_buffer_snapshot] writes
+/// the `.snap` file instead of comparing against it.
+pub const UPDATE_SNAPSHOTS_ENV_VAR: &str = "R3BL_UPDATE_SNAPSHOTS";
+
+/// The max number of cells that are listed when a snapshot doesn't match.
+const MAX_CELL_DIFFS_TO_SHOW: usize = 20;
+
+const SPACER_CODE: char = ' ';
+const VOID_CODE: char = '~';
+const NO_STYLE_CODE: char = '.';
+const LEGEND_KEYS: &str =
+    "abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789";
+
+const ALL_ANSI_BASIC_COLORS: [ANSIBasicColor; 16] = [
+    ANSIBasicColor::Black,
+    ANSIBasicColor::White,
+    ANSIBasicColor::Grey,
+    ANSIBasicColor::DarkGrey,
+    ANSIBasicColor::Red,
+    ANSIBasicColor::DarkRed,
+    ANSIBasicColor::Green,
+    ANSIBasicColor::DarkGreen,
+    ANSIBasicColor::Yellow,
+    ANSIBasicColor::DarkYellow,
+    ANSIBasicColor::Blue,
+    ANSIBasicColor::DarkBlue,
+    ANSIBasicColor::Magenta,
+    ANSIBasicColor::DarkMagenta,
+    ANSIBasicColor::Cyan,
+    ANSIBasicColor::DarkCyan,
+];
+
+/// Compare `offscreen_buffer` against the snapshot in `snapshots/<name>.snap` (in the directory of
+/// the crate that is being tested). The snapshot is created if it doesn't exist yet (or if the
+/// [UPDATE_SNAPSHOTS_ENV_VAR] environment variable is set). If it doesn't match, then this panics
+/// w/ a list of the cells that are different.
+///
+/// ```ignore
+/// assert_snapshot!("editor_component_empty", offscreen_buffer);
+/// ```
+#[macro_export]
+macro_rules! assert_snapshot {
+    ($arg_name: expr, $arg_offscreen_buffer: expr) => {
+        $crate::assert_offscreen_buffer_snapshot(
+            std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+                .join("snapshots")
+                .join(format!("{}.snap", $arg_name)),
+            &$arg_offscreen_buffer,
+        )
+    };
+}
+
+/// See [assert_snapshot!].
+pub fn assert_offscreen_buffer_snapshot(
+    snapshot_file_path: impl AsRef<Path>,
+    offscreen_buffer: &OffscreenBuffer,
+) {
+    let snapshot_file_path = snapshot_file_path.as_ref();
+    let actual_snapshot = offscreen_buffer.to_snapshot_string();
+
+    if !snapshot_file_path.exists()
+        || std::env::var_os(UPDATE_SNAPSHOTS_ENV_VAR).is_some()
+    {
+        if let Some(parent) = snapshot_file_path.parent() {
+            std::fs::create_dir_all(parent).ok();
+        }
+        if let Err(error) = std::fs::write(snapshot_file_path, &actual_snapshot) {
+            panic!("Failed to write snapshot {snapshot_file_path:?} due to {error}");
+        }
+        return;
+    }
+
+    let expected_snapshot = match std::fs::read_to_string(snapshot_file_path) {
+        Ok(it) => it,
+        Err(error) => {
+            panic!("Failed to read snapshot {snapshot_file_path:?} due to {error}")
+        }
+    };
+    let expected = match OffscreenBuffer::try_from_snapshot_string(&expected_snapshot) {
+        Ok(it) => it,
+        Err(error) => {
+            panic!("Failed to parse snapshot {snapshot_file_path:?} due to {error}")
+        }
+    };
+
+    if let Some(msg) = expected.describe_snapshot_diff(offscreen_buffer) {
+        panic!(
+            "Snapshot {snapshot_file_path:?} doesn't match (set {UPDATE_SNAPSHOTS_ENV_VAR} to \
+            update it).\n{msg}\nActual snapshot:\n{actual_snapshot}"
+        );
+    }
+}
+
+impl OffscreenBuffer {
+    /// See the [module docs](crate::offscreen_buffer_snapshot) for the format.
+    pub fn to_snapshot_string(&self) -> String {
+        let mut legend: Vec<Style> = vec![];
+        let mut text_layer = String::new();
+        let mut style_layer = String::new();
+
+        for line in self.buffer.iter() {
+            text_layer.push('|');
+            style_layer.push('|');
+            for pixel_char in line.iter() {
+                match pixel_char {
+                    PixelChar::Void => style_layer.push(VOID_CODE),
+                    PixelChar::Spacer => {
+                        text_layer.push(' ');
+                        style_layer.push(SPACER_CODE);
+                    }
+                    PixelChar::PlainText {
+                        content,
+                        maybe_style: None,
+                    } => {
+                        text_layer.push_str(&content.string);
+                        style_layer.push(NO_STYLE_CODE);
+                    }
+                    PixelChar::PlainText {
+                        content,
+                        maybe_style: Some(style),
+                    } => {
+                        text_layer.push_str(&content.string);
+                        let index = match legend.iter().position(|it| it == style) {
+                            Some(index) => index,
+                            None => {
+                                legend.push(*style);
+                                legend.len() - 1
+                            }
+                        };
+                        style_layer.push(get_legend_key(index));
+                    }
+                }
+            }
+            text_layer.push_str("|\n");
+            style_layer.push_str("|\n");
+        }
+
+        let mut it = format!(
+            "size: col_count={} row_count={}\n",
+            ch!(@to_usize self.window_size.col_count),
+            ch!(@to_usize self.window_size.row_count)
+        );
+        it.push_str("text:\n");
+        it.push_str(&text_layer);
+        it.push_str("styles:\n");
+        it.push_str(&style_layer);
+        it.push_str("legend:\n");
+        for (index, style) in legend.iter().enumerate() {
+            let line = format!("{}: {}", get_legend_key(index), style_to_snapshot(style));
+            let _ = writeln!(it, "{}", line.trim_end());
+        }
+        it
+    }
+
+    /// Parses the output of [OffscreenBuffer::to_snapshot_string].
+    pub fn try_from_snapshot_string(snapshot: &str) -> CommonResult<OffscreenBuffer> {
+        let mut lines = snapshot.lines().enumerate();
+
+        let (col_count, row_count) = {
+            let (_, line) = lines.next().unwrap_or((0, ""));
+            let it = line
+                .strip_prefix("size: col_count=")
+                .and_then(|it| it.split_once(" row_count="))
+                .and_then(|(col, row)| {
+                    Some((col.parse::<usize>().ok()?, row.parse::<usize>().ok()?))
+                });
+            match it {
+                Some(it) => it,
+                None => {
+                    return parse_error(0, "expected `size: col_count=N row_count=N`")
+                }
+            }
+        };
+
+        let text_rows = parse_layer(&mut lines, "text:", row_count)?;
+        let style_rows = parse_layer(&mut lines, "styles:", row_count)?;
+
+        // Legend.
+        match lines.next() {
+            Some((_, "legend:")) => {}
+            it => {
+                return parse_error(it.map(|(i, _)| i).unwrap_or(0), "expected `legend:`")
+            }
+        }
+        let mut legend: HashMap<char, Style> = HashMap::new();
+        for (line_index, line) in lines {
+            if line.is_empty() {
+                continue;
+            }
+            let mut chars = line.chars();
+            let (Some(key), Some(':')) = (chars.next(), chars.next()) else {
+                return parse_error(line_index, "expected `<key>: <style>`");
+            };
+            match try_style_from_snapshot(chars.as_str()) {
+                Some(style) => legend.insert(key, style),
+                None => return parse_error(line_index, "invalid style"),
+            };
+        }
+
+        // Cells.
+        let window_size = size!(col_count: col_count, row_count: row_count);
+        let mut it = OffscreenBuffer::new_with_capacity_initialized(window_size);
+        for (row_index, ((text_line_index, text_row), (style_line_index, style_row))) in
+            text_rows.into_iter().zip(style_rows).enumerate()
+        {
+            let codes = style_row.chars().collect::<Vec<_>>();
+            if codes.len() != col_count {
+                return parse_error(style_line_index, "wrong number of cells");
+            }
+            let mut graphemes = UnicodeString::from(text_row).vec_segment.into_iter();
+            for (col_index, code) in codes.into_iter().enumerate() {
+                let pixel_char = match code {
+                    VOID_CODE => PixelChar::Void,
+                    SPACER_CODE => {
+                        graphemes.next();
+                        PixelChar::Spacer
+                    }
+                    _ => {
+                        let Some(grapheme) = graphemes.next() else {
+                            return parse_error(text_line_index, "not enough text");
+                        };
+                        let maybe_style = match code {
+                            NO_STYLE_CODE => None,
+                            _ => match legend.get(&code) {
+                                Some(style) => Some(*style),
+                                None => {
+                                    return parse_error(
+                                        style_line_index,
+                                        &format!("`{code}` isn't in the legend"),
+                                    )
+                                }
+                            },
+                        };
+                        PixelChar::PlainText {
+                            content: GraphemeClusterSegment::from(
+                                grapheme.string.as_str(),
+                            ),
+                            maybe_style,
+                        }
+                    }
+                };
+                it.buffer[row_index][col_index] = pixel_char;
+            }
+        }
+
+        Ok(it)
+    }
+
+    /// Returns [None] if the cells of `self` & `other` are the same. Otherwise, it describes what
+    /// is different (w/ `self` being the expected buffer).
+    pub fn describe_snapshot_diff(&self, other: &OffscreenBuffer) -> Option<String> {
+        let diff_chunks = match self.diff(other) {
+            OffscreenBufferDiffResult::NotComparable => {
+                return Some(format!(
+                    "expected size: {:?}, actual size: {:?}",
+                    self.window_size, other.window_size
+                ));
+            }
+            OffscreenBufferDiffResult::Comparable(it) if it.is_empty() => return None,
+            OffscreenBufferDiffResult::Comparable(it) => it,
+        };
+
+        let mut it = format!("{} cell(s) are different:\n", diff_chunks.len());
+        for (pos, actual) in diff_chunks.iter().take(MAX_CELL_DIFFS_TO_SHOW) {
+            let expected = self.get_pixel_char(*pos);
+            let _ = writeln!(
+                it,
+                "  row {}, col {}: expected {}, actual {}",
+                ch!(@to_usize pos.row_index),
+                ch!(@to_usize pos.col_index),
+                expected.map(describe_pixel_char).unwrap_or_default(),
+                describe_pixel_char(actual)
+            );
+        }
+        if diff_chunks.len() > MAX_CELL_DIFFS_TO_SHOW {
+            let _ = writeln!(it, "  ...");
+        }
+        Some(it)
+    }
+}
+
+fn get_legend_key(index: usize) -> char {
+    // Use Latin-1 letters (starting w/ 'À') once the ASCII keys run out.
+    LEGEND_KEYS
+        .chars()
+        .nth(index)
+        .or_else(|| char::from_u32(0xC0 + (index - LEGEND_KEYS.len()) as u32))
+        .unwrap_or('?')
+}
+
+fn describe_pixel_char(pixel_char: &PixelChar) -> String {
+    match pixel_char {
+        PixelChar::Void => "void".to_string(),
+        PixelChar::Spacer => "spacer".to_string(),
+        PixelChar::PlainText {
+            content,
+            maybe_style: None,
+        } => format!("{:?} w/ no style", content.string),
+        PixelChar::PlainText {
+            content,
+            maybe_style: Some(style),
+        } => format!(
+            "{:?} w/ style [{}]",
+            content.string,
+            style_to_snapshot(style)
+        ),
+    }
+}
+
+/// Returns the line index & the content (in between the `|` chars) of each row in the layer.
+fn parse_layer<'a>(
+    lines: &mut impl Iterator<Item = (usize, &'a str)>,
+    header: &str,
+    row_count: usize,
+) -> CommonResult<Vec<(usize, &'a str)>> {
+    match lines.next() {
+        Some((_, line)) if line == header => {}
+        it => {
+            return parse_error(
+                it.map(|(i, _)| i).unwrap_or(0),
+                &format!("expected `{header}`"),
+            )
+        }
+    }
+    let mut it = vec![];
+    for _ in 0..row_count {
+        let Some((line_index, line)) = lines.next() else {
+            return parse_error(
+                0,
+                &format!("expected {row_count} rows after `{header}`"),
+            );
+        };
+        match line.strip_prefix('|').and_then(|it| it.strip_suffix('|')) {
+            Some(row) => it.push((line_index, row)),
+            None => {
+                return parse_error(line_index, "expected a row in between `|` chars")
+            }
+        }
+    }
+    Ok(it)
+}
+
+fn parse_error<T>(line_index: usize, msg: &str) -> CommonResult<T> {
+    CommonError::new(
+        CommonErrorType::ParsingError,
+        &format!("snapshot line {}: {msg}", line_index + 1),
+    )
+}
+
+fn style_to_snapshot(style: &Style) -> String {
+    let mut it: Vec<String> = vec![];
+    if style.id != Style::default().id {
+        it.push(format!("id={}", style.id));
+    }
+    for (is_set, name) in [
+        (style.bold, "bold"),
+        (style.italic, "italic"),
+        (style.dim, "dim"),
+        (style.underline, "underline"),
+        (style.reverse, "reverse"),
+        (style.hidden, "hidden"),
+        (style.strikethrough, "strikethrough"),
+        (style.computed, "computed"),
+        (style.lolcat, "lolcat"),
+    ] {
+        if is_set {
+            it.push(name.to_string());
+        }
+    }
+    if let Some(color) = style.color_fg {
+        it.push(format!("fg={}", color_to_snapshot(color)));
+    }
+    if let Some(color) = style.color_bg {
+        it.push(format!("bg={}", color_to_snapshot(color)));
+    }
+    if let Some(padding) = style.padding {
+        it.push(format!("padding={}", ch!(@to_usize padding)));
+    }
+    if let Some(border) = style.border {
+        it.push(format!("border={border:?}"));
+    }
+    it.join(" ")
+}
+
+fn try_style_from_snapshot(input: &str) -> Option<Style> {
+    let mut it = Style::default();
+    for token in input.split_whitespace() {
+        match token.split_once('=') {
+            None => match token {
+                "bold" => it.bold = true,
+                "italic" => it.italic = true,
+                "dim" => it.dim = true,
+                "underline" => it.underline = true,
+                "reverse" => it.reverse = true,
+                "hidden" => it.hidden = true,
+                "strikethrough" => it.strikethrough = true,
+                "computed" => it.computed = true,
+                "lolcat" => it.lolcat = true,
+                _ => return None,
+            },
+            Some(("id", value)) => it.id = value.parse().ok()?,
+            Some(("fg", value)) => it.color_fg = Some(try_color_from_snapshot(value)?),
+            Some(("bg", value)) => it.color_bg = Some(try_color_from_snapshot(value)?),
+            Some(("padding", value)) => {
+                it.padding = Some(ch!(value.parse::<usize>().ok()?))
+            }
+            Some(("border", value)) => {
+                it.border = Some(match value {
+                    "Single" => BorderStyle::Single,
+                    "Double" => BorderStyle::Double,
+                    "Rounded" => BorderStyle::Rounded,
+                    "Thick" => BorderStyle::Thick,
+                    "Ascii" => BorderStyle::Ascii,
+                    _ => return None,
+                })
+            }
+            _ => return None,
+        }
+    }
+    Some(it)
+}
+
+fn color_to_snapshot(color: TuiColor) -> String {
+    match color {
+        TuiColor::Reset => "reset".to_string(),
+        TuiColor::Basic(basic_color) => format!("{basic_color:?}"),
+        TuiColor::Rgb(RgbValue { red, green, blue }) => {
+            format!("#{red:02x}{green:02x}{blue:02x}")
+        }
+        TuiColor::Ansi(AnsiValue { color }) => format!("ansi({color})"),
+    }
+}
+
+fn try_color_from_snapshot(input: &str) -> Option<TuiColor> {
+    if input == "reset" {
+        return Some(TuiColor::Reset);
+    }
+    if input.starts_with('#') {
+        return RgbValue::try_from_hex_color(input).ok().map(TuiColor::Rgb);
+    }
+    if let Some(value) = input
+        .strip_prefix("ansi(")
+        .and_then(|it| it.strip_suffix(')'))
+    {
+        return Some(TuiColor::Ansi(AnsiValue::new(value.parse().ok()?)));
+    }
+    ALL_ANSI_BASIC_COLORS
+        .into_iter()
+        .find(|it| format!("{it:?}") == input)
+        .map(TuiColor::Basic)
+}
+
+#[cfg(test)]
+mod tests {
+    use r3bl_rs_utils_macro::style;
+
+    use super::*;
+
+    fn make_offscreen_buffer() -> OffscreenBuffer {
+        let mut it = OffscreenBuffer::new_with_capacity_initialized(
+            size!(col_count: 8, row_count: 2),
+        );
+        let bold_green =
+            Some(style! { id: 1 attrib: [bold] color_fg: color!(0, 255, 0) });
+        let ansi = Some(style! {
+            id: 3
+            padding: 1
+            color_fg: TuiColor::Ansi(AnsiValue::new(208))
+            color_bg: color!(@dark_blue)
+        });
+        for (col_index, (text, maybe_style)) in [
+            ("h", bold_green),
+            ("i", bold_green),
+            ("|", None),
+            ("😃", ansi),
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            it.buffer[0][col_index] = PixelChar::PlainText {
+                content: GraphemeClusterSegment::from(text),
+                maybe_style,
+            };
+        }
+        it.buffer[0][4] = PixelChar::Void;
+        it.buffer[1][7] = PixelChar::PlainText {
+            content: GraphemeClusterSegment::from("z"),
+            maybe_style: Some(Style::default()),
+        };
+        it
+    }
+
+    #[test]
+    fn test_to_snapshot_string() {
+        let snapshot = make_offscreen_buffer().to_snapshot_string();
+        assert_eq2!(
+            snapshot,
+            "\
+size: col_count=8 row_count=2
+text:
+|hi|😃   |
+|       z|
+styles:
+|aa.b~   |
+|       c|
+legend:
+a: id=1 bold fg=#00ff00
+b: id=3 fg=ansi(208) bg=DarkBlue padding=1
+c:
+"
+        );
+    }
+
+    #[test]
+    fn test_snapshot_round_trip() {
+        let offscreen_buffer = make_offscreen_buffer();
+        let snapshot = offscreen_buffer.to_snapshot_string();
+        let parsed = OffscreenBuffer::try_from_snapshot_string(&snapshot).unwrap();
+        assert_eq2!(parsed.window_size, offscreen_buffer.window_size);
+        assert_eq2!(parsed.buffer, offscreen_buffer.buffer);
+        assert_eq2!(parsed.describe_snapshot_diff(&offscreen_buffer), None);
+    }
+
+    #[test]
+    fn test_parse_invalid_snapshot() {
+        let snapshot = make_offscreen_buffer().to_snapshot_string();
+        for invalid_snapshot in [
+            "",
+            snapshot.replace("col_count=8", "col_count=x").as_str(),
+            snapshot.replace("|aa.b~   |", "|aa.b~  |").as_str(),
+            snapshot.replace("|aa.b~   |", "|aa.x~   |").as_str(),
+            snapshot.replace("bg=DarkBlue", "bg=Purple").as_str(),
+            snapshot.replace("legend:\n", "").as_str(),
+        ] {
+            assert!(OffscreenBuffer::try_from_snapshot_string(invalid_snapshot).is_err());
+        }
+    }
+
+    #[test]
+    fn test_describe_snapshot_diff() {
+        let expected = make_offscreen_buffer();
+        let mut actual = make_offscreen_buffer();
+        actual.buffer[1][7] = PixelChar::Spacer;
+        assert_eq2!(
+            expected.describe_snapshot_diff(&actual).unwrap(),
+            "1 cell(s) are different:\n  row 1, col 7: expected \"z\" w/ style [], actual \
+            spacer\n"
+        );
+
+        let other_size = OffscreenBuffer::new_with_capacity_initialized(
+            size!(col_count: 1, row_count: 1),
+        );
+        assert!(expected
+            .describe_snapshot_diff(&other_size)
+            .unwrap()
+            .starts_with("expected size"));
+    }
+}
@@ -24,7 +24,7 @@ use crate::*;
 /// Paint the render pipeline. The render pipeline contains a list of [RenderOps] for each [ZOrder].
 /// This function is responsible for:
 /// 1. Actually executing those [RenderOps] in the correct order.
-/// 2. And routing the execution to the correct backend specified in [TERMINAL_LIB_BACKEND].
+/// 2. And routing the execution to the correct backend returned by [TerminalLibBackend::get].
 ///
 /// See [RenderOps] for more details of "atomic paint operations".
 pub async fn paint(
@@ -38,19 +38,52 @@ pub async fn paint(
         .maybe_saved_offscreen_buffer
         .clone();
     let offscreen_buffer = pipeline.convert(shared_global_data).await;
-    match maybe_saved_offscreen_buffer {
-        None => {
-            perform_full_paint(&offscreen_buffer, flush_kind, shared_global_data).await;
+
+    // If there's no terminal, then capture the frame instead of painting it.
+    let is_headless = {
+        let mut global_data = shared_global_data.write().await;
+        match global_data.maybe_headless_frames {
+            Some(ref mut headless_frames) => {
+                headless_frames.push(offscreen_buffer.clone());
+                true
+            }
+            None => false,
         }
-        Some(saved_offscreen_buffer) => {
-            // Compare offscreen buffers & paint only the diff.
-            match saved_offscreen_buffer.diff(&offscreen_buffer) {
-                OffscreenBufferDiffResult::NotComparable => {
-                    perform_full_paint(&offscreen_buffer, flush_kind, shared_global_data)
-                        .await;
+    };
+
+    if !is_headless {
+        match maybe_saved_offscreen_buffer {
+            None => {
+                perform_full_paint(&offscreen_buffer, flush_kind, shared_global_data)
+                    .await;
+            }
+            Some(mut saved_offscreen_buffer) => {
+                // Scroll the rows that moved, so that they don't have to be repainted.
+                let maybe_vertical_shift =
+                    VerticalShift::detect(&saved_offscreen_buffer, &offscreen_buffer);
+                if let Some(ref vertical_shift) = maybe_vertical_shift {
+                    vertical_shift.apply_to(&mut saved_offscreen_buffer);
                 }
-                OffscreenBufferDiffResult::Comparable(ref diff_chunks) => {
-                    perform_diff_paint(diff_chunks, shared_global_data).await;
+
+                // Compare offscreen buffers & paint only the diff.
+                match saved_offscreen_buffer.diff(&offscreen_buffer) {
+                    OffscreenBufferDiffResult::NotComparable => {
+                        perform_full_paint(
+                            &offscreen_buffer,
+                            flush_kind,
+                            shared_global_data,
+                        )
+                        .await;
+                    }
+                    OffscreenBufferDiffResult::Comparable(ref diff_chunks) => {
+                        perform_diff_paint(
+                            maybe_vertical_shift,
+                            diff_chunks,
+                            offscreen_buffer.window_size,
+                            shared_global_data,
+                        )
+                        .await;
+                    }
                 }
             }
         }
@@ -61,18 +94,42 @@ pub async fn paint(
         .maybe_saved_offscreen_buffer = Some(offscreen_buffer);
 
     async fn perform_diff_paint(
+        maybe_vertical_shift: Option<VerticalShift>,
         diff_chunks: &PixelCharDiffChunks,
+        window_size: Size,
         shared_global_data: &SharedGlobalData,
     ) {
-        match TERMINAL_LIB_BACKEND {
+        let mut render_ops = match maybe_vertical_shift {
+            Some(vertical_shift) => vertical_shift.to_render_ops(),
+            None => render_ops!(),
+        };
+
+        match TerminalLibBackend::get() {
             TerminalLibBackend::Crossterm => {
                 let mut crossterm_impl = OffscreenBufferPaintImplCrossterm {};
-                let render_ops = crossterm_impl.render_diff(diff_chunks).await;
+                render_ops.extend(
+                    crossterm_impl
+                        .render_diff(diff_chunks, window_size)
+                        .await
+                        .list,
+                );
                 crossterm_impl
                     .paint_diff(render_ops, shared_global_data)
                     .await;
             }
-            TerminalLibBackend::Termion => todo!(), // FUTURE: implement OffscreenBufferPaint trait for termion
+            #[cfg(all(unix, feature = "termion"))]
+            TerminalLibBackend::Termion => {
+                let mut termion_impl = OffscreenBufferPaintImplTermion {};
+                render_ops.extend(
+                    termion_impl
+                        .render_diff(diff_chunks, window_size)
+                        .await
+                        .list,
+                );
+                termion_impl
+                    .paint_diff(render_ops, shared_global_data)
+                    .await;
+            }
         }
     }
 
@@ -81,7 +138,7 @@ pub async fn paint(
         flush_kind: FlushKind,
         shared_global_data: &SharedGlobalData,
     ) {
-        match TERMINAL_LIB_BACKEND {
+        match TerminalLibBackend::get() {
             TerminalLibBackend::Crossterm => {
                 let mut crossterm_impl = OffscreenBufferPaintImplCrossterm {};
                 let render_ops = crossterm_impl.render(offscreen_buffer).await;
@@ -89,7 +146,14 @@ pub async fn paint(
                     .paint(render_ops, flush_kind, shared_global_data)
                     .await;
             }
-            TerminalLibBackend::Termion => todo!(), // FUTURE: implement OffscreenBufferPaint trait for termion
+            #[cfg(all(unix, feature = "termion"))]
+            TerminalLibBackend::Termion => {
+                let mut termion_impl = OffscreenBufferPaintImplTermion {};
+                let render_ops = termion_impl.render(offscreen_buffer).await;
+                termion_impl
+                    .paint(render_ops, flush_kind, shared_global_data)
+                    .await;
+            }
         }
     }
 }
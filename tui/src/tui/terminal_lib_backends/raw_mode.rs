@@ -18,12 +18,16 @@
 use crate::*;
 
 /// To use this directly, you need to make sure to create an instance using [start](RawMode::start)
-/// which enables raw mode and then make sure to call [end](RawMode::end) when you are done.
+/// which enables raw mode and then make sure to call [end](RawMode::end) when you are done. Both
+/// do nothing if [GlobalData::is_headless].
 #[derive(Debug, Clone)]
 pub struct RawMode;
 
 impl RawMode {
     pub async fn start(shared_global_data: &SharedGlobalData) {
+        if shared_global_data.read().await.is_headless() {
+            return;
+        }
         let mut skip_flush = false;
         RenderOps::route_paint_render_op_to_backend(
             &mut RenderOpsLocalData::default(),
@@ -35,6 +39,9 @@ impl RawMode {
     }
 
     pub async fn end(shared_global_data: &SharedGlobalData) {
+        if shared_global_data.read().await.is_headless() {
+            return;
+        }
         let mut skip_flush = false;
         RenderOps::route_paint_render_op_to_backend(
             &mut RenderOpsLocalData::default(),
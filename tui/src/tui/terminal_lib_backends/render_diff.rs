@@ -0,0 +1,532 @@
+/*
+ *   Copyright (c) 2023 R3BL LLC
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+//! Convert the diff between 2 [OffscreenBuffer]s ([PixelCharDiffChunks]) into [RenderOps] that
+//! paint it w/ as few bytes as possible:
+//! 1. Adjacent changed cells (in the same row) w/ the same style are coalesced into a single run of
+//!    text, which is painted w/ 1 cursor move.
+//! 2. The SGR state of the terminal (colors & attributes) is tracked, so it is only changed when
+//!    the next run needs it to be different. It is only reset when an attribute or color has to be
+//!    turned off.
+//! 3. The cheapest cursor move is picked by comparing the byte count of the escape sequences for
+//!    relative moves, carriage return, and absolute moves.
+//! 4. A run of [PixelChar::Spacer]s that reaches the end of the row is replaced w/ erase to end of
+//!    line, when that is shorter.
+//!
+//! Run `cargo bench --bench render_diff -- --nocapture` to compare the number of bytes that are
+//! painted w/ the previous implementation (which moved the cursor & reset the colors for every
+//! changed cell).
+
+use r3bl_rs_utils_core::*;
+
+use crate::*;
+
+/// The byte count of `ESC [ K`.
+const CLEAR_TO_END_OF_LINE_BYTE_COUNT: usize = 3;
+
+/// Returns the [RenderOps] that paint `diff_chunks`, which must be the diff between 2
+/// [OffscreenBuffer]s w/ the given `window_size`. No assumptions are made about the position of
+/// the cursor, or the colors & attributes that are set on the terminal before these are painted.
+pub fn render_diff_to_render_ops(
+    diff_chunks: &PixelCharDiffChunks,
+    window_size: Size,
+) -> RenderOps {
+    let mut state = DiffPaintState {
+        render_ops: render_ops!(),
+        maybe_cursor_position: None,
+        maybe_pen: None,
+    };
+
+    let mut index = 0;
+    while index < diff_chunks.len() {
+        let Some(run) = Run::collect(diff_chunks, &mut index) else {
+            continue;
+        };
+
+        state.move_cursor_to(run.start);
+        state.set_pen(run.pen);
+
+        let is_at_end_of_line = run.end_col_index >= window_size.col_count;
+        let run_width = ch!(@to_usize run.end_col_index - run.start.col_index);
+        if run.is_blank
+            && is_at_end_of_line
+            && run_width > CLEAR_TO_END_OF_LINE_BYTE_COUNT
+        {
+            // The cursor doesn't move.
+            state.render_ops.push(RenderOp::ClearToEndOfLine);
+        } else {
+            state.render_ops.push(
+                RenderOp::CompositorNoClipTruncPaintTextWithAttributes(run.text, None),
+            );
+            // Painting the last column leaves the cursor in a "pending wrap" state, which
+            // terminals don't handle consistently w/ relative moves.
+            state.maybe_cursor_position = if is_at_end_of_line {
+                None
+            } else {
+                Some(
+                    position!(col_index: run.end_col_index, row_index: run.start.row_index),
+                )
+            };
+        }
+    }
+
+    state.render_ops
+}
+
+/// Adjacent changed cells in the same row that have the same [Pen].
+#[derive(Debug)]
+struct Run {
+    start: Position,
+    /// The column index right after the last cell of the run.
+    end_col_index: ChUnit,
+    text: String,
+    pen: Pen,
+    /// Only made up of [PixelChar::Spacer]s.
+    is_blank: bool,
+}
+
+impl Run {
+    /// Collect the run that starts at `index`, and advance `index` past it. Returns [None] if
+    /// there's only a [PixelChar::Void] at `index` (it is painted by the wide grapheme cluster
+    /// before it).
+    fn collect(diff_chunks: &PixelCharDiffChunks, index: &mut usize) -> Option<Run> {
+        let (start, first_pixel_char) = &diff_chunks[*index];
+        if let PixelChar::Void = first_pixel_char {
+            *index += 1;
+            return None;
+        }
+
+        let mut it = Run {
+            start: *start,
+            end_col_index: start.col_index,
+            text: String::new(),
+            pen: Pen::from(first_pixel_char),
+            is_blank: true,
+        };
+
+        while let Some((position, pixel_char)) = diff_chunks.get(*index) {
+            if position.row_index != it.start.row_index {
+                break;
+            }
+            let (text, width) = match pixel_char {
+                PixelChar::Void => {
+                    *index += 1;
+                    continue;
+                }
+                PixelChar::Spacer => (SPACER, ch!(1)),
+                PixelChar::PlainText { content, .. } => {
+                    (content.string.as_str(), content.unicode_width)
+                }
+            };
+            if position.col_index != it.end_col_index || Pen::from(pixel_char) != it.pen {
+                break;
+            }
+            it.text.push_str(text);
+            it.end_col_index += width;
+            it.is_blank = it.is_blank && matches!(pixel_char, PixelChar::Spacer);
+            *index += 1;
+        }
+
+        Some(it)
+    }
+}
+
+/// The colors & attributes that are set on the terminal (via SGR sequences).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct Pen {
+    color_fg: Option<TuiColor>,
+    color_bg: Option<TuiColor>,
+    /// Only the attributes of the [Style] are used (the colors are not).
+    attributes: Style,
+}
+
+impl From<&PixelChar> for Pen {
+    fn from(pixel_char: &PixelChar) -> Self {
+        match pixel_char {
+            PixelChar::PlainText {
+                maybe_style: Some(style),
+                ..
+            } => Pen {
+                color_fg: style.color_fg,
+                color_bg: style.color_bg,
+                attributes: Style {
+                    bold: style.bold,
+                    italic: style.italic,
+                    dim: style.dim,
+                    underline: style.underline,
+                    reverse: style.reverse,
+                    hidden: style.hidden,
+                    strikethrough: style.strikethrough,
+                    ..Default::default()
+                },
+            },
+            _ => Pen::default(),
+        }
+    }
+}
+
+impl Pen {
+    /// Returns true if any of the colors or attributes of `self` has to be turned off to get to
+    /// `other`. This can only be done w/ a reset.
+    fn needs_reset_to_become(&self, other: &Pen) -> bool {
+        let this = &self.attributes;
+        let that = &other.attributes;
+        (self.color_fg.is_some() && other.color_fg.is_none())
+            || (self.color_bg.is_some() && other.color_bg.is_none())
+            || (this.bold && !that.bold)
+            || (this.italic && !that.italic)
+            || (this.dim && !that.dim)
+            || (this.underline && !that.underline)
+            || (this.reverse && !that.reverse)
+            || (this.hidden && !that.hidden)
+            || (this.strikethrough && !that.strikethrough)
+    }
+}
+
+#[derive(Debug)]
+struct DiffPaintState {
+    render_ops: RenderOps,
+    /// [None] if the cursor position isn't known.
+    maybe_cursor_position: Option<Position>,
+    /// [None] if the colors & attributes that are set on the terminal aren't known.
+    maybe_pen: Option<Pen>,
+}
+
+impl DiffPaintState {
+    fn set_pen(&mut self, pen: Pen) {
+        let current_pen = match self.maybe_pen {
+            Some(current_pen) if current_pen == pen => return,
+            Some(current_pen) if !current_pen.needs_reset_to_become(&pen) => current_pen,
+            _ => {
+                self.render_ops.push(RenderOp::ResetColor);
+                Pen::default()
+            }
+        };
+
+        if let Some(color) = pen.color_fg {
+            if current_pen.color_fg != Some(color) {
+                self.render_ops.push(RenderOp::SetFgColor(color));
+            }
+        }
+        if let Some(color) = pen.color_bg {
+            if current_pen.color_bg != Some(color) {
+                self.render_ops.push(RenderOp::SetBgColor(color));
+            }
+        }
+
+        let this = &current_pen.attributes;
+        let that = &pen.attributes;
+        let new_attributes = Style {
+            bold: that.bold && !this.bold,
+            italic: that.italic && !this.italic,
+            dim: that.dim && !this.dim,
+            underline: that.underline && !this.underline,
+            reverse: that.reverse && !this.reverse,
+            hidden: that.hidden && !this.hidden,
+            strikethrough: that.strikethrough && !this.strikethrough,
+            ..Default::default()
+        };
+        if new_attributes != Style::default() {
+            self.render_ops
+                .push(RenderOp::ApplyAttributes(new_attributes));
+        }
+
+        self.maybe_pen = Some(pen);
+    }
+
+    fn move_cursor_to(&mut self, target: Position) {
+        if self.maybe_cursor_position == Some(target) {
+            return;
+        }
+        let moves = match self.maybe_cursor_position {
+            Some(current) => get_cheapest_cursor_moves(current, target),
+            None => vec![RenderOp::MoveCursorPositionAbs(target)],
+        };
+        self.render_ops.extend(moves);
+        self.maybe_cursor_position = Some(target);
+    }
+}
+
+/// Returns the [RenderOp]s w/ the smallest byte count that move the cursor from `current` to
+/// `target`.
+fn get_cheapest_cursor_moves(current: Position, target: Position) -> Vec<RenderOp> {
+    let mut candidates: Vec<Vec<RenderOp>> =
+        vec![vec![RenderOp::MoveCursorPositionAbs(target)]];
+
+    let row_count = ch!(@to_usize target.row_index) as isize
+        - ch!(@to_usize current.row_index) as isize;
+    let vertical_move = match row_count {
+        0 => None,
+        1.. => Some(RenderOp::MoveCursorDown(ch!(row_count as usize))),
+        _ => Some(RenderOp::MoveCursorUp(ch!(row_count.unsigned_abs()))),
+    };
+
+    for horizontal_moves in
+        get_horizontal_cursor_moves(current.col_index, target.col_index)
+    {
+        candidates.push(
+            vertical_move
+                .clone()
+                .into_iter()
+                .chain(horizontal_moves)
+                .collect(),
+        );
+    }
+
+    if row_count > 0 {
+        for horizontal_moves in get_horizontal_cursor_moves(ch!(0), target.col_index) {
+            candidates.push(
+                std::iter::once(RenderOp::MoveCursorToStartOfNextLine(ch!(
+                    row_count as usize
+                )))
+                .chain(horizontal_moves)
+                .collect(),
+            );
+        }
+    }
+
+    candidates
+        .into_iter()
+        .min_by_key(|moves| moves.iter().map(get_cursor_move_byte_count).sum::<usize>())
+        .unwrap_or_default()
+}
+
+/// Returns all the ways to move the cursor from `current` to `target` column in the same row.
+fn get_horizontal_cursor_moves(current: ChUnit, target: ChUnit) -> Vec<Vec<RenderOp>> {
+    if current == target {
+        return vec![vec![]];
+    }
+    let mut it = vec![vec![RenderOp::MoveCursorToColumn(target)]];
+    if target == ch!(0) {
+        it.push(vec![RenderOp::MoveCursorToStartOfLine]);
+    }
+    if target > current {
+        it.push(vec![RenderOp::MoveCursorForward(target - current)]);
+    } else {
+        it.push(vec![RenderOp::MoveCursorBackward(current - target)]);
+    }
+    it
+}
+
+/// The byte count of the escape sequence that is painted for each cursor move. Both the crossterm
+/// & termion backends use the same sequences, eg: `ESC [ <row> ; <col> H` for
+/// [RenderOp::MoveCursorPositionAbs] (1 based).
+fn get_cursor_move_byte_count(render_op: &RenderOp) -> usize {
+    fn digit_count(value: usize) -> usize { value.to_string().len() }
+
+    match render_op {
+        RenderOp::MoveCursorPositionAbs(position) => {
+            4 + digit_count(ch!(@to_usize position.row_index) + 1)
+                + digit_count(ch!(@to_usize position.col_index) + 1)
+        }
+        RenderOp::MoveCursorToColumn(col) => 3 + digit_count(ch!(@to_usize *col) + 1),
+        RenderOp::MoveCursorForward(count)
+        | RenderOp::MoveCursorBackward(count)
+        | RenderOp::MoveCursorUp(count)
+        | RenderOp::MoveCursorDown(count)
+        | RenderOp::MoveCursorToStartOfNextLine(count) => {
+            3 + digit_count(ch!(@to_usize *count))
+        }
+        RenderOp::MoveCursorToStartOfLine => 1,
+        _ => 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use r3bl_rs_utils_macro::style;
+
+    use super::*;
+    use crate::test_editor::mock_real_objects_for_editor::make_shared_global_data;
+
+    fn make_plain_text(text: &str, maybe_style: Option<Style>) -> PixelChar {
+        PixelChar::PlainText {
+            content: GraphemeClusterSegment::from(text),
+            maybe_style,
+        }
+    }
+
+    fn make_diff_chunks(cells: Vec<(usize, usize, PixelChar)>) -> PixelCharDiffChunks {
+        let mut it = PixelCharDiffChunks::default();
+        for (col_index, row_index, pixel_char) in cells {
+            it.push((
+                position!(col_index: col_index, row_index: row_index),
+                pixel_char,
+            ));
+        }
+        it
+    }
+
+    async fn paint_into_string(render_ops: &RenderOps, window_size: Size) -> String {
+        let shared_global_data = make_shared_global_data(Some(window_size));
+        let mut crossterm_impl = RenderOpImplCrossterm::new(Vec::<u8>::new());
+        let mut local_data = RenderOpsLocalData::default();
+        let mut skip_flush = false;
+        for render_op in render_ops.iter() {
+            crossterm_impl
+                .paint(
+                    &mut skip_flush,
+                    render_op,
+                    &shared_global_data,
+                    &mut local_data,
+                )
+                .await;
+        }
+        String::from_utf8(crossterm_impl.writer).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_render_diff_coalesces_runs() {
+        let window_size = size!(col_count: 10, row_count: 3);
+        let bold_green = Some(style! { attrib: [bold] color_fg: color!(@green) });
+        let mut cells = vec![
+            (2, 0, make_plain_text("a", bold_green)),
+            (3, 0, make_plain_text("b", bold_green)),
+            (5, 0, make_plain_text("c", None)),
+        ];
+        for col_index in 0..10 {
+            cells.push((col_index, 1, PixelChar::Spacer));
+        }
+        cells.push((0, 2, make_plain_text("x", None)));
+
+        let render_ops = render_diff_to_render_ops(&make_diff_chunks(cells), window_size);
+        assert_eq2!(
+            render_ops.list,
+            vec![
+                RenderOp::MoveCursorPositionAbs(position!(col_index: 2, row_index: 0)),
+                RenderOp::ResetColor,
+                RenderOp::SetFgColor(color!(@green)),
+                RenderOp::ApplyAttributes(Style {
+                    bold: true,
+                    ..Default::default()
+                }),
+                RenderOp::CompositorNoClipTruncPaintTextWithAttributes("ab".into(), None),
+                RenderOp::MoveCursorToColumn(ch!(5)),
+                RenderOp::ResetColor,
+                RenderOp::CompositorNoClipTruncPaintTextWithAttributes("c".into(), None),
+                RenderOp::MoveCursorToStartOfNextLine(ch!(1)),
+                RenderOp::ClearToEndOfLine,
+                RenderOp::MoveCursorDown(ch!(1)),
+                RenderOp::CompositorNoClipTruncPaintTextWithAttributes("x".into(), None),
+            ]
+        );
+
+        assert_eq2!(
+            paint_into_string(&render_ops, window_size).await,
+            "\x1b[1;3H\x1b[0m\x1b[38;5;10m\x1b[1mab\x1b[6G\x1b[0mc\x1b[1E\x1b[K\x1b[1Bx"
+        );
+    }
+
+    #[test]
+    fn test_render_diff_skips_redundant_sgr() {
+        let window_size = size!(col_count: 10, row_count: 1);
+        let bold = style! { attrib: [bold] color_fg: color!(@red) };
+        let bold_italic = style! { attrib: [bold, italic] color_fg: color!(@red) };
+        let render_ops = render_diff_to_render_ops(
+            &make_diff_chunks(vec![
+                (0, 0, make_plain_text("a", Some(bold))),
+                (1, 0, make_plain_text("b", Some(bold_italic))),
+                (2, 0, make_plain_text("c", Some(bold))),
+            ]),
+            window_size,
+        );
+        assert_eq2!(
+            render_ops.list,
+            vec![
+                RenderOp::MoveCursorPositionAbs(position!(col_index: 0, row_index: 0)),
+                RenderOp::ResetColor,
+                RenderOp::SetFgColor(color!(@red)),
+                RenderOp::ApplyAttributes(Style {
+                    bold: true,
+                    ..Default::default()
+                }),
+                RenderOp::CompositorNoClipTruncPaintTextWithAttributes("a".into(), None),
+                // Only italic is added.
+                RenderOp::ApplyAttributes(Style {
+                    italic: true,
+                    ..Default::default()
+                }),
+                RenderOp::CompositorNoClipTruncPaintTextWithAttributes("b".into(), None),
+                // Italic can only be turned off w/ a reset.
+                RenderOp::ResetColor,
+                RenderOp::SetFgColor(color!(@red)),
+                RenderOp::ApplyAttributes(Style {
+                    bold: true,
+                    ..Default::default()
+                }),
+                RenderOp::CompositorNoClipTruncPaintTextWithAttributes("c".into(), None),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_render_diff_wide_grapheme_cluster_and_end_of_line() {
+        let window_size = size!(col_count: 4, row_count: 2);
+        let render_ops = render_diff_to_render_ops(
+            &make_diff_chunks(vec![
+                (1, 0, make_plain_text("😃", None)),
+                (2, 0, PixelChar::Void),
+                (3, 0, make_plain_text("a", None)),
+                (3, 1, make_plain_text("b", None)),
+            ]),
+            window_size,
+        );
+        assert_eq2!(
+            render_ops.list,
+            vec![
+                RenderOp::MoveCursorPositionAbs(position!(col_index: 1, row_index: 0)),
+                RenderOp::ResetColor,
+                RenderOp::CompositorNoClipTruncPaintTextWithAttributes(
+                    "😃a".into(),
+                    None
+                ),
+                // The cursor position is unknown after painting the last column.
+                RenderOp::MoveCursorPositionAbs(position!(col_index: 3, row_index: 1)),
+                RenderOp::CompositorNoClipTruncPaintTextWithAttributes("b".into(), None),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_get_cheapest_cursor_moves() {
+        let pos = |col_index: usize, row_index: usize| position!(col_index: col_index, row_index: row_index);
+        assert_eq2!(
+            get_cheapest_cursor_moves(pos(20, 3), pos(0, 3)),
+            vec![RenderOp::MoveCursorToStartOfLine]
+        );
+        assert_eq2!(
+            get_cheapest_cursor_moves(pos(20, 3), pos(22, 3)),
+            vec![RenderOp::MoveCursorForward(ch!(2))]
+        );
+        assert_eq2!(
+            get_cheapest_cursor_moves(pos(20, 3), pos(2, 3)),
+            vec![RenderOp::MoveCursorToColumn(ch!(2))]
+        );
+        assert_eq2!(
+            get_cheapest_cursor_moves(pos(20, 3), pos(20, 5)),
+            vec![RenderOp::MoveCursorDown(ch!(2))]
+        );
+        assert_eq2!(
+            get_cheapest_cursor_moves(pos(200, 100), pos(0, 101)),
+            vec![RenderOp::MoveCursorToStartOfNextLine(ch!(1))]
+        );
+        assert_eq2!(
+            get_cheapest_cursor_moves(pos(50, 30), pos(100, 2)),
+            vec![RenderOp::MoveCursorPositionAbs(pos(100, 2))]
+        );
+    }
+}
@@ -21,7 +21,6 @@ use std::{fmt::{Debug, Formatter, Result},
 use r3bl_rs_utils_core::*;
 use serde::{Deserialize, Serialize};
 
-use super::TERMINAL_LIB_BACKEND;
 use crate::*;
 
 /// Here's an example. Refer to [RenderOps] for more details.
@@ -155,6 +154,8 @@ pub struct RenderOps {
 #[derive(Default, Debug)]
 pub struct RenderOpsLocalData {
     pub cursor_position: Position,
+    /// Only used by the compositor (see [RenderPipeline::convert]).
+    pub clip_state: ClipState,
 }
 
 pub mod render_ops_impl {
@@ -186,13 +187,18 @@ pub mod render_ops_impl {
             render_op: &RenderOp,
             shared_global_data: &SharedGlobalData,
         ) {
-            match TERMINAL_LIB_BACKEND {
+            match TerminalLibBackend::get() {
                 TerminalLibBackend::Crossterm => {
-                    RenderOpImplCrossterm {}
+                    RenderOpImplCrossterm::default()
+                        .paint(skip_flush, render_op, shared_global_data, local_data)
+                        .await;
+                }
+                #[cfg(all(unix, feature = "termion"))]
+                TerminalLibBackend::Termion => {
+                    RenderOpImplTermion::default()
                         .paint(skip_flush, render_op, shared_global_data, local_data)
                         .await;
                 }
-                TerminalLibBackend::Termion => todo!(), // FUTURE: implement PaintRenderOp trait for termion
             }
         }
     }
@@ -284,6 +290,92 @@ pub enum RenderOp {
     /// already handle the clipping and padding.
     CompositorNoClipTruncPaintTextWithAttributes(String, Option<Style>),
 
+    /// Move the cursor to the given column index, in the same row. This is **not** meant for use
+    /// directly by apps, and neither are the other relative cursor moves below. They are used by
+    /// [render_diff_to_render_ops] to paint the diff between 2 [OffscreenBuffer]s w/ as few bytes
+    /// as possible, since they are usually shorter than [RenderOp::MoveCursorPositionAbs].
+    MoveCursorToColumn(ChUnit),
+
+    /// Move the cursor right by the given number of columns.
+    MoveCursorForward(ChUnit),
+
+    /// Move the cursor left by the given number of columns.
+    MoveCursorBackward(ChUnit),
+
+    /// Move the cursor up by the given number of rows.
+    MoveCursorUp(ChUnit),
+
+    /// Move the cursor down by the given number of rows.
+    MoveCursorDown(ChUnit),
+
+    /// Move the cursor to the first column of the current row (a carriage return).
+    MoveCursorToStartOfLine,
+
+    /// Move the cursor down by the given number of rows, to the first column.
+    MoveCursorToStartOfNextLine(ChUnit),
+
+    /// Clear from the cursor position to the end of the current row, using the current bg color.
+    /// The cursor doesn't move.
+    ClearToEndOfLine,
+
+    /// Translate [Style] into *only* attributes (bold, italic, underline, strikethrough, etc) w/out
+    /// painting any text, and w/out resetting them afterwards (unlike
+    /// [RenderOp::CompositorNoClipTruncPaintTextWithAttributes]). Use [RenderOp::ResetColor] to
+    /// reset them.
+    ApplyAttributes(Style),
+
+    /// Limit scrolling (and [RenderOp::InsertLines] & [RenderOp::DeleteLines]) to the rows
+    /// between the given top & bottom row indices (inclusive), using DECSTBM. The cursor moves to
+    /// the top left corner of the screen. This is **not** meant for use directly by apps, and
+    /// neither are the other scroll region ops below. They are used by [VerticalShift] to scroll
+    /// the rows that moved between 2 [OffscreenBuffer]s, instead of repainting them.
+    SetScrollRegion(ChUnit, ChUnit),
+
+    /// Let the entire screen scroll again. The cursor moves to the top left corner of the screen.
+    ResetScrollRegion,
+
+    /// Insert the given number of blank rows at the cursor row. The rows below it move down, and
+    /// the ones that move past the bottom of the scroll region are lost. The cursor moves to the
+    /// first column.
+    InsertLines(ChUnit),
+
+    /// Delete the given number of rows at the cursor row. The rows below it move up, and blank
+    /// rows are exposed at the bottom of the scroll region. The cursor moves to the first column.
+    DeleteLines(ChUnit),
+
+    /// Only paint the cells inside the rectangle w/ the given origin & size, until the matching
+    /// [RenderOp::PopClipRect]. Text that overflows it is cut off (wide grapheme clusters that are
+    /// cut are replaced w/ spacers), so a component can clip what it paints to its [FlexBox]. The
+    /// origin is translated (see [RenderOp::PushTranslate]), and a nested clip rect is
+    /// intersected w/ the one it is nested in. This is only honored by the compositor (when it
+    /// converts a [RenderPipeline] to an [OffscreenBuffer]), and only for the [RenderOps] it is
+    /// in. See [ClipState] for more details.
+    PushClipRect(Position, Size),
+
+    /// Go back to the clip rect that was in effect before the last [RenderOp::PushClipRect].
+    PopClipRect,
+
+    /// Render into a virtual canvas that is scrolled: after this, the 2nd [Position] (the scroll
+    /// offset in the canvas) is painted at the 1st [Position] (the origin, eg: of the [FlexBox]).
+    /// The positions of [RenderOp::MoveCursorPositionAbs], [RenderOp::MoveCursorPositionRelTo] &
+    /// [RenderOp::PushClipRect] are translated, until the matching [RenderOp::PopTranslate].
+    /// This is usually combined w/ [RenderOp::PushClipRect], to clip the canvas to the box.
+    PushTranslate(
+        /* origin */ Position,
+        /* scroll offset */ ScrollOffset,
+    ),
+
+    /// Go back to the translation that was in effect before the last [RenderOp::PushTranslate].
+    PopTranslate,
+
+    /// Paint the edges of the rectangle w/ the given origin & size using box drawing characters
+    /// (see [Border]). Where it is painted over another border w/ the same line weight, their
+    /// lines are merged into junctions. The origin is translated & the border is clipped (see
+    /// [RenderOp::PushTranslate] & [RenderOp::PushClipRect]), and the cursor moves to the origin.
+    /// This is only honored by the compositor (when it converts a [RenderPipeline] to an
+    /// [OffscreenBuffer]). See [box_drawing] for more details.
+    PaintBorder(Position, Size, Border),
+
     /// For [Default] impl.
     Noop,
 }
@@ -295,15 +387,64 @@ mod render_op_impl {
         fn default() -> Self { Self::Noop }
     }
 
+    impl RenderOp {
+        /// Returns the position of the cursor after this [RenderOp] is executed, if it is one of
+        /// the relative cursor moves (eg: [RenderOp::MoveCursorForward]), or one of the scroll
+        /// region ops (which also move the cursor). The returned position isn't clamped to the
+        /// bounds of the terminal window.
+        pub fn get_cursor_position_after_rel_move(
+            &self,
+            cursor_position: Position,
+        ) -> Option<Position> {
+            let Position {
+                col_index,
+                row_index,
+            } = cursor_position;
+            match self {
+                RenderOp::MoveCursorToColumn(col) => {
+                    Some(position!(col_index: *col, row_index: row_index))
+                }
+                RenderOp::MoveCursorForward(count) => {
+                    Some(position!(col_index: col_index + *count, row_index: row_index))
+                }
+                RenderOp::MoveCursorBackward(count) => {
+                    Some(position!(col_index: col_index - *count, row_index: row_index))
+                }
+                RenderOp::MoveCursorUp(count) => {
+                    Some(position!(col_index: col_index, row_index: row_index - *count))
+                }
+                RenderOp::MoveCursorDown(count) => {
+                    Some(position!(col_index: col_index, row_index: row_index + *count))
+                }
+                RenderOp::MoveCursorToStartOfLine => {
+                    Some(position!(col_index: 0, row_index: row_index))
+                }
+                RenderOp::MoveCursorToStartOfNextLine(count) => {
+                    Some(position!(col_index: 0, row_index: row_index + *count))
+                }
+                RenderOp::SetScrollRegion(..) | RenderOp::ResetScrollRegion => {
+                    Some(position!(col_index: 0, row_index: 0))
+                }
+                RenderOp::InsertLines(_) | RenderOp::DeleteLines(_) => {
+                    Some(position!(col_index: 0, row_index: row_index))
+                }
+                _ => None,
+            }
+        }
+    }
+
     impl Debug for RenderOp {
         /// When [RenderPipeline] is printed as debug, each [RenderOp] is printed using this method. Also
         /// [exec_render_op!] does not use this; it has its own way of logging output.
         fn fmt(&self, f: &mut Formatter<'_>) -> Result {
-            match TERMINAL_LIB_BACKEND {
+            match TerminalLibBackend::get() {
                 TerminalLibBackend::Crossterm => {
                     CrosstermDebugFormatRenderOp {}.debug_format(self, f)
                 }
-                TerminalLibBackend::Termion => todo!(), // FUTURE: implement debug formatter for termion
+                #[cfg(all(unix, feature = "termion"))]
+                TerminalLibBackend::Termion => {
+                    TermionDebugFormatRenderOp {}.debug_format(self, f)
+                }
             }
         }
     }
@@ -314,20 +455,50 @@ mod render_op_impl_trait_flush {
 
     impl Flush for RenderOp {
         fn flush(&mut self) {
-            match TERMINAL_LIB_BACKEND {
+            match TerminalLibBackend::get() {
                 TerminalLibBackend::Crossterm => {
-                    RenderOpImplCrossterm {}.flush();
+                    RenderOpImplCrossterm::default().flush();
+                }
+                #[cfg(all(unix, feature = "termion"))]
+                TerminalLibBackend::Termion => {
+                    RenderOpImplTermion::default().flush();
                 }
-                TerminalLibBackend::Termion => todo!(), // FUTURE: implement flush for termion
             }
         }
 
         fn clear_before_flush(&mut self) {
-            match TERMINAL_LIB_BACKEND {
+            match TerminalLibBackend::get() {
+                TerminalLibBackend::Crossterm => {
+                    RenderOpImplCrossterm::default().clear_before_flush();
+                }
+                #[cfg(all(unix, feature = "termion"))]
+                TerminalLibBackend::Termion => {
+                    RenderOpImplTermion::default().clear_before_flush();
+                }
+            }
+        }
+
+        fn begin_synchronized_update(&mut self) {
+            match TerminalLibBackend::get() {
                 TerminalLibBackend::Crossterm => {
-                    RenderOpImplCrossterm {}.clear_before_flush();
+                    RenderOpImplCrossterm::default().begin_synchronized_update();
+                }
+                #[cfg(all(unix, feature = "termion"))]
+                TerminalLibBackend::Termion => {
+                    RenderOpImplTermion::default().begin_synchronized_update();
+                }
+            }
+        }
+
+        fn end_synchronized_update(&mut self) {
+            match TerminalLibBackend::get() {
+                TerminalLibBackend::Crossterm => {
+                    RenderOpImplCrossterm::default().end_synchronized_update();
+                }
+                #[cfg(all(unix, feature = "termion"))]
+                TerminalLibBackend::Termion => {
+                    RenderOpImplTermion::default().end_synchronized_update();
                 }
-                TerminalLibBackend::Termion => todo!(), // FUTURE: implement clear_before_flush for termion
             }
         }
     }
@@ -342,6 +513,10 @@ pub enum FlushKind {
 pub trait Flush {
     fn flush(&mut self);
     fn clear_before_flush(&mut self);
+    /// See [SynchronizedOutput].
+    fn begin_synchronized_update(&mut self);
+    /// See [SynchronizedOutput].
+    fn end_synchronized_update(&mut self);
 }
 
 pub trait DebugFormatRenderOp {
@@ -15,7 +15,7 @@
  *   limitations under the License.
  */
 
-use std::{collections::{hash_map::Entry, HashMap},
+use std::{collections::{btree_map::Entry, BTreeMap, BTreeSet},
           fmt::Debug,
           ops::{AddAssign, Deref, DerefMut}};
 
@@ -29,8 +29,8 @@ use crate::{tui::DEBUG_TUI_SHOW_PIPELINE_EXPANDED, *};
 /// 1. This pipeline is meant to hold a list of [RenderOp] items.
 /// 2. Once all the [RenderOp] items are added to the correct [ZOrder]s they can then be
 ///    flushed at the end in order to [paint](RenderPipeline::paint()) them to the screen.
-/// 3. [get_render_order()](ZOrder::get_render_order) contains the priority that is used
-///    to paint the different groups of [RenderOp] items.
+/// 3. The [ZOrder]s are painted in ascending order, so the [RenderOp] items in a higher
+///    [ZOrder] are painted on top of the ones in lower [ZOrder]s.
 ///
 /// This adds given [RenderOp]s to a [RenderOps] and adds that the the pipeline, but does not flush
 /// anything. It will return a [RenderPipeline].
@@ -157,11 +157,15 @@ macro_rules! render_pipeline {
 /// ```
 #[derive(Default, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct RenderPipeline {
-    /// [RenderOps] to paint for each [ZOrder].
+    /// [RenderOps] to paint for each [ZOrder], in the order that they are painted.
     pub pipeline_map: PipelineMap,
+    /// The [ZOrder]s that are composited on top of the lower ones instead of replacing them (see
+    /// [RenderPipeline::set_transparent]).
+    #[serde(default)]
+    pub transparent_z_orders: BTreeSet<ZOrder>,
 }
 
-type PipelineMap = HashMap<ZOrder, Vec<RenderOps>>;
+type PipelineMap = BTreeMap<ZOrder, Vec<RenderOps>>;
 
 mod render_pipeline_impl {
     use super::*;
@@ -169,7 +173,9 @@ mod render_pipeline_impl {
     impl RenderPipeline {
         /// This will add `rhs` to `self`.
         pub fn join_into(&mut self, mut rhs: RenderPipeline) {
-            for (z_order, mut rhs_render_ops_vec) in rhs.drain() {
+            self.transparent_z_orders
+                .append(&mut rhs.transparent_z_orders);
+            for (z_order, mut rhs_render_ops_vec) in rhs.pipeline_map {
                 // Insert rhs_render_ops_vec into self_render_ops_vec.
                 match self.entry(z_order) {
                     Entry::Occupied(mut self_existing_entry) => {
@@ -213,6 +219,23 @@ mod render_pipeline_impl {
             Some(vec_render_op)
         }
 
+        /// When a [ZOrder] is transparent, the cells that its [RenderOps] don't paint (or paint
+        /// w/ plain spaces) let the lower [ZOrder]s show through. And the cells that are painted
+        /// w/ spaces in a [Style] that only has a bg color tint the cells beneath them w/ that
+        /// bg color (eg: for a drop shadow behind a dialog). See
+        /// [composite_transparent_layer] for more details.
+        pub fn set_transparent(&mut self, z_order: ZOrder, is_transparent: bool) {
+            if is_transparent {
+                self.transparent_z_orders.insert(z_order);
+            } else {
+                self.transparent_z_orders.remove(&z_order);
+            }
+        }
+
+        pub fn is_transparent(&self, z_order: ZOrder) -> bool {
+            self.transparent_z_orders.contains(&z_order)
+        }
+
         pub async fn paint(
             &self,
             flush_kind: FlushKind,
@@ -261,13 +284,17 @@ mod render_pipeline_impl {
             let mut vec_lines: Vec<String> = vec![];
             if DEBUG_TUI_SHOW_PIPELINE_EXPANDED {
                 for (z_order, render_ops) in &**self {
-                    let line: String = format!("[{z_order:?}] {render_ops:?}");
+                    let line: String =
+                        format!("[{}] {render_ops:?}", self.format_z_order(*z_order));
                     vec_lines.push(line);
                 }
             } else {
                 for (z_order, vec_render_ops) in &**self {
-                    let line: String =
-                        format!("[{z_order:?}] {:?} RenderOps", vec_render_ops.len());
+                    let line: String = format!(
+                        "[{}] {:?} RenderOps",
+                        self.format_z_order(*z_order),
+                        vec_render_ops.len()
+                    );
                     vec_lines.push(line);
                 }
             }
@@ -275,29 +302,141 @@ mod render_pipeline_impl {
         }
     }
 
+    impl RenderPipeline {
+        fn format_z_order(&self, z_order: ZOrder) -> String {
+            if self.is_transparent(z_order) {
+                format!("{z_order:?}, transparent")
+            } else {
+                format!("{z_order:?}")
+            }
+        }
+    }
+
     impl AddAssign for RenderPipeline {
         fn add_assign(&mut self, other: RenderPipeline) { self.join_into(other); }
     }
 }
 
-#[derive(Debug, Copy, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
-pub enum ZOrder {
-    Normal,
-    High,
-    Glass,
-}
+/// A layer in a [RenderPipeline]. The layers are painted in ascending order, so the [RenderOps]
+/// in a higher layer are painted on top of the ones in lower layers. Use the named constants for
+/// the common layers, and [ZOrder::above] or [ZOrder::below] to stack more layers around them
+/// (eg: a popup on top of a dialog on top of a panel).
+///
+/// ```rust
+/// use r3bl_tui::*;
+///
+/// let dialog_z_order = ZOrder::Glass;
+/// let popup_z_order = dialog_z_order.above(1);
+/// let shadow_z_order = dialog_z_order.below(1);
+/// assert!(shadow_z_order < dialog_z_order && dialog_z_order < popup_z_order);
+/// ```
+///
+/// [ZOrder] used to be an enum, so the named layers are serialized w/ the names of its variants
+/// (`"Normal"`, `"High"` & `"Glass"`), and the other layers as numbers. This means that
+/// serialized [RenderPipeline]s from before can still be deserialized.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct ZOrder(pub i16);
 
 mod z_order_impl {
     use super::*;
 
+    // These are named like the enum variants that they replace, so existing code keeps working.
+    #[allow(non_upper_case_globals)]
     impl ZOrder {
+        /// The default layer, for the components in a layout.
+        pub const Normal: ZOrder = ZOrder(0);
+        /// On top of [ZOrder::Normal], eg: for the caret.
+        pub const High: ZOrder = ZOrder(100);
+        /// On top of everything else, eg: for modal dialogs.
+        pub const Glass: ZOrder = ZOrder(200);
+
+        /// The names of the variants of the enum that [ZOrder] used to be.
+        const SERDE_NAMES: [(ZOrder, &'static str); 3] = [
+            (ZOrder::Normal, "Normal"),
+            (ZOrder::High, "High"),
+            (ZOrder::Glass, "Glass"),
+        ];
+
+        pub fn above(&self, layer_count: i16) -> ZOrder {
+            ZOrder(self.0.saturating_add(layer_count))
+        }
+
+        pub fn below(&self, layer_count: i16) -> ZOrder {
+            ZOrder(self.0.saturating_sub(layer_count))
+        }
+
         /// Contains the priority that is used to paint the different groups of [RenderOp] items.
+        #[deprecated(
+            note = "The layers are painted in ascending order, and there can be any number of \
+                    them. Iterate over the RenderPipeline instead."
+        )]
         pub fn get_render_order() -> [ZOrder; 3] {
             [ZOrder::Normal, ZOrder::High, ZOrder::Glass]
         }
     }
 
-    impl Default for ZOrder {
-        fn default() -> Self { Self::Normal }
+    impl Debug for ZOrder {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            match *self {
+                ZOrder::Normal => write!(f, "Normal"),
+                ZOrder::High => write!(f, "High"),
+                ZOrder::Glass => write!(f, "Glass"),
+                ZOrder(layer) => write!(f, "ZOrder({layer})"),
+            }
+        }
+    }
+
+    impl Serialize for ZOrder {
+        fn serialize<S: serde::Serializer>(
+            &self,
+            serializer: S,
+        ) -> Result<S::Ok, S::Error> {
+            match ZOrder::SERDE_NAMES.iter().find(|(it, _)| it == self) {
+                Some((_, name)) => serializer.serialize_str(name),
+                None => serializer.serialize_i16(self.0),
+            }
+        }
+    }
+
+    /// Numbers are also accepted as strings, since that is how they are serialized when they are
+    /// keys in a map (eg: in JSON).
+    impl<'de> Deserialize<'de> for ZOrder {
+        fn deserialize<D: serde::Deserializer<'de>>(
+            deserializer: D,
+        ) -> Result<Self, D::Error> {
+            deserializer.deserialize_any(ZOrderVisitor)
+        }
+    }
+
+    struct ZOrderVisitor;
+
+    impl<'de> serde::de::Visitor<'de> for ZOrderVisitor {
+        type Value = ZOrder;
+
+        fn expecting(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(
+                f,
+                "a layer number, or one of: \"Normal\", \"High\", \"Glass\""
+            )
+        }
+
+        fn visit_i64<E: serde::de::Error>(self, value: i64) -> Result<ZOrder, E> {
+            i16::try_from(value).map(ZOrder).map_err(E::custom)
+        }
+
+        fn visit_u64<E: serde::de::Error>(self, value: u64) -> Result<ZOrder, E> {
+            i16::try_from(value).map(ZOrder).map_err(E::custom)
+        }
+
+        fn visit_str<E: serde::de::Error>(self, value: &str) -> Result<ZOrder, E> {
+            if let Some((it, _)) = ZOrder::SERDE_NAMES.iter().find(|(_, it)| *it == value)
+            {
+                return Ok(*it);
+            }
+            value
+                .parse::<i16>()
+                .map(ZOrder)
+                .map_err(|_| E::invalid_value(serde::de::Unexpected::Str(value), &self))
+        }
     }
 }
@@ -37,20 +37,41 @@ impl RenderPipeline {
 
         let mut local_data = RenderOpsLocalData::default();
 
-        for z_order in ZOrder::get_render_order().iter() {
-            if let Some(render_ops_vec) = self.get(z_order) {
-                for (_render_ops_index, render_ops) in render_ops_vec.iter().enumerate() {
-                    for (_render_op_index, render_op) in render_ops.iter().enumerate() {
-                        process_render_op(
-                            render_op,
-                            shared_global_data,
-                            &mut my_offscreen_buffer,
-                            &mut local_data,
-                        )
-                        .await;
-                    }
-                }
+        // The z_orders are painted in ascending order.
+        for (z_order, render_ops_vec) in self.iter() {
+            if !self.is_transparent(*z_order) {
+                process_render_ops_vec(
+                    render_ops_vec,
+                    shared_global_data,
+                    &mut my_offscreen_buffer,
+                    &mut local_data,
+                )
+                .await;
+                continue;
             }
+
+            // A transparent z_order is painted into its own offscreen buffer first, which is then
+            // composited on top of the z_orders beneath it.
+            let mut layer_offscreen_buffer = OffscreenBuffer {
+                my_pos: my_offscreen_buffer.my_pos,
+                my_fg_color: my_offscreen_buffer.my_fg_color,
+                my_bg_color: my_offscreen_buffer.my_bg_color,
+                ..OffscreenBuffer::new_with_capacity_initialized(my_window_size)
+            };
+            process_render_ops_vec(
+                render_ops_vec,
+                shared_global_data,
+                &mut layer_offscreen_buffer,
+                &mut local_data,
+            )
+            .await;
+            composite_transparent_layer(
+                &mut my_offscreen_buffer,
+                &layer_offscreen_buffer,
+            );
+            my_offscreen_buffer.my_pos = layer_offscreen_buffer.my_pos;
+            my_offscreen_buffer.my_fg_color = layer_offscreen_buffer.my_fg_color;
+            my_offscreen_buffer.my_bg_color = layer_offscreen_buffer.my_bg_color;
         }
 
         call_if_true!(DEBUG_TUI_COMPOSITOR, {
@@ -62,6 +83,81 @@ impl RenderPipeline {
     }
 }
 
+async fn process_render_ops_vec(
+    render_ops_vec: &[RenderOps],
+    shared_global_data: &SharedGlobalData,
+    my_offscreen_buffer: &mut OffscreenBuffer,
+    local_data: &mut RenderOpsLocalData,
+) {
+    for render_ops in render_ops_vec.iter() {
+        // Clip rects & translations don't carry over from one RenderOps to the next.
+        local_data.clip_state.clear();
+        for render_op in render_ops.iter() {
+            process_render_op(
+                render_op,
+                shared_global_data,
+                my_offscreen_buffer,
+                local_data,
+            )
+            .await;
+        }
+    }
+}
+
+/// Paint the cells of `layer_offscreen_buffer` (which is the offscreen buffer of a transparent
+/// [ZOrder]) on top of `my_offscreen_buffer`.
+/// 1. [PixelChar::Spacer]s (cells that aren't painted, or are painted w/ plain spaces) are
+///    transparent, so the cells beneath them show through.
+/// 2. Spaces that are painted w/ a [Style] that only has a bg color are tinted glass, so the
+///    cells beneath them keep their text, but get that bg color (eg: a drop shadow).
+/// 3. All other cells are opaque, and replace the cells beneath them.
+pub fn composite_transparent_layer(
+    my_offscreen_buffer: &mut OffscreenBuffer,
+    layer_offscreen_buffer: &OffscreenBuffer,
+) {
+    for (line, layer_line) in my_offscreen_buffer
+        .buffer
+        .iter_mut()
+        .zip(layer_offscreen_buffer.buffer.iter())
+    {
+        for (col_index, layer_pixel_char) in layer_line.iter().enumerate() {
+            match layer_pixel_char {
+                PixelChar::Spacer => {}
+                PixelChar::PlainText {
+                    content,
+                    maybe_style: Some(layer_style),
+                } if content.string == SPACER && is_bg_color_only(layer_style) => {
+                    match &mut line[col_index] {
+                        PixelChar::PlainText { maybe_style, .. } => {
+                            let mut style = maybe_style.unwrap_or_default();
+                            style.color_bg = layer_style.color_bg;
+                            *maybe_style = Some(style);
+                        }
+                        PixelChar::Spacer => line[col_index] = layer_pixel_char.clone(),
+                        PixelChar::Void => {}
+                    }
+                }
+                // This includes the rest of a wide grapheme cluster in the layer
+                // ([PixelChar::Void]), which can cut a wide grapheme cluster beneath it.
+                _ => replace_pixel_char(line, col_index, layer_pixel_char.clone()),
+            }
+        }
+    }
+}
+
+/// Returns true if the only thing that `style` changes when it is painted is the bg color.
+fn is_bg_color_only(style: &Style) -> bool {
+    style.color_bg.is_some()
+        && style.color_fg.is_none()
+        && !(style.bold
+            || style.italic
+            || style.dim
+            || style.underline
+            || style.reverse
+            || style.hidden
+            || style.strikethrough)
+}
+
 async fn process_render_op(
     render_op: &RenderOp,
     shared_global_data: &SharedGlobalData,
@@ -71,23 +167,64 @@ async fn process_render_op(
     match render_op {
         // Don't process these.
         RenderOp::Noop | RenderOp::EnterRawMode | RenderOp::ExitRawMode => {}
+        // These are only used to paint the diff between offscreen buffers.
+        RenderOp::MoveCursorToColumn(_)
+        | RenderOp::MoveCursorForward(_)
+        | RenderOp::MoveCursorBackward(_)
+        | RenderOp::MoveCursorUp(_)
+        | RenderOp::MoveCursorDown(_)
+        | RenderOp::MoveCursorToStartOfLine
+        | RenderOp::MoveCursorToStartOfNextLine(_)
+        | RenderOp::ClearToEndOfLine
+        | RenderOp::ApplyAttributes(_)
+        | RenderOp::SetScrollRegion(..)
+        | RenderOp::ResetScrollRegion
+        | RenderOp::InsertLines(_)
+        | RenderOp::DeleteLines(_) => {}
         // Do process these.
         RenderOp::ClearScreen => {
             my_offscreen_buffer.clear();
         }
+        RenderOp::PushClipRect(origin, size) => {
+            local_data.clip_state.push_clip_rect(*origin, *size);
+        }
+        RenderOp::PopClipRect => {
+            local_data.clip_state.pop_clip_rect();
+        }
+        RenderOp::PushTranslate(origin, scroll_offset) => {
+            local_data
+                .clip_state
+                .push_translate(*origin, *scroll_offset);
+        }
+        RenderOp::PopTranslate => {
+            local_data.clip_state.pop_translate();
+        }
+        RenderOp::PaintBorder(origin, size, border) => {
+            print_border(
+                *origin,
+                *size,
+                border,
+                my_offscreen_buffer,
+                &mut local_data.clip_state,
+            );
+            move_cursor_to(*origin, shared_global_data, my_offscreen_buffer, local_data)
+                .await;
+        }
         RenderOp::MoveCursorPositionAbs(new_abs_pos) => {
-            my_offscreen_buffer.my_pos = sanitize_and_save_abs_position(
+            move_cursor_to(
                 *new_abs_pos,
                 shared_global_data,
+                my_offscreen_buffer,
                 local_data,
             )
             .await;
         }
         RenderOp::MoveCursorPositionRelTo(box_origin_pos_ref, content_rel_pos_ref) => {
             let new_abs_pos = *box_origin_pos_ref + *content_rel_pos_ref;
-            my_offscreen_buffer.my_pos = sanitize_and_save_abs_position(
+            move_cursor_to(
                 new_abs_pos,
                 shared_global_data,
+                my_offscreen_buffer,
                 local_data,
             )
             .await;
@@ -114,6 +251,22 @@ async fn process_render_op(
         ) => {
             // This is a no-op. This operation is executed by RenderOpImplCrossterm.
         }
+        RenderOp::PaintTextWithAttributes(arg_text_ref, maybe_style_ref)
+            if local_data.clip_state.is_active() =>
+        {
+            print_clipped_text(
+                arg_text_ref,
+                maybe_style_ref,
+                my_offscreen_buffer,
+                &mut local_data.clip_state,
+            );
+            my_offscreen_buffer.my_pos = sanitize_and_save_abs_position(
+                local_data.clip_state.get_cursor_position(),
+                shared_global_data,
+                local_data,
+            )
+            .await;
+        }
         RenderOp::PaintTextWithAttributes(arg_text_ref, maybe_style_ref) => {
             let result_new_pos = print_text_with_attributes(
                 shared_global_data,
@@ -124,9 +277,10 @@ async fn process_render_op(
             )
             .await;
             if let Ok(new_pos) = result_new_pos {
-                my_offscreen_buffer.my_pos = sanitize_and_save_abs_position(
+                move_cursor_to(
                     new_pos,
                     shared_global_data,
+                    my_offscreen_buffer,
                     local_data,
                 )
                 .await;
@@ -135,6 +289,253 @@ async fn process_render_op(
     }
 }
 
+/// The cursor position is translated (see [RenderOp::PushTranslate]) & saved in the
+/// [ClipState], and then it is clamped to the window & saved in `my_offscreen_buffer`.
+async fn move_cursor_to(
+    new_pos: Position,
+    shared_global_data: &SharedGlobalData,
+    my_offscreen_buffer: &mut OffscreenBuffer,
+    local_data: &mut RenderOpsLocalData,
+) {
+    local_data.clip_state.move_cursor_to(new_pos);
+    my_offscreen_buffer.my_pos = sanitize_and_save_abs_position(
+        local_data.clip_state.get_cursor_position(),
+        shared_global_data,
+        local_data,
+    )
+    .await;
+}
+
+/// The colors come from `my_fg_color` & `my_bg_color` of `my_offscreen_buffer`, and the attributes
+/// from `maybe_style_ref`.
+fn get_style_for_pixel_chars(
+    maybe_style_ref: &Option<Style>,
+    my_offscreen_buffer: &OffscreenBuffer,
+) -> Option<Style> {
+    if let Some(maybe_style) = maybe_style_ref {
+        // We get the attributes from `maybe_style_ref`.
+        let mut it = *maybe_style;
+        // We get the colors from `my_fg_color` and `my_bg_color`.
+        it.color_fg = my_offscreen_buffer.my_fg_color;
+        it.color_bg = my_offscreen_buffer.my_bg_color;
+        Some(it)
+    } else if my_offscreen_buffer.my_fg_color.is_some()
+        || my_offscreen_buffer.my_bg_color.is_some()
+    {
+        Some(Style {
+            color_fg: my_offscreen_buffer.my_fg_color,
+            color_bg: my_offscreen_buffer.my_bg_color,
+            ..Default::default()
+        })
+    } else {
+        None
+    }
+}
+
+fn make_pixel_char(text: &str, maybe_style: &Option<Style>) -> PixelChar {
+    let new_gc_segment = GraphemeClusterSegment::from(text);
+    match (maybe_style, new_gc_segment.string.as_str()) {
+        (None, SPACER) => PixelChar::Spacer,
+        _ => PixelChar::PlainText {
+            content: new_gc_segment,
+            maybe_style: *maybe_style,
+        },
+    }
+}
+
+/// Same as [print_plain_text], except that the text is painted at the cursor position of the
+/// `clip_state` (which can be outside the window), and only the cells inside its clip rect are
+/// painted. A wide grapheme cluster that is cut by an edge of the clip rect is replaced w/
+/// spacers (in the cells that are inside it). The cursor moves forward by the display width of
+/// the entire text.
+///
+/// ```text
+///              clip rect
+///            ↓           ↓
+/// text:    "ab😃cdef😃gh"
+/// painted:   "␣cdef␣"
+/// ```
+pub fn print_clipped_text(
+    arg_text_ref: &str,
+    maybe_style_ref: &Option<Style>,
+    my_offscreen_buffer: &mut OffscreenBuffer,
+    clip_state: &mut ClipState,
+) {
+    let clip_rect = clip_state.get_clip_rect(my_offscreen_buffer.window_size);
+    let (start_col_index, row_index) = clip_state.get_cursor();
+    let maybe_style = get_style_for_pixel_chars(maybe_style_ref, my_offscreen_buffer);
+    let text = arg_text_ref.unicode_string();
+
+    let maybe_line = if clip_rect.contains_row(row_index) {
+        my_offscreen_buffer.buffer.get_mut(row_index as usize)
+    } else {
+        None
+    };
+
+    if let Some(line) = maybe_line {
+        let mut col_index = start_col_index;
+        for gc_segment in text.iter() {
+            let segment_display_width = ch!(@to_usize gc_segment.unicode_width) as isize;
+            if segment_display_width == 0 {
+                continue;
+            }
+            let last_col_index = col_index + segment_display_width - 1;
+            let is_cut = !clip_rect.contains_col(col_index)
+                || !clip_rect.contains_col(last_col_index);
+            for it in col_index..=last_col_index {
+                if !clip_rect.contains_col(it) {
+                    continue;
+                }
+                let Some(pixel_char) = line.get_mut(it as usize) else {
+                    continue;
+                };
+                *pixel_char = if is_cut {
+                    make_pixel_char(SPACER, &maybe_style)
+                } else if it == col_index {
+                    make_pixel_char(&gc_segment.string, &maybe_style)
+                } else {
+                    PixelChar::Void
+                };
+            }
+            col_index += segment_display_width;
+        }
+    }
+
+    clip_state.move_cursor_forward(ch!(@to_usize text.display_width) as isize);
+}
+
+/// Paint the edges of the rectangle w/ the given (translated) `origin` & `size`, clipped to the
+/// clip rect of the `clip_state`, and then paint the title over the top edge (see [Border]). A
+/// box drawing character that is already in a cell is merged w/ the one that is painted over it
+/// (see [BoxGlyph::merge]). The colors that are currently set in `my_offscreen_buffer` are only
+/// changed while the border is painted.
+///
+/// ```text
+/// origin
+/// ↓
+/// ┌Title───┐ ← title is painted after the edges
+/// │        │
+/// └────────┘
+/// ```
+pub fn print_border(
+    origin: Position,
+    size: Size,
+    border: &Border,
+    my_offscreen_buffer: &mut OffscreenBuffer,
+    clip_state: &mut ClipState,
+) {
+    if *size.col_count == 0 || *size.row_count == 0 {
+        return;
+    }
+
+    let saved_colors = (
+        my_offscreen_buffer.my_fg_color,
+        my_offscreen_buffer.my_bg_color,
+    );
+    let maybe_border_style_ref = &border.maybe_style.map(|mut it| {
+        my_offscreen_buffer.my_fg_color = it.color_fg;
+        my_offscreen_buffer.my_bg_color = it.color_bg;
+        it.padding = None;
+        it.border = None;
+        it
+    });
+    let maybe_style =
+        get_style_for_pixel_chars(maybe_border_style_ref, my_offscreen_buffer);
+
+    let clip_rect = clip_state.get_clip_rect(my_offscreen_buffer.window_size);
+    clip_state.move_cursor_to(origin);
+    let (first_col_index, first_row_index) = clip_state.get_cursor();
+    let last_col_index = first_col_index + ch!(@to_usize size.col_count) as isize - 1;
+    let last_row_index = first_row_index + ch!(@to_usize size.row_count) as isize - 1;
+
+    for row_index in first_row_index..=last_row_index {
+        if !clip_rect.contains_row(row_index) {
+            continue;
+        }
+        let Some(line) = my_offscreen_buffer.buffer.get_mut(row_index as usize) else {
+            continue;
+        };
+        let is_first_or_last_row =
+            row_index == first_row_index || row_index == last_row_index;
+        for col_index in first_col_index..=last_col_index {
+            let is_first_or_last_col =
+                col_index == first_col_index || col_index == last_col_index;
+            if !(is_first_or_last_row || is_first_or_last_col)
+                || !clip_rect.contains_col(col_index)
+            {
+                continue;
+            }
+            let mut glyph = BoxGlyph::new(
+                border.border_style,
+                /* up */ is_first_or_last_col && row_index > first_row_index,
+                /* down */ is_first_or_last_col && row_index < last_row_index,
+                /* left */ is_first_or_last_row && col_index > first_col_index,
+                /* right */ is_first_or_last_row && col_index < last_col_index,
+            );
+            if glyph.is_empty() {
+                continue;
+            }
+            if let Some(PixelChar::PlainText { content, .. }) =
+                line.get(col_index as usize)
+            {
+                if let Some(existing_glyph) = BoxGlyph::try_from_str(&content.string) {
+                    glyph = existing_glyph.merge(&glyph);
+                }
+            }
+            replace_pixel_char(
+                line,
+                col_index as usize,
+                make_pixel_char(glyph.as_str(), &maybe_style),
+            );
+        }
+    }
+
+    if let Some(title) = &border.maybe_title {
+        let inner_col_count = size.col_count - 2;
+        if *inner_col_count > 0 {
+            let (offset, text) = title.get_offset_and_text(inner_col_count);
+            clip_state.move_cursor_to(position!(
+                col_index: origin.col_index + 1 + offset,
+                row_index: origin.row_index
+            ));
+            print_clipped_text(
+                &text,
+                maybe_border_style_ref,
+                my_offscreen_buffer,
+                clip_state,
+            );
+        }
+    }
+
+    (
+        my_offscreen_buffer.my_fg_color,
+        my_offscreen_buffer.my_bg_color,
+    ) = saved_colors;
+}
+
+/// Replace the [PixelChar] at `col_index` w/ `pixel_char`. If this cuts a wide grapheme cluster,
+/// then the rest of it is replaced w/ spacers. If `pixel_char` is a wide grapheme cluster, then
+/// the caller has to paint the [PixelChar::Void]s after it.
+fn replace_pixel_char(line: &mut PixelCharLine, col_index: usize, pixel_char: PixelChar) {
+    if matches!(line.get(col_index), Some(PixelChar::Void)) {
+        let mut start_col_index = col_index;
+        while start_col_index > 0 && matches!(line[start_col_index], PixelChar::Void) {
+            start_col_index -= 1;
+        }
+        for it in start_col_index..col_index {
+            line[it] = PixelChar::Spacer;
+        }
+    }
+    let mut it = col_index + 1;
+    while matches!(line.get(it), Some(PixelChar::Void)) {
+        line[it] = PixelChar::Spacer;
+        it += 1;
+    }
+    if let Some(it) = line.get_mut(col_index) {
+        *it = pixel_char;
+    }
+}
+
 /// This diagram shows what happens per line of text.
 ///
 /// `my_offscreen_buffer[my_pos.row_index]` is the line.
@@ -216,26 +617,7 @@ pub async fn print_plain_text(
     let mut insertion_col_index = display_col_index;
     let mut already_inserted_display_width = ch!(0);
 
-    let maybe_style: Option<Style> = {
-        if let Some(maybe_style) = maybe_style_ref {
-            // We get the attributes from `maybe_style_ref`.
-            let mut it = *maybe_style;
-            // We get the colors from `my_fg_color` and `my_bg_color`.
-            it.color_fg = my_offscreen_buffer.my_fg_color;
-            it.color_bg = my_offscreen_buffer.my_bg_color;
-            Some(it)
-        } else if my_offscreen_buffer.my_fg_color.is_some()
-            || my_offscreen_buffer.my_bg_color.is_some()
-        {
-            Some(Style {
-                color_fg: my_offscreen_buffer.my_fg_color,
-                color_bg: my_offscreen_buffer.my_bg_color,
-                ..Default::default()
-            })
-        } else {
-            None
-        }
-    };
+    let maybe_style = get_style_for_pixel_chars(maybe_style_ref, my_offscreen_buffer);
 
     call_if_true!(
         DEBUG_TUI_COMPOSITOR,
@@ -262,17 +644,7 @@ pub async fn print_plain_text(
 
         // Set the `PixelChar` at `insertion_col_index`.
         if line_copy.get(insertion_col_index).is_some() {
-            let pixel_char = {
-                let new_gc_segment =
-                    GraphemeClusterSegment::from(gc_segment.string.as_ref());
-                match (&maybe_style, new_gc_segment.string.as_str()) {
-                    (None, SPACER) => PixelChar::Spacer,
-                    _ => PixelChar::PlainText {
-                        content: new_gc_segment,
-                        maybe_style,
-                    },
-                }
-            };
+            let pixel_char = make_pixel_char(&gc_segment.string, &maybe_style);
 
             if line_copy.get(insertion_col_index).is_some() {
                 line_copy[insertion_col_index] = pixel_char;
@@ -984,4 +1356,178 @@ mod tests {
             assert_eq2!(my_offscreen_buffer.buffer[1][9], PixelChar::Spacer);
         }
     }
+
+    #[tokio::test]
+    async fn test_convert_clip_rect_and_translate() {
+        let window_size = size! { col_count: 10, row_count: 3 };
+        let shared_global_data = make_shared_global_data(window_size.into());
+
+        // A 5x2 viewport at (2, 0), into a canvas that is scrolled to (1, 10).
+        let origin = position! { col_index: 2, row_index: 0 };
+        let pipeline = render_pipeline!(@new ZOrder::Normal =>
+            RenderOp::PushClipRect(origin, size! { col_count: 5, row_count: 2 }),
+            RenderOp::PushTranslate(origin, position! { col_index: 1, row_index: 10 }),
+            // Screen cols 1..9, the 2nd 😃 is cut by the right edge.
+            RenderOp::MoveCursorPositionAbs(position! { col_index: 0, row_index: 10 }),
+            RenderOp::PaintTextWithAttributes("a😃bc😃d".to_string(), None),
+            // A nested translation, scrolled 1 more col to the right. Screen cols 0..8, the 1st
+            // 😃 is cut by the left edge.
+            RenderOp::PushTranslate(
+                position! { col_index: 0, row_index: 0 },
+                position! { col_index: 1, row_index: 0 }
+            ),
+            RenderOp::MoveCursorPositionAbs(position! { col_index: 0, row_index: 11 }),
+            RenderOp::PaintTextWithAttributes("a😃bc😃d".to_string(), None),
+            RenderOp::PopTranslate,
+            // Below the viewport.
+            RenderOp::MoveCursorPositionAbs(position! { col_index: 0, row_index: 12 }),
+            RenderOp::PaintTextWithAttributes("hidden".to_string(), None),
+            RenderOp::PopTranslate,
+            RenderOp::PopClipRect,
+            // Not clipped or translated anymore.
+            RenderOp::MoveCursorPositionAbs(position! { col_index: 0, row_index: 2 }),
+            RenderOp::PaintTextWithAttributes("ok".to_string(), None)
+        );
+
+        let my_offscreen_buffer = pipeline.convert(&shared_global_data).await;
+        assert_eq2!(my_offscreen_buffer.get_row_text(0).unwrap(), "  😃bc    ");
+        assert_eq2!(my_offscreen_buffer.buffer[0][3], PixelChar::Void);
+        assert_eq2!(my_offscreen_buffer.buffer[0][6], PixelChar::Spacer);
+        assert_eq2!(my_offscreen_buffer.get_row_text(1).unwrap(), "   bc😃   ");
+        assert_eq2!(my_offscreen_buffer.buffer[1][2], PixelChar::Spacer);
+        assert_eq2!(my_offscreen_buffer.get_row_text(2).unwrap(), "ok        ");
+    }
+
+    #[tokio::test]
+    async fn test_convert_border() {
+        let window_size = size! { col_count: 10, row_count: 4 };
+        let shared_global_data = make_shared_global_data(window_size.into());
+
+        let border = |maybe_title: Option<BorderTitle>| Border {
+            border_style: BorderStyle::Single,
+            maybe_title,
+            maybe_style: None,
+        };
+
+        // 2 boxes that share their middle column, and a 3rd one that is clipped.
+        let pipeline = render_pipeline!(@new ZOrder::Normal =>
+            RenderOp::MoveCursorPositionAbs(position! { col_index: 6, row_index: 0 }),
+            RenderOp::PaintTextWithAttributes("😃".to_string(), None),
+            RenderOp::PaintBorder(
+                position! { col_index: 0, row_index: 0 },
+                size! { col_count: 6, row_count: 3 },
+                border(Some(BorderTitle::new("abcdef", BorderTitleAlignment::Left)))
+            ),
+            RenderOp::PaintBorder(
+                position! { col_index: 5, row_index: 0 },
+                size! { col_count: 5, row_count: 3 },
+                border(None)
+            ),
+            RenderOp::PushClipRect(
+                position! { col_index: 0, row_index: 3 },
+                size! { col_count: 3, row_count: 1 }
+            ),
+            RenderOp::PaintBorder(
+                position! { col_index: 0, row_index: 3 },
+                size! { col_count: 5, row_count: 2 },
+                Border {
+                    border_style: BorderStyle::Rounded,
+                    ..Default::default()
+                }
+            ),
+            RenderOp::PopClipRect
+        );
+
+        let my_offscreen_buffer = pipeline.convert(&shared_global_data).await;
+        assert_eq2!(my_offscreen_buffer.get_row_text(0).unwrap(), "┌abcd┬───┐");
+        assert_eq2!(my_offscreen_buffer.get_row_text(1).unwrap(), "│    │   │");
+        assert_eq2!(my_offscreen_buffer.get_row_text(2).unwrap(), "└────┴───┘");
+        assert_eq2!(my_offscreen_buffer.get_row_text(3).unwrap(), "╭──       ");
+        assert_eq2!(
+            my_offscreen_buffer.my_pos,
+            position! { col_index: 0, row_index: 3 }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_convert_z_orders_and_transparency() {
+        let window_size = size! { col_count: 6, row_count: 3 };
+        let shared_global_data = make_shared_global_data(window_size.into());
+        let shadow_color = color!(@dark_grey);
+
+        let mut pipeline = render_pipeline!(@new ZOrder::Normal =>
+            RenderOp::MoveCursorPositionAbs(position! { col_index: 0, row_index: 0 }),
+            RenderOp::PaintTextWithAttributes("abcdef".to_string(), None),
+            RenderOp::MoveCursorPositionAbs(position! { col_index: 0, row_index: 1 }),
+            RenderOp::PaintTextWithAttributes("ghijkl".to_string(), None)
+        );
+        // Painted above the Glass layer, even though it is added first.
+        render_pipeline!(@push_into pipeline at ZOrder::Glass.above(1) =>
+            RenderOp::ResetColor,
+            RenderOp::MoveCursorPositionAbs(position! { col_index: 0, row_index: 2 }),
+            RenderOp::PaintTextWithAttributes("X".to_string(), None)
+        );
+        render_pipeline!(@push_into pipeline at ZOrder::Glass =>
+            RenderOp::ResetColor,
+            RenderOp::MoveCursorPositionAbs(position! { col_index: 0, row_index: 2 }),
+            RenderOp::PaintTextWithAttributes("YY".to_string(), None),
+            RenderOp::MoveCursorPositionAbs(position! { col_index: 5, row_index: 1 }),
+            RenderOp::PaintTextWithAttributes("Z".to_string(), None)
+        );
+        // A drop shadow, w/ plain spaces that let the cells beneath them show through.
+        render_pipeline!(@push_into pipeline at ZOrder::Glass.below(1) =>
+            RenderOp::MoveCursorPositionAbs(position! { col_index: 4, row_index: 0 }),
+            RenderOp::PaintTextWithAttributes("  ".to_string(), None),
+            RenderOp::SetBgColor(shadow_color),
+            RenderOp::MoveCursorPositionAbs(position! { col_index: 1, row_index: 1 }),
+            RenderOp::PaintTextWithAttributes("  ".to_string(), None)
+        );
+        pipeline.set_transparent(ZOrder::Glass.below(1), true);
+        pipeline.set_transparent(ZOrder::Glass, true);
+
+        let my_offscreen_buffer = pipeline.convert(&shared_global_data).await;
+        assert_eq2!(my_offscreen_buffer.get_row_text(0).unwrap(), "abcdef");
+        assert_eq2!(my_offscreen_buffer.get_row_text(1).unwrap(), "ghijkZ");
+        assert_eq2!(my_offscreen_buffer.get_row_text(2).unwrap(), "XY    ");
+
+        let get_color_bg =
+            |col_index: usize| match &my_offscreen_buffer.buffer[1][col_index] {
+                PixelChar::PlainText { maybe_style, .. } => {
+                    maybe_style.and_then(|it| it.color_bg)
+                }
+                _ => None,
+            };
+        assert_eq2!(get_color_bg(0), None);
+        assert_eq2!(get_color_bg(1), Some(shadow_color));
+        assert_eq2!(get_color_bg(2), Some(shadow_color));
+        assert_eq2!(get_color_bg(3), None);
+    }
+
+    #[tokio::test]
+    async fn test_convert_transparent_layer_over_wide_grapheme_cluster() {
+        let window_size = size! { col_count: 6, row_count: 2 };
+        let shared_global_data = make_shared_global_data(window_size.into());
+
+        let mut pipeline = render_pipeline!(@new ZOrder::Normal =>
+            RenderOp::MoveCursorPositionAbs(position! { col_index: 0, row_index: 0 }),
+            RenderOp::PaintTextWithAttributes("a😃bcd".to_string(), None),
+            RenderOp::MoveCursorPositionAbs(position! { col_index: 0, row_index: 1 }),
+            RenderOp::PaintTextWithAttributes("a😃bcd".to_string(), None)
+        );
+        // The first row is cut by the tail of a wide grapheme cluster, and the second one by
+        // a narrow one that is painted over the tail of the one beneath it.
+        render_pipeline!(@push_into pipeline at ZOrder::Glass =>
+            RenderOp::MoveCursorPositionAbs(position! { col_index: 0, row_index: 0 }),
+            RenderOp::PaintTextWithAttributes("😃".to_string(), None),
+            RenderOp::MoveCursorPositionAbs(position! { col_index: 2, row_index: 1 }),
+            RenderOp::PaintTextWithAttributes("x".to_string(), None)
+        );
+        pipeline.set_transparent(ZOrder::Glass, true);
+
+        let my_offscreen_buffer = pipeline.convert(&shared_global_data).await;
+        assert_eq2!(my_offscreen_buffer.get_row_text(0).unwrap(), "😃 bcd");
+        assert_eq2!(my_offscreen_buffer.get_row_text(1).unwrap(), "a xbcd");
+        assert_eq2!(my_offscreen_buffer.buffer[0][2], PixelChar::Spacer);
+        assert_eq2!(my_offscreen_buffer.buffer[1][1], PixelChar::Spacer);
+    }
 }
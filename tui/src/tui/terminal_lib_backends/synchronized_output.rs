@@ -0,0 +1,122 @@
+/*
+ *   Copyright (c) 2023 R3BL LLC
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+//! Synchronized output (DEC private mode 2026) lets the terminal hold off on updating the screen
+//! until an entire frame has been written, so a partially painted frame is never displayed
+//! (tearing). Terminals that don't support it ignore the escape sequences. More info:
+//! - <https://gist.github.com/christianparpart/d8a62cc1ab659194337d73e399004036>
+//! - <https://vt100.net/docs/vt510-rm/DECRQM.html>
+
+use std::time::Duration;
+
+use crate::{synchronized_output_global_static::{clear_synchronized_output_override,
+                                                get_synchronized_output,
+                                                set_synchronized_output_override},
+            *};
+
+/// How long to wait for the terminal to respond to the DECRQM query.
+pub const DECRQM_QUERY_TIMEOUT: Duration = Duration::from_millis(100);
+
+/// Begin synchronized update (BSU).
+pub const BEGIN_SYNCHRONIZED_UPDATE_SEQUENCE: &str = "\x1b[?2026h";
+/// End synchronized update (ESU).
+pub const END_SYNCHRONIZED_UPDATE_SEQUENCE: &str = "\x1b[?2026l";
+
+/// Whether each frame is wrapped in [BEGIN_SYNCHRONIZED_UPDATE_SEQUENCE] and
+/// [END_SYNCHRONIZED_UPDATE_SEQUENCE] when it is painted. Use [SynchronizedOutput::get] to find
+/// out which one is in use.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum SynchronizedOutput {
+    Enabled,
+    Disabled,
+}
+
+impl SynchronizedOutput {
+    /// Ask the terminal whether it supports DEC mode 2026 using a DECRQM query. If the terminal
+    /// doesn't respond (or stdin & stdout aren't connected to a terminal), then
+    /// [SynchronizedOutput::Disabled] is assumed.
+    ///
+    /// The terminal is put in raw mode while waiting for the response, and the response is read
+    /// from stdin. So this must be called before any other code starts reading input events (eg:
+    /// before [TerminalWindow::main_event_loop] starts its event stream).
+    pub fn detect() -> SynchronizedOutput {
+        try_query_terminal(b"\x1b[?2026$p", DECRQM_QUERY_TIMEOUT)
+            .and_then(|response| parse_decrqm_2026_response(&response))
+            .unwrap_or(SynchronizedOutput::Disabled)
+    }
+
+    pub fn set_override(synchronized_output: SynchronizedOutput) {
+        set_synchronized_output_override(synchronized_output);
+    }
+
+    pub fn clear_override() { clear_synchronized_output_override(); }
+
+    /// Returns the override if it is set (using
+    /// [set_override](SynchronizedOutput::set_override)). Otherwise the value that was detected
+    /// when [TerminalWindow::main_event_loop] started is used. If neither is available, then
+    /// [SynchronizedOutput::Disabled] is used.
+    pub fn get() -> SynchronizedOutput { get_synchronized_output() }
+
+    pub fn is_enabled() -> bool {
+        SynchronizedOutput::get() == SynchronizedOutput::Enabled
+    }
+}
+
+/// Parse the response to a DECRQM query for mode 2026, eg: `ESC [ ? 2026 ; 2 $ y`. The 2nd
+/// parameter is the state of the mode:
+/// - `0`: not recognized, `4`: permanently reset -> [SynchronizedOutput::Disabled].
+/// - `1`: set, `2`: reset, `3`: permanently set -> [SynchronizedOutput::Enabled].
+pub fn parse_decrqm_2026_response(response: &str) -> Option<SynchronizedOutput> {
+    let start = response.find("\x1b[?2026;")?;
+    let rest = &response[start + "\x1b[?2026;".len()..];
+    let end = rest.find("$y")?;
+    match rest[..end].parse::<u8>().ok()? {
+        1..=3 => Some(SynchronizedOutput::Enabled),
+        0 | 4 => Some(SynchronizedOutput::Disabled),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use r3bl_rs_utils_core::*;
+
+    use super::*;
+
+    #[test]
+    fn test_parse_decrqm_2026_response() {
+        // Supported, followed by the DA1 response.
+        assert_eq2!(
+            parse_decrqm_2026_response("\x1b[?2026;2$y\x1b[?62;22c"),
+            Some(SynchronizedOutput::Enabled)
+        );
+        assert_eq2!(
+            parse_decrqm_2026_response("\x1b[?2026;1$y"),
+            Some(SynchronizedOutput::Enabled)
+        );
+        // Not recognized.
+        assert_eq2!(
+            parse_decrqm_2026_response("\x1b[?2026;0$y"),
+            Some(SynchronizedOutput::Disabled)
+        );
+        // No DECRQM response, only the DA1 response.
+        assert_eq2!(parse_decrqm_2026_response("\x1b[?62;22c"), None);
+        // Malformed.
+        assert_eq2!(parse_decrqm_2026_response("\x1b[?2026;$y"), None);
+        assert_eq2!(parse_decrqm_2026_response("\x1b[?2026;2"), None);
+    }
+}
@@ -15,11 +15,101 @@
  *   limitations under the License.
  */
 
+use std::time::Duration;
+
 use r3bl_rs_utils_core::*;
 
-/// Interrogate crossterm [crossterm::terminal::size()] to get the size of the terminal window.
+use crate::*;
+
+/// Interrogate the [TerminalLibBackend] to get the size of the terminal window.
 pub fn lookup_size() -> CommonResult<Size> {
-    let (col, row) = crossterm::terminal::size()?;
+    let (col, row) = match TerminalLibBackend::get() {
+        TerminalLibBackend::Crossterm => crossterm::terminal::size()?,
+        #[cfg(all(unix, feature = "termion"))]
+        TerminalLibBackend::Termion => termion::terminal_size()?,
+    };
     let size: Size = size!(col_count: col, row_count: row);
     Ok(size)
 }
+
+/// Write the `query` to the terminal, followed by a primary device attributes (DA1) query, and
+/// return everything the terminal responds with. Almost all terminals respond to DA1, so once that
+/// response arrives, there's no need to wait for the `timeout` (when the terminal doesn't support
+/// the `query`).
+///
+/// Returns [None] if stdin & stdout aren't connected to a terminal. The terminal is put in raw
+/// mode while waiting for the response, and the response is read from `/dev/tty`. So this must be
+/// called before any other code starts reading input events.
+#[cfg(unix)]
+pub fn try_query_terminal(query: &[u8], timeout: Duration) -> Option<String> {
+    use std::{fs::OpenOptions,
+              io::{stdin, stdout, Read, Write},
+              os::unix::io::AsRawFd,
+              time::Instant};
+
+    use crossterm::terminal::{disable_raw_mode, enable_raw_mode, is_raw_mode_enabled};
+    use is_terminal::IsTerminal;
+
+    if !stdin().is_terminal() || !stdout().is_terminal() {
+        return None;
+    }
+
+    let mut tty = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open("/dev/tty")
+        .ok()?;
+
+    let was_raw_mode_enabled = is_raw_mode_enabled().unwrap_or(false);
+    if !was_raw_mode_enabled {
+        enable_raw_mode().ok()?;
+    }
+
+    let mut response = Vec::<u8>::new();
+    let start = Instant::now();
+    if tty.write_all(query).is_ok()
+        && tty.write_all(b"\x1b[c").is_ok()
+        && tty.flush().is_ok()
+    {
+        let mut buffer = [0_u8; 64];
+        while let Some(remaining) = timeout.checked_sub(start.elapsed()) {
+            let mut poll_fd = libc::pollfd {
+                fd: tty.as_raw_fd(),
+                events: libc::POLLIN,
+                revents: 0,
+            };
+            // SAFETY: `poll_fd` is a valid `pollfd` that outlives the call, `nfds` is 1, and `tty`
+            // keeps its file descriptor open.
+            let poll_result = unsafe {
+                libc::poll(&mut poll_fd, 1, remaining.as_millis() as libc::c_int)
+            };
+            if poll_result <= 0 {
+                break;
+            }
+            match tty.read(&mut buffer) {
+                Ok(0) | Err(_) => break,
+                Ok(count) => response.extend_from_slice(&buffer[..count]),
+            }
+            if is_da1_response_complete(&response) {
+                break;
+            }
+        }
+    }
+
+    if !was_raw_mode_enabled {
+        disable_raw_mode().ok();
+    }
+
+    return Some(String::from_utf8_lossy(&response).to_string());
+
+    /// The DA1 response looks like `ESC [ ? 6 2 ; 2 2 c`.
+    fn is_da1_response_complete(response: &[u8]) -> bool {
+        let Some(start) = response.windows(3).position(|it| it == b"\x1b[?") else {
+            return false;
+        };
+        response[start..].contains(&b'c')
+    }
+}
+
+#[cfg(not(unix))]
+pub fn try_query_terminal(_query: &[u8], _timeout: Duration) -> Option<String> { None }
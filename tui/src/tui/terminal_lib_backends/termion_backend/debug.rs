@@ -0,0 +1,30 @@
+/*
+ *   Copyright (c) 2022 R3BL LLC
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+use std::fmt::{Formatter, Result};
+
+use crate::*;
+
+/// The [RenderOp]s are the same for all backends, so this produces the same output as
+/// [CrosstermDebugFormatRenderOp].
+pub struct TermionDebugFormatRenderOp;
+
+impl DebugFormatRenderOp for TermionDebugFormatRenderOp {
+    fn debug_format(&self, this: &RenderOp, f: &mut Formatter<'_>) -> Result {
+        CrosstermDebugFormatRenderOp {}.debug_format(this, f)
+    }
+}
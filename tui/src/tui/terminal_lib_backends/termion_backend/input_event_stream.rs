@@ -0,0 +1,399 @@
+/*
+ *   Copyright (c) 2022 R3BL LLC
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+use std::{io,
+          os::unix::io::RawFd,
+          sync::{atomic::{AtomicBool, Ordering},
+                 Arc},
+          thread::{self, JoinHandle},
+          time::Duration};
+
+use r3bl_rs_utils_core::*;
+use termion::event::{parse_event, Event, Key as TermionKey, MouseButton, MouseEvent};
+use tokio::{signal::unix::{signal, Signal, SignalKind},
+            sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender}};
+
+use crate::*;
+
+/// How long the reader thread waits for input before it checks whether it has been stopped.
+const READER_THREAD_POLL_TIMEOUT: Duration = Duration::from_millis(50);
+
+/// termion only provides a blocking iterator over stdin events. So a thread is spawned to read
+/// them, and they are sent over a channel to this struct, which can be awaited (just like
+/// crossterm's `EventStream`). termion doesn't report terminal resizes as input events, so the
+/// `SIGWINCH` signal is used to generate [InputEvent::Resize].
+///
+/// Dropping this struct stops the reader thread, and waits for it to finish (which takes at most
+/// [READER_THREAD_POLL_TIMEOUT]). So once the event loop is done, no more input is read, and the
+/// next keystroke goes to whatever reads stdin next (eg: the shell).
+pub struct TermionEventStream {
+    receiver: UnboundedReceiver<Event>,
+    maybe_resize_signal: Option<Signal>,
+    _reader_thread: ReaderThread,
+}
+
+impl Default for TermionEventStream {
+    fn default() -> Self {
+        let (sender, receiver) = unbounded_channel();
+        Self {
+            receiver,
+            maybe_resize_signal: signal(SignalKind::window_change()).ok(),
+            _reader_thread: ReaderThread::spawn(libc::STDIN_FILENO, sender),
+        }
+    }
+}
+
+/// A thread that reads [Event]s from a file descriptor until it is dropped, the receiver is
+/// dropped, or the end of the input is reached. Instead of blocking on a read, it polls for input
+/// w/ a timeout, so that it can check whether it has been stopped.
+struct ReaderThread {
+    is_stopped: Arc<AtomicBool>,
+    maybe_join_handle: Option<JoinHandle<()>>,
+}
+
+impl ReaderThread {
+    fn spawn(fd: RawFd, sender: UnboundedSender<Event>) -> Self {
+        let is_stopped = Arc::new(AtomicBool::new(false));
+        let join_handle = thread::spawn({
+            let is_stopped = is_stopped.clone();
+            move || read_events(fd, &sender, &is_stopped)
+        });
+        Self {
+            is_stopped,
+            maybe_join_handle: Some(join_handle),
+        }
+    }
+}
+
+impl Drop for ReaderThread {
+    fn drop(&mut self) {
+        self.is_stopped.store(true, Ordering::Release);
+        if let Some(join_handle) = self.maybe_join_handle.take() {
+            join_handle.join().ok();
+        }
+    }
+}
+
+fn read_events(fd: RawFd, sender: &UnboundedSender<Event>, is_stopped: &AtomicBool) {
+    let mut buffer = [0_u8; 1024];
+    while !is_stopped.load(Ordering::Acquire) {
+        let mut poll_fd = libc::pollfd {
+            fd,
+            events: libc::POLLIN,
+            revents: 0,
+        };
+        // SAFETY: `poll_fd` is a valid `pollfd` that lives for the duration of the call, and the
+        // count of 1 matches the single `pollfd` that is passed.
+        let poll_result = unsafe {
+            libc::poll(
+                &mut poll_fd,
+                1,
+                READER_THREAD_POLL_TIMEOUT.as_millis() as libc::c_int,
+            )
+        };
+        if poll_result == 0 || (poll_result < 0 && is_interrupted()) {
+            continue;
+        }
+
+        // SAFETY: `buffer` is valid for writes of `buffer.len()` bytes, and it isn't aliased
+        // while the call is in progress.
+        let byte_count =
+            unsafe { libc::read(fd, buffer.as_mut_ptr().cast(), buffer.len()) };
+        if poll_result < 0 || byte_count <= 0 {
+            if byte_count < 0 && is_interrupted() {
+                continue;
+            }
+            // The end of the input has been reached, or it can't be read.
+            call_if_true!(DEBUG_TUI_SHOW_TERMINAL_BACKEND, {
+                log_error(format!(
+                    "termion: ❌ Stopped reading events due to {}",
+                    io::Error::last_os_error()
+                ));
+            });
+            break;
+        }
+
+        for event in parse_events(&buffer[..byte_count as usize]) {
+            // The receiver has been dropped, so stop reading.
+            if sender.send(event).is_err() {
+                return;
+            }
+        }
+    }
+
+    fn is_interrupted() -> bool {
+        io::Error::last_os_error().kind() == io::ErrorKind::Interrupted
+    }
+}
+
+/// Parse all the [Event]s in `bytes`, which were read from the terminal at once. Just like
+/// termion's own iterator, an `ESC` that isn't followed by anything is the escape key (and not the
+/// start of an escape sequence).
+fn parse_events(bytes: &[u8]) -> Vec<Event> {
+    let mut acc = vec![];
+    let mut iter = bytes.iter().map(|byte| Ok(*byte));
+    while let Some(Ok(byte)) = iter.next() {
+        if byte == b'\x1B' && iter.len() == 0 {
+            acc.push(Event::Key(TermionKey::Esc));
+            continue;
+        }
+        match parse_event(byte, &mut iter) {
+            Ok(event) => acc.push(event),
+            Err(error) => call_if_true!(DEBUG_TUI_SHOW_TERMINAL_BACKEND, {
+                log_error(format!("termion: ❌ Failed to parse event due to {error}"));
+            }),
+        }
+    }
+    acc
+}
+
+impl TermionEventStream {
+    /// Wait for the next [Event] (or terminal resize), and convert it into an [InputEvent]. [None]
+    /// is returned if the event can't be converted.
+    pub async fn try_to_get_input_event(&mut self) -> Option<InputEvent> {
+        let resize = async {
+            match self.maybe_resize_signal.as_mut() {
+                Some(resize_signal) => resize_signal.recv().await,
+                None => std::future::pending().await,
+            }
+        };
+
+        tokio::select! {
+            maybe_event = self.receiver.recv() => match maybe_event {
+                Some(event) => event.try_into().ok(),
+                // stdin has been closed, so there will never be any more events.
+                None => std::future::pending().await,
+            },
+            _ = resize => lookup_size().ok().map(InputEvent::Resize),
+        }
+    }
+}
+
+impl TryFrom<Event> for InputEvent {
+    type Error = ();
+    /// Typecast / convert termion [Event] to [InputEvent].
+    fn try_from(event: Event) -> Result<Self, Self::Error> {
+        match event {
+            Event::Key(key) => Ok(InputEvent::Keyboard(key.try_into()?)),
+            Event::Mouse(mouse_event) => Ok(InputEvent::Mouse(mouse_event.into())),
+            Event::Unsupported(_) => Err(()),
+        }
+    }
+}
+
+impl TryFrom<TermionKey> for KeyPress {
+    type Error = ();
+    /// Typecast / convert termion [TermionKey] to [KeyPress]. In raw mode, termion reports the
+    /// enter key as `'\n'` and the tab key as `'\t'`. It doesn't report shift & ctrl w/ the special
+    /// keys.
+    fn try_from(key: TermionKey) -> Result<Self, Self::Error> {
+        let it = match key {
+            TermionKey::Char('\n') => keypress!(@special SpecialKey::Enter),
+            TermionKey::Char('\t') => keypress!(@special SpecialKey::Tab),
+            TermionKey::Char(character) => keypress!(@char character),
+            TermionKey::Ctrl(character) => {
+                keypress!(@char ModifierKeysMask::CTRL, character)
+            }
+            TermionKey::Alt(character) => {
+                keypress!(@char ModifierKeysMask::ALT, character)
+            }
+            // Ctrl + Space.
+            TermionKey::Null => keypress!(@char ModifierKeysMask::CTRL, ' '),
+            TermionKey::Backspace => keypress!(@special SpecialKey::Backspace),
+            TermionKey::Left => keypress!(@special SpecialKey::Left),
+            TermionKey::Right => keypress!(@special SpecialKey::Right),
+            TermionKey::Up => keypress!(@special SpecialKey::Up),
+            TermionKey::Down => keypress!(@special SpecialKey::Down),
+            TermionKey::Home => keypress!(@special SpecialKey::Home),
+            TermionKey::End => keypress!(@special SpecialKey::End),
+            TermionKey::PageUp => keypress!(@special SpecialKey::PageUp),
+            TermionKey::PageDown => keypress!(@special SpecialKey::PageDown),
+            TermionKey::BackTab => keypress!(@special SpecialKey::BackTab),
+            TermionKey::Delete => keypress!(@special SpecialKey::Delete),
+            TermionKey::Insert => keypress!(@special SpecialKey::Insert),
+            TermionKey::Esc => keypress!(@special SpecialKey::Esc),
+            TermionKey::F(number) => keypress!(@fn convert_function_key(number)?),
+            _ => return Err(()),
+        };
+        Ok(it)
+    }
+}
+
+fn convert_function_key(number: u8) -> Result<FunctionKey, ()> {
+    match number {
+        1 => Ok(FunctionKey::F1),
+        2 => Ok(FunctionKey::F2),
+        3 => Ok(FunctionKey::F3),
+        4 => Ok(FunctionKey::F4),
+        5 => Ok(FunctionKey::F5),
+        6 => Ok(FunctionKey::F6),
+        7 => Ok(FunctionKey::F7),
+        8 => Ok(FunctionKey::F8),
+        9 => Ok(FunctionKey::F9),
+        10 => Ok(FunctionKey::F10),
+        11 => Ok(FunctionKey::F11),
+        12 => Ok(FunctionKey::F12),
+        _ => Err(()),
+    }
+}
+
+impl From<MouseEvent> for MouseInput {
+    /// Typecast / convert termion [MouseEvent] to [MouseInput]. termion's coordinates are one
+    /// based, and it doesn't report which button was released or is being dragged (so
+    /// [Button::Left] is assumed).
+    fn from(mouse_event: MouseEvent) -> Self {
+        let (kind, col, row) = match mouse_event {
+            MouseEvent::Press(MouseButton::WheelUp, col, row) => {
+                (MouseInputKind::ScrollUp, col, row)
+            }
+            MouseEvent::Press(MouseButton::WheelDown, col, row) => {
+                (MouseInputKind::ScrollDown, col, row)
+            }
+            MouseEvent::Press(MouseButton::Left, col, row) => {
+                (MouseInputKind::MouseDown(Button::Left), col, row)
+            }
+            MouseEvent::Press(MouseButton::Right, col, row) => {
+                (MouseInputKind::MouseDown(Button::Right), col, row)
+            }
+            MouseEvent::Press(MouseButton::Middle, col, row) => {
+                (MouseInputKind::MouseDown(Button::Middle), col, row)
+            }
+            MouseEvent::Release(col, row) => {
+                (MouseInputKind::MouseUp(Button::Left), col, row)
+            }
+            MouseEvent::Hold(col, row) => {
+                (MouseInputKind::MouseDrag(Button::Left), col, row)
+            }
+        };
+        MouseInput {
+            pos: position!(col_index: col.saturating_sub(1), row_index: row.saturating_sub(1)),
+            kind,
+            maybe_modifier_keys: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_convert_key() {
+        let convert = |it: TermionKey| -> Result<KeyPress, ()> { it.try_into() };
+        assert_eq2!(convert(TermionKey::Char('x')), Ok(keypress!(@char 'x')));
+        assert_eq2!(
+            convert(TermionKey::Char('\n')),
+            Ok(keypress!(@special SpecialKey::Enter))
+        );
+        assert_eq2!(
+            convert(TermionKey::Ctrl('q')),
+            Ok(keypress!(@char ModifierKeysMask::CTRL, 'q'))
+        );
+        assert_eq2!(
+            convert(TermionKey::Alt('b')),
+            Ok(keypress!(@char ModifierKeysMask::ALT, 'b'))
+        );
+        assert_eq2!(
+            convert(TermionKey::F(12)),
+            Ok(keypress!(@fn FunctionKey::F12))
+        );
+        assert_eq2!(convert(TermionKey::F(13)), Err(()));
+    }
+
+    #[test]
+    fn test_convert_mouse_event() {
+        let input_event: InputEvent =
+            Event::Mouse(MouseEvent::Press(MouseButton::Left, 1, 5))
+                .try_into()
+                .unwrap();
+        assert_eq2!(
+            input_event,
+            InputEvent::Mouse(MouseInput {
+                pos: position!(col_index: 0, row_index: 4),
+                kind: MouseInputKind::MouseDown(Button::Left),
+                maybe_modifier_keys: None,
+            })
+        );
+
+        let mouse_input: MouseInput =
+            MouseEvent::Press(MouseButton::WheelDown, 3, 3).into();
+        assert_eq2!(mouse_input.kind, MouseInputKind::ScrollDown);
+    }
+
+    #[test]
+    fn test_parse_events() {
+        assert_eq2!(
+            parse_events(b"a\x1b[A\x1b"),
+            vec![
+                Event::Key(TermionKey::Char('a')),
+                Event::Key(TermionKey::Up),
+                Event::Key(TermionKey::Esc),
+            ]
+        );
+        assert_eq2!(
+            parse_events(b"\x1bb"),
+            vec![Event::Key(TermionKey::Alt('b'))]
+        );
+    }
+
+    /// Once the reader thread is dropped, it doesn't read any more input.
+    #[test]
+    fn test_reader_thread_stops_on_drop() {
+        let mut fds = [0 as libc::c_int; 2];
+        // SAFETY: `fds` is valid for writes of 2 file descriptors.
+        assert_eq2!(unsafe { libc::pipe(fds.as_mut_ptr()) }, 0);
+        let [read_fd, write_fd] = fds;
+        let write = |bytes: &[u8]| {
+            // SAFETY: `bytes` is valid for reads of `bytes.len()` bytes.
+            let count =
+                unsafe { libc::write(write_fd, bytes.as_ptr().cast(), bytes.len()) };
+            assert_eq2!(count, bytes.len() as isize);
+        };
+
+        let (sender, mut receiver) = unbounded_channel();
+        let reader_thread = ReaderThread::spawn(read_fd, sender);
+        write(b"a");
+        assert_eq2!(
+            receiver.blocking_recv(),
+            Some(Event::Key(TermionKey::Char('a')))
+        );
+
+        // The write end is still open, so the thread is only stopped by the drop.
+        drop(reader_thread);
+        assert_eq2!(receiver.blocking_recv(), None);
+
+        // The next keystroke is left for whoever reads next.
+        write(b"b");
+        let mut buffer = [0_u8; 8];
+        // SAFETY: `buffer` is valid for writes of `buffer.len()` bytes.
+        let count =
+            unsafe { libc::read(read_fd, buffer.as_mut_ptr().cast(), buffer.len()) };
+        assert_eq2!(&buffer[..count as usize], b"b");
+
+        // SAFETY: Both file descriptors are open, and they aren't used after this.
+        unsafe {
+            libc::close(read_fd);
+            libc::close(write_fd);
+        }
+    }
+
+    #[test]
+    fn test_convert_unsupported_event() {
+        let result: Result<InputEvent, ()> = Event::Unsupported(vec![0x1b]).try_into();
+        assert_eq2!(result, Err(()));
+    }
+}
@@ -14,3 +14,22 @@
  *   See the License for the specific language governing permissions and
  *   limitations under the License.
  */
+
+//! termion:
+//! - docs: <https://docs.rs/termion/latest/termion/>
+//! - Raw mode: <https://docs.rs/termion/latest/termion/raw/index.html>
+//! - Input events: <https://docs.rs/termion/latest/termion/input/trait.TermRead.html>
+//!
+//! This backend is only compiled when the `termion` feature is enabled, and it only works on Unix.
+
+// Attach.
+mod debug;
+mod input_event_stream;
+mod offscreen_buffer_paint_impl;
+mod render_op_impl;
+
+// Re-export.
+pub use debug::*;
+pub use input_event_stream::*;
+pub use offscreen_buffer_paint_impl::*;
+pub use render_op_impl::*;
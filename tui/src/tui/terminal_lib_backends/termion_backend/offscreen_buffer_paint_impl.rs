@@ -0,0 +1,122 @@
+/*
+ *   Copyright (c) 2022 R3BL LLC
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+use async_trait::async_trait;
+use r3bl_rs_utils_core::*;
+
+use crate::*;
+
+pub struct OffscreenBufferPaintImplTermion;
+
+#[async_trait]
+impl OffscreenBufferPaint for OffscreenBufferPaintImplTermion {
+    async fn paint(
+        &mut self,
+        render_ops: RenderOps,
+        flush_kind: FlushKind,
+        shared_global_data: &SharedGlobalData,
+    ) {
+        let mut skip_flush = false;
+
+        // Hold off on updating the screen until the entire frame is written.
+        let is_synchronized_output_enabled = SynchronizedOutput::is_enabled();
+        if is_synchronized_output_enabled {
+            RenderOpImplTermion::default().begin_synchronized_update();
+        }
+
+        if let FlushKind::ClearBeforeFlush = flush_kind {
+            RenderOpImplTermion::default().clear_before_flush();
+        }
+
+        // Execute each RenderOp.
+        render_ops
+            .execute_all(&mut skip_flush, shared_global_data)
+            .await;
+
+        if is_synchronized_output_enabled {
+            RenderOpImplTermion::default().end_synchronized_update();
+        }
+
+        // Flush everything to the terminal.
+        if !skip_flush {
+            RenderOpImplTermion::default().flush()
+        };
+
+        // Debug output.
+        call_if_true!(DEBUG_TUI_SHOW_PIPELINE, {
+            let msg = format!(
+                "🎨 offscreen_buffer_paint_impl_termion::paint() ok ✅: render_ops: \n{render_ops:?}",
+            );
+            log_info(msg);
+        });
+    }
+
+    async fn paint_diff(
+        &mut self,
+        render_ops: RenderOps,
+        shared_global_data: &SharedGlobalData,
+    ) {
+        let mut skip_flush = false;
+
+        // Hold off on updating the screen until the entire frame is written.
+        let is_synchronized_output_enabled = SynchronizedOutput::is_enabled();
+        if is_synchronized_output_enabled {
+            RenderOpImplTermion::default().begin_synchronized_update();
+        }
+
+        // Execute each RenderOp.
+        render_ops
+            .execute_all(&mut skip_flush, shared_global_data)
+            .await;
+
+        if is_synchronized_output_enabled {
+            RenderOpImplTermion::default().end_synchronized_update();
+        }
+
+        // Flush everything to the terminal.
+        if !skip_flush {
+            RenderOpImplTermion::default().flush()
+        };
+
+        // Debug output.
+        call_if_true!(DEBUG_TUI_SHOW_PIPELINE, {
+            let msg = format!(
+                "🎨 offscreen_buffer_paint_impl_termion::paint_diff() ok ✅: render_ops: \n{render_ops:?}"
+            );
+            log_info(msg);
+        });
+    }
+
+    /// The [RenderOps] that are generated from an [OffscreenBuffer] don't depend on the backend, so
+    /// this is the same as [OffscreenBufferPaintImplCrossterm::render].
+    async fn render(&mut self, offscreen_buffer: &OffscreenBuffer) -> RenderOps {
+        OffscreenBufferPaintImplCrossterm {}
+            .render(offscreen_buffer)
+            .await
+    }
+
+    /// Same as [OffscreenBufferPaintImplCrossterm::render_diff].
+    async fn render_diff(
+        &mut self,
+        diff_chunks: &PixelCharDiffChunks,
+        window_size: Size,
+    ) -> RenderOps {
+        OffscreenBufferPaintImplCrossterm {}
+            .render_diff(diff_chunks, window_size)
+            .await
+    }
+}
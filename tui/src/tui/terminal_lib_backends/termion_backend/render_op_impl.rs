@@ -0,0 +1,501 @@
+/*
+ *   Copyright (c) 2022 R3BL LLC
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+use std::{fmt::Display,
+          io::{stderr, stdout, Stdout, Write},
+          sync::Mutex};
+
+use async_trait::async_trait;
+use r3bl_rs_utils_core::*;
+use termion::{clear,
+              color::{self, Bg, Fg},
+              cursor,
+              raw::{IntoRawMode, RawTerminal},
+              screen,
+              style};
+
+use crate::*;
+
+/// Enable mouse reporting: button presses, drags, and the extended (SGR & urxvt) coordinates. These
+/// are the same sequences that [termion::input::MouseTerminal] uses.
+const ENTER_MOUSE_SEQUENCE: &str = "\x1b[?1000h\x1b[?1002h\x1b[?1015h\x1b[?1006h";
+const EXIT_MOUSE_SEQUENCE: &str = "\x1b[?1006l\x1b[?1015l\x1b[?1002l\x1b[?1000l";
+
+/// termion has no sequence for hidden (concealed) text.
+const HIDDEN_SEQUENCE: &str = "\x1b[8m";
+
+/// Raw mode is enabled for as long as the [RawTerminal] is alive.
+static RAW_TERMINAL: Mutex<Option<RawTerminal<Stdout>>> = Mutex::new(None);
+
+/// Struct representing the implementation of [RenderOp] for termion terminal backend. Everything
+/// is written to `writer`, which is [stdout] by default (use [RenderOpImplTermion::new] to paint
+/// into something else, eg: in tests).
+pub struct RenderOpImplTermion<W: Write + Send> {
+    pub writer: W,
+}
+
+impl Default for RenderOpImplTermion<Stdout> {
+    fn default() -> Self { Self { writer: stdout() } }
+}
+
+impl<W: Write + Send> RenderOpImplTermion<W> {
+    pub fn new(writer: W) -> Self { Self { writer } }
+}
+
+mod render_op_impl_termion_impl_trait_paint_render_op {
+    use super::*;
+
+    #[async_trait]
+    impl<W: Write + Send> PaintRenderOp for RenderOpImplTermion<W> {
+        async fn paint(
+            &mut self,
+            skip_flush: &mut bool,
+            command_ref: &RenderOp,
+            shared_global_data: &SharedGlobalData,
+            local_data: &mut RenderOpsLocalData,
+        ) {
+            match command_ref {
+                RenderOp::Noop => {}
+                RenderOp::EnterRawMode => {
+                    self.raw_mode_enter(skip_flush);
+                }
+                RenderOp::ExitRawMode => {
+                    self.raw_mode_exit(skip_flush);
+                }
+                RenderOp::MoveCursorPositionAbs(abs_pos) => {
+                    self.move_cursor_position_abs(
+                        abs_pos,
+                        shared_global_data,
+                        local_data,
+                    )
+                    .await;
+                }
+                RenderOp::MoveCursorPositionRelTo(box_origin_pos, content_rel_pos) => {
+                    let abs_pos = *box_origin_pos + *content_rel_pos;
+                    self.move_cursor_position_abs(
+                        &abs_pos,
+                        shared_global_data,
+                        local_data,
+                    )
+                    .await;
+                }
+                RenderOp::MoveCursorToColumn(col) => {
+                    // termion has no sequence for this, and the column is 1 based.
+                    self.write(format!("\x1b[{}G", **col + 1), "MoveCursorToColumn");
+                    self.save_rel_move(command_ref, shared_global_data, local_data)
+                        .await;
+                }
+                RenderOp::MoveCursorForward(count) => {
+                    self.write(cursor::Right(**count), "MoveCursorForward");
+                    self.save_rel_move(command_ref, shared_global_data, local_data)
+                        .await;
+                }
+                RenderOp::MoveCursorBackward(count) => {
+                    self.write(cursor::Left(**count), "MoveCursorBackward");
+                    self.save_rel_move(command_ref, shared_global_data, local_data)
+                        .await;
+                }
+                RenderOp::MoveCursorUp(count) => {
+                    self.write(cursor::Up(**count), "MoveCursorUp");
+                    self.save_rel_move(command_ref, shared_global_data, local_data)
+                        .await;
+                }
+                RenderOp::MoveCursorDown(count) => {
+                    self.write(cursor::Down(**count), "MoveCursorDown");
+                    self.save_rel_move(command_ref, shared_global_data, local_data)
+                        .await;
+                }
+                RenderOp::MoveCursorToStartOfLine => {
+                    self.write('\r', "MoveCursorToStartOfLine");
+                    self.save_rel_move(command_ref, shared_global_data, local_data)
+                        .await;
+                }
+                RenderOp::MoveCursorToStartOfNextLine(count) => {
+                    // termion has no sequence for this.
+                    self.write(
+                        format!("\x1b[{}E", **count),
+                        "MoveCursorToStartOfNextLine",
+                    );
+                    self.save_rel_move(command_ref, shared_global_data, local_data)
+                        .await;
+                }
+                RenderOp::SetScrollRegion(top, bottom) => {
+                    // termion has no sequence for DECSTBM, and the rows are 1 based.
+                    self.write(
+                        format!("\x1b[{};{}r", **top + 1, **bottom + 1),
+                        "SetScrollRegion",
+                    );
+                    self.save_rel_move(command_ref, shared_global_data, local_data)
+                        .await;
+                }
+                RenderOp::ResetScrollRegion => {
+                    self.write("\x1b[r", "ResetScrollRegion");
+                    self.save_rel_move(command_ref, shared_global_data, local_data)
+                        .await;
+                }
+                RenderOp::InsertLines(count) => {
+                    // termion has no sequence for this.
+                    self.write(format!("\x1b[{}L", **count), "InsertLines");
+                    self.save_rel_move(command_ref, shared_global_data, local_data)
+                        .await;
+                }
+                RenderOp::DeleteLines(count) => {
+                    // termion has no sequence for this.
+                    self.write(format!("\x1b[{}M", **count), "DeleteLines");
+                    self.save_rel_move(command_ref, shared_global_data, local_data)
+                        .await;
+                }
+                RenderOp::ClearScreen => {
+                    self.write(clear::All, "ClearScreen");
+                }
+                RenderOp::ClearToEndOfLine => {
+                    self.write(clear::UntilNewline, "ClearToEndOfLine");
+                }
+                RenderOp::SetFgColor(color) => {
+                    self.write(Fg(to_termion_color(*color).as_ref()), "SetFgColor");
+                }
+                RenderOp::SetBgColor(color) => {
+                    self.write(Bg(to_termion_color(*color).as_ref()), "SetBgColor");
+                }
+                RenderOp::ResetColor => {
+                    self.write(style::Reset, "ResetColor");
+                }
+                RenderOp::ApplyColors(maybe_style) => {
+                    self.apply_colors(maybe_style);
+                }
+                RenderOp::ApplyAttributes(style) => {
+                    self.write(
+                        render_op_impl_termion_impl::style_to_attributes(style),
+                        "ApplyAttributes",
+                    );
+                }
+                RenderOp::CompositorNoClipTruncPaintTextWithAttributes(
+                    text,
+                    maybe_style,
+                ) => {
+                    self.paint_text_with_attributes(
+                        text,
+                        maybe_style,
+                        shared_global_data,
+                        local_data,
+                    )
+                    .await;
+                }
+                RenderOp::PaintTextWithAttributes(_text, _maybe_style) => {
+                    // This should never be executed! The compositor always renders to an offscreen
+                    // buffer first, then that is diff'd and then painted via calls to
+                    // CompositorNoClipTruncPaintTextWithAttributes.
+                }
+                RenderOp::PushClipRect(..)
+                | RenderOp::PopClipRect
+                | RenderOp::PushTranslate(..)
+                | RenderOp::PopTranslate
+                | RenderOp::PaintBorder(..) => {
+                    // These are only honored by the compositor, when it renders to an offscreen
+                    // buffer.
+                }
+            }
+        }
+    }
+}
+
+pub mod render_op_impl_termion_impl_trait_flush {
+    use super::*;
+
+    impl<W: Write + Send> Flush for RenderOpImplTermion<W> {
+        fn flush(&mut self) {
+            exec_termion_op(self.writer.flush(), "flush() -> writer");
+            exec_termion_op(stderr().flush(), "flush() -> stderr");
+        }
+
+        fn clear_before_flush(&mut self) {
+            self.write(
+                format!("{}{}", style::Reset, clear::All),
+                "flush() -> after ResetColor, Clear",
+            );
+        }
+
+        fn begin_synchronized_update(&mut self) {
+            self.write(
+                BEGIN_SYNCHRONIZED_UPDATE_SEQUENCE,
+                "BeginSynchronizedUpdate",
+            );
+        }
+
+        fn end_synchronized_update(&mut self) {
+            self.write(END_SYNCHRONIZED_UPDATE_SEQUENCE, "EndSynchronizedUpdate");
+        }
+    }
+}
+
+mod render_op_impl_termion_impl {
+    use super::*;
+
+    impl<W: Write + Send> RenderOpImplTermion<W> {
+        pub fn write(&mut self, it: impl Display, log_msg: &str) {
+            exec_termion_op(write!(self.writer, "{it}"), log_msg);
+        }
+
+        pub async fn move_cursor_position_abs(
+            &mut self,
+            abs_pos: &Position,
+            shared_global_data: &SharedGlobalData,
+            local_data: &mut RenderOpsLocalData,
+        ) {
+            let Position {
+                col_index: col,
+                row_index: row,
+            } = sanitize_and_save_abs_position(*abs_pos, shared_global_data, local_data)
+                .await;
+            // termion's cursor positions are 1 based.
+            self.write(
+                cursor::Goto(*col + 1, *row + 1),
+                &format!("MoveCursorPosition(col: {}, row: {})", *col, *row),
+            );
+        }
+
+        /// Save the cursor position after a relative cursor move (eg:
+        /// [RenderOp::MoveCursorForward]) to [RenderOpsLocalData].
+        pub async fn save_rel_move(
+            &mut self,
+            render_op: &RenderOp,
+            shared_global_data: &SharedGlobalData,
+            local_data: &mut RenderOpsLocalData,
+        ) {
+            if let Some(new_pos) =
+                render_op.get_cursor_position_after_rel_move(local_data.cursor_position)
+            {
+                sanitize_and_save_abs_position(new_pos, shared_global_data, local_data)
+                    .await;
+            }
+        }
+
+        pub fn raw_mode_enter(&mut self, skip_flush: &mut bool) {
+            match stdout().into_raw_mode() {
+                Ok(raw_terminal) => {
+                    if let Ok(mut it) = RAW_TERMINAL.lock() {
+                        *it = Some(raw_terminal);
+                    }
+                }
+                Err(error) => {
+                    exec_termion_op(Err(error), "EnterRawMode -> into_raw_mode()")
+                }
+            }
+            self.write(
+                format!(
+                    "{ENTER_MOUSE_SEQUENCE}{}{}{}{}",
+                    screen::ToAlternateScreen,
+                    cursor::Goto(1, 1),
+                    clear::All,
+                    cursor::Hide
+                ),
+                "EnterRawMode -> EnableMouseCapture, EnterAlternateScreen, MoveTo(0,0), \
+                Clear(ClearType::All), Hide",
+            );
+            self.flush();
+            *skip_flush = true;
+        }
+
+        pub fn raw_mode_exit(&mut self, skip_flush: &mut bool) {
+            self.write(
+                format!(
+                    "{}{}{EXIT_MOUSE_SEQUENCE}",
+                    cursor::Show,
+                    screen::ToMainScreen
+                ),
+                "ExitRawMode -> Show, LeaveAlternateScreen, DisableMouseCapture",
+            );
+            self.flush();
+            // Dropping the RawTerminal restores the original terminal mode.
+            if let Ok(mut it) = RAW_TERMINAL.lock() {
+                it.take();
+            }
+            *skip_flush = true;
+        }
+
+        pub fn apply_colors(&mut self, maybe_style: &Option<Style>) {
+            if let Some(style) = maybe_style {
+                if let Some(color_bg) = style.color_bg {
+                    self.write(
+                        Bg(to_termion_color(color_bg).as_ref()),
+                        "ApplyColors -> SetBgColor",
+                    );
+                }
+                if let Some(color_fg) = style.color_fg {
+                    self.write(
+                        Fg(to_termion_color(color_fg).as_ref()),
+                        "ApplyColors -> SetFgColor",
+                    );
+                }
+            }
+        }
+
+        pub async fn paint_text_with_attributes(
+            &mut self,
+            text: &str,
+            maybe_style: &Option<Style>,
+            shared_global_data: &SharedGlobalData,
+            local_data: &mut RenderOpsLocalData,
+        ) {
+            let attributes = maybe_style
+                .as_ref()
+                .map(style_to_attributes)
+                .unwrap_or_default();
+            let needs_reset = !attributes.is_empty();
+
+            if needs_reset {
+                self.write(attributes, "PaintWithAttributes -> SetAttribute");
+            }
+            self.write(text, &format!("Print( {text} )"));
+            if needs_reset {
+                self.write(style::Reset, "PaintWithAttributes -> SetAttribute(Reset)");
+            }
+
+            // Update cursor position after paint.
+            let mut cursor_position_copy = local_data.cursor_position;
+            cursor_position_copy.col_index += UnicodeString::from(text).display_width;
+            sanitize_and_save_abs_position(
+                cursor_position_copy,
+                shared_global_data,
+                local_data,
+            )
+            .await;
+        }
+    }
+
+    pub fn style_to_attributes(style: &Style) -> String {
+        let mut it = String::new();
+        if style.bold {
+            it.push_str(style::Bold.as_ref());
+        }
+        if style.italic {
+            it.push_str(style::Italic.as_ref());
+        }
+        if style.dim {
+            it.push_str(style::Faint.as_ref());
+        }
+        if style.underline {
+            it.push_str(style::Underline.as_ref());
+        }
+        if style.reverse {
+            it.push_str(style::Invert.as_ref());
+        }
+        if style.hidden {
+            it.push_str(HIDDEN_SEQUENCE);
+        }
+        if style.strikethrough {
+            it.push_str(style::CrossedOut.as_ref());
+        }
+        it
+    }
+}
+
+/// Respect the color support of the terminal and downgrade the color if needed (using
+/// [color_converter::to_crossterm_color]), then convert it to a termion color.
+pub fn to_termion_color(value: TuiColor) -> Box<dyn color::Color> {
+    use crossterm::style::Color;
+
+    match color_converter::to_crossterm_color(value) {
+        Color::Reset => Box::new(color::Reset),
+        Color::Black => Box::new(color::Black),
+        Color::DarkGrey => Box::new(color::LightBlack),
+        Color::Red => Box::new(color::LightRed),
+        Color::DarkRed => Box::new(color::Red),
+        Color::Green => Box::new(color::LightGreen),
+        Color::DarkGreen => Box::new(color::Green),
+        Color::Yellow => Box::new(color::LightYellow),
+        Color::DarkYellow => Box::new(color::Yellow),
+        Color::Blue => Box::new(color::LightBlue),
+        Color::DarkBlue => Box::new(color::Blue),
+        Color::Magenta => Box::new(color::LightMagenta),
+        Color::DarkMagenta => Box::new(color::Magenta),
+        Color::Cyan => Box::new(color::LightCyan),
+        Color::DarkCyan => Box::new(color::Cyan),
+        Color::White => Box::new(color::LightWhite),
+        Color::Grey => Box::new(color::White),
+        Color::Rgb { r, g, b } => Box::new(color::Rgb(r, g, b)),
+        Color::AnsiValue(value) => Box::new(color::AnsiValue(value)),
+    }
+}
+
+/// Run the termion command and [log_error] or [log_info] the [Result] that is returned.
+fn exec_termion_op(result: std::io::Result<()>, log_msg: &str) {
+    match result {
+        Err(error) => call_if_true!(DEBUG_TUI_SHOW_TERMINAL_BACKEND, {
+            log_error(format!("termion: ❌ Failed to {log_msg} due to {error}"));
+        }),
+        Ok(_) => call_if_true!(DEBUG_TUI_SHOW_TERMINAL_BACKEND, {
+            log_info(format!("termion: ✅ {log_msg} successfully"));
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use r3bl_rs_utils_macro::style;
+
+    use super::*;
+    use crate::test_editor::mock_real_objects_for_editor::make_shared_global_data;
+
+    async fn paint_into_string(render_ops: RenderOps) -> String {
+        let shared_global_data =
+            make_shared_global_data(Some(size! { col_count: 10, row_count: 2 }));
+        let mut termion_impl = RenderOpImplTermion::new(Vec::<u8>::new());
+        let mut local_data = RenderOpsLocalData::default();
+        let mut skip_flush = false;
+        for render_op in render_ops.iter() {
+            termion_impl
+                .paint(
+                    &mut skip_flush,
+                    render_op,
+                    &shared_global_data,
+                    &mut local_data,
+                )
+                .await;
+        }
+        String::from_utf8(termion_impl.writer).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_paint_render_ops() {
+        let output = paint_into_string(render_ops!(
+            @new
+            RenderOp::ResetColor,
+            RenderOp::MoveCursorPositionAbs(position! { col_index: 2, row_index: 1 }),
+            RenderOp::SetFgColor(TuiColor::Ansi(AnsiValue::new(208))),
+            RenderOp::CompositorNoClipTruncPaintTextWithAttributes(
+                "hi".to_string(),
+                Some(style! { attrib: [bold, strikethrough] }),
+            ),
+        ))
+        .await;
+        assert_eq2!(
+            output,
+            "\x1b[m\x1b[2;3H\x1b[38;5;208m\x1b[1m\x1b[9mhi\x1b[m"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_paint_clamps_cursor_position() {
+        let output = paint_into_string(render_ops!(
+            @new
+            RenderOp::MoveCursorPositionAbs(position! { col_index: 50, row_index: 50 }),
+        ))
+        .await;
+        assert_eq2!(output, "\x1b[3;11H");
+    }
+}
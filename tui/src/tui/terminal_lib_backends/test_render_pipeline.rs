@@ -144,4 +144,31 @@ mod tests {
             2
         );
     }
+
+    #[test]
+    fn z_orders_are_ordered_and_transparency_is_joined() {
+        let popup_z_order = ZOrder::Glass.above(1);
+        let panel_z_order = ZOrder::Normal.above(1);
+
+        let mut pipeline = render_pipeline!(@new popup_z_order => RenderOp::ResetColor);
+        render_pipeline!(@push_into pipeline at ZOrder::Glass => RenderOp::ResetColor);
+
+        let mut other_pipeline =
+            render_pipeline!(@new panel_z_order => RenderOp::ResetColor);
+        other_pipeline.set_transparent(panel_z_order, true);
+        pipeline += other_pipeline;
+
+        assert_eq2!(
+            pipeline.keys().copied().collect::<Vec<_>>(),
+            vec![panel_z_order, ZOrder::Glass, popup_z_order]
+        );
+        assert_eq2!(pipeline.is_transparent(panel_z_order), true);
+        assert_eq2!(pipeline.is_transparent(ZOrder::Glass), false);
+
+        pipeline.set_transparent(panel_z_order, false);
+        assert_eq2!(pipeline.is_transparent(panel_z_order), false);
+
+        assert_eq2!(format!("{:?}", ZOrder::Glass), "Glass");
+        assert_eq2!(format!("{popup_z_order:?}"), "ZOrder(201)");
+    }
 }
@@ -0,0 +1,397 @@
+/*
+ *   Copyright (c) 2023 R3BL LLC
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+//! When the content of a region of the screen moves up or down (eg: the editor scrolls by one
+//! line), every row in that region is different from the previous frame. Instead of repainting
+//! all of them, the terminal is asked to scroll the region, and only the rows that are exposed (or
+//! that still differ) are painted. This matters a lot over slow connections (eg: SSH).
+//!
+//! The region is a band of rows that spans the entire width of the screen, since that is what
+//! DECSTBM supports. Any cells in the band that didn't move (eg: a box that is next to the editor)
+//! are simply repainted by the diff, since the diff is computed against the scrolled
+//! [OffscreenBuffer] ([VerticalShift::apply_to]). More info:
+//! - DECSTBM: <https://vt100.net/docs/vt510-rm/DECSTBM.html>
+//! - IL & DL: <https://vt100.net/docs/vt510-rm/IL.html>, <https://vt100.net/docs/vt510-rm/DL.html>
+
+use std::{collections::hash_map::DefaultHasher,
+          hash::{Hash, Hasher}};
+
+use r3bl_rs_utils_core::*;
+
+use crate::*;
+
+/// Rows of a region of the screen that moved up or down by `row_count` rows between 2
+/// [OffscreenBuffer]s.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VerticalShift {
+    /// The first row of the scroll region.
+    pub top_row_index: ChUnit,
+    /// The last row of the scroll region (inclusive).
+    pub bottom_row_index: ChUnit,
+    pub direction: VerticalShiftDirection,
+    pub row_count: ChUnit,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VerticalShiftDirection {
+    /// The content moved up (eg: the editor scrolled down), and blank rows are exposed at the
+    /// bottom of the region.
+    Up,
+    /// The content moved down, and blank rows are exposed at the top of the region.
+    Down,
+}
+
+impl VerticalShift {
+    /// Find the region of rows that moved between `old` and `new`, which saves the most rows from
+    /// being repainted. Returns [None] if the buffers have different sizes, or if scrolling
+    /// wouldn't save any rows from being repainted.
+    ///
+    /// Each candidate is a run of consecutive rows in `new` that are the same as the rows
+    /// `row_count` rows below (or above) them in `old`. The rows in the run that are the same in
+    /// `old` & `new` don't count, since they don't have to be repainted anyway. And neither do the
+    /// exposed rows that have to be repainted only because of the scroll.
+    pub fn detect(old: &OffscreenBuffer, new: &OffscreenBuffer) -> Option<VerticalShift> {
+        if old.window_size != new.window_size || old.buffer.len() != new.buffer.len() {
+            return None;
+        }
+
+        let rows = Rows::new(old, new);
+        let mut maybe_best: Option<(usize, VerticalShift)> = None;
+
+        for row_count in 1..rows.len() {
+            for direction in [VerticalShiftDirection::Up, VerticalShiftDirection::Down] {
+                let mut maybe_run_start = None;
+                for row_index in 0..=rows.len() {
+                    let maybe_old_row_index = match direction {
+                        VerticalShiftDirection::Up => Some(row_index + row_count),
+                        VerticalShiftDirection::Down => row_index.checked_sub(row_count),
+                    };
+                    let is_match = match maybe_old_row_index {
+                        Some(old_row_index) => rows.is_same(row_index, old_row_index),
+                        None => false,
+                    };
+                    if is_match {
+                        maybe_run_start.get_or_insert(row_index);
+                        continue;
+                    }
+
+                    // The run (if any) ends at this row.
+                    let Some(run_start) = maybe_run_start.take() else {
+                        continue;
+                    };
+                    let it = VerticalShift::from_run(
+                        run_start..row_index,
+                        direction,
+                        row_count,
+                    );
+                    let saved_row_count = rows.get_saved_row_count(&it);
+                    let is_better = match maybe_best {
+                        Some((best_saved_row_count, _)) => {
+                            saved_row_count > best_saved_row_count
+                        }
+                        None => saved_row_count > 0,
+                    };
+                    if is_better {
+                        maybe_best = Some((saved_row_count, it));
+                    }
+                }
+            }
+        }
+
+        maybe_best.map(|(_, it)| it)
+    }
+
+    /// `run` is the range of rows in the new buffer that came from the old buffer.
+    fn from_run(
+        run: std::ops::Range<usize>,
+        direction: VerticalShiftDirection,
+        row_count: usize,
+    ) -> VerticalShift {
+        let (top_row_index, bottom_row_index) = match direction {
+            VerticalShiftDirection::Up => (run.start, run.end - 1 + row_count),
+            VerticalShiftDirection::Down => (run.start - row_count, run.end - 1),
+        };
+        VerticalShift {
+            top_row_index: ch!(top_row_index),
+            bottom_row_index: ch!(bottom_row_index),
+            direction,
+            row_count: ch!(row_count),
+        }
+    }
+
+    /// The rows of the region that are exposed by the scroll (they are blank).
+    pub fn get_exposed_row_indices(&self) -> std::ops::RangeInclusive<usize> {
+        let top = ch!(@to_usize self.top_row_index);
+        let bottom = ch!(@to_usize self.bottom_row_index);
+        let row_count = ch!(@to_usize self.row_count);
+        match self.direction {
+            VerticalShiftDirection::Up => bottom + 1 - row_count..=bottom,
+            VerticalShiftDirection::Down => top..=top + row_count - 1,
+        }
+    }
+
+    /// Do to `offscreen_buffer` what [VerticalShift::to_render_ops] does to the terminal. Use this
+    /// on the saved [OffscreenBuffer] before diffing it against the new one.
+    pub fn apply_to(&self, offscreen_buffer: &mut OffscreenBuffer) {
+        let width = ch!(@to_usize offscreen_buffer.window_size.col_count);
+        let top = ch!(@to_usize self.top_row_index);
+        let bottom = ch!(@to_usize self.bottom_row_index);
+        let row_count = ch!(@to_usize self.row_count);
+
+        let region = &mut offscreen_buffer.buffer[top..=bottom];
+        match self.direction {
+            VerticalShiftDirection::Up => region.rotate_left(row_count),
+            VerticalShiftDirection::Down => region.rotate_right(row_count),
+        }
+
+        for row_index in self.get_exposed_row_indices() {
+            offscreen_buffer.buffer[row_index] =
+                PixelCharLine::new_with_capacity_initialized(width);
+        }
+    }
+
+    /// Scroll the region w/ DECSTBM & delete line (to move rows up) or insert line (to move rows
+    /// down). The colors are reset first, since the exposed rows are blanked w/ the current bg
+    /// color. The cursor is left at the top left corner of the screen.
+    pub fn to_render_ops(&self) -> RenderOps {
+        render_ops!(
+            @new
+            RenderOp::ResetColor,
+            RenderOp::SetScrollRegion(self.top_row_index, self.bottom_row_index),
+            RenderOp::MoveCursorPositionAbs(
+                position!(col_index: 0, row_index: self.top_row_index)
+            ),
+            match self.direction {
+                VerticalShiftDirection::Up => RenderOp::DeleteLines(self.row_count),
+                VerticalShiftDirection::Down => RenderOp::InsertLines(self.row_count),
+            },
+            RenderOp::ResetScrollRegion,
+        )
+    }
+}
+
+/// Compares the rows of 2 [OffscreenBuffer]s. Each row is hashed once, so most comparisons
+/// don't have to look at each [PixelChar].
+struct Rows<'a> {
+    old: &'a OffscreenBuffer,
+    new: &'a OffscreenBuffer,
+    old_hashes: Vec<u64>,
+    new_hashes: Vec<u64>,
+}
+
+impl<'a> Rows<'a> {
+    fn new(old: &'a OffscreenBuffer, new: &'a OffscreenBuffer) -> Self {
+        fn hash_rows(offscreen_buffer: &OffscreenBuffer) -> Vec<u64> {
+            offscreen_buffer
+                .buffer
+                .iter()
+                .map(|row| {
+                    let mut hasher = DefaultHasher::new();
+                    row.hash(&mut hasher);
+                    hasher.finish()
+                })
+                .collect()
+        }
+
+        Rows {
+            old,
+            new,
+            old_hashes: hash_rows(old),
+            new_hashes: hash_rows(new),
+        }
+    }
+
+    fn len(&self) -> usize { self.new_hashes.len() }
+
+    /// Returns false if either row index is out of bounds.
+    fn is_same(&self, new_row_index: usize, old_row_index: usize) -> bool {
+        match (
+            self.new_hashes.get(new_row_index),
+            self.old_hashes.get(old_row_index),
+        ) {
+            (Some(new_hash), Some(old_hash)) => {
+                new_hash == old_hash
+                    && self.new.buffer[new_row_index] == self.old.buffer[old_row_index]
+            }
+            _ => false,
+        }
+    }
+
+    fn is_blank(&self, new_row_index: usize) -> bool {
+        self.new.buffer[new_row_index]
+            .iter()
+            .all(|it| matches!(it, PixelChar::Spacer))
+    }
+
+    /// The number of rows that don't have to be repainted when the `vertical_shift` is used,
+    /// minus the number of rows that have to be repainted only because of it.
+    fn get_saved_row_count(&self, vertical_shift: &VerticalShift) -> usize {
+        let top = ch!(@to_usize vertical_shift.top_row_index);
+        let bottom = ch!(@to_usize vertical_shift.bottom_row_index);
+        let exposed_row_indices = vertical_shift.get_exposed_row_indices();
+
+        let mut saved_row_count: usize = 0;
+        let mut extra_row_count = 0;
+        for row_index in top..=bottom {
+            let is_unchanged = self.is_same(row_index, row_index);
+            if exposed_row_indices.contains(&row_index) {
+                if is_unchanged && !self.is_blank(row_index) {
+                    extra_row_count += 1;
+                }
+            } else if !is_unchanged {
+                saved_row_count += 1;
+            }
+        }
+        saved_row_count.saturating_sub(extra_row_count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_editor::mock_real_objects_for_editor::make_shared_global_data;
+
+    const WINDOW_SIZE: Size = Size {
+        col_count: ChUnit { value: 8 },
+        row_count: ChUnit { value: 5 },
+    };
+
+    fn make_offscreen_buffer(rows: [&str; 5]) -> OffscreenBuffer {
+        let mut it = OffscreenBuffer::new_with_capacity_initialized(WINDOW_SIZE);
+        for (row_index, row) in rows.iter().enumerate() {
+            for (col_index, character) in row.chars().enumerate() {
+                it.buffer[row_index][col_index] = PixelChar::PlainText {
+                    content: GraphemeClusterSegment::from(character.to_string()),
+                    maybe_style: None,
+                };
+            }
+        }
+        it
+    }
+
+    fn get_changed_row_indices(
+        old: &OffscreenBuffer,
+        new: &OffscreenBuffer,
+    ) -> Vec<usize> {
+        let OffscreenBufferDiffResult::Comparable(diff_chunks) = old.diff(new) else {
+            panic!("buffers are not comparable");
+        };
+        let mut it = diff_chunks
+            .iter()
+            .map(|(position, _)| ch!(@to_usize position.row_index))
+            .collect::<Vec<_>>();
+        it.dedup();
+        it
+    }
+
+    #[test]
+    fn test_detect_and_apply_shift_up() {
+        let mut old =
+            make_offscreen_buffer(["1 one", "2 two", "3 three", "4 four", "status"]);
+        let new =
+            make_offscreen_buffer(["2 two", "3 three", "4 four", "5 five", "status"]);
+
+        let vertical_shift = VerticalShift::detect(&old, &new).unwrap();
+        assert_eq2!(
+            vertical_shift,
+            VerticalShift {
+                top_row_index: ch!(0),
+                bottom_row_index: ch!(3),
+                direction: VerticalShiftDirection::Up,
+                row_count: ch!(1),
+            }
+        );
+
+        // Only the exposed row has to be painted.
+        vertical_shift.apply_to(&mut old);
+        assert_eq2!(get_changed_row_indices(&old, &new), vec![3]);
+    }
+
+    #[test]
+    fn test_detect_and_apply_shift_down() {
+        let mut old =
+            make_offscreen_buffer(["title", "1 one", "2 two", "3 three", "4 four"]);
+        let new = make_offscreen_buffer(["title", "", "", "1 one", "2 two"]);
+
+        let vertical_shift = VerticalShift::detect(&old, &new).unwrap();
+        assert_eq2!(
+            vertical_shift,
+            VerticalShift {
+                top_row_index: ch!(1),
+                bottom_row_index: ch!(4),
+                direction: VerticalShiftDirection::Down,
+                row_count: ch!(2),
+            }
+        );
+
+        // The exposed rows are blank, so nothing has to be painted.
+        vertical_shift.apply_to(&mut old);
+        assert_eq2!(get_changed_row_indices(&old, &new), Vec::<usize>::new());
+    }
+
+    #[test]
+    fn test_detect_no_shift() {
+        let old =
+            make_offscreen_buffer(["1 one", "2 two", "3 three", "4 four", "status"]);
+        assert_eq2!(VerticalShift::detect(&old, &old), None);
+
+        let new =
+            make_offscreen_buffer(["1 one", "2 TWO", "3 three", "4 four", "status"]);
+        assert_eq2!(VerticalShift::detect(&old, &new), None);
+
+        // Scrolling would save 1 row, but the unchanged row that is exposed would have to be
+        // repainted.
+        let new =
+            make_offscreen_buffer(["2 two", "2 two", "3 three", "4 four", "status"]);
+        assert_eq2!(VerticalShift::detect(&old, &new), None);
+    }
+
+    #[tokio::test]
+    async fn test_vertical_shift_render_ops() {
+        let vertical_shift = VerticalShift {
+            top_row_index: ch!(2),
+            bottom_row_index: ch!(10),
+            direction: VerticalShiftDirection::Up,
+            row_count: ch!(3),
+        };
+
+        let shared_global_data =
+            make_shared_global_data(Some(size!(col_count: 80, row_count: 24)));
+        let mut crossterm_impl = RenderOpImplCrossterm::new(Vec::<u8>::new());
+        let mut local_data = RenderOpsLocalData::default();
+        let mut skip_flush = false;
+        for render_op in vertical_shift.to_render_ops().iter() {
+            crossterm_impl
+                .paint(
+                    &mut skip_flush,
+                    render_op,
+                    &shared_global_data,
+                    &mut local_data,
+                )
+                .await;
+        }
+
+        assert_eq2!(
+            String::from_utf8(crossterm_impl.writer).unwrap(),
+            "\x1b[0m\x1b[3;11r\x1b[3;1H\x1b[3M\x1b[r"
+        );
+        assert_eq2!(
+            local_data.cursor_position,
+            position!(col_index: 0, row_index: 0)
+        );
+    }
+}
@@ -0,0 +1,163 @@
+/*
+ *   Copyright (c) 2023 R3BL LLC
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+use std::time::{Duration, Instant};
+
+/// Frames are painted at most this many times per second, unless the app overrides it using
+/// [max_fps_global_static::set_max_fps_override](crate::max_fps_global_static::set_max_fps_override).
+pub const DEFAULT_MAX_FPS: u16 = 60;
+
+/// Every input event (and every action that is dispatched) asks for the app to be rendered &
+/// painted. When these requests arrive faster than the max FPS (eg: fast key repeats or
+/// animations), painting each one is wasted work, and can cause tearing. This coalesces all the
+/// requests that arrive within one frame interval into a single paint:
+/// 1. If no frame has been painted during the last frame interval, then paint right away
+///    ([FrameSchedule::PaintNow]).
+/// 2. Otherwise, paint once the frame interval is over ([FrameSchedule::PaintAfter]). Any
+///    requests that arrive until then are covered by that paint ([FrameSchedule::Skip]), since
+///    it uses the latest state.
+///
+/// It is saved in [GlobalData](crate::GlobalData), and is used by the main event loop.
+#[derive(Debug, Clone, Default)]
+pub struct FrameScheduler {
+    /// [None] means that there's no limit, and every request is painted right away.
+    pub maybe_frame_interval: Option<Duration>,
+    pub maybe_last_paint_instant: Option<Instant>,
+    pub is_paint_pending: bool,
+    pub is_stopped: bool,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum FrameSchedule {
+    PaintNow,
+    /// Wait for the given [Duration], call [FrameScheduler::take_pending_paint], and then paint.
+    PaintAfter(Duration),
+    /// A paint is already pending, or the scheduler is stopped.
+    Skip,
+}
+
+impl FrameScheduler {
+    /// Pass [None] (or `0`) for `maybe_max_fps` to paint every request right away.
+    pub fn new(maybe_max_fps: Option<u16>) -> Self {
+        FrameScheduler {
+            maybe_frame_interval: match maybe_max_fps {
+                None | Some(0) => None,
+                Some(max_fps) => Some(Duration::from_secs(1) / max_fps as u32),
+            },
+            ..Default::default()
+        }
+    }
+
+    pub fn request_paint(&mut self, now: Instant) -> FrameSchedule {
+        if self.is_stopped || self.is_paint_pending {
+            return FrameSchedule::Skip;
+        }
+
+        let (Some(frame_interval), Some(last_paint_instant)) =
+            (self.maybe_frame_interval, self.maybe_last_paint_instant)
+        else {
+            return FrameSchedule::PaintNow;
+        };
+
+        let elapsed = now.saturating_duration_since(last_paint_instant);
+        match frame_interval.checked_sub(elapsed) {
+            Some(remaining) if !remaining.is_zero() => {
+                self.is_paint_pending = true;
+                FrameSchedule::PaintAfter(remaining)
+            }
+            _ => FrameSchedule::PaintNow,
+        }
+    }
+
+    /// Returns `true` if the paint that was scheduled by [FrameSchedule::PaintAfter] should
+    /// happen. Requests that arrive after this call are not covered by it.
+    pub fn take_pending_paint(&mut self) -> bool {
+        let is_paint_pending = self.is_paint_pending;
+        self.is_paint_pending = false;
+        is_paint_pending && !self.is_stopped
+    }
+
+    /// Call this right before a frame is rendered.
+    pub fn record_paint(&mut self, now: Instant) {
+        self.maybe_last_paint_instant = Some(now);
+    }
+
+    /// Nothing is painted after this (eg: once the main event loop exits), including any paint
+    /// that is pending.
+    pub fn stop(&mut self) { self.is_stopped = true; }
+}
+
+#[cfg(test)]
+mod tests {
+    use r3bl_rs_utils_core::*;
+
+    use super::*;
+
+    #[test]
+    fn test_requests_within_frame_interval_are_coalesced() {
+        let mut frame_scheduler = FrameScheduler::new(Some(50));
+        let start = Instant::now();
+
+        // The first request is painted right away.
+        assert_eq2!(
+            frame_scheduler.request_paint(start),
+            FrameSchedule::PaintNow
+        );
+        frame_scheduler.record_paint(start);
+
+        // The next one is deferred until the 20ms frame interval is over.
+        let now = start + Duration::from_millis(5);
+        assert_eq2!(
+            frame_scheduler.request_paint(now),
+            FrameSchedule::PaintAfter(Duration::from_millis(15))
+        );
+
+        // The ones after that are covered by the pending paint.
+        assert_eq2!(frame_scheduler.request_paint(now), FrameSchedule::Skip);
+        assert_eq2!(frame_scheduler.request_paint(now), FrameSchedule::Skip);
+
+        // The pending paint happens.
+        let now = start + Duration::from_millis(20);
+        assert_eq2!(frame_scheduler.take_pending_paint(), true);
+        assert_eq2!(frame_scheduler.take_pending_paint(), false);
+        frame_scheduler.record_paint(now);
+
+        // Once a whole frame interval has passed, requests are painted right away again.
+        let now = start + Duration::from_millis(45);
+        assert_eq2!(frame_scheduler.request_paint(now), FrameSchedule::PaintNow);
+    }
+
+    #[test]
+    fn test_no_max_fps_and_stop() {
+        let mut frame_scheduler = FrameScheduler::new(None);
+        let now = Instant::now();
+        for _ in 0..3 {
+            assert_eq2!(frame_scheduler.request_paint(now), FrameSchedule::PaintNow);
+            frame_scheduler.record_paint(now);
+        }
+
+        let mut frame_scheduler = FrameScheduler::new(Some(60));
+        frame_scheduler.record_paint(now);
+        assert!(matches!(
+            frame_scheduler.request_paint(now),
+            FrameSchedule::PaintAfter(_)
+        ));
+        frame_scheduler.stop();
+        assert_eq2!(frame_scheduler.take_pending_paint(), false);
+        assert_eq2!(frame_scheduler.request_paint(now), FrameSchedule::Skip);
+    }
+}
@@ -15,7 +15,7 @@
  *   limitations under the License.
  */
 
-use std::{fmt::Debug, sync::Arc};
+use std::{fmt::Debug, sync::Arc, time::Instant};
 
 use async_trait::async_trait;
 use get_size::GetSize;
@@ -76,6 +76,14 @@ impl TerminalWindow {
         let _global_data = GlobalData::try_to_create_instance()?;
         let shared_global_data: SharedGlobalData = Arc::new(RwLock::new(_global_data));
 
+        // Pick the markdown theme that matches the terminal background (before any input events
+        // are read).
+        markdown_theme_global_static::detect_markdown_theme();
+
+        // Find out whether the terminal supports synchronized output (before any input events are
+        // read).
+        synchronized_output_global_static::detect_synchronized_output();
+
         // Start raw mode.
         RawMode::start(&shared_global_data).await;
 
@@ -108,6 +116,7 @@ impl TerminalWindow {
                 // Handle exit channel.
                 result = exit_channel_reciever.recv() => {
                     if result.is_some() {
+                        shared_global_data.write().await.frame_scheduler.stop();
                         RawMode::end(&shared_global_data).await;
                         break;
                     }
@@ -166,6 +175,131 @@ impl TerminalWindow {
         Ok(())
     }
 
+    /// Same as [TerminalWindow::main_event_loop], except that it doesn't need a terminal. This is
+    /// meant to be used in integration tests:
+    /// - The size of the window is [HeadlessTerminal::size].
+    /// - The input events are read from [HeadlessTerminal::input_events] (instead of stdin). The
+    ///   main event loop exits once they've all been processed (or an exit key is pressed).
+    /// - The frame after each input event is captured once all the actions that it dispatched
+    ///   (w/ [spawn_dispatch_action!](r3bl_redux::spawn_dispatch_action)) have run, using a
+    ///   [DispatchTracker].
+    /// - Nothing is painted, raw mode is not entered, and the markdown theme isn't detected.
+    ///   Instead, every [OffscreenBuffer] that would have been painted is returned in the
+    ///   [HeadlessOutput].
+    ///
+    /// This is only available w/ the `headless` feature.
+    #[cfg(any(test, feature = "headless"))]
+    pub async fn main_event_loop_headless<S, A>(
+        shared_app: SharedApp<S, A>,
+        store: Store<S, A>,
+        exit_keys: Vec<InputEvent>,
+        headless_terminal: HeadlessTerminal,
+    ) -> CommonResult<HeadlessOutput>
+    where
+        S: Debug + Default + Clone + PartialEq + Sync + Send + 'static,
+        A: Debug + Default + Clone + Sync + Send + 'static,
+    {
+        let HeadlessTerminal {
+            size,
+            input_events,
+            maybe_max_fps,
+        } = headless_terminal;
+
+        // Initialize the terminal window data struct.
+        let mut _global_data = GlobalData::new_headless(size);
+        if maybe_max_fps.is_some() {
+            _global_data.frame_scheduler = FrameScheduler::new(maybe_max_fps);
+        }
+        let shared_global_data: SharedGlobalData = Arc::new(RwLock::new(_global_data));
+
+        // Move the store into an Arc & RwLock.
+        let shared_store: SharedStore<S, A> = Arc::new(RwLock::new(store));
+
+        // Create a subscriber (AppManager) & attach it to the store.
+        let _subscriber =
+            AppManager::new_box(&shared_app, &shared_store, &shared_global_data);
+        shared_store.write().await.add_subscriber(_subscriber).await;
+
+        // Perform first render.
+        AppManager::render_app(&shared_store, &shared_app, &shared_global_data, None)
+            .await?;
+        let initial_frame = get_saved_offscreen_buffer(&shared_global_data).await;
+
+        // mpsc channel to send exit signal to main loop.
+        let (exit_channel_sender, mut exit_channel_reciever) = mpsc::channel::<bool>(1);
+
+        // Main event loop.
+        let mut frames_after_each_event = vec![];
+        let dispatch_tracker = DispatchTracker::default();
+        for input_event in input_events {
+            // Track the actions that are dispatched w/ [spawn_dispatch_action!] while the event
+            // is processed.
+            dispatch_tracker
+                .scope(async {
+                    Self::handle_resize_event(
+                        &input_event,
+                        &shared_global_data,
+                        &shared_store,
+                        &shared_app,
+                    )
+                    .await;
+
+                    Self::actually_process_input_event(
+                        shared_global_data.clone(),
+                        shared_store.clone(),
+                        shared_app.clone(),
+                        input_event.clone(),
+                        exit_keys.clone(),
+                        exit_channel_sender.clone(),
+                    )
+                    .await;
+                })
+                .await;
+
+            // Wait for these actions (and the renders that they cause) before the frame is
+            // captured. Also paint the frame that the [FrameScheduler] deferred (if any).
+            dispatch_tracker.wait_for_pending().await;
+            let _ = AppManager::flush_pending_paint(
+                &shared_store,
+                &shared_app,
+                &shared_global_data,
+            )
+            .await;
+
+            frames_after_each_event
+                .push(get_saved_offscreen_buffer(&shared_global_data).await);
+
+            if exit_channel_reciever.try_recv().is_ok() {
+                break;
+            }
+        } // End loop.
+
+        let all_frames = shared_global_data
+            .write()
+            .await
+            .maybe_headless_frames
+            .take()
+            .unwrap_or_default();
+
+        return Ok(HeadlessOutput {
+            initial_frame,
+            frames_after_each_event,
+            all_frames,
+        });
+
+        async fn get_saved_offscreen_buffer(
+            shared_global_data: &SharedGlobalData,
+        ) -> OffscreenBuffer {
+            let global_data = shared_global_data.read().await;
+            match global_data.maybe_saved_offscreen_buffer {
+                Some(ref offscreen_buffer) => offscreen_buffer.clone(),
+                None => OffscreenBuffer::new_with_capacity_initialized(
+                    global_data.window_size,
+                ),
+            }
+        }
+    }
+
     async fn actually_process_input_event<S, A>(
         shared_global_data: SharedGlobalData,
         shared_store: SharedStore<S, A>,
@@ -327,13 +461,87 @@ where
         });
     }
 
+    /// Ask for the app to be rendered & painted. The [FrameScheduler] decides when this happens:
+    /// - Right away, using `maybe_state` (or the state in the store if it is [None]).
+    /// - Or once the current frame interval is over, in a spawned task, using the state in the
+    ///   store at that time. All the requests that arrive until then are coalesced into this
+    ///   single paint. When headless, nothing is spawned, and the main event loop calls
+    ///   [AppManager::flush_pending_paint] instead.
     pub async fn render_app(
         shared_store: &SharedStore<S, A>,
         shared_app: &SharedApp<S, A>,
         shared_global_data: &SharedGlobalData,
         maybe_state: Option<S>,
+    ) -> CommonResult<()> {
+        let (frame_schedule, is_headless) = {
+            let mut global_data = shared_global_data.write().await;
+            let frame_schedule =
+                global_data.frame_scheduler.request_paint(Instant::now());
+            (frame_schedule, global_data.is_headless())
+        };
+
+        match frame_schedule {
+            FrameSchedule::PaintNow => {
+                Self::actually_render_app(
+                    shared_store,
+                    shared_app,
+                    shared_global_data,
+                    maybe_state,
+                )
+                .await
+            }
+            FrameSchedule::PaintAfter(_) if is_headless => Ok(()),
+            FrameSchedule::PaintAfter(delay) => {
+                let shared_store = shared_store.clone();
+                let shared_app = shared_app.clone();
+                let shared_global_data = shared_global_data.clone();
+                tokio::spawn(async move {
+                    tokio::time::sleep(delay).await;
+                    let _ = Self::flush_pending_paint(
+                        &shared_store,
+                        &shared_app,
+                        &shared_global_data,
+                    )
+                    .await;
+                });
+                Ok(())
+            }
+            FrameSchedule::Skip => Ok(()),
+        }
+    }
+
+    /// Paint the frame that was deferred by [FrameSchedule::PaintAfter] (if it is still
+    /// pending), using the state in the store.
+    pub async fn flush_pending_paint(
+        shared_store: &SharedStore<S, A>,
+        shared_app: &SharedApp<S, A>,
+        shared_global_data: &SharedGlobalData,
+    ) -> CommonResult<()> {
+        let is_paint_pending = shared_global_data
+            .write()
+            .await
+            .frame_scheduler
+            .take_pending_paint();
+        if !is_paint_pending {
+            return Ok(());
+        }
+        Self::actually_render_app(shared_store, shared_app, shared_global_data, None)
+            .await
+    }
+
+    async fn actually_render_app(
+        shared_store: &SharedStore<S, A>,
+        shared_app: &SharedApp<S, A>,
+        shared_global_data: &SharedGlobalData,
+        maybe_state: Option<S>,
     ) -> CommonResult<()> {
         throws!({
+            shared_global_data
+                .write()
+                .await
+                .frame_scheduler
+                .record_paint(Instant::now());
+
             // Create global scope args.
             let window_size = shared_global_data.read().await.get_size();
             let state: S = if let Some(state) = maybe_state {
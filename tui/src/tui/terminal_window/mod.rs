@@ -20,6 +20,7 @@ pub mod app;
 pub mod component;
 pub mod default_input_handler;
 pub mod event_routing_support;
+pub mod frame_scheduler;
 pub mod main_event_loop;
 pub mod manage_focus;
 pub mod shared_global_data;
@@ -31,6 +32,7 @@ pub use app::*;
 pub use component::*;
 pub use default_input_handler::*;
 pub use event_routing_support::*;
+pub use frame_scheduler::*;
 pub use main_event_loop::*;
 pub use manage_focus::*;
 pub use shared_global_data::*;
@@ -28,10 +28,17 @@ use crate::*;
 /// These are global state values for the entire application:
 /// - The `window_size` holds the [Size] of the terminal window.
 /// - The `maybe_saved_offscreen_buffer` holds the last rendered [OffscreenBuffer].
+/// - The `maybe_headless_frames` is only set when the app is run w/out a terminal (see
+///   [TerminalWindow::main_event_loop_headless]). Nothing is painted to the terminal in this case,
+///   and every [OffscreenBuffer] that would have been painted is captured in it instead.
+/// - The `frame_scheduler` coalesces render requests that arrive faster than the max FPS (it has
+///   no limit when headless, so that every frame is captured).
 #[derive(Clone, Default)]
 pub struct GlobalData {
     pub window_size: Size,
     pub maybe_saved_offscreen_buffer: Option<OffscreenBuffer>,
+    pub maybe_headless_frames: Option<Vec<OffscreenBuffer>>,
+    pub frame_scheduler: FrameScheduler,
 }
 
 mod global_data_impl {
@@ -50,17 +57,36 @@ mod global_data_impl {
                     true => offscreen_buffer.pretty_print(),
                 },
             });
+            if let Some(ref headless_frames) = self.maybe_headless_frames {
+                vec_lines.push(format!("headless, frames: {}", headless_frames.len()));
+            }
             write!(f, "\nGlobalData\n  - {}", vec_lines.join("\n  - "))
         }
     }
 
     impl GlobalData {
         pub fn try_to_create_instance() -> CommonResult<GlobalData> {
-            let mut global_data = GlobalData::default();
+            let mut global_data = GlobalData {
+                frame_scheduler: FrameScheduler::new(max_fps_global_static::get_max_fps()),
+                ..Default::default()
+            };
             global_data.set_size(terminal_lib_operations::lookup_size()?);
             Ok(global_data)
         }
 
+        /// Doesn't interrogate the terminal for its size, since there might not be one. The FPS
+        /// isn't limited, so every render request is painted right away (synchronously).
+        pub fn new_headless(window_size: Size) -> GlobalData {
+            GlobalData {
+                window_size,
+                maybe_headless_frames: Some(vec![]),
+                frame_scheduler: FrameScheduler::new(None),
+                ..Default::default()
+            }
+        }
+
+        pub fn is_headless(&self) -> bool { self.maybe_headless_frames.is_some() }
+
         pub fn set_size(&mut self, new_size: Size) {
             self.window_size = new_size;
             self.dump_to_log("main_event_loop -> Resize");
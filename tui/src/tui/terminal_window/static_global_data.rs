@@ -19,7 +19,7 @@ use std::sync::atomic::{AtomicI64, Ordering};
 
 use chrono::Utc;
 
-use crate::ColorSupport;
+use crate::{ColorSupport, TerminalLibBackend};
 const NOT_SET_VALUE: i64 = -1;
 
 /// This module contains static global data that is meant to be used by the entire application. It
@@ -211,3 +211,188 @@ pub mod color_support_global_static {
         };
     }
 }
+
+/// This module contains static global data that is meant to be used by the entire application. It
+/// also provides functions to manipulate this data.
+///
+/// ### Terminal lib backend
+/// The app can override the [TerminalLibBackend] that is selected by the cargo features.
+pub mod terminal_lib_backend_global_static {
+    use super::*;
+
+    /// Global [TerminalLibBackend] override.
+    static TERMINAL_LIB_BACKEND_OVERRIDE: AtomicI64 = AtomicI64::new(NOT_SET_VALUE);
+
+    pub fn get_terminal_lib_backend_override() -> Option<TerminalLibBackend> {
+        match TERMINAL_LIB_BACKEND_OVERRIDE.load(Ordering::SeqCst) {
+            0 => Some(TerminalLibBackend::Crossterm),
+            #[cfg(all(unix, feature = "termion"))]
+            1 => Some(TerminalLibBackend::Termion),
+            _ => None,
+        }
+    }
+
+    pub fn clear_terminal_lib_backend_override() {
+        TERMINAL_LIB_BACKEND_OVERRIDE.store(NOT_SET_VALUE, Ordering::SeqCst);
+    }
+
+    pub fn set_terminal_lib_backend_override(backend: TerminalLibBackend) {
+        let value = match backend {
+            TerminalLibBackend::Crossterm => 0,
+            #[cfg(all(unix, feature = "termion"))]
+            TerminalLibBackend::Termion => 1,
+        };
+        TERMINAL_LIB_BACKEND_OVERRIDE.store(value, Ordering::SeqCst);
+    }
+}
+
+/// This module contains static global data that is meant to be used by the entire application. It
+/// also provides functions to manipulate this data.
+///
+/// ### Markdown theme
+/// The [MarkdownTheme] that is used to highlight MD documents. By default, the preset is picked
+/// based on the terminal background, when [TerminalWindow::main_event_loop] starts. The app can
+/// override this by providing its own [MarkdownTheme] (eg: one that is loaded from JSON).
+pub mod markdown_theme_global_static {
+    use std::sync::{atomic::AtomicBool, RwLock};
+
+    use super::*;
+    use crate::{MarkdownTheme, TerminalBackground};
+
+    static MARKDOWN_THEME: RwLock<Option<MarkdownTheme>> = RwLock::new(None);
+    static IS_MARKDOWN_THEME_OVERRIDE: AtomicBool = AtomicBool::new(false);
+
+    /// Run `f` w/ the saved [MarkdownTheme]. If it isn't saved yet, then the preset that matches
+    /// [TerminalBackground::detect_from_env] is saved (this doesn't perform any terminal I/O).
+    pub fn with_markdown_theme<T>(f: impl FnOnce(&MarkdownTheme) -> T) -> T {
+        if let Ok(guard) = MARKDOWN_THEME.read() {
+            if let Some(theme) = guard.as_ref() {
+                return f(theme);
+            }
+        }
+
+        let theme = MarkdownTheme::from(TerminalBackground::detect_from_env());
+        let result = f(&theme);
+        if let Ok(mut guard) = MARKDOWN_THEME.write() {
+            guard.get_or_insert(theme);
+        }
+        result
+    }
+
+    /// Save the preset that matches [TerminalBackground::detect], unless the app has provided
+    /// an override. This queries the terminal, so it must be called before any other code starts
+    /// reading input events.
+    pub fn detect_markdown_theme() {
+        if IS_MARKDOWN_THEME_OVERRIDE.load(Ordering::SeqCst) {
+            return;
+        }
+        save_markdown_theme(Some(MarkdownTheme::detect()));
+    }
+
+    pub fn set_markdown_theme_override(theme: MarkdownTheme) {
+        IS_MARKDOWN_THEME_OVERRIDE.store(true, Ordering::SeqCst);
+        save_markdown_theme(Some(theme));
+    }
+
+    pub fn clear_markdown_theme_override() {
+        IS_MARKDOWN_THEME_OVERRIDE.store(false, Ordering::SeqCst);
+        save_markdown_theme(None);
+    }
+
+    fn save_markdown_theme(maybe_theme: Option<MarkdownTheme>) {
+        if let Ok(mut guard) = MARKDOWN_THEME.write() {
+            *guard = maybe_theme;
+        }
+    }
+}
+
+/// This module contains static global data that is meant to be used by the entire application. It
+/// also provides functions to manipulate this data.
+///
+/// ### Synchronized output
+/// Whether [SynchronizedOutput] is used when painting. By default, the terminal is asked whether
+/// it supports it, when [TerminalWindow::main_event_loop] starts. The app can override this (eg:
+/// to turn it off for a terminal that misbehaves).
+pub mod synchronized_output_global_static {
+    use std::sync::atomic::AtomicBool;
+
+    use super::*;
+    use crate::SynchronizedOutput;
+
+    static SYNCHRONIZED_OUTPUT: AtomicI64 = AtomicI64::new(NOT_SET_VALUE);
+    static IS_SYNCHRONIZED_OUTPUT_OVERRIDE: AtomicBool = AtomicBool::new(false);
+
+    pub fn get_synchronized_output() -> SynchronizedOutput {
+        match SYNCHRONIZED_OUTPUT.load(Ordering::SeqCst) {
+            1 => SynchronizedOutput::Enabled,
+            _ => SynchronizedOutput::Disabled,
+        }
+    }
+
+    /// Save the result of [SynchronizedOutput::detect], unless the app has provided an override.
+    /// This queries the terminal, so it must be called before any other code starts reading input
+    /// events.
+    pub fn detect_synchronized_output() {
+        if IS_SYNCHRONIZED_OUTPUT_OVERRIDE.load(Ordering::SeqCst) {
+            return;
+        }
+        save_synchronized_output(Some(SynchronizedOutput::detect()));
+    }
+
+    pub fn set_synchronized_output_override(synchronized_output: SynchronizedOutput) {
+        IS_SYNCHRONIZED_OUTPUT_OVERRIDE.store(true, Ordering::SeqCst);
+        save_synchronized_output(Some(synchronized_output));
+    }
+
+    pub fn clear_synchronized_output_override() {
+        IS_SYNCHRONIZED_OUTPUT_OVERRIDE.store(false, Ordering::SeqCst);
+        save_synchronized_output(None);
+    }
+
+    fn save_synchronized_output(maybe_synchronized_output: Option<SynchronizedOutput>) {
+        let value = match maybe_synchronized_output {
+            None => NOT_SET_VALUE,
+            Some(SynchronizedOutput::Disabled) => 0,
+            Some(SynchronizedOutput::Enabled) => 1,
+        };
+        SYNCHRONIZED_OUTPUT.store(value, Ordering::SeqCst);
+    }
+}
+
+/// This module contains static global data that is meant to be used by the entire application. It
+/// also provides functions to manipulate this data.
+///
+/// ### Max FPS
+/// The [FrameScheduler] paints at most this many frames per second. Render requests that arrive
+/// faster than this are coalesced into a single paint. The app can override the default
+/// ([DEFAULT_MAX_FPS]), or turn off the limit.
+pub mod max_fps_global_static {
+    use super::*;
+    use crate::DEFAULT_MAX_FPS;
+
+    /// Global max FPS override. `0` means that there's no limit.
+    static MAX_FPS_OVERRIDE: AtomicI64 = AtomicI64::new(NOT_SET_VALUE);
+
+    /// Returns [None] if there's no limit (every render request is painted right away).
+    pub fn get_max_fps() -> Option<u16> {
+        match MAX_FPS_OVERRIDE.load(Ordering::SeqCst) {
+            NOT_SET_VALUE => Some(DEFAULT_MAX_FPS),
+            0 => None,
+            max_fps => Some(max_fps as u16),
+        }
+    }
+
+    /// Pass [None] to turn off the limit. This should be set before
+    /// [TerminalWindow::main_event_loop] is called.
+    pub fn set_max_fps_override(maybe_max_fps: Option<u16>) {
+        let value = match maybe_max_fps {
+            None | Some(0) => 0,
+            Some(max_fps) => max_fps as i64,
+        };
+        MAX_FPS_OVERRIDE.store(value, Ordering::SeqCst);
+    }
+
+    pub fn clear_max_fps_override() {
+        MAX_FPS_OVERRIDE.store(NOT_SET_VALUE, Ordering::SeqCst);
+    }
+}
@@ -108,6 +108,24 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_with_border() {
+        with! {
+          style! {
+            id: 1
+            padding: 1
+            border: BorderStyle::Rounded
+            color_fg: color!(@red)
+          },
+          as it,
+          run {
+            assert_eq!(it.padding, Some(ch!(1)));
+            assert_eq!(it.border, Some(BorderStyle::Rounded));
+            assert_eq!(it.color_fg, color!(@red).into());
+          }
+        }
+    }
+
     #[test]
     fn test_with_color_fg() {
         with! {
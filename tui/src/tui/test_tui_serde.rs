@@ -55,6 +55,34 @@ mod tests {
         assert_eq2!(pipeline, og_pipeline);
     }
 
+    #[test]
+    fn test_serde_render_pipeline_z_orders() {
+        let mut pipeline = render_pipeline!(
+          @new ZOrder::Glass.above(1)
+          =>
+            RenderOp::ClearScreen
+        );
+        render_pipeline!(@push_into pipeline at ZOrder::Glass => RenderOp::ResetColor);
+        pipeline.set_transparent(ZOrder::Glass, true);
+        let ser_str = serde_json::to_string(&pipeline).unwrap();
+        let og_pipeline: RenderPipeline = serde_json::from_str(&ser_str).unwrap();
+        assert_eq2!(pipeline, og_pipeline);
+
+        // Serialized w/ the names of the variants of the enum that ZOrder used to be.
+        assert_eq2!(serde_json::to_string(&ZOrder::High).unwrap(), "\"High\"");
+        assert_eq2!(
+            serde_json::to_string(&ZOrder::High.above(1)).unwrap(),
+            "101"
+        );
+        let old_ser_str = r#"{"pipeline_map":{"Normal":[{"list":["ClearScreen"]}]}}"#;
+        let og_pipeline: RenderPipeline = serde_json::from_str(old_ser_str).unwrap();
+        assert_eq2!(
+            og_pipeline,
+            render_pipeline!(@new ZOrder::Normal => RenderOp::ClearScreen)
+        );
+        assert!(serde_json::from_str::<ZOrder>("\"Low\"").is_err());
+    }
+
     #[test]
     fn test_serde_position() {
         let position = position!(col_index: 0, row_index:0);